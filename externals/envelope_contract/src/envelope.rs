@@ -75,7 +75,9 @@ pub fn update(
             return handle_send_msg(&mut storage, request.args);
         }
         _ => {
-            return Err(format!("Wrong request type has been found in execution").into());
+            return Err(ContractError::InvalidRequest(format!(
+                "Wrong request type has been found in execution"
+            )));
         }
     }
 }
@@ -108,7 +110,10 @@ fn get_msgs(storage: Storage, args: RequestArgs) -> Result<Vec<u8>, ContractErro
     let chats = evl_storage
         .chats
         .get(&ch_id)
-        .ok_or(format!("Chat is not initialized, ch_id: {}", &ch_id))?;
+        .ok_or(ContractError::NotFound(format!(
+            "Chat is not initialized, ch_id: {}",
+            &ch_id
+        )))?;
 
     let ret = serde_json::to_vec(chats)?;
 
@@ -176,7 +181,9 @@ fn handle_open_channel(
 
     match evl_storage.chats.get_mut(&open_ch.ch_id) {
         Some(_) => {
-            return Err(format!("The channel is already opened").into());
+            return Err(ContractError::AlreadyExists(format!(
+                "The channel is already opened"
+            )));
         }
         None => {}
     };
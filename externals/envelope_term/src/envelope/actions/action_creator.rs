@@ -182,6 +182,7 @@ pub async fn request_ch_list(
         ENVELOPE_CTR_ADDR.into(),
         GET_CH_LIST.to_string(),
         args,
+        None,
     )
     .await?;
 
@@ -201,6 +202,7 @@ pub async fn get_messages(
         ENVELOPE_CTR_ADDR.into(),
         GET_MSG.to_string(),
         args,
+        None,
     )
     .await?;
 
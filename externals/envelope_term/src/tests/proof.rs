@@ -67,7 +67,10 @@ pub(crate) async fn make_machine(block: BlockCandidate) -> SakMachine {
     };
 
     let mrs = {
-        let mrs_args = SakMRSArgs { mrs_db_path };
+        let mrs_args = SakMRSArgs {
+            mrs_db_path,
+            session_ttl: None,
+        };
 
         let m = SakMRS::init(mrs_args).await.unwrap();
         let m = Box::new(m) as MRSAccessor;
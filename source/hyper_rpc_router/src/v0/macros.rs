@@ -4,52 +4,26 @@ macro_rules! require_some_params {
         match $obj {
             Some(t) => t,
             None => {
-                return hyper_rpc_router::make_error_response(
-                    $route_state.resp,
-                    Some($route_state.id),
-                    $msg.into(),
-                );
+                return hyper_rpc_router::make_invalid_params_response($route_state, $msg.into());
             }
         }
     };
     ($route_state: expr, $obj: expr, $msg: tt,) => {
-        match $obj {
-            Some(t) => t,
-            None => {
-                return hyper_rpc_router::make_error_response(
-                    $route_state.resp,
-                    Some($route_state.id),
-                    $msg.into(),
-                );
-            }
-        }
+        hyper_rpc_router::require_some_params!($route_state, $obj, $msg)
     };
 }
 
 #[macro_export]
 macro_rules! require_params_parsed {
     ($route_state: expr, $params: expr) => {
-        match serde_json::from_slice($params) {
+        match hyper_rpc_router::parse_params($params) {
             Ok(r) => r,
             Err(err) => {
-                return hyper_rpc_router::make_error_response(
-                    $route_state.resp,
-                    Some($route_state.id),
-                    err.into(),
-                );
+                return hyper_rpc_router::make_invalid_params_response($route_state, err.into());
             }
         }
     };
     ($route_state: expr, $params: expr,) => {
-        match serde_json::from_slice($params) {
-            Ok(r) => r,
-            Err(err) => {
-                return router::make_error_response(
-                    $route_state.resp,
-                    Some($route_state.id),
-                    err.into(),
-                );
-            }
-        }
+        hyper_rpc_router::require_params_parsed!($route_state, $params)
     };
 }
@@ -1,49 +1,35 @@
 use crate::{header, RPCRouterError};
 use hyper::{Body, Response, StatusCode};
-use sak_rpc_interface::{JsonRPCError, JsonResponse, JSON_RPC_2};
-use serde::Serialize;
+use sak_rpc_interface::{
+    JsonRPCError, JsonResponse, JsonRpcId, INTERNAL_ERROR, INVALID_PARAMS, METHOD_NOT_FOUND,
+};
+use serde::{de::DeserializeOwned, Serialize};
 
 pub struct RouteState {
-    pub id: String,
+    pub id: JsonRpcId,
     pub resp: Response<Body>,
 }
 
-pub fn make_success_response<D: Serialize>(route_state: RouteState, result: D) -> Response<Body> {
-    let mut resp = route_state.resp;
-
-    header::add_application_json_header(&mut resp);
-
-    *resp.status_mut() = StatusCode::OK;
+/// Parses the params of a request, which are decoded of the legacy byte
+/// array form by the router beforehand.
+pub fn parse_params<P: DeserializeOwned>(params: &serde_json::Value) -> Result<P, RPCRouterError> {
+    Ok(P::deserialize(params)?)
+}
 
-    *resp.body_mut() = {
-        let response = JsonResponse {
-            jsonrpc: JSON_RPC_2.into(),
-            error: None,
-            result: Some(result),
-            id: route_state.id.to_string(),
-        };
-
-        let body_str = match serde_json::to_string(&response) {
-            Ok(s) => s,
-            Err(err) => {
-                return make_serialize_err_response(resp, route_state.id, Some(err.into()));
-            }
-        };
-
-        Body::from(body_str)
-    };
+pub fn make_success_response<D: Serialize>(route_state: RouteState, result: D) -> Response<Body> {
+    let response = JsonResponse::new_result(route_state.id.clone(), result);
 
-    resp
+    make_json_response(route_state.resp, route_state.id, &response)
 }
 
 pub fn make_serialize_err_response(
     mut resp: Response<Body>,
-    id: String,
+    id: JsonRpcId,
     original_err: Option<RPCRouterError>,
 ) -> Response<Body> {
     header::add_application_json_header(&mut resp);
 
-    *resp.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+    *resp.status_mut() = StatusCode::OK;
 
     let err = if let Some(e) = original_err {
         e.to_string()
@@ -51,67 +37,106 @@ pub fn make_serialize_err_response(
         "".into()
     };
 
+    // Built by hand, as serializing has failed once
     *resp.body_mut() = {
-        let msg = format!(
-            r#"
-    {{
-        id: {},
-        error: {{
-            msg: Cannot serialize error, original err (if any): {}
-        }}
-    }}
-    "#,
-            id, err,
-        );
-
-        Body::from(msg)
+        let response = serde_json::json!({
+            "jsonrpc": sak_rpc_interface::JSON_RPC_2,
+            "error": {
+                "code": INTERNAL_ERROR,
+                "message": format!(
+                    "Cannot serialize the response, original err (if any): {}",
+                    err,
+                ),
+            },
+            "id": id,
+        });
+
+        Body::from(response.to_string())
     };
 
     resp
 }
 
-pub fn make_not_found_response(route_state: RouteState) -> Response<Body> {
-    let mut resp = route_state.resp;
+pub fn make_method_not_found_response(route_state: RouteState, method: &str) -> Response<Body> {
+    make_error_response_with_code(
+        route_state.resp,
+        Some(route_state.id),
+        METHOD_NOT_FOUND,
+        format!("Method is not found, method: {}", method).into(),
+        None,
+    )
+}
 
-    *resp.status_mut() = StatusCode::NOT_FOUND;
-    *resp.body_mut() = Body::from("not found");
-    resp
+pub fn make_invalid_params_response(
+    route_state: RouteState,
+    error: RPCRouterError,
+) -> Response<Body> {
+    make_error_response_with_code(
+        route_state.resp,
+        Some(route_state.id),
+        INVALID_PARAMS,
+        error,
+        None,
+    )
 }
 
 pub fn make_error_response(
-    mut resp: Response<Body>,
-    id: Option<String>,
+    resp: Response<Body>,
+    id: Option<JsonRpcId>,
+    error: RPCRouterError,
+) -> Response<Body> {
+    make_error_response_with_data(resp, id, error, None)
+}
+
+/// Same as `make_error_response` but attaches structured `data` that lets
+/// the client tell errors apart without parsing the message.
+pub fn make_error_response_with_data(
+    resp: Response<Body>,
+    id: Option<JsonRpcId>,
+    error: RPCRouterError,
+    data: Option<serde_json::Value>,
+) -> Response<Body> {
+    make_error_response_with_code(resp, id, INTERNAL_ERROR, error, data)
+}
+
+/// Error response of a JSON-RPC 2.0 error code. The id is null if it could
+/// not be read off the request.
+pub fn make_error_response_with_code(
+    resp: Response<Body>,
+    id: Option<JsonRpcId>,
+    code: i32,
     error: RPCRouterError,
+    data: Option<serde_json::Value>,
 ) -> Response<Body> {
-    let id = id.unwrap_or("none".to_string());
+    let id = id.unwrap_or(JsonRpcId::Null);
+
+    let response: JsonResponse<()> = JsonResponse::new_error(
+        id.clone(),
+        JsonRPCError {
+            code,
+            message: error.to_string(),
+            data,
+        },
+    );
+
+    make_json_response(resp, id, &response)
+}
 
+fn make_json_response<R: Serialize>(
+    mut resp: Response<Body>,
+    id: JsonRpcId,
+    response: &R,
+) -> Response<Body> {
     header::add_application_json_header(&mut resp);
 
-    *resp.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+    // Errors are told of in the body, as JSON-RPC over HTTP does
+    *resp.status_mut() = StatusCode::OK;
 
-    *resp.body_mut() = {
-        let response: JsonResponse<()> = JsonResponse {
-            jsonrpc: JSON_RPC_2.into(),
-            error: Some(JsonRPCError {
-                msg: error.to_string(),
-            }),
-            result: None,
-            id: id.to_string(),
-        };
-
-        let body_str = match serde_json::to_string(&response) {
-            Ok(s) => s,
-            Err(err) => {
-                return make_serialize_err_response(
-                    resp,
-                    id,
-                    // route_state,
-                    Some(err.into()),
-                );
-            }
-        };
-
-        Body::from(body_str)
+    *resp.body_mut() = match serde_json::to_string(response) {
+        Ok(s) => Body::from(s),
+        Err(err) => {
+            return make_serialize_err_response(resp, id, Some(err.into()));
+        }
     };
 
     resp
@@ -5,14 +5,10 @@ use std::pin::Pin;
 
 pub type MethodName = &'static str;
 
-pub type Params = Option<Vec<u8>>;
+pub type Params = Option<serde_json::Value>;
 
 pub type Handler<C> = Box<
-    dyn Fn(
-            RouteState,
-            Params,
-            C,
-        ) -> Pin<Box<dyn Future<Output = Response<Body>> + Send + Sync + 'static>>
+    dyn Fn(RouteState, Params, C) -> Pin<Box<dyn Future<Output = Response<Body>> + Send + 'static>>
         + Send
         + Sync
         + 'static,
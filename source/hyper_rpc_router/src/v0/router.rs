@@ -1,17 +1,26 @@
-use super::{response, Handler, RouteState};
-use hyper::{Body, Request, Response};
+use super::{header, response, Handler, RouteState};
+use hyper::{Body, Request, Response, StatusCode};
 use hyper_server::MiddlewareResult;
 use sak_logger::debug;
-use sak_rpc_interface::JsonRequest;
+use sak_rpc_interface::{
+    decode_legacy_params, JsonRPCError, JsonRequest, JsonResponse, JsonRpcId, INTERNAL_ERROR,
+    INVALID_REQUEST, JSON_RPC_2, PARSE_ERROR,
+};
+use serde_json::Value;
 use std::{collections::HashMap, sync::Arc};
 
+type RouteMap<C> = Arc<HashMap<&'static str, Handler<C>>>;
+
+/// Most requests a batch may carry.
+pub const MAX_BATCH_LEN: usize = 100;
+
 pub struct Router<C> {
-    route_map: Arc<HashMap<&'static str, Handler<C>>>,
+    route_map: RouteMap<C>,
 }
 
 impl<C> Router<C>
 where
-    C: Send + Sync + 'static,
+    C: Clone + Send + Sync + 'static,
 {
     pub fn new(route_map: HashMap<&'static str, Handler<C>>) -> Router<C> {
         let route_map = Arc::new(route_map);
@@ -19,12 +28,13 @@ where
         Router { route_map }
     }
 
+    /// Routes a JSON-RPC 2.0 request, or a batch of them. Notifications are
+    /// handled but not replied to, so a body of notifications only gets
+    /// `204 No Content`.
     pub fn route(&self, req: Request<Body>, resp: Response<Body>, ctx: C) -> MiddlewareResult<C> {
         let route_map = self.route_map.clone();
 
         let result = Box::pin(async move {
-            let route_map = route_map.clone();
-
             let rb = match hyper::body::to_bytes(req.into_body()).await {
                 Ok(b) => b,
                 Err(err) => {
@@ -32,33 +42,195 @@ where
                 }
             };
 
-            let json_request: JsonRequest = match serde_json::from_slice(&rb) {
-                Ok(r) => r,
+            let body: Value = match serde_json::from_slice(&rb) {
+                Ok(v) => v,
                 Err(err) => {
-                    return Ok(response::make_error_response(
+                    return Ok(response::make_error_response_with_code(
                         resp,
                         None,
-                        format!("Failed to parse as json_request, err: {}", err).into(),
+                        PARSE_ERROR,
+                        format!("Failed to parse as json, err: {}", err).into(),
+                        None,
                     ));
                 }
             };
 
-            debug!("router, json_rpc, method: {}", json_request.method);
-
-            let route_state = RouteState {
-                id: json_request.id,
-                resp,
+            let no_content = {
+                let mut r = Response::new(Body::empty());
+                *r.headers_mut() = resp.headers().clone();
+                *r.status_mut() = StatusCode::NO_CONTENT;
+                r
             };
 
-            if let Some(handler) = route_map.get(json_request.method.as_str()) {
-                let resp = handler(route_state, json_request.params, ctx).await;
+            let resp = match body {
+                Value::Array(reqs) => route_batch(&route_map, reqs, resp, ctx).await,
+                req => handle_request(&route_map, req, resp, ctx).await,
+            };
 
-                Ok(resp)
-            } else {
-                return Ok(response::make_not_found_response(route_state));
-            }
+            Ok(resp.unwrap_or(no_content))
         });
 
         MiddlewareResult::End(result)
     }
 }
+
+async fn route_batch<C>(
+    route_map: &RouteMap<C>,
+    reqs: Vec<Value>,
+    mut resp: Response<Body>,
+    ctx: C,
+) -> Option<Response<Body>>
+where
+    C: Clone + Send + Sync + 'static,
+{
+    if reqs.is_empty() {
+        return Some(response::make_error_response_with_code(
+            resp,
+            None,
+            INVALID_REQUEST,
+            "Batch should not be empty".into(),
+            None,
+        ));
+    }
+
+    if reqs.len() > MAX_BATCH_LEN {
+        return Some(response::make_error_response_with_code(
+            resp,
+            None,
+            INVALID_REQUEST,
+            format!(
+                "Batch is too large, len: {}, max: {}",
+                reqs.len(),
+                MAX_BATCH_LEN
+            )
+            .into(),
+            None,
+        ));
+    }
+
+    let handles = reqs.into_iter().map(|req| {
+        let ctx = ctx.clone();
+
+        async move {
+            let r = handle_request(route_map, req, Response::default(), ctx).await?;
+
+            Some(read_response(r).await)
+        }
+    });
+
+    let responses: Vec<Value> = futures::future::join_all(handles)
+        .await
+        .into_iter()
+        .flatten()
+        .collect();
+
+    if responses.is_empty() {
+        return None;
+    }
+
+    header::add_application_json_header(&mut resp);
+
+    *resp.status_mut() = StatusCode::OK;
+    *resp.body_mut() = Body::from(Value::Array(responses).to_string());
+
+    Some(resp)
+}
+
+/// Response of a request, `None` if it is a notification.
+async fn handle_request<C>(
+    route_map: &RouteMap<C>,
+    req: Value,
+    resp: Response<Body>,
+    ctx: C,
+) -> Option<Response<Body>> {
+    let json_request = match parse_request(req) {
+        Ok(r) => r,
+        Err((id, err)) => {
+            return Some(response::make_error_response_with_code(
+                resp,
+                id,
+                INVALID_REQUEST,
+                err.into(),
+                None,
+            ));
+        }
+    };
+
+    debug!("router, json_rpc, method: {}", json_request.method);
+
+    let is_notification = json_request.is_notification();
+
+    let route_state = RouteState {
+        id: json_request.id.unwrap_or(JsonRpcId::Null),
+        resp,
+    };
+
+    let resp = match route_map.get(json_request.method.as_str()) {
+        Some(handler) => {
+            let params = json_request.params.map(decode_legacy_params);
+
+            handler(route_state, params, ctx).await
+        }
+        None => response::make_method_not_found_response(route_state, &json_request.method),
+    };
+
+    if is_notification {
+        None
+    } else {
+        Some(resp)
+    }
+}
+
+fn parse_request(req: Value) -> Result<JsonRequest, (Option<JsonRpcId>, String)> {
+    // The id is kept, if there is one, to reply to the invalid request with
+    let id = req
+        .get("id")
+        .and_then(|id| serde_json::from_value(id.clone()).ok());
+
+    let json_request: JsonRequest = match serde_json::from_value(req) {
+        Ok(r) => r,
+        Err(err) => {
+            return Err((id, format!("Failed to parse as json_request, err: {}", err)));
+        }
+    };
+
+    if json_request.jsonrpc != JSON_RPC_2 {
+        return Err((
+            id,
+            format!(
+                "jsonrpc should be {}, jsonrpc: {}",
+                JSON_RPC_2, json_request.jsonrpc
+            ),
+        ));
+    }
+
+    if let Some(p) = &json_request.params {
+        if !(p.is_object() || p.is_array()) {
+            return Err((id, "params should be an object or an array".into()));
+        }
+    }
+
+    Ok(json_request)
+}
+
+async fn read_response(resp: Response<Body>) -> Value {
+    let result = match hyper::body::to_bytes(resp.into_body()).await {
+        Ok(b) => serde_json::from_slice(&b).map_err(|err| err.to_string()),
+        Err(err) => Err(err.to_string()),
+    };
+
+    match result {
+        Ok(v) => v,
+        Err(err) => {
+            let response: JsonResponse<()> = JsonResponse::new_error(
+                JsonRpcId::Null,
+                JsonRPCError::new(
+                    INTERNAL_ERROR,
+                    format!("Failed to read the response, err: {}", err),
+                ),
+            );
+
+            serde_json::to_value(response).unwrap_or(Value::Null)
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use crate::{Middleware, MiddlewareResult};
+use hyper::{
+    header::{self, HeaderValue},
+    Body, Method,
+};
+
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Answers the GET requests at `path` with what `gather` renders out of the
+/// context, e.g. the metrics in the Prometheus text format. Other requests
+/// are passed on.
+pub fn metrics<C, F>(path: String, gather: F) -> Middleware<C>
+where
+    F: Fn(&C) -> String + Send + Sync + 'static,
+{
+    Middleware::new(Box::new(move |req, mut resp, ctx| {
+        if req.method() != Method::GET || req.uri().path() != path {
+            return MiddlewareResult::Passing(req, resp, ctx);
+        }
+
+        let body = gather(&ctx);
+
+        resp.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(METRICS_CONTENT_TYPE),
+        );
+        *resp.body_mut() = Body::from(body);
+
+        MiddlewareResult::End(Box::pin(async { Ok(resp) }))
+    }))
+}
@@ -1,3 +1,7 @@
 mod cors;
+mod metrics;
+mod websocket;
 
 pub use cors::*;
+pub use metrics::*;
+pub use websocket::*;
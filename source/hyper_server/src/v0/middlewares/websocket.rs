@@ -0,0 +1,91 @@
+use crate::{Middleware, MiddlewareResult};
+use futures::Future;
+use hyper::{
+    header::{self, HeaderValue},
+    upgrade::Upgraded,
+    Body, Method, Request, StatusCode,
+};
+use sak_logger::warn;
+use tokio_tungstenite::tungstenite::{handshake::derive_accept_key, protocol::Role};
+use tokio_tungstenite::WebSocketStream;
+
+pub use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+pub type WsStream = WebSocketStream<Upgraded>;
+
+/// Upgrades the GET requests at `path` to WebSocket and hands the stream to
+/// `on_conn`, which serves it on a task of its own. Other requests are
+/// passed on.
+pub fn websocket<C, F, Fut>(path: String, on_conn: F) -> Middleware<C>
+where
+    C: Send + 'static,
+    F: Fn(WsStream, C) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    Middleware::new(Box::new(move |mut req, mut resp, ctx| {
+        if req.method() != Method::GET || req.uri().path() != path {
+            return MiddlewareResult::Passing(req, resp, ctx);
+        }
+
+        let accept_key = match get_accept_key(&req) {
+            Some(k) => k,
+            None => {
+                *resp.status_mut() = StatusCode::BAD_REQUEST;
+                *resp.body_mut() = Body::from("Expected a WebSocket upgrade request");
+
+                return MiddlewareResult::End(Box::pin(async { Ok(resp) }));
+            }
+        };
+
+        let on_upgrade = hyper::upgrade::on(&mut req);
+        let on_conn = on_conn.clone();
+
+        tokio::spawn(async move {
+            let upgraded = match on_upgrade.await {
+                Ok(u) => u,
+                Err(err) => {
+                    warn!("WebSocket upgrade has failed, err: {}", err);
+
+                    return;
+                }
+            };
+
+            let ws_stream = WebSocketStream::from_raw_socket(upgraded, Role::Server, None).await;
+
+            on_conn(ws_stream, ctx).await;
+        });
+
+        *resp.status_mut() = StatusCode::SWITCHING_PROTOCOLS;
+
+        let headers = resp.headers_mut();
+
+        headers.insert(header::CONNECTION, HeaderValue::from_static("Upgrade"));
+        headers.insert(header::UPGRADE, HeaderValue::from_static("websocket"));
+        headers.insert(header::SEC_WEBSOCKET_ACCEPT, accept_key);
+
+        MiddlewareResult::End(Box::pin(async { Ok(resp) }))
+    }))
+}
+
+fn get_accept_key(req: &Request<Body>) -> Option<HeaderValue> {
+    let headers = req.headers();
+
+    let is_upgrade = headers
+        .get(header::UPGRADE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.eq_ignore_ascii_case("websocket"))
+        .unwrap_or(false);
+
+    let is_version_13 = headers
+        .get(header::SEC_WEBSOCKET_VERSION)
+        .map(|v| v == "13")
+        .unwrap_or(false);
+
+    if !is_upgrade || !is_version_13 {
+        return None;
+    }
+
+    let key = headers.get(header::SEC_WEBSOCKET_KEY)?;
+
+    HeaderValue::from_str(&derive_accept_key(key.as_bytes())).ok()
+}
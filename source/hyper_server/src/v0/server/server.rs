@@ -36,6 +36,23 @@ impl HttpServer {
     ) -> Result<(), RPCServerError>
     where
         C: Clone + Send + Sync + 'static,
+    {
+        self.run_until(tcp_socket, ctx, middlewares, std::future::pending())
+            .await
+    }
+
+    /// Runs the server until `shutdown_signal` resolves. Requests in flight
+    /// are answered before it returns.
+    pub async fn run_until<C, F>(
+        self,
+        tcp_socket: TcpListener,
+        ctx: C,
+        middlewares: Vec<Middleware<C>>,
+        shutdown_signal: F,
+    ) -> Result<(), RPCServerError>
+    where
+        C: Clone + Send + Sync + 'static,
+        F: Future<Output = ()>,
     {
         let addr_incoming = match AddrIncoming::from_listener(tcp_socket) {
             Ok(a) => a,
@@ -78,7 +95,9 @@ impl HttpServer {
             }
         });
 
-        let server = Server::builder(addr_incoming).serve(make_svc);
+        let server = Server::builder(addr_incoming)
+            .serve(make_svc)
+            .with_graceful_shutdown(shutdown_signal);
 
         if let Err(err) = server.await {
             error!("Error running rpc server, err: {}", err);
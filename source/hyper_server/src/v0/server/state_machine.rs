@@ -6,7 +6,7 @@ use std::{pin::Pin, sync::Arc};
 
 pub enum MiddlewareResult<C> {
     Passing(Request<Body>, Response<Body>, C),
-    End(Pin<Box<dyn Future<Output = Result<Response<Body>, hyper::Error>> + Send + Sync>>),
+    End(Pin<Box<dyn Future<Output = Result<Response<Body>, hyper::Error>> + Send>>),
 }
 
 pub struct StateMachine<C> {
@@ -19,7 +19,7 @@ impl<C> StateMachine<C> {
         req: Request<Body>,
         res: Response<Body>,
         ctx: C,
-    ) -> Pin<Box<dyn Future<Output = Result<Response<Body>, hyper::Error>> + Send + Sync>> {
+    ) -> Pin<Box<dyn Future<Output = Result<Response<Body>, hyper::Error>> + Send>> {
         let mut rq = req;
         let mut rs = res;
         let mut ct = ctx;
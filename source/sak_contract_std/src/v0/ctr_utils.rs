@@ -1,4 +1,5 @@
-use crate::RET_LEN_SIZE;
+use crate::{ContractError, RET_LEN_SIZE};
+use sak_store_interface::{RangeRequest, RangeResponse, RangeResult};
 use std::convert::TryInto;
 
 crate::define_host_ffi!();
@@ -60,6 +61,41 @@ pub fn get_ctr_state_from_host(key: &String) -> Vec<u8> {
     // vec![]
 }
 
+pub fn get_mrs_data_range_from_host(range: &RangeRequest) -> Result<RangeResponse, ContractError> {
+    unsafe { get_range_from_host(HOST__get_mrs_data_range, range) }
+}
+
+pub fn get_ctr_state_range_from_host(range: &RangeRequest) -> Result<RangeResponse, ContractError> {
+    unsafe { get_range_from_host(HOST__get_ctr_state_range, range) }
+}
+
+unsafe fn get_range_from_host(
+    host_fn: unsafe extern "C" fn(*mut u8, u32, *mut u32) -> i32,
+    range: &RangeRequest,
+) -> Result<RangeResponse, ContractError> {
+    let req = serde_json::to_vec(range)?;
+
+    let req_len = req.len();
+    let req_ptr = CTR__alloc(req_len);
+    req_ptr.copy_from(req.as_ptr(), req_len);
+
+    let ret_len_ptr = CTR__alloc(RET_LEN_SIZE);
+    let ret_ptr = host_fn(req_ptr, req_len as u32, ret_len_ptr as *mut u32);
+    let ret_len = {
+        let bytes: [u8; RET_LEN_SIZE] =
+            std::slice::from_raw_parts(ret_len_ptr as *mut u8, RET_LEN_SIZE)
+                .try_into()
+                .unwrap();
+        u32::from_be_bytes(bytes)
+    };
+
+    let data = Vec::from_raw_parts(ret_ptr as *mut u8, ret_len as usize, ret_len as usize);
+
+    let res: RangeResult = serde_json::from_slice(&data)?;
+
+    res.map_err(ContractError::Internal)
+}
+
 // pub fn put_mrs_data_to_host(key: &String, value: &String) {
 //     unsafe {
 //         let key_len = key.len();
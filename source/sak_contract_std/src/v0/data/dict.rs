@@ -1,5 +1,8 @@
-use crate::{get_ctr_state_from_host, get_mrs_data_from_host, HostStorage};
-use std::collections::HashMap;
+use crate::{get_ctr_state_from_host, get_mrs_data_from_host, ContractError, HostStorage};
+use sak_store_interface::RangeRequest;
+use std::collections::{HashMap, VecDeque};
+
+const DICT_ITER_PAGE_SIZE: usize = 32;
 
 #[derive(Debug)]
 pub struct Dict<T> {
@@ -30,6 +33,29 @@ impl<T> Dict<T> {
         data
     }
 
+    /// Iterates over the entries whose key is equal to or greater than
+    /// `start_key`, in key order. Entries are fetched from the host a page
+    /// at a time, and a page the host fails to fetch ends the iteration with
+    /// an error.
+    pub fn iter_from(&self, start_key: &String) -> DictIter<T> {
+        let prefix = format!("{}_", self._name);
+
+        let range = RangeRequest {
+            start: Some(format!("{}{}", prefix, start_key)),
+            prefix,
+            end: None,
+            cursor: None,
+            limit: DICT_ITER_PAGE_SIZE,
+        };
+
+        DictIter {
+            dict: self,
+            range,
+            buf: VecDeque::new(),
+            is_exhausted: false,
+        }
+    }
+
     pub fn push(&mut self, key: String, value: Vec<u8>) {
         let key = format!("{}_{}", self._name, key);
 
@@ -44,3 +70,42 @@ impl<T> Dict<T> {
         HashMap::from([("str_1".to_string(), vec![123])])
     }
 }
+
+pub struct DictIter<'a, T> {
+    dict: &'a Dict<T>,
+    range: RangeRequest,
+    buf: VecDeque<(String, Vec<u8>)>,
+    is_exhausted: bool,
+}
+
+impl<'a, T> Iterator for DictIter<'a, T> {
+    type Item = Result<(String, Vec<u8>), ContractError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.is_empty() && !self.is_exhausted {
+            let res = match self.dict._host_storage.get_range(&self.range) {
+                Ok(r) => r,
+                Err(err) => {
+                    self.is_exhausted = true;
+
+                    return Some(Err(err));
+                }
+            };
+
+            match res.cursor {
+                Some(c) => self.range.cursor = Some(c),
+                None => self.is_exhausted = true,
+            };
+
+            self.buf.extend(res.items);
+        }
+
+        let (key, value) = self.buf.pop_front()?;
+        let key = match key.strip_prefix(&self.range.prefix) {
+            Some(k) => k.to_string(),
+            None => key,
+        };
+
+        Some(Ok((key, value)))
+    }
+}
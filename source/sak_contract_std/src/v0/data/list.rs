@@ -1,6 +1,9 @@
-use crate::{get_ctr_state_from_host, get_mrs_data_from_host, HostStorage};
+use crate::{get_ctr_state_from_host, get_mrs_data_from_host, ContractError, HostStorage};
+use sak_store_interface::{make_list_key, RangeRequest};
 use std::collections::HashMap;
 
+const LIST_RANGE_PAGE_SIZE: usize = 64;
+
 #[derive(Debug)]
 pub struct List<T>
 where
@@ -30,7 +33,7 @@ where
         B: IntoIterator<Item = T>,
     {
         for (idx, d) in data.into_iter().enumerate() {
-            let key: String = format!("{}_{}", self._name, idx);
+            let key = self.make_key(idx);
 
             let val = d.as_ref().to_vec();
 
@@ -39,7 +42,10 @@ where
     }
 
     pub fn get(&self, key: &String) -> Vec<u8> {
-        let key: String = format!("{}_{}", self._name, key);
+        let key = match key.parse::<usize>() {
+            Ok(idx) => self.make_key(idx),
+            Err(_) => format!("{}_{}", self._name, key),
+        };
 
         let data = match self._host_storage {
             HostStorage::MRS => get_mrs_data_from_host(&key),
@@ -49,6 +55,35 @@ where
         data
     }
 
+    /// Returns the elements whose index is in `start..end`, ordered by index.
+    pub fn range(&self, start: usize, end: usize) -> Result<Vec<Vec<u8>>, ContractError> {
+        if start >= end {
+            return Ok(vec![]);
+        }
+
+        let mut range = RangeRequest {
+            prefix: format!("{}_", self._name),
+            start: Some(self.make_key(start)),
+            end: Some(self.make_key(end)),
+            cursor: None,
+            limit: (end - start).min(LIST_RANGE_PAGE_SIZE),
+        };
+
+        let mut elems = vec![];
+        loop {
+            let res = self._host_storage.get_range(&range)?;
+
+            elems.extend(res.items.into_iter().map(|(_, v)| v));
+
+            match res.cursor {
+                Some(c) => range.cursor = Some(c),
+                None => break,
+            };
+        }
+
+        Ok(elems)
+    }
+
     pub fn push(&mut self, value: Vec<u8>) {
         //TO-DO: get latest idx of the stored List and update index
         let latest_idx_key = String::from("latest_idx");
@@ -57,7 +92,7 @@ where
 
         let latest_idx = 0;
 
-        let key: String = format!("{}_{}", self._name, latest_idx);
+        let key = self.make_key(latest_idx);
 
         self.receipt.insert(key, value);
     }
@@ -65,4 +100,8 @@ where
     pub fn receipt(&self) -> HashMap<String, Vec<u8>> {
         self.receipt.clone()
     }
+
+    fn make_key(&self, idx: usize) -> String {
+        make_list_key(&self._name, idx as u64)
+    }
 }
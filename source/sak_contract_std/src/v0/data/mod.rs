@@ -1,6 +1,11 @@
 mod dict;
 mod list;
 
+use crate::ContractError;
+#[cfg(not(test))]
+use crate::{get_ctr_state_range_from_host, get_mrs_data_range_from_host};
+use sak_store_interface::{RangeRequest, RangeResponse};
+
 pub use dict::*;
 pub use list::*;
 
@@ -9,3 +14,19 @@ pub enum HostStorage {
     MRS,
     CtrState,
 }
+
+impl HostStorage {
+    #[cfg(not(test))]
+    pub(crate) fn get_range(&self, range: &RangeRequest) -> Result<RangeResponse, ContractError> {
+        match self {
+            HostStorage::MRS => get_mrs_data_range_from_host(range),
+            HostStorage::CtrState => get_ctr_state_range_from_host(range),
+        }
+    }
+
+    // There is no host outside of wasm, so tests range over a mock store
+    #[cfg(test)]
+    pub(crate) fn get_range(&self, range: &RangeRequest) -> Result<RangeResponse, ContractError> {
+        crate::v0::tests::MockHost::get_range(range)
+    }
+}
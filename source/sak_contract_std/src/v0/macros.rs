@@ -19,6 +19,15 @@ macro_rules! define_host_ffi {
 
             fn HOST__get_ctr_state(param1: *mut u8, param2: u32, ptr_ret_len: *mut u32) -> i32;
 
+            fn HOST__get_mrs_data_range(param1: *mut u8, param2: u32, ptr_ret_len: *mut u32)
+                -> i32;
+
+            fn HOST__get_ctr_state_range(
+                param1: *mut u8,
+                param2: u32,
+                ptr_ret_len: *mut u32,
+            ) -> i32;
+
             // fn HOST__put_mrs_data(
             //     param1: *mut u8,
             //     param2: u32,
@@ -73,7 +82,8 @@ macro_rules! define_ctr_fns {
 
             let result: Result<$crate::Storage, $crate::ContractError> = init(&mut ctx);
 
-            let mut result = $crate::return_err_4!(result, "error");
+            let result = $crate::return_err_4!(result);
+            let mut result = $crate::make_result_vec(Ok(result));
 
             let result_ptr = result.as_mut_ptr();
             let result_len = result.len();
@@ -119,8 +129,8 @@ macro_rules! define_ctr_fns {
 
             HOST__log(20, 20);
 
-            let mut result: $crate::InvokeResult =
-                $crate::return_err_4!(result, "something failed (ctr__execute)");
+            let result: $crate::InvokeResult = $crate::return_err_4!(result);
+            let mut result = $crate::make_result_vec(Ok(result));
             HOST__log(30, 30);
 
             let result_ptr = result.as_mut_ptr();
@@ -150,8 +160,8 @@ macro_rules! define_ctr_fns {
 
             let result: Result<$crate::InvokeResult, $crate::ContractError> = update(ctx, request);
 
-            let mut result: $crate::InvokeResult =
-                $crate::return_err_4!(result, "serde result parsing fail");
+            let result: $crate::InvokeResult = $crate::return_err_4!(result);
+            let mut result = $crate::make_result_vec(Ok(result));
             let result_ptr = result.as_mut_ptr();
             let result_len = result.len();
             std::mem::forget(result);
@@ -162,10 +172,10 @@ macro_rules! define_ctr_fns {
             std::mem::forget(storage);
 
             return (
-                storage_ptr,
-                storage_len as i32,
                 result_ptr,
                 result_len as i32,
+                storage_ptr,
+                storage_len as i32,
             );
         }
     };
@@ -188,8 +198,7 @@ macro_rules! parse_request {
     ($ptr: expr, $len: expr) => {{
         let request_vec = Vec::from_raw_parts($ptr, $len, $len);
         let maybe_req = serde_json::from_slice(&request_vec);
-        let req: sak_contract_std::CtrRequest =
-            $crate::return_err_4!(maybe_req, "something failed");
+        let req: sak_contract_std::CtrRequest = $crate::return_err_4!(maybe_req);
         req
     }};
 }
@@ -200,7 +209,7 @@ macro_rules! return_err_2 {
         match $obj {
             Ok(r) => r,
             Err(err) => {
-                let mut err = sak_contract_std::make_error_vec(err.into(), "");
+                let mut err = $crate::make_error_vec(err.into());
                 let err_ptr = err.as_mut_ptr();
                 let err_len = err.len();
 
@@ -214,11 +223,11 @@ macro_rules! return_err_2 {
 
 #[macro_export]
 macro_rules! return_err_4 {
-    ($obj: expr, $msg: expr) => {
+    ($obj: expr) => {
         match $obj {
             Ok(r) => r,
             Err(err) => {
-                let mut err = sak_contract_std::make_error_vec(err.into(), $msg);
+                let mut err = $crate::make_error_vec(err.into());
                 let err_ptr = err.as_mut_ptr();
                 let err_len = err.len();
                 std::mem::forget(err);
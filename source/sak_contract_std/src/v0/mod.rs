@@ -8,6 +8,9 @@ mod size;
 mod storage;
 pub mod symbols;
 
+#[cfg(test)]
+mod tests;
+
 pub use ctr_fn::*;
 pub use ctr_utils::*;
 pub use data::*;
@@ -18,5 +21,3 @@ pub use std::error::Error;
 pub use storage::*;
 
 pub type StorageError = Box<dyn std::error::Error + Send + Sync>;
-
-pub type ContractError = Box<dyn std::error::Error + Send + Sync>;
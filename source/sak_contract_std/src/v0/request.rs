@@ -14,6 +14,9 @@ pub struct CtrRequest {
     pub req_type: String,
     pub args: RequestArgs,
     pub ctr_call_type: CtrCallType,
+    // Public key of the client a session opened by this request belongs to
+    #[serde(default)]
+    pub session_owner: Option<String>,
 }
 
 unsafe impl Send for CtrRequest {}
@@ -40,6 +43,7 @@ impl CtrRequest {
             req_type: data.req_type,
             args: data.args,
             ctr_call_type: data.ctr_call_type,
+            session_owner: None,
         };
 
         Ok(req)
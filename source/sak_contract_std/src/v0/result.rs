@@ -1,13 +1,170 @@
-use crate::ContractError;
+use serde::{Deserialize, Serialize};
 
 pub type InvokeResult = Vec<u8>;
 
-pub const ERROR_PLACEHOLDER: [u8; 6] = [1, 2, 3, 4, 5, 6];
+/// First byte of every result returned across the wasm boundary.
+pub const RESULT_STATUS_OK: u8 = 0;
 
-pub fn make_error_vec(err: ContractError, msg: &str) -> Vec<u8> {
-    let err_str = err.to_string();
-    let err_msg = format!("{} - {}", err_str, msg);
-    let v = err_msg.as_bytes().to_vec();
+pub const RESULT_STATUS_ERR: u8 = 1;
 
-    [ERROR_PLACEHOLDER.to_vec(), v].concat()
+/// Errors a contract can return from `init`, `execute` and `update`.
+/// Contracts that need their own revert reasons use `Custom`.
+#[derive(Debug)]
+pub enum ContractError {
+    InvalidRequest(String),
+    Unauthorized(String),
+    NotFound(String),
+    AlreadyExists(String),
+    Serialization(String),
+    Internal(String),
+    Custom {
+        code: u16,
+        kind: String,
+        msg: String,
+        data: Option<Vec<u8>>,
+    },
+}
+
+impl ContractError {
+    pub fn code(&self) -> u16 {
+        match self {
+            ContractError::InvalidRequest(_) => 400,
+            ContractError::Unauthorized(_) => 401,
+            ContractError::NotFound(_) => 404,
+            ContractError::AlreadyExists(_) => 409,
+            ContractError::Serialization(_) => 422,
+            ContractError::Internal(_) => 500,
+            ContractError::Custom { code, .. } => *code,
+        }
+    }
+
+    pub fn kind(&self) -> &str {
+        match self {
+            ContractError::InvalidRequest(_) => "invalid_request",
+            ContractError::Unauthorized(_) => "unauthorized",
+            ContractError::NotFound(_) => "not_found",
+            ContractError::AlreadyExists(_) => "already_exists",
+            ContractError::Serialization(_) => "serialization",
+            ContractError::Internal(_) => "internal",
+            ContractError::Custom { kind, .. } => kind,
+        }
+    }
+
+    pub fn msg(&self) -> &str {
+        match self {
+            ContractError::InvalidRequest(msg)
+            | ContractError::Unauthorized(msg)
+            | ContractError::NotFound(msg)
+            | ContractError::AlreadyExists(msg)
+            | ContractError::Serialization(msg)
+            | ContractError::Internal(msg)
+            | ContractError::Custom { msg, .. } => msg,
+        }
+    }
+
+    pub fn into_revert(self) -> ContractRevert {
+        let status = self.code();
+        let kind = self.kind().to_string();
+
+        let (msg, data) = match self {
+            ContractError::Custom { msg, data, .. } => (msg, data),
+            err => (err.msg().to_string(), None),
+        };
+
+        ContractRevert {
+            status,
+            kind,
+            msg,
+            data,
+        }
+    }
+}
+
+impl std::fmt::Display for ContractError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}): {}", self.kind(), self.code(), self.msg())
+    }
+}
+
+impl std::error::Error for ContractError {}
+
+impl From<String> for ContractError {
+    fn from(msg: String) -> Self {
+        ContractError::Internal(msg)
+    }
+}
+
+impl From<&str> for ContractError {
+    fn from(msg: &str) -> Self {
+        ContractError::Internal(msg.to_string())
+    }
+}
+
+impl From<serde_json::Error> for ContractError {
+    fn from(err: serde_json::Error) -> Self {
+        ContractError::Serialization(err.to_string())
+    }
+}
+
+impl From<Box<dyn std::error::Error + Send + Sync>> for ContractError {
+    fn from(err: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        ContractError::Internal(err.to_string())
+    }
+}
+
+/// A contract error as it is seen outside the contract, i.e. by the node,
+/// the RPC and the wallet.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ContractRevert {
+    pub status: u16,
+    pub kind: String,
+    pub msg: String,
+    pub data: Option<Vec<u8>>,
+}
+
+impl std::fmt::Display for ContractRevert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Contract reverted, status: {}, kind: {}, msg: {}",
+            self.status, self.kind, self.msg
+        )
+    }
+}
+
+impl std::error::Error for ContractRevert {}
+
+pub fn make_result_vec(result: Result<Vec<u8>, ContractError>) -> Vec<u8> {
+    match result {
+        Ok(data) => [vec![RESULT_STATUS_OK], data].concat(),
+        Err(err) => make_error_vec(err),
+    }
+}
+
+pub fn make_error_vec(err: ContractError) -> Vec<u8> {
+    let revert = err.into_revert();
+
+    // serializing a struct of plain fields does not fail
+    let v = serde_json::to_vec(&revert).unwrap_or_default();
+
+    [vec![RESULT_STATUS_ERR], v].concat()
+}
+
+pub fn parse_result_vec(result: &[u8]) -> Result<InvokeResult, ContractRevert> {
+    match result.split_first() {
+        Some((&RESULT_STATUS_OK, data)) => Ok(data.to_vec()),
+        Some((&RESULT_STATUS_ERR, data)) => match serde_json::from_slice(data) {
+            Ok(revert) => Err(revert),
+            Err(err) => Err(ContractError::Serialization(format!(
+                "Cannot parse contract error, err: {}",
+                err
+            ))
+            .into_revert()),
+        },
+        _ => Err(ContractError::Internal(format!(
+            "Invalid contract result envelope, len: {}",
+            result.len()
+        ))
+        .into_revert()),
+    }
 }
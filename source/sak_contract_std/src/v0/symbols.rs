@@ -21,6 +21,12 @@ pub const HOST__GET_MRS_DATA: &str = "HOST__get_mrs_data";
 #[allow(non_upper_case_globals)]
 pub const HOST__GET_CTR_STATE: &str = "HOST__get_ctr_state";
 
+#[allow(non_upper_case_globals)]
+pub const HOST__GET_MRS_DATA_RANGE: &str = "HOST__get_mrs_data_range";
+
+#[allow(non_upper_case_globals)]
+pub const HOST__GET_CTR_STATE_RANGE: &str = "HOST__get_ctr_state_range";
+
 #[allow(non_upper_case_globals)]
 pub const HOST__GET_LATEST_RETURN_LEN: &str = "HOST__get_latest_return_len";
 
@@ -0,0 +1,49 @@
+use super::MockHost;
+use crate::{Dict, HostStorage, List};
+
+#[test]
+fn test_list_range_returns_elements_in_index_order() {
+    let mut list: List<Vec<u8>> = List::new("list".to_string(), HostStorage::CtrState);
+
+    // More elements than a page, and enough of them for unpadded indices to
+    // sort out of order, e.g. "10" before "2"
+    list.init((0..150u8).map(|i| vec![i]));
+    MockHost::put_receipt(list.receipt());
+
+    MockHost::put("list_other", b"x");
+    MockHost::put("lisu_0", b"y");
+
+    let elems = list.range(2, 12).unwrap();
+    assert_eq!(elems, (2..12u8).map(|i| vec![i]).collect::<Vec<_>>());
+
+    let elems = list.range(0, 150).unwrap();
+    assert_eq!(elems, (0..150u8).map(|i| vec![i]).collect::<Vec<_>>());
+
+    // The end is exclusive and past the last element the range is short
+    assert_eq!(list.range(148, 200).unwrap(), vec![vec![148], vec![149]]);
+    assert!(list.range(5, 5).unwrap().is_empty());
+    assert!(list.range(200, 300).unwrap().is_empty());
+}
+
+#[test]
+fn test_dict_iter_from_pages_through_the_entries() {
+    let dict: Dict<Vec<u8>> = Dict::new("dict".to_string(), HostStorage::MRS);
+
+    for i in 0..100 {
+        MockHost::put(&format!("dict_key_{:03}", i), &[i as u8]);
+    }
+
+    MockHost::put("dicu_key_000", b"x");
+
+    let entries: Vec<(String, Vec<u8>)> = dict
+        .iter_from(&"key_040".to_string())
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(entries.len(), 60);
+    assert_eq!(entries[0], ("key_040".to_string(), vec![40]));
+    assert_eq!(entries[59], ("key_099".to_string(), vec![99]));
+    assert!(entries.windows(2).all(|w| w[0].0 < w[1].0));
+
+    assert!(dict.iter_from(&"key_100".to_string()).next().is_none());
+}
@@ -0,0 +1,4 @@
+mod data;
+mod utils;
+
+pub(crate) use utils::*;
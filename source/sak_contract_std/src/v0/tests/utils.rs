@@ -0,0 +1,50 @@
+use crate::ContractError;
+use sak_store_interface::{RangeRequest, RangeResponse};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+thread_local! {
+    static MOCK_STORE: RefCell<BTreeMap<String, Vec<u8>>> = RefCell::new(BTreeMap::new());
+}
+
+/// Stands in for the host functions, with a store of its own per test
+/// thread. Ranges are scanned the way the ledger and the MRS scan them.
+pub(crate) struct MockHost;
+
+impl MockHost {
+    pub fn put(key: &str, value: &[u8]) {
+        MOCK_STORE.with(|s| s.borrow_mut().insert(key.to_string(), value.to_vec()));
+    }
+
+    pub fn put_receipt(receipt: impl IntoIterator<Item = (String, Vec<u8>)>) {
+        for (k, v) in receipt {
+            Self::put(&k, &v);
+        }
+    }
+
+    pub fn get_range(range: &RangeRequest) -> Result<RangeResponse, ContractError> {
+        MOCK_STORE.with(|s| {
+            let store = s.borrow();
+            let mut res = RangeResponse::default();
+
+            for (key, value) in store.range(range.seek_key().to_string()..) {
+                if range.is_past_end(key) {
+                    break;
+                }
+
+                if range.is_before_start(key) {
+                    continue;
+                }
+
+                if res.items.len() >= range.limit {
+                    res.cursor = res.items.last().map(|(k, _)| k.to_string());
+                    break;
+                }
+
+                res.items.push((key.to_string(), value.to_vec()));
+            }
+
+            Ok(res)
+        })
+    }
+}
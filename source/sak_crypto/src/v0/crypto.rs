@@ -4,7 +4,7 @@ use k256::{
         signature::{Signer, Verifier},
         Signature, SigningKey, VerifyingKey,
     },
-    elliptic_curve::ecdh::SharedSecret,
+    elliptic_curve::{ecdh::SharedSecret, sec1::ToEncodedPoint},
     EncodedPoint, Secp256k1,
 };
 use sha3::{Digest, Keccak256, Sha3_256};
@@ -112,6 +112,27 @@ pub fn verify(verifying_key: VerifyingKey, data: &[u8], sig: &Signature) -> Resu
     }
 }
 
+pub fn verify_with_public_key_str(
+    public_key_str: &String,
+    data: &[u8],
+    sig: &Signature,
+) -> Result<(), String> {
+    let public_key = convert_public_key_str_into_public_key(public_key_str)?;
+
+    let verifying_key = match VerifyingKey::from_encoded_point(&public_key.to_encoded_point(false))
+    {
+        Ok(v) => v,
+        Err(err) => {
+            return Err(format!(
+                "Cannot create VerifyingKey from public key, err: {}",
+                err
+            ));
+        }
+    };
+
+    verify(verifying_key, data, sig)
+}
+
 pub fn compute_hash(values: &[impl AsRef<[u8]>]) -> String {
     let mut hasher = Sha3_256::new();
 
@@ -42,6 +42,20 @@ pub fn derive_aes_key(
     Ok(aes_key)
 }
 
+/// HKDF-SHA256 over `ikm`, with `info` separating the keys derived from the
+/// same input.
+pub fn derive_key(ikm: &[u8], salt: &[u8], info: &[u8]) -> Result<[u8; 32], CryptoError> {
+    let h = Hkdf::<Sha256>::new(Some(salt), ikm);
+    let mut out = [0u8; 32];
+
+    match h.expand(info, &mut out) {
+        Ok(_) => (),
+        Err(err) => return Err(format!("Could not derive key, err: {}", err).into()),
+    };
+
+    Ok(out)
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct AesParams {
     pub key: String,
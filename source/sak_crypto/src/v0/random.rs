@@ -1,6 +1,67 @@
 use rand::prelude::*;
+use std::sync::{Arc, Mutex};
 
 pub fn rand() -> usize {
     let mut rng = rand::thread_rng();
     rng.gen::<usize>() % 1000000
 }
+
+pub fn rand_bytes_32() -> [u8; 32] {
+    let mut rng = rand::thread_rng();
+    let mut buf = [0u8; 32];
+    rng.fill(&mut buf);
+
+    buf
+}
+
+pub fn shuffle<T>(items: &mut [T]) {
+    let mut rng = rand::thread_rng();
+    items.shuffle(&mut rng);
+}
+
+/// Source of randomness of a node's routines that pick peers. It is
+/// injected, like the clock, so that a simulator can replay a run.
+pub trait RandomSource: Send + Sync {
+    fn next_u64(&self) -> u64;
+}
+
+/// Backed by the thread local rng of `rand`.
+pub struct ThreadRandomSource;
+
+impl RandomSource for ThreadRandomSource {
+    fn next_u64(&self) -> u64 {
+        rand::thread_rng().gen()
+    }
+}
+
+pub fn thread_random_source() -> Arc<dyn RandomSource> {
+    Arc::new(ThreadRandomSource)
+}
+
+/// splitmix64 seeded by the caller, which yields the same numbers for the
+/// same seed.
+pub struct SeededRandomSource {
+    state: Mutex<u64>,
+}
+
+impl SeededRandomSource {
+    pub fn new(seed: u64) -> SeededRandomSource {
+        SeededRandomSource {
+            state: Mutex::new(seed),
+        }
+    }
+}
+
+impl RandomSource for SeededRandomSource {
+    fn next_u64(&self) -> u64 {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+
+        z ^ (z >> 31)
+    }
+}
@@ -63,3 +63,51 @@ where
         }
     }
 }
+
+/// Flushes the memtables of every column family of `db` to disk, so that
+/// nothing is left only in the write ahead log when the db is closed.
+pub fn flush_db(db: &DB) -> Result<(), KVDBError> {
+    let cf_names = DB::list_cf(&Options::default(), db.path())?;
+
+    for cf_name in cf_names {
+        if let Some(cf) = db.cf_handle(&cf_name) {
+            db.flush_cf(&cf)?;
+        }
+    }
+
+    db.flush()?;
+
+    Ok(())
+}
+
+/// Approximate size and key count of a db, summed over its column families.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DBStats {
+    // Bytes in sst files and memtables
+    pub size_bytes: u64,
+    pub key_count: u64,
+}
+
+/// Estimates made by the db itself, hence cheap but not exact.
+pub fn get_db_stats(db: &DB) -> Result<DBStats, KVDBError> {
+    let cf_names = DB::list_cf(&Options::default(), db.path())?;
+
+    let mut stats = DBStats::default();
+
+    for cf_name in cf_names {
+        if let Some(cf) = db.cf_handle(&cf_name) {
+            for prop in [
+                "rocksdb.total-sst-files-size",
+                "rocksdb.cur-size-all-mem-tables",
+            ] {
+                stats.size_bytes += db.property_int_value_cf(&cf, prop)?.unwrap_or(0);
+            }
+
+            stats.key_count += db
+                .property_int_value_cf(&cf, "rocksdb.estimate-num-keys")?
+                .unwrap_or(0);
+        }
+    }
+
+    Ok(stats)
+}
@@ -65,41 +65,54 @@ impl SakLedger {
     }
 
     pub async fn send_tx(&self, tx_candidate: TxCandidate) -> Result<TxHash, LedgerError> {
-        let tx_hash = match tx_candidate.clone() {
-            TxCandidate::Mint(_) => self.sync_pool.insert_tx(tx_candidate).await?,
-            TxCandidate::Pour(tc) => {
-                let mut is_valid_sn = true;
-                let mut is_valid_merkle_rt = true;
-
-                for sn in &tc.sns {
-                    is_valid_sn = self.verify_sn(&sn)?;
-                    if !is_valid_sn {
-                        break;
-                    }
-                }
+        self.validate_tx_candidate(&tx_candidate)?;
 
-                for merkle_rt in &tc.merkle_rts {
-                    is_valid_merkle_rt = self.verify_merkle_rt(merkle_rt);
-                    if !is_valid_merkle_rt {
-                        break;
-                    }
-                }
+        let tx_hash = self.sync_pool.insert_tx(tx_candidate).await?;
 
-                let is_valid_tx = self.verify_proof(&tc)?;
+        Ok(tx_hash)
+    }
 
-                if is_valid_merkle_rt & is_valid_sn & is_valid_tx {
-                    self.sync_pool.insert_tx(tx_candidate).await?
-                } else {
-                    return Err(format!(
-                        "Is valid sn: {}, merkle_rt: {}, verified tx:{} ",
-                        is_valid_sn, is_valid_merkle_rt, is_valid_tx,
-                    )
-                    .into());
-                }
-            }
+    /// Checks a tx before it gets in the pool, whether sent by a client or
+    /// relayed by a peer.
+    pub(crate) fn validate_tx_candidate(
+        &self,
+        tx_candidate: &TxCandidate,
+    ) -> Result<(), LedgerError> {
+        self.validate_ctr_deploy(tx_candidate)?;
+
+        let tc = match tx_candidate {
+            TxCandidate::Mint(_) => return Ok(()),
+            TxCandidate::Pour(tc) => tc,
         };
 
-        Ok(tx_hash)
+        let mut is_valid_sn = true;
+        let mut is_valid_merkle_rt = true;
+
+        for sn in &tc.sns {
+            is_valid_sn = self.verify_sn(&sn)?;
+            if !is_valid_sn {
+                break;
+            }
+        }
+
+        for merkle_rt in &tc.merkle_rts {
+            is_valid_merkle_rt = self.verify_merkle_rt(merkle_rt);
+            if !is_valid_merkle_rt {
+                break;
+            }
+        }
+
+        let is_valid_tx = self.verify_proof(&tc)?;
+
+        if !(is_valid_merkle_rt & is_valid_sn & is_valid_tx) {
+            return Err(format!(
+                "Is valid sn: {}, merkle_rt: {}, verified tx:{} ",
+                is_valid_sn, is_valid_merkle_rt, is_valid_tx,
+            )
+            .into());
+        }
+
+        Ok(())
     }
 
     pub async fn get_tx(&self, tx_hash: &String) -> Result<Option<Tx>, LedgerError> {
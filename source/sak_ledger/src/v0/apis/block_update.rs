@@ -1,16 +1,18 @@
 use crate::{CtrStateUpdate, LedgerCols, LedgerError, MerkleUpdate, SakLedger};
 use colored::Colorize;
-use sak_contract_std::{ContractFn, CtrCallType, CtrRequest, ERROR_PLACEHOLDER};
+use sak_contract_std::{ContractFn, ContractRevert, CtrCallType, CtrRequest};
 use sak_crypto::hasher::MiMC;
 use sak_crypto::{Bls12, MerkleTree, Proof, ScalarExt};
 use sak_ledger_cfg::CM_TREE_DEPTH;
 use sak_ledger_testing::DUMMY_SN;
 use sak_logger::{debug, info, warn};
 use sak_proof::CoinProof;
+use sak_store_interface::make_ctr_key;
 use sak_types::{
-    Block, BlockCandidate, CmIdx, MintTxCandidate, PourTxCandidate, Sn, Tx, TxCandidate, TxCtrOp,
-    TxHash,
+    Block, BlockCandidate, BlockHeight, CmIdx, MintTxCandidate, PourTxCandidate, Sn, Tx,
+    TxCandidate, TxCtrOp, TxHash,
 };
+use std::time::{Duration, Instant};
 
 impl SakLedger {
     pub async fn insert_genesis_block(
@@ -46,6 +48,8 @@ impl SakLedger {
         &self,
         bc: Option<BlockCandidate>,
     ) -> Result<Option<String>, LedgerError> {
+        let started_at = Instant::now();
+
         let mut bc = match bc {
             Some(bc) => bc,
             None => match self.make_block_candidate().await? {
@@ -157,6 +161,8 @@ impl SakLedger {
             warn!("Error inserting block into the sync pool, err: {}", err);
         }
 
+        self.record_block_written(block.block_height, txs.len(), started_at.elapsed());
+
         debug!(
             "Success writing block, hash: {}, block_height: {}",
             block_hash.green(),
@@ -168,13 +174,31 @@ impl SakLedger {
 
     pub async fn write_blocks(
         &self,
-        mut blocks: Vec<(Block, Vec<Tx>)>,
+        blocks: Vec<(Block, Vec<Tx>)>,
+    ) -> Result<Vec<String>, LedgerError> {
+        let blocks = blocks
+            .into_iter()
+            .map(|(block, txs)| {
+                let tx_candidates = txs.into_iter().map(|tx| tx.downgrade()).collect();
+
+                (block, tx_candidates)
+            })
+            .collect();
+
+        self.write_blocks_from_candidates(blocks).await
+    }
+
+    /// Writes blocks whose txs are given as candidates, e.g. the ones
+    /// rebuilt out of the tx pool.
+    pub async fn write_blocks_from_candidates(
+        &self,
+        mut blocks: Vec<(Block, Vec<TxCandidate>)>,
     ) -> Result<Vec<String>, LedgerError> {
         let mut block_hashes = vec![];
 
         blocks.sort_by(|a, b| a.0.block_height.cmp(&b.0.block_height));
 
-        for (block, txs) in blocks {
+        for (block, tx_candidates) in blocks {
             let latest_block_height = self.get_latest_block_height()?.unwrap_or(0);
 
             if block.block_height != (latest_block_height + 1) {
@@ -186,8 +210,6 @@ impl SakLedger {
                 continue;
             }
 
-            let tx_candidates = txs.into_iter().map(|tx| tx.downgrade()).collect();
-
             let bc_candidate = BlockCandidate {
                 validator_sig: block.validator_sig,
                 tx_candidates,
@@ -237,6 +259,23 @@ impl SakLedger {
     }
 
     pub(crate) fn verify_proof(&self, tc: &PourTxCandidate) -> Result<bool, LedgerError> {
+        let metrics = &sak_metrics::metrics().ledger;
+
+        let started_at = Instant::now();
+        let res = self.check_proof(tc);
+
+        metrics
+            .proof_verify_seconds
+            .observe_duration(started_at.elapsed());
+
+        if res.is_err() {
+            metrics.proof_verify_failures.inc();
+        }
+
+        res
+    }
+
+    fn check_proof(&self, tc: &PourTxCandidate) -> Result<bool, LedgerError> {
         let hasher = MiMC::new();
 
         let mut public_inputs = vec![];
@@ -275,6 +314,29 @@ impl SakLedger {
         Ok(verification_result)
     }
 
+    fn record_block_written(&self, block_height: BlockHeight, tx_count: usize, took: Duration) {
+        let metrics = &sak_metrics::metrics().ledger;
+
+        metrics.blocks_written.inc();
+        metrics.txs_written.inc_by(tx_count as u64);
+        metrics.latest_block_height.set(block_height as f64);
+        metrics.block_tx_count.observe(tx_count as f64);
+        metrics.block_write_seconds.observe_duration(took);
+
+        let now = self.clock.now();
+
+        let mut last_block_written_at = match self.last_block_written_at.lock() {
+            Ok(l) => l,
+            Err(err) => err.into_inner(),
+        };
+
+        if let Some(prev) = last_block_written_at.replace(now) {
+            metrics
+                .block_interval_seconds
+                .observe_duration(now.saturating_duration_since(prev));
+        }
+    }
+
     pub(crate) fn filter_tx_candidates(&self, bc: &mut BlockCandidate) -> Result<(), LedgerError> {
         bc.tx_candidates.retain(|tx_candidate| match tx_candidate {
             TxCandidate::Mint(_tc) => {
@@ -324,9 +386,21 @@ impl SakLedger {
     ) -> Result<(), LedgerError> {
         match tx_ctr_op {
             TxCtrOp::ContractDeploy => {
-                let receipt = self
-                    .contract_processor
-                    .invoke(ctr_addr, &data, ContractFn::Init)?;
+                if let Err(err) = self.contract_processor.validate_wasm(data) {
+                    warn!(
+                        "Contract is not deployed, ctr_addr: {}, err: {}",
+                        ctr_addr, err
+                    );
+
+                    return Ok(());
+                }
+
+                let receipt = self.contract_processor.invoke(
+                    ctr_addr,
+                    &data,
+                    ContractFn::Init,
+                    self.ledger_db.clone(),
+                )?;
 
                 let updated_ctr_state = receipt
                     .updated_ctr_state
@@ -335,7 +409,7 @@ impl SakLedger {
                 for entry in updated_ctr_state {
                     let (field, value) = entry.to_owned();
 
-                    let key = format!("{}_{}", ctr_addr, field);
+                    let key = make_ctr_key(ctr_addr, &field);
 
                     ctr_state_update.insert(key.clone(), value.clone());
 
@@ -373,25 +447,29 @@ impl SakLedger {
                                 // receipt.updated_storage.ok_or("State needs to be updated")?
                                 vec![]
                             }
-                            None => {
-                                self.execute_ctr(
+                            None => match self
+                                .execute_ctr(
                                     // ctr_addr,
                                     req,
                                 )
-                                .await?
-                            }
+                                .await
+                            {
+                                Ok(s) => s,
+                                Err(err) => match err.downcast_ref::<ContractRevert>() {
+                                    Some(revert) => {
+                                        warn!(
+                                            "Contract call has reverted, ctr_addr: {}, {}",
+                                            ctr_addr, revert
+                                        );
+
+                                        return Ok(());
+                                    }
+                                    None => return Err(err),
+                                },
+                            },
                         };
 
-                        let maybe_error_placehorder = match &new_state.get(0..6) {
-                            Some(ep) => ep.to_owned(),
-                            None => {
-                                return Err("new_state should be bigger than 6-byte".into());
-                            }
-                        };
-
-                        if maybe_error_placehorder != ERROR_PLACEHOLDER {
-                            ctr_state_update.insert(ctr_addr.clone(), new_state.clone());
-                        }
+                        ctr_state_update.insert(ctr_addr.clone(), new_state.clone());
                     }
                 };
             }
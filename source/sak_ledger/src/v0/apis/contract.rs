@@ -2,9 +2,20 @@ use crate::LedgerError;
 use crate::SakLedger;
 use sak_contract_std::ContractFn;
 use sak_contract_std::CtrRequest;
+use sak_types::{TxCandidate, TxCtrOp};
+use sak_vm_interface::InvokeReceipt;
 
 impl SakLedger {
     pub async fn execute_ctr(&self, req: CtrRequest) -> Result<Vec<u8>, LedgerError> {
+        let receipt = self.execute_ctr_with_receipt(req).await?;
+
+        Ok(receipt.result)
+    }
+
+    pub async fn execute_ctr_with_receipt(
+        &self,
+        req: CtrRequest,
+    ) -> Result<InvokeReceipt, LedgerError> {
         let ctr_wasm = self
             .ledger_db
             .get_ctr_data_by_ctr_addr(&req.ctr_addr)
@@ -15,13 +26,11 @@ impl SakLedger {
 
         let ctr_fn = ContractFn::Execute(req);
 
-        let receipt = self
-            .contract_processor
-            .invoke(&ctr_addr, &ctr_wasm, ctr_fn)?;
-
-        let result = receipt.result;
+        let receipt =
+            self.contract_processor
+                .invoke(&ctr_addr, &ctr_wasm, ctr_fn, self.ledger_db.clone())?;
 
-        Ok(result)
+        Ok(receipt)
     }
 
     pub async fn update_ctr(&self, req: CtrRequest) -> Result<Vec<u8>, LedgerError> {
@@ -35,9 +44,9 @@ impl SakLedger {
 
         let ctr_fn = ContractFn::Execute(req);
 
-        let receipt = self
-            .contract_processor
-            .invoke(&ctr_addr, &ctr_wasm, ctr_fn)?;
+        let receipt =
+            self.contract_processor
+                .invoke(&ctr_addr, &ctr_wasm, ctr_fn, self.ledger_db.clone())?;
 
         let _ctr_state_receipt = receipt
             .updated_ctr_state
@@ -50,4 +59,14 @@ impl SakLedger {
         // Ok(state)
         Ok(vec![222])
     }
+
+    /// A contract deploying tx is accepted only if every node would execute
+    /// the contract the same way.
+    pub(crate) fn validate_ctr_deploy(&self, tc: &TxCandidate) -> Result<(), LedgerError> {
+        if let TxCtrOp::ContractDeploy = tc.get_ctr_op() {
+            self.contract_processor.validate_wasm(tc.get_data())?;
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,98 @@
+use crate::{CtrStateUpdate, LedgerError, MerkleUpdate, SakLedger};
+use colored::Colorize;
+use sak_ledger_cfg::CM_TREE_DEPTH;
+use sak_logger::{debug, warn};
+use sak_types::{Block, Cm, MerkleRt};
+
+impl SakLedger {
+    /// Writes blocks without their txs, as a light node keeps them. Blocks
+    /// not following the latest one are skipped. Txs of the pool found in a
+    /// written block are removed from the pool.
+    pub async fn write_block_headers(
+        &self,
+        mut blocks: Vec<Block>,
+    ) -> Result<Vec<String>, LedgerError> {
+        let mut block_hashes = vec![];
+
+        blocks.sort_by(|a, b| a.block_height.cmp(&b.block_height));
+
+        for block in blocks {
+            if self.get_block(block.get_block_hash())?.is_some() {
+                continue;
+            }
+
+            // A block is written only if it has got a tx
+            if block.tx_hashes.is_empty() {
+                return Err(format!(
+                    "Block header has no tx, block_hash: {}",
+                    block.get_block_hash(),
+                )
+                .into());
+            }
+
+            let latest_block_height = self.get_latest_block_height()?.unwrap_or(0);
+
+            if block.block_height != latest_block_height + 1 {
+                warn!(
+                    "received not continuous block header height, \
+                    block_height: {}, received: {}",
+                    latest_block_height, block.block_height,
+                );
+
+                continue;
+            }
+
+            let block_hash = self
+                .ledger_db
+                .put_block(
+                    &block,
+                    &vec![],
+                    &CtrStateUpdate::new(),
+                    &MerkleUpdate::new(),
+                )
+                .await?;
+
+            let (written_tcs, _) = self.sync_pool.find_txs(&block.tx_hashes).await;
+
+            if let Err(err) = self.sync_pool.remove_tcs(&written_tcs).await {
+                warn!("Error removing txs from the tx pool, err: {}", err);
+            }
+
+            debug!(
+                "Success writing block header, hash: {}, block_height: {}",
+                block_hash.green(),
+                block.block_height,
+            );
+
+            block_hashes.push(block_hash);
+        }
+
+        Ok(block_hashes)
+    }
+
+    /// Whether `auth_path`, as `get_auth_path()` makes it, leads from `cm`
+    /// to `merkle_rt`.
+    pub fn verify_auth_path(
+        &self,
+        cm: &Cm,
+        auth_path: &[(Cm, bool)],
+        merkle_rt: &MerkleRt,
+    ) -> Result<bool, LedgerError> {
+        if auth_path.len() != CM_TREE_DEPTH as usize {
+            return Ok(false);
+        }
+
+        let mut curr_node = *cm;
+
+        for (sibling_node, direction) in auth_path {
+            let (lv, rv) = match direction {
+                true => (sibling_node, &curr_node),
+                false => (&curr_node, sibling_node),
+            };
+
+            curr_node = self.hasher.mimc(lv, rv)?.to_bytes();
+        }
+
+        Ok(&curr_node == merkle_rt)
+    }
+}
@@ -1,4 +1,5 @@
 mod block;
 mod block_update;
 mod contract;
+mod light;
 mod pool;
@@ -1,22 +1,46 @@
 use crate::{LedgerError, SakLedger};
 use sak_logger::warn;
-use sak_types::{BlockCandidate, TxCandidate};
+use sak_types::{BlockCandidate, TxCandidate, TxHash};
 
 impl SakLedger {
-    pub async fn insert_into_pool(&self, tx_candidates: Vec<TxCandidate>) {
+    /// Returns the number of txs rejected as invalid, e.g. a pour tx with a
+    /// bad proof. Txs already in the pool are not counted.
+    pub async fn insert_into_pool(&self, tx_candidates: Vec<TxCandidate>) -> usize {
+        let mut invalid_count = 0;
+
         for tx in tx_candidates.into_iter() {
             println!("insert into pool, tx: {}", tx.get_tx_hash());
 
+            // Its proof is not verified again each time a peer relays it
+            if self.sync_pool.contains_tx(tx.get_tx_hash()).await {
+                continue;
+            }
+
+            if let Err(err) = self.validate_tx_candidate(&tx) {
+                warn!("Tx pool insertion aborted, reason: {}", err);
+
+                invalid_count += 1;
+
+                continue;
+            }
+
             if let Err(err) = self.sync_pool.insert_tx(tx).await {
                 warn!("Tx pool insertion aborted, reason: {}", err);
             };
         }
+
+        invalid_count
     }
 
     pub async fn tx_pool_contains(&self, tx_hash: &String) -> bool {
         self.sync_pool.contains_tx(tx_hash).await
     }
 
+    /// Number of txs waiting in the pool to be put in a block.
+    pub async fn get_tx_pool_size(&self) -> usize {
+        self.sync_pool.count_txs().await
+    }
+
     pub async fn get_tx_pool_diff(&self, tx_hashes: Vec<String>) -> Vec<String> {
         self.sync_pool.get_tx_pool_diff(tx_hashes).await
     }
@@ -25,6 +49,21 @@ impl SakLedger {
         self.sync_pool.get_txs(tx_hashes).await
     }
 
+    /// Looks up the txs of a block in the pool. Returns the hashes of the
+    /// txs missing in the pool if there is any.
+    pub async fn get_block_txs_from_pool(
+        &self,
+        tx_hashes: &[TxHash],
+    ) -> Result<Vec<TxCandidate>, Vec<TxHash>> {
+        let (tx_candidates, missing) = self.sync_pool.find_txs(tx_hashes).await;
+
+        if missing.is_empty() {
+            Ok(tx_candidates)
+        } else {
+            Err(missing)
+        }
+    }
+
     pub(crate) async fn make_block_candidate(&self) -> Result<Option<BlockCandidate>, LedgerError> {
         let tx_candidates = self.sync_pool.get_all_txs().await?;
 
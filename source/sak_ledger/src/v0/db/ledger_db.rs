@@ -1,7 +1,7 @@
 use crate::LedgerError;
 use crate::{col_labels, LedgerCols};
 use sak_kv_db::{
-    BoundColumnFamily, ColumnFamilyDescriptor, DBIteratorWithThreadMode, DBWithThreadMode,
+    BoundColumnFamily, ColumnFamilyDescriptor, DBIteratorWithThreadMode, DBStats, DBWithThreadMode,
     IteratorMode, KeyValueDatabase, MultiThreaded, Options, WriteBatch, DB,
 };
 use sak_types::{BlockHash, Cm, MerkleRt, Sn, TxCtrOp, TxHash, TxType};
@@ -93,6 +93,14 @@ impl LedgerDB {
         Ok(database)
     }
 
+    pub(crate) fn flush(&self) -> Result<(), LedgerError> {
+        sak_kv_db::flush_db(&self.db)
+    }
+
+    pub(crate) fn get_stats(&self) -> Result<DBStats, LedgerError> {
+        sak_kv_db::get_db_stats(&self.db)
+    }
+
     pub(crate) fn make_cf_descriptors() -> Vec<ColumnFamilyDescriptor> {
         vec![
             ColumnFamilyDescriptor::new(col_labels::TX_HASH_BY_CTR_ADDR, Options::default()),
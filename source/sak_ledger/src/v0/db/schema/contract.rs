@@ -1,5 +1,7 @@
 use crate::LedgerDB;
 use crate::{LedgerCols, LedgerError};
+use sak_kv_db::{Direction, IteratorMode};
+use sak_store_interface::{LedgerInterface, RangeRequest, RangeResponse, StoreInterfaceError};
 use sak_types::TxHash;
 
 impl LedgerDB {
@@ -17,4 +19,55 @@ impl LedgerDB {
 
         Ok(Some(ctr_data))
     }
+
+    pub fn get_ctr_state(&self, key: &String) -> Result<Option<Vec<u8>>, LedgerError> {
+        self.get(LedgerCols::CtrState, key.as_bytes())
+    }
+
+    pub fn get_ctr_state_range(&self, range: &RangeRequest) -> Result<RangeResponse, LedgerError> {
+        let cf = self.make_cf_handle(&self.db, LedgerCols::CtrState.as_str())?;
+
+        let iter = self.db.iterator_cf(
+            &cf,
+            IteratorMode::From(range.seek_key().as_bytes(), Direction::Forward),
+        );
+
+        let mut res = RangeResponse::default();
+
+        for (k, v) in iter {
+            let key = String::from_utf8(k.to_vec())?;
+
+            if range.is_past_end(&key) {
+                break;
+            }
+
+            if range.is_before_start(&key) {
+                continue;
+            }
+
+            if res.items.len() >= range.limit {
+                res.cursor = res.items.last().map(|(k, _)| k.to_string());
+                break;
+            }
+
+            let value: Vec<u8> = serde_json::from_slice(&v)?;
+
+            res.items.push((key, value));
+        }
+
+        Ok(res)
+    }
+}
+
+impl LedgerInterface for LedgerDB {
+    fn get_ctr_state(&self, key: &String) -> Result<Option<Vec<u8>>, StoreInterfaceError> {
+        LedgerDB::get_ctr_state(self, key)
+    }
+
+    fn get_ctr_state_range(
+        &self,
+        range: &RangeRequest,
+    ) -> Result<RangeResponse, StoreInterfaceError> {
+        LedgerDB::get_ctr_state_range(self, range)
+    }
 }
@@ -17,9 +17,10 @@ impl LedgerDB {
     }
 
     pub async fn get_tx(&self, tx_hash: &String) -> Result<Option<Tx>, LedgerError> {
-        let tx_type = self
-            .get(LedgerCols::TxType, tx_hash.as_bytes())?
-            .ok_or(format!("Tx type does not exist, tx_hash: {}", tx_hash))?;
+        let tx_type = match self.get(LedgerCols::TxType, tx_hash.as_bytes())? {
+            Some(t) => t,
+            None => return Ok(None),
+        };
 
         let tx = match tx_type {
             TxType::Mint => self.get_mint_tx(tx_hash),
@@ -5,25 +5,31 @@ use crate::LedgerError;
 use crate::SyncPool;
 use sak_crypto::hasher::MiMC;
 use sak_crypto::MerkleTree;
+use sak_kv_db::DBStats;
 use sak_ledger_cfg::CM_TREE_DEPTH;
 use sak_logger::info;
 use sak_types::BlockCandidate;
+use sak_utils_time::Clock;
 use sak_vm_interface::ContractProcess;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tokio::sync::broadcast;
 use tokio::sync::broadcast::Sender;
+use tokio::time::Instant;
 
 const BLOCKCHAIN_EVENT_QUEUE_CAPACITY: usize = 32;
 
 pub struct SakLedger {
     pub ledger_event_tx: Arc<Sender<DistLedgerEvent>>,
-    pub ledger_db: LedgerDB,
+    pub ledger_db: Arc<LedgerDB>,
     pub sync_pool: Arc<SyncPool>,
     pub merkle_tree: MerkleTree,
     pub hasher: MiMC,
     pub consensus: Box<dyn Consensus + Send + Sync>,
     pub contract_processor: Box<dyn ContractProcess + Send + Sync>,
+    pub clock: Arc<dyn Clock>,
+    // Read on the next block written to tell the block time
+    pub(crate) last_block_written_at: Mutex<Option<Instant>>,
 }
 
 pub struct SakLedgerArgs {
@@ -33,6 +39,8 @@ pub struct SakLedgerArgs {
     pub block_sync_interval: Option<u64>,
     pub ledger_path: PathBuf,
     pub contract_processor: Box<dyn ContractProcess + Send + Sync>,
+    // Wall clock if not given
+    pub clock: Option<Arc<dyn Clock>>,
 }
 
 impl SakLedger {
@@ -44,9 +52,16 @@ impl SakLedger {
             block_sync_interval,
             ledger_path,
             contract_processor,
+            clock,
         } = ledger_args;
 
-        let ledger_db = LedgerDB::init(&ledger_path).await?;
+        let clock = clock.unwrap_or_else(sak_utils_time::system_clock);
+
+        let ledger_db = {
+            let d = LedgerDB::init(&ledger_path).await?;
+
+            Arc::new(d)
+        };
 
         let ledger_event_tx = {
             let (tx, _rx) = broadcast::channel(BLOCKCHAIN_EVENT_QUEUE_CAPACITY);
@@ -57,7 +72,7 @@ impl SakLedger {
         let sync_pool = {
             let tx = ledger_event_tx.clone();
 
-            let p = SyncPool::new(tx, tx_sync_interval, block_sync_interval);
+            let p = SyncPool::new(tx, tx_sync_interval, block_sync_interval, clock.clone());
 
             Arc::new(p)
         };
@@ -74,6 +89,8 @@ impl SakLedger {
             hasher,
             consensus,
             contract_processor,
+            clock,
+            last_block_written_at: Mutex::new(None),
         };
 
         if let Some(bc) = genesis_block {
@@ -93,4 +110,14 @@ impl SakLedger {
 
         Ok(ledger)
     }
+
+    /// Writes what the ledger db holds in memory to disk. Called before the
+    /// node exits.
+    pub fn flush(&self) -> Result<(), LedgerError> {
+        self.ledger_db.flush()
+    }
+
+    pub fn get_db_stats(&self) -> Result<DBStats, LedgerError> {
+        self.ledger_db.get_stats()
+    }
 }
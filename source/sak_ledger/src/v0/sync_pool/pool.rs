@@ -1,6 +1,7 @@
 use crate::DistLedgerEvent;
 use sak_logger::{debug, warn};
 use sak_types::{Block, BlockHash, BlockHeight, TxCandidate, TxCtrOp, TxHash};
+use sak_utils_time::Clock;
 use std::{
     collections::{HashMap, HashSet},
     sync::Arc,
@@ -19,6 +20,7 @@ pub struct SyncPool {
     ledger_event_tx: Arc<Sender<DistLedgerEvent>>,
     tx_sync_interval: Duration,
     block_sync_interval: Duration,
+    clock: Arc<dyn Clock>,
 }
 
 impl SyncPool {
@@ -26,6 +28,7 @@ impl SyncPool {
         ledger_event_tx: Arc<Sender<DistLedgerEvent>>,
         tx_sync_interval: Option<u64>,
         block_sync_interval: Option<u64>,
+        clock: Arc<dyn Clock>,
     ) -> SyncPool {
         let tx_hash_set = {
             let s = HashSet::new();
@@ -61,6 +64,7 @@ impl SyncPool {
             ledger_event_tx,
             tx_sync_interval,
             block_sync_interval,
+            clock,
         }
     }
 
@@ -95,10 +99,11 @@ impl SyncPool {
 
             let ledger_event_tx = self.ledger_event_tx.clone();
 
-            let block_interval = self.block_sync_interval;
+            // New blocks are batched until then
+            let batch_wait = self.clock.sleep(self.block_sync_interval);
 
             tokio::spawn(async move {
-                tokio::time::sleep(block_interval).await;
+                batch_wait.await;
 
                 let tx_hashes = new_blocks_set.write().await.drain().collect();
 
@@ -148,10 +153,17 @@ impl SyncPool {
             let mut tx_map_lock = self.tx_map.write().await;
 
             if tx_map_lock.contains_key(&tx_hash) {
+                sak_metrics::metrics().sync_pool.txs_rejected.inc();
+
                 return Err("tx already exist".to_string());
             } else {
                 tx_map_lock.insert(tx_hash.clone(), tc.clone());
             };
+
+            let metrics = &sak_metrics::metrics().sync_pool;
+
+            metrics.txs_inserted.inc();
+            metrics.tx_pool_size.set(tx_map_lock.len() as f64);
         }
 
         {
@@ -164,10 +176,10 @@ impl SyncPool {
 
             let ledger_event_tx = self.ledger_event_tx.clone();
 
-            let tx_interval = self.tx_sync_interval;
+            let batch_wait = self.clock.sleep(self.tx_sync_interval);
 
             tokio::spawn(async move {
-                tokio::time::sleep(tx_interval).await;
+                batch_wait.await;
 
                 let tx_hashes: Vec<String> = new_tx_hashes.write().await.drain().collect();
 
@@ -207,6 +219,11 @@ impl SyncPool {
             tx_map_lock.remove(tx.get_tx_hash());
         }
 
+        sak_metrics::metrics()
+            .sync_pool
+            .tx_pool_size
+            .set(tx_map_lock.len() as f64);
+
         Ok(())
     }
 
@@ -229,9 +246,31 @@ impl SyncPool {
         tx_pool
     }
 
+    /// Returns the txs found in the pool, in the order of `tx_hashes`, and
+    /// the hashes of the ones not found.
+    pub async fn find_txs(&self, tx_hashes: &[TxHash]) -> (Vec<TxCandidate>, Vec<TxHash>) {
+        let tx_map_lock = self.tx_map.read().await;
+
+        let mut found = vec![];
+        let mut missing = vec![];
+
+        for tx_hash in tx_hashes {
+            match tx_map_lock.get(tx_hash) {
+                Some(tx) => found.push(tx.clone()),
+                None => missing.push(tx_hash.clone()),
+            };
+        }
+
+        (found, missing)
+    }
+
     pub async fn contains_tx(&self, tx_hash: &String) -> bool {
         let tx_map_lock = self.tx_map.read().await;
 
         tx_map_lock.contains_key(tx_hash)
     }
+
+    pub async fn count_txs(&self) -> usize {
+        self.tx_map.read().await.len()
+    }
 }
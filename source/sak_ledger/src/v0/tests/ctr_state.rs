@@ -0,0 +1,119 @@
+use crate::{LedgerCols, LedgerDB};
+use sak_kv_db::WriteBatch;
+use sak_store_interface::{make_ctr_key, RangeRequest};
+
+async fn make_ledger_db(name: &str) -> LedgerDB {
+    let db_path = std::env::temp_dir()
+        .join("saksaha_test")
+        .join(name)
+        .join("ledger");
+
+    if db_path.is_dir() {
+        std::fs::remove_dir_all(&db_path).unwrap();
+    }
+
+    LedgerDB::init(&db_path).await.unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ctr_state_range_is_bounded_and_paginated() {
+    let ledger_db = make_ledger_db("test_ctr_state_range").await;
+
+    let mut batch = WriteBatch::default();
+
+    for i in 0..6 {
+        let key = format!("ctr_list_{}", i);
+
+        ledger_db
+            .put(
+                &mut batch,
+                LedgerCols::CtrState,
+                key.as_bytes(),
+                &vec![i as u8],
+            )
+            .unwrap();
+    }
+
+    ledger_db
+        .put(&mut batch, LedgerCols::CtrState, b"ctr_other_0", &vec![9u8])
+        .unwrap();
+
+    ledger_db.db.write(batch).unwrap();
+
+    let mut range = RangeRequest {
+        prefix: "ctr_list_".to_string(),
+        start: Some("ctr_list_1".to_string()),
+        end: Some("ctr_list_4".to_string()),
+        cursor: None,
+        limit: 2,
+    };
+
+    let page_1 = ledger_db.get_ctr_state_range(&range).unwrap();
+    let keys: Vec<&str> = page_1.items.iter().map(|(k, _)| k.as_str()).collect();
+    assert_eq!(keys, vec!["ctr_list_1", "ctr_list_2"]);
+    assert_eq!(page_1.cursor, Some("ctr_list_2".to_string()));
+
+    range.cursor = page_1.cursor;
+
+    // The end is exclusive
+    let page_2 = ledger_db.get_ctr_state_range(&range).unwrap();
+    assert_eq!(page_2.items, vec![("ctr_list_3".to_string(), vec![3u8])]);
+    assert_eq!(page_2.cursor, None);
+
+    // Keys of another prefix are never returned
+    range.end = None;
+    range.cursor = None;
+    range.limit = 10;
+
+    let page = ledger_db.get_ctr_state_range(&range).unwrap();
+    assert_eq!(page.items.len(), 5);
+    assert!(page.items.iter().all(|(k, _)| k.starts_with("ctr_list_")));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ctr_state_range_excludes_ctrs_sharing_the_addr_prefix() {
+    let ledger_db = make_ledger_db("test_ctr_state_range_addr_prefix").await;
+
+    // Joined by `_` alone, both would be "a_b_x"
+    let key_1 = make_ctr_key("a", "b_x");
+    let key_2 = make_ctr_key("a_b", "x");
+    assert_ne!(key_1, key_2);
+
+    let mut batch = WriteBatch::default();
+
+    ledger_db
+        .put(
+            &mut batch,
+            LedgerCols::CtrState,
+            key_1.as_bytes(),
+            &vec![1u8],
+        )
+        .unwrap();
+
+    ledger_db
+        .put(
+            &mut batch,
+            LedgerCols::CtrState,
+            key_2.as_bytes(),
+            &vec![2u8],
+        )
+        .unwrap();
+
+    ledger_db.db.write(batch).unwrap();
+
+    let range = RangeRequest {
+        limit: 10,
+        ..Default::default()
+    };
+
+    for (ctr_addr, item) in [("a", ("b_x", 1u8)), ("a_b", ("x", 2u8))] {
+        let ctr_addr = ctr_addr.to_string();
+
+        let page = ledger_db
+            .get_ctr_state_range(&range.with_namespace(&ctr_addr))
+            .unwrap()
+            .strip_namespace(&ctr_addr);
+
+        assert_eq!(page.items, vec![(item.0.to_string(), vec![item.1])]);
+    }
+}
@@ -1,4 +1,5 @@
 mod block;
+mod ctr_state;
 mod others;
 mod tx;
 mod utils;
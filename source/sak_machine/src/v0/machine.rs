@@ -3,7 +3,7 @@ use std::sync::Arc;
 use crate::MachineError;
 use sak_ledger::SakLedger;
 use sak_mrs::SakMRS;
-use sak_store_interface::MRSAccessor;
+use sak_store_interface::{MRSAccessor, MRSInterface};
 
 pub struct SakMachine {
     pub ledger: SakLedger,
@@ -24,7 +24,18 @@ impl SakMachine {
         Ok(machine)
     }
 
-    pub async fn run(&self) {}
+    pub async fn run(&self) {
+        self.mrs.run().await;
+    }
+
+    /// Flushes the ledger and the MRS dbs to disk.
+    pub fn flush(&self) -> Result<(), MachineError> {
+        self.ledger.flush()?;
+
+        self.mrs.flush()?;
+
+        Ok(())
+    }
 
     // pub async fn update_mrs(
     //     &self,
@@ -26,7 +26,10 @@ pub async fn mock_machine(block: BlockCandidate) -> SakMachine {
     };
 
     let mrs: Arc<MRSAccessor> = {
-        let mrs_args = SakMRSArgs { mrs_db_path };
+        let mrs_args = SakMRSArgs {
+            mrs_db_path,
+            session_ttl: None,
+        };
 
         let m = SakMRS::init(mrs_args).await.unwrap();
         Arc::new(Box::new(m))
@@ -44,6 +47,7 @@ pub async fn mock_machine(block: BlockCandidate) -> SakMachine {
         consensus: pos,
         ledger_path,
         contract_processor: vm,
+        clock: None,
     };
 
     let ledger = {
@@ -81,7 +85,10 @@ pub async fn mock_machine_1() -> SakMachine {
     let mrs_db_path = { test_dir.join("mrs") };
 
     let mrs: Arc<MRSAccessor> = {
-        let mrs_args = SakMRSArgs { mrs_db_path };
+        let mrs_args = SakMRSArgs {
+            mrs_db_path,
+            session_ttl: None,
+        };
 
         let m = SakMRS::init(mrs_args).await.unwrap();
         Arc::new(Box::new(m))
@@ -99,6 +106,7 @@ pub async fn mock_machine_1() -> SakMachine {
         consensus: pos,
         ledger_path,
         contract_processor: vm,
+        clock: None,
     };
 
     let ledger = {
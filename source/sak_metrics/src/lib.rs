@@ -0,0 +1,3 @@
+mod v0;
+
+pub use v0::*;
@@ -0,0 +1,292 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Buckets of the histograms timing an operation, in seconds.
+pub const LATENCY_BUCKETS: &[f64] = &[
+    0.0005, 0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Something the registry can render in the Prometheus text format.
+pub trait Metric: Send + Sync {
+    fn kind(&self) -> &'static str;
+
+    fn encode(&self, name: &str, labels: &[(&str, &str)], out: &mut String);
+}
+
+/// Value that only goes up, e.g. the number of the blocks written.
+#[derive(Default)]
+pub struct Counter {
+    value: AtomicU64,
+}
+
+impl Counter {
+    pub fn new() -> Counter {
+        Counter::default()
+    }
+
+    pub fn inc(&self) {
+        self.inc_by(1);
+    }
+
+    pub fn inc_by(&self, n: u64) {
+        self.value.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.value.load(Ordering::Relaxed)
+    }
+}
+
+impl Metric for Counter {
+    fn kind(&self) -> &'static str {
+        "counter"
+    }
+
+    fn encode(&self, name: &str, labels: &[(&str, &str)], out: &mut String) {
+        write_sample(out, name, "", labels, None, self.get() as f64);
+    }
+}
+
+/// Value that goes up and down, e.g. the size of the tx pool.
+#[derive(Default)]
+pub struct Gauge {
+    // Bits of an f64
+    value: AtomicU64,
+}
+
+impl Gauge {
+    pub fn new() -> Gauge {
+        Gauge::default()
+    }
+
+    pub fn set(&self, v: f64) {
+        self.value.store(v.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> f64 {
+        f64::from_bits(self.value.load(Ordering::Relaxed))
+    }
+}
+
+impl Metric for Gauge {
+    fn kind(&self) -> &'static str {
+        "gauge"
+    }
+
+    fn encode(&self, name: &str, labels: &[(&str, &str)], out: &mut String) {
+        write_sample(out, name, "", labels, None, self.get());
+    }
+}
+
+/// Distribution of observed values over fixed buckets, cumulative on
+/// rendering as Prometheus expects.
+pub struct Histogram {
+    bounds: Vec<f64>,
+    // One per bound, the last one counting what exceeds all of them
+    bucket_counts: Vec<AtomicU64>,
+    count: AtomicU64,
+    // Bits of an f64
+    sum: AtomicU64,
+}
+
+impl Histogram {
+    pub fn new(bounds: &[f64]) -> Histogram {
+        let bucket_counts = (0..=bounds.len()).map(|_| AtomicU64::new(0)).collect();
+
+        Histogram {
+            bounds: bounds.to_vec(),
+            bucket_counts,
+            count: AtomicU64::new(0),
+            sum: AtomicU64::new(0_f64.to_bits()),
+        }
+    }
+
+    pub fn observe(&self, v: f64) {
+        let idx = self
+            .bounds
+            .iter()
+            .position(|b| v <= *b)
+            .unwrap_or(self.bounds.len());
+
+        self.bucket_counts[idx].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+
+        let _ = self
+            .sum
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |s| {
+                Some((f64::from_bits(s) + v).to_bits())
+            });
+    }
+
+    pub fn observe_duration(&self, d: Duration) {
+        self.observe(d.as_secs_f64());
+    }
+
+    pub fn get_count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    pub fn get_sum(&self) -> f64 {
+        f64::from_bits(self.sum.load(Ordering::Relaxed))
+    }
+}
+
+impl Metric for Histogram {
+    fn kind(&self) -> &'static str {
+        "histogram"
+    }
+
+    fn encode(&self, name: &str, labels: &[(&str, &str)], out: &mut String) {
+        let mut cumulative = 0;
+
+        for (idx, bucket_count) in self.bucket_counts.iter().enumerate() {
+            cumulative += bucket_count.load(Ordering::Relaxed);
+
+            let le = match self.bounds.get(idx) {
+                Some(b) => b.to_string(),
+                None => "+Inf".to_string(),
+            };
+
+            write_sample(
+                out,
+                name,
+                "_bucket",
+                labels,
+                Some(("le", &le)),
+                cumulative as f64,
+            );
+        }
+
+        write_sample(out, name, "_sum", labels, None, self.get_sum());
+        write_sample(out, name, "_count", labels, None, self.get_count() as f64);
+    }
+}
+
+/// Metrics of one name told apart by their label values, e.g. the msgs
+/// received per msg type. Children are made on their first use.
+pub struct Family<M> {
+    kind: &'static str,
+    label_names: &'static [&'static str],
+    children: RwLock<BTreeMap<Vec<String>, Arc<M>>>,
+    make_metric: Box<dyn Fn() -> M + Send + Sync>,
+}
+
+impl<M: Metric> Family<M> {
+    pub fn new(
+        label_names: &'static [&'static str],
+        make_metric: Box<dyn Fn() -> M + Send + Sync>,
+    ) -> Family<M> {
+        Family {
+            kind: make_metric().kind(),
+            label_names,
+            children: RwLock::new(BTreeMap::new()),
+            make_metric,
+        }
+    }
+
+    /// Metric of the given label values, in the order of the label names.
+    pub fn with_labels(&self, label_values: &[&str]) -> Arc<M> {
+        let key: Vec<String> = label_values.iter().map(|v| v.to_string()).collect();
+
+        {
+            let children = match self.children.read() {
+                Ok(c) => c,
+                Err(err) => err.into_inner(),
+            };
+
+            if let Some(m) = children.get(&key) {
+                return m.clone();
+            }
+        }
+
+        let mut children = match self.children.write() {
+            Ok(c) => c,
+            Err(err) => err.into_inner(),
+        };
+
+        children
+            .entry(key)
+            .or_insert_with(|| Arc::new((self.make_metric)()))
+            .clone()
+    }
+}
+
+impl Family<Counter> {
+    pub fn new_counter(label_names: &'static [&'static str]) -> Family<Counter> {
+        Family::new(label_names, Box::new(Counter::new))
+    }
+}
+
+impl Family<Histogram> {
+    pub fn new_histogram(
+        label_names: &'static [&'static str],
+        bounds: &'static [f64],
+    ) -> Family<Histogram> {
+        Family::new(label_names, Box::new(move || Histogram::new(bounds)))
+    }
+}
+
+impl<M: Metric> Metric for Family<M> {
+    fn kind(&self) -> &'static str {
+        self.kind
+    }
+
+    fn encode(&self, name: &str, labels: &[(&str, &str)], out: &mut String) {
+        let children = match self.children.read() {
+            Ok(c) => c,
+            Err(err) => err.into_inner(),
+        };
+
+        for (label_values, metric) in children.iter() {
+            let mut child_labels = labels.to_vec();
+
+            for (label_name, label_value) in self.label_names.iter().zip(label_values) {
+                child_labels.push((*label_name, label_value.as_str()));
+            }
+
+            metric.encode(name, &child_labels, out);
+        }
+    }
+}
+
+fn write_sample(
+    out: &mut String,
+    name: &str,
+    suffix: &str,
+    labels: &[(&str, &str)],
+    extra_label: Option<(&str, &str)>,
+    value: f64,
+) {
+    let _ = write!(out, "{}{}", name, suffix);
+
+    let labels = labels.iter().chain(extra_label.iter());
+    let mut is_first = true;
+
+    for (label_name, label_value) in labels {
+        let sep = if is_first { "{" } else { "," };
+        is_first = false;
+
+        let _ = write!(
+            out,
+            "{}{}=\"{}\"",
+            sep,
+            label_name,
+            escape_label_value(label_value)
+        );
+    }
+
+    if !is_first {
+        out.push('}');
+    }
+
+    let _ = writeln!(out, " {}", value);
+}
+
+fn escape_label_value(v: &str) -> String {
+    v.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,10 @@
+mod metric;
+mod node_metrics;
+mod registry;
+
+#[cfg(test)]
+mod tests;
+
+pub use metric::*;
+pub use node_metrics::*;
+pub use registry::*;
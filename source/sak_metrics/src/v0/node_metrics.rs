@@ -0,0 +1,200 @@
+use crate::{Counter, Family, Gauge, Histogram, Registry, LATENCY_BUCKETS};
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+
+const BLOCK_TX_COUNT_BUCKETS: &[f64] = &[0.0, 1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0];
+
+const BLOCK_INTERVAL_BUCKETS: &[f64] = &[0.5, 1.0, 2.0, 5.0, 10.0, 15.0, 30.0, 60.0, 120.0, 300.0];
+
+static METRICS: Lazy<NodeMetrics> = Lazy::new(NodeMetrics::new);
+
+/// Metrics of the node, shared by the crates that feed them. There is one
+/// set per process, so nodes sharing a process (e.g. in tests) add up.
+pub fn metrics() -> &'static NodeMetrics {
+    &METRICS
+}
+
+pub struct NodeMetrics {
+    pub ledger: LedgerMetrics,
+    pub sync_pool: SyncPoolMetrics,
+    pub p2p: P2PMetrics,
+    pub vm: VMMetrics,
+    pub db: DBMetrics,
+    registry: Registry,
+}
+
+pub struct LedgerMetrics {
+    pub blocks_written: Arc<Counter>,
+    pub txs_written: Arc<Counter>,
+    pub latest_block_height: Arc<Gauge>,
+    pub block_tx_count: Arc<Histogram>,
+    // Time between two blocks written
+    pub block_interval_seconds: Arc<Histogram>,
+    pub block_write_seconds: Arc<Histogram>,
+    pub proof_verify_seconds: Arc<Histogram>,
+    pub proof_verify_failures: Arc<Counter>,
+}
+
+pub struct SyncPoolMetrics {
+    pub tx_pool_size: Arc<Gauge>,
+    pub txs_inserted: Arc<Counter>,
+    pub txs_rejected: Arc<Counter>,
+}
+
+pub struct P2PMetrics {
+    // Labeled by msg_type
+    pub msgs_received: Arc<Family<Counter>>,
+    pub msgs_sent: Arc<Family<Counter>>,
+    pub bytes_received: Arc<Family<Counter>>,
+    pub bytes_sent: Arc<Family<Counter>>,
+}
+
+pub struct VMMetrics {
+    // Labeled by ctr_fn
+    pub invoke_seconds: Arc<Family<Histogram>>,
+    pub invoke_failures: Arc<Family<Counter>>,
+}
+
+/// Sizes of the stores, which are read at scrape.
+pub struct DBMetrics {
+    pub ledger_db_size_bytes: Arc<Gauge>,
+    pub ledger_db_keys: Arc<Gauge>,
+    pub mrs_db_size_bytes: Arc<Gauge>,
+    pub mrs_records: Arc<Gauge>,
+}
+
+impl NodeMetrics {
+    fn new() -> NodeMetrics {
+        let r = Registry::new();
+
+        let ledger = LedgerMetrics {
+            blocks_written: r.register(
+                "saksaha_ledger_blocks_written_total",
+                "Blocks written to the ledger",
+                Counter::new(),
+            ),
+            txs_written: r.register(
+                "saksaha_ledger_txs_written_total",
+                "Txs written to the ledger as part of the blocks",
+                Counter::new(),
+            ),
+            latest_block_height: r.register(
+                "saksaha_ledger_latest_block_height",
+                "Height of the latest block written",
+                Gauge::new(),
+            ),
+            block_tx_count: r.register(
+                "saksaha_ledger_block_tx_count",
+                "Txs per block written",
+                Histogram::new(BLOCK_TX_COUNT_BUCKETS),
+            ),
+            block_interval_seconds: r.register(
+                "saksaha_ledger_block_interval_seconds",
+                "Time between two blocks written",
+                Histogram::new(BLOCK_INTERVAL_BUCKETS),
+            ),
+            block_write_seconds: r.register(
+                "saksaha_ledger_block_write_seconds",
+                "Time taken to verify and write a block",
+                Histogram::new(LATENCY_BUCKETS),
+            ),
+            proof_verify_seconds: r.register(
+                "saksaha_ledger_proof_verify_seconds",
+                "Time taken to verify the proof of a pour tx",
+                Histogram::new(LATENCY_BUCKETS),
+            ),
+            proof_verify_failures: r.register(
+                "saksaha_ledger_proof_verify_failures_total",
+                "Pour txs whose proof failed to verify",
+                Counter::new(),
+            ),
+        };
+
+        let sync_pool = SyncPoolMetrics {
+            tx_pool_size: r.register("saksaha_sync_pool_txs", "Txs in the tx pool", Gauge::new()),
+            txs_inserted: r.register(
+                "saksaha_sync_pool_txs_inserted_total",
+                "Txs inserted into the tx pool",
+                Counter::new(),
+            ),
+            txs_rejected: r.register(
+                "saksaha_sync_pool_txs_rejected_total",
+                "Txs not inserted into the tx pool, e.g. the ones already in it",
+                Counter::new(),
+            ),
+        };
+
+        let p2p = P2PMetrics {
+            msgs_received: r.register(
+                "saksaha_p2p_msgs_received_total",
+                "P2P msgs received, by msg type",
+                Family::new_counter(&["msg_type"]),
+            ),
+            msgs_sent: r.register(
+                "saksaha_p2p_msgs_sent_total",
+                "P2P msgs sent, by msg type",
+                Family::new_counter(&["msg_type"]),
+            ),
+            bytes_received: r.register(
+                "saksaha_p2p_bytes_received_total",
+                "Bytes of the p2p msgs received before decryption, by msg type",
+                Family::new_counter(&["msg_type"]),
+            ),
+            bytes_sent: r.register(
+                "saksaha_p2p_bytes_sent_total",
+                "Bytes of the p2p msgs sent after encryption, by msg type",
+                Family::new_counter(&["msg_type"]),
+            ),
+        };
+
+        let vm = VMMetrics {
+            invoke_seconds: r.register(
+                "saksaha_vm_invoke_seconds",
+                "Time taken by a contract invocation, by contract fn",
+                Family::new_histogram(&["ctr_fn"], LATENCY_BUCKETS),
+            ),
+            invoke_failures: r.register(
+                "saksaha_vm_invoke_failures_total",
+                "Contract invocations that failed, by contract fn",
+                Family::new_counter(&["ctr_fn"]),
+            ),
+        };
+
+        let db = DBMetrics {
+            ledger_db_size_bytes: r.register(
+                "saksaha_ledger_db_size_bytes",
+                "Size of the ledger db, on disk and in the memtables",
+                Gauge::new(),
+            ),
+            ledger_db_keys: r.register(
+                "saksaha_ledger_db_keys",
+                "Estimated number of the keys in the ledger db",
+                Gauge::new(),
+            ),
+            mrs_db_size_bytes: r.register(
+                "saksaha_mrs_db_size_bytes",
+                "Size of the MRS db, on disk and in the memtables",
+                Gauge::new(),
+            ),
+            mrs_records: r.register(
+                "saksaha_mrs_records",
+                "Estimated number of the records in the MRS",
+                Gauge::new(),
+            ),
+        };
+
+        NodeMetrics {
+            ledger,
+            sync_pool,
+            p2p,
+            vm,
+            db,
+            registry: r,
+        }
+    }
+
+    /// All the metrics in the Prometheus text format.
+    pub fn gather(&self) -> String {
+        self.registry.gather()
+    }
+}
@@ -0,0 +1,66 @@
+use crate::Metric;
+use std::fmt::Write;
+use std::sync::{Arc, RwLock};
+
+struct Entry {
+    name: String,
+    help: String,
+    metric: Arc<dyn Metric>,
+}
+
+/// Named metrics, rendered together in the Prometheus text format on a
+/// scrape.
+pub struct Registry {
+    entries: RwLock<Vec<Entry>>,
+}
+
+impl Registry {
+    pub fn new() -> Registry {
+        Registry {
+            entries: RwLock::new(vec![]),
+        }
+    }
+
+    pub fn register<M: Metric + 'static>(&self, name: &str, help: &str, metric: M) -> Arc<M> {
+        let metric = Arc::new(metric);
+
+        let mut entries = match self.entries.write() {
+            Ok(e) => e,
+            Err(err) => err.into_inner(),
+        };
+
+        entries.push(Entry {
+            name: name.to_string(),
+            help: help.to_string(),
+            metric: metric.clone(),
+        });
+
+        metric
+    }
+
+    pub fn gather(&self) -> String {
+        let entries = match self.entries.read() {
+            Ok(e) => e,
+            Err(err) => err.into_inner(),
+        };
+
+        let mut out = String::new();
+
+        for entry in entries.iter() {
+            let help = entry.help.replace('\\', "\\\\").replace('\n', "\\n");
+
+            let _ = writeln!(out, "# HELP {} {}", entry.name, help);
+            let _ = writeln!(out, "# TYPE {} {}", entry.name, entry.metric.kind());
+
+            entry.metric.encode(&entry.name, &[], &mut out);
+        }
+
+        out
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Registry {
+        Registry::new()
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{Counter, Family, Gauge, Histogram, Registry};
+
+#[test]
+fn test_gather_renders_counters_and_gauges() {
+    let r = Registry::new();
+
+    let blocks = r.register("blocks_total", "Blocks written", Counter::new());
+    let pool = r.register("pool_size", "Txs in the pool", Gauge::new());
+
+    blocks.inc();
+    blocks.inc_by(2);
+    pool.set(7.0);
+
+    let out = r.gather();
+
+    assert_eq!(
+        out,
+        "# HELP blocks_total Blocks written\n\
+        # TYPE blocks_total counter\n\
+        blocks_total 3\n\
+        # HELP pool_size Txs in the pool\n\
+        # TYPE pool_size gauge\n\
+        pool_size 7\n"
+    );
+}
+
+#[test]
+fn test_histogram_buckets_are_cumulative() {
+    let r = Registry::new();
+
+    let h = r.register("write_seconds", "Write time", Histogram::new(&[0.1, 1.0]));
+
+    h.observe(0.0625);
+    h.observe(0.5);
+    h.observe(3.0);
+
+    let out = r.gather();
+
+    assert!(out.contains("# TYPE write_seconds histogram\n"));
+    assert!(out.contains("write_seconds_bucket{le=\"0.1\"} 1\n"));
+    assert!(out.contains("write_seconds_bucket{le=\"1\"} 2\n"));
+    assert!(out.contains("write_seconds_bucket{le=\"+Inf\"} 3\n"));
+    assert!(out.contains("write_seconds_sum 3.5625\n"));
+    assert!(out.contains("write_seconds_count 3\n"));
+}
+
+#[test]
+fn test_family_renders_a_sample_per_label_value() {
+    let r = Registry::new();
+
+    let msgs = r.register(
+        "msgs_total",
+        "Msgs received",
+        Family::new_counter(&["msg_type"]),
+    );
+
+    msgs.with_labels(&["ping"]).inc();
+    msgs.with_labels(&["ping"]).inc();
+    msgs.with_labels(&["tx_syn"]).inc();
+    msgs.with_labels(&["a\"b"]).inc();
+
+    let out = r.gather();
+
+    assert!(out.contains("# TYPE msgs_total counter\n"));
+    assert!(out.contains("msgs_total{msg_type=\"ping\"} 2\n"));
+    assert!(out.contains("msgs_total{msg_type=\"tx_syn\"} 1\n"));
+    assert!(out.contains("msgs_total{msg_type=\"a\\\"b\"} 1\n"));
+}
@@ -2,7 +2,7 @@ use super::columns::{self, CFSenum};
 use chrono::offset::Utc;
 use chrono::DateTime;
 use sak_kv_db::{
-    BoundColumnFamily, ColumnFamilyDescriptor, DBIteratorWithThreadMode, DBWithThreadMode,
+    BoundColumnFamily, ColumnFamilyDescriptor, DBIteratorWithThreadMode, DBStats, DBWithThreadMode,
     Direction, IteratorMode, KeyValueDatabase, MultiThreaded, Options, WriteBatch, DB,
 };
 use std::time::SystemTime;
@@ -76,6 +76,26 @@ impl MRSDB {
         Ok(database)
     }
 
+    pub(crate) fn flush(&self) -> Result<(), MRSError> {
+        sak_kv_db::flush_db(&self.db)
+    }
+
+    pub(crate) fn get_stats(&self) -> Result<DBStats, MRSError> {
+        sak_kv_db::get_db_stats(&self.db)
+    }
+
+    // Estimated by the db, as the rest of the stats are
+    pub(crate) fn get_slot_count(&self) -> Result<u64, MRSError> {
+        let cf = self.make_cf_handle(&self.db, CFSenum::Slot.as_str())?;
+
+        let count = self
+            .db
+            .property_int_value_cf(&cf, "rocksdb.estimate-num-keys")?
+            .unwrap_or(0);
+
+        Ok(count)
+    }
+
     pub(crate) fn make_cf_descriptors() -> Vec<ColumnFamilyDescriptor> {
         vec![
             ColumnFamilyDescriptor::new(CFSenum::Slot.as_str(), Options::default()),
@@ -1,8 +1,8 @@
 use crate::{v0::db::CFSenum, v0::db::MRSDB, MRSError};
 use sak_crypto::Proof;
 use sak_crypto::{Bls12, ScalarExt};
-use sak_kv_db::WriteBatch;
-use sak_kv_db::DB;
+use sak_kv_db::{Direction, IteratorMode, WriteBatch, DB};
+use sak_store_interface::{RangeRequest, RangeResponse};
 use std::convert::TryInto;
 
 impl MRSDB {
@@ -21,6 +21,46 @@ impl MRSDB {
         };
     }
 
+    pub fn get_mrs_data(&self, key: &String) -> Result<Option<Vec<u8>>, MRSError> {
+        let cf = self.make_cf_handle(&self.db, CFSenum::Record.as_str())?;
+
+        let v = self.db.get_cf(&cf, key)?;
+
+        Ok(v)
+    }
+
+    pub fn get_mrs_data_range(&self, range: &RangeRequest) -> Result<RangeResponse, MRSError> {
+        let cf = self.make_cf_handle(&self.db, CFSenum::Record.as_str())?;
+
+        let iter = self.db.iterator_cf(
+            &cf,
+            IteratorMode::From(range.seek_key().as_bytes(), Direction::Forward),
+        );
+
+        let mut res = RangeResponse::default();
+
+        for (k, v) in iter {
+            let key = String::from_utf8(k.to_vec())?;
+
+            if range.is_past_end(&key) {
+                break;
+            }
+
+            if range.is_before_start(&key) {
+                continue;
+            }
+
+            if res.items.len() >= range.limit {
+                res.cursor = res.items.last().map(|(k, _)| k.to_string());
+                break;
+            }
+
+            res.items.push((key, v.to_vec()));
+        }
+
+        Ok(res)
+    }
+
     pub(crate) fn batch_put_dummy(
         &self,
         // db: &DB,
@@ -34,4 +74,17 @@ impl MRSDB {
 
         Ok(())
     }
+
+    pub(crate) fn batch_put_mrs_data(
+        &self,
+        batch: &mut WriteBatch,
+        key: &String,
+        value: &[u8],
+    ) -> Result<(), MRSError> {
+        let cf = self.make_cf_handle(&self.db, CFSenum::Record.as_str())?;
+
+        batch.put_cf(&cf, key, value);
+
+        Ok(())
+    }
 }
@@ -3,9 +3,13 @@ use crate::v0::db::{MrsRecord, MRSDB};
 use crate::MRSError;
 use async_trait::async_trait;
 
+use sak_crypto::{Signature, ToEncodedPoint, VerifyingKey};
 use sak_kv_db::WriteBatch;
 use sak_logger::info;
-use sak_store_interface::{MRSInterface, Session};
+use sak_store_interface::{
+    make_ctr_key, ConfirmSessionArgs, MRSInterface, RangeRequest, RangeResponse, Session,
+    StoreStats,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -17,6 +21,7 @@ pub struct SakMRS {
 
 pub struct SakMRSArgs {
     pub mrs_db_path: PathBuf,
+    pub session_ttl: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -31,11 +36,14 @@ pub struct PutMrsDataArgs {
 
 impl SakMRS {
     pub async fn init(mrs_args: SakMRSArgs) -> Result<Self, MRSError> {
-        let SakMRSArgs { mrs_db_path } = mrs_args;
+        let SakMRSArgs {
+            mrs_db_path,
+            session_ttl,
+        } = mrs_args;
 
         let db = MRSDB::init(&mrs_db_path)?;
 
-        let session_store = SessionStore::init();
+        let session_store = SessionStore::init(session_ttl);
 
         let mrs = SakMRS { db, session_store };
 
@@ -44,8 +52,6 @@ impl SakMRS {
         Ok(mrs)
     }
 
-    pub async fn run(&self) {}
-
     pub async fn put_data(&self, pks: Vec<usize>, args: PutMrsDataArgs) -> Result<(), MRSError> {
         // 1. old ts check
         // args.old_ts
@@ -58,12 +64,39 @@ impl SakMRS {
 
         Ok(())
     }
+
+    /// Writes the receipt of a confirmed session. Keys are namespaced by
+    /// the contract address which produced the receipt.
+    pub(crate) fn commit_session(&self, session: Session) -> Result<(), MRSError> {
+        let mut batch = WriteBatch::default();
+
+        for (key, value) in session.receipt.iter() {
+            let key = make_ctr_key(&session.ctr_addr, key);
+
+            self.db.batch_put_mrs_data(&mut batch, &key, value)?;
+        }
+
+        self.db.db.write(batch)?;
+
+        info!(
+            "Committed session, session_id: {}, ctr_addr: {}, record count: {}",
+            session.id,
+            session.ctr_addr,
+            session.receipt.len(),
+        );
+
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl MRSInterface for SakMRS {
-    fn get_mrs_data(&self, key: &String) -> Result<Option<String>, MRSError> {
-        self.db.get_dummy(key)
+    fn get_mrs_data(&self, key: &String) -> Result<Option<Vec<u8>>, MRSError> {
+        self.db.get_mrs_data(key)
+    }
+
+    fn get_mrs_data_range(&self, range: &RangeRequest) -> Result<RangeResponse, MRSError> {
+        self.db.get_mrs_data_range(range)
     }
 
     fn put_mrs_data(&self, key: &String, value: &String) -> Result<(), MRSError> {
@@ -76,21 +109,89 @@ impl MRSInterface for SakMRS {
         Ok(())
     }
 
-    // async fn get_session(&self, session_id: String) -> Result<Session, MRSError> {
-    //     let mut session_store_lock = self.session_store.lock().await;
+    fn add_session(&self, session: Session) {
+        self.session_store.add_session(session);
+    }
 
-    //     let receipt = session_store_lock
-    //         .get_key_value(&session_id)
-    //         .ok_or("session not found")?;
-    //     session_store_lock.remove(receipt.0);
-    //     let sess = *(receipt.1);
-    //     Ok(sess)
-    // }
+    async fn get_session(&self, session_id: &String) -> Option<Session> {
+        self.session_store.get_session(session_id).await
+    }
 
-    fn add_session(&self, session: Session) {
-        // let mut session_store_lock = self.session_store.lock().await;
-        // session_store_lock.insert(session_id, session);
+    async fn confirm_session(&self, confirm_args: ConfirmSessionArgs) -> Result<(), MRSError> {
+        let ConfirmSessionArgs {
+            session_id,
+            public_key,
+            sig,
+        } = confirm_args;
+
+        let owner_public_key = self
+            .session_store
+            .get_session(&session_id)
+            .await
+            .map(|s| s.owner_public_key)
+            .ok_or(format!(
+                "Session not found or expired, session_id: {}",
+                session_id
+            ))?;
+
+        if public_key != owner_public_key {
+            return Err(format!(
+                "Session is not owned by the key, session_id: {}, public_key: {}",
+                session_id, public_key
+            )
+            .into());
+        }
+
+        let verifying_key = {
+            let pk = sak_crypto::convert_public_key_str_into_public_key(&public_key)?;
+
+            match VerifyingKey::from_encoded_point(&pk.to_encoded_point(false)) {
+                Ok(v) => v,
+                Err(err) => {
+                    return Err(format!("Error creating verifying key, err: {}", err).into());
+                }
+            }
+        };
+
+        let sig = match Signature::from_der(&sig) {
+            Ok(s) => s,
+            Err(err) => {
+                return Err(format!("Error parsing session signature, err: {}", err).into());
+            }
+        };
+
+        sak_crypto::verify(verifying_key, session_id.as_bytes(), &sig)?;
+
+        let session = self
+            .session_store
+            .remove_session(&session_id)
+            .await
+            .ok_or(format!(
+                "Session not found or expired, session_id: {}",
+                session_id
+            ))?;
+
+        self.commit_session(session)
+    }
 
-        self.session_store.add_session(session);
+    async fn run(&self) {
+        self.session_store.run().await;
+    }
+
+    fn flush(&self) -> Result<(), MRSError> {
+        self.db.flush()
+    }
+
+    fn get_stats(&self) -> Result<StoreStats, MRSError> {
+        let db_stats = self.db.get_stats()?;
+
+        Ok(StoreStats {
+            size_bytes: db_stats.size_bytes,
+            record_count: db_stats.key_count,
+        })
+    }
+
+    fn get_slot_count(&self) -> Result<u64, MRSError> {
+        self.db.get_slot_count()
     }
 }
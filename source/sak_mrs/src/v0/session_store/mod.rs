@@ -1,19 +1,28 @@
-use crate::MRSError;
+use sak_logger::{debug, error};
 use sak_store_interface::Session;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 use tokio::sync::{
     mpsc::{self, UnboundedReceiver, UnboundedSender},
     Mutex, RwLock,
 };
 
+const SESSION_TTL: u64 = 60 * 1000;
+
+const SESSION_PRUNE_INTERVAL: u64 = 5 * 1000;
+
 pub struct SessionStore {
     store: Arc<RwLock<HashMap<String, Session>>>,
     store_tx: UnboundedSender<Session>,
     store_rx: Arc<Mutex<UnboundedReceiver<Session>>>,
+    session_ttl: Duration,
 }
 
 impl SessionStore {
-    pub fn init() -> SessionStore {
+    pub fn init(session_ttl: Option<u64>) -> SessionStore {
         let store = {
             let s = HashMap::new();
             Arc::new(RwLock::new(s))
@@ -25,36 +34,105 @@ impl SessionStore {
             (tx, Arc::new(Mutex::new(rx)))
         };
 
+        let session_ttl = match session_ttl {
+            Some(t) => Duration::from_millis(t),
+            None => Duration::from_millis(SESSION_TTL),
+        };
+
         SessionStore {
             store,
             store_tx,
             store_rx,
+            session_ttl,
         }
     }
 
     pub fn add_session(&self, session: Session) {
-        self.store_tx.send(session);
+        if let Err(err) = self.store_tx.send(session) {
+            error!("Error sending session to the store, err: {}", err);
+        }
     }
 
-    pub fn run(&self) -> Result<(), MRSError> {
-        let store_rx = self.store_rx.clone();
-        let store = self.store.clone();
-
-        tokio::spawn(async move {
-            let mut store_rx_lock = store_rx.lock().await;
-            loop {
-                let session = match store_rx_lock.try_recv() {
-                    Ok(s) => s,
-                    Err(err) => {
-                        panic!("session should be received, err: {}", err);
-                    }
-                };
-
-                let mut store_lock = store.write().await;
-                store_lock.insert(session.id.to_string(), session);
+    pub async fn get_session(&self, session_id: &String) -> Option<Session> {
+        let store_lock = self.store.read().await;
+
+        match store_lock.get(session_id) {
+            Some(s) => {
+                if self.is_expired(s) {
+                    return None;
+                }
+
+                Some(s.clone())
             }
-        });
+            None => None,
+        }
+    }
+
+    pub async fn remove_session(&self, session_id: &String) -> Option<Session> {
+        let mut store_lock = self.store.write().await;
+
+        match store_lock.remove(session_id) {
+            Some(s) => {
+                if self.is_expired(&s) {
+                    return None;
+                }
+
+                Some(s)
+            }
+            None => None,
+        }
+    }
+
+    pub async fn run(&self) {
+        let mut store_rx_lock = self.store_rx.lock().await;
+
+        let mut prune_interval =
+            tokio::time::interval(Duration::from_millis(SESSION_PRUNE_INTERVAL));
+
+        loop {
+            tokio::select! {
+                maybe_session = store_rx_lock.recv() => {
+                    let session = match maybe_session {
+                        Some(s) => s,
+                        None => {
+                            error!("Session channel has been closed, terminating session store");
+
+                            return;
+                        }
+                    };
+
+                    let mut store_lock = self.store.write().await;
+                    store_lock.insert(session.id.to_string(), session);
+                },
+                _ = prune_interval.tick() => {
+                    self.prune_expired().await;
+                }
+            }
+        }
+    }
+
+    async fn prune_expired(&self) {
+        let mut store_lock = self.store.write().await;
+
+        let len_before = store_lock.len();
+        store_lock.retain(|_, s| !self.is_expired(s));
+
+        let pruned_count = len_before - store_lock.len();
+        if pruned_count > 0 {
+            debug!("Pruned expired sessions, count: {}", pruned_count);
+        }
+    }
+
+    fn is_expired(&self, session: &Session) -> bool {
+        let now = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(d) => d.as_millis() as u64,
+            Err(err) => {
+                error!("Error getting the current timestamp, err: {}", err);
+
+                return true;
+            }
+        };
 
-        Ok(())
+        now.saturating_sub(session.created_at) > self.session_ttl.as_millis() as u64
     }
 }
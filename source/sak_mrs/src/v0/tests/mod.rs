@@ -1,2 +1,4 @@
+mod range;
+mod session;
 mod slot;
 mod utils;
@@ -0,0 +1,37 @@
+use super::utils::MRSTestUtils;
+use sak_store_interface::{MRSInterface, RangeRequest};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_mrs_data_range_pagination() {
+    let mrs = MRSTestUtils::mock_mrs_db().await;
+
+    for i in 0..5 {
+        let key = format!("ctr_channel_msg_{}", i);
+        mrs.put_mrs_data(&key, &i.to_string()).unwrap();
+    }
+
+    mrs.put_mrs_data(&"ctr_other_0".to_string(), &"x".to_string())
+        .unwrap();
+
+    let mut range = RangeRequest {
+        prefix: "ctr_channel_".to_string(),
+        start: Some("ctr_channel_msg_1".to_string()),
+        end: None,
+        cursor: None,
+        limit: 2,
+    };
+
+    let page_1 = mrs.get_mrs_data_range(&range).unwrap();
+    let keys: Vec<String> = page_1.items.iter().map(|(k, _)| k.to_string()).collect();
+    assert_eq!(keys, vec!["ctr_channel_msg_1", "ctr_channel_msg_2"]);
+    assert_eq!(page_1.cursor, Some("ctr_channel_msg_2".to_string()));
+
+    range.cursor = page_1.cursor;
+
+    let page_2 = mrs.get_mrs_data_range(&range).unwrap();
+    let keys: Vec<String> = page_2.items.iter().map(|(k, _)| k.to_string()).collect();
+    assert_eq!(keys, vec!["ctr_channel_msg_3", "ctr_channel_msg_4"]);
+    assert_eq!(page_2.items[0].1, b"3".to_vec());
+
+    assert_eq!(page_2.cursor, None);
+}
@@ -0,0 +1,146 @@
+use super::utils::MRSTestUtils;
+use sak_credential::{Credential, CredentialProfile};
+use sak_crypto::SigningKey;
+use sak_store_interface::{make_ctr_key, ConfirmSessionArgs, MRSInterface, Session};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn make_session(session_id: &str, created_at: u64, owner_public_key: &str) -> Session {
+    Session {
+        id: session_id.to_string(),
+        ctr_addr: "test_ctr_addr".to_string(),
+        receipt: HashMap::from([("channels_0".to_string(), b"power".to_vec())]),
+        created_at,
+        owner_public_key: owner_public_key.to_string(),
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_confirm_session_commits_receipt() {
+    let mrs = Arc::new(MRSTestUtils::mock_mrs_db().await);
+    let credential = {
+        let c = CredentialProfile::test_1();
+        Credential::new(&c.secret, &c.public_key_str).unwrap()
+    };
+
+    let mrs_clone = mrs.clone();
+    tokio::spawn(async move { mrs_clone.run().await });
+
+    let session_id = "session_1".to_string();
+    mrs.add_session(make_session(
+        &session_id,
+        now_millis(),
+        &credential.public_key_str,
+    ));
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let session = mrs.get_session(&session_id).await;
+    assert!(session.is_some());
+
+    let sig = {
+        let signing_key = SigningKey::from(&credential.secret_key);
+        let s = sak_crypto::make_signature(signing_key, session_id.as_bytes());
+        s.to_der().to_bytes().to_vec()
+    };
+
+    let confirm_args = ConfirmSessionArgs {
+        session_id: session_id.to_string(),
+        public_key: credential.public_key_str.to_string(),
+        sig,
+    };
+
+    mrs.confirm_session(confirm_args).await.unwrap();
+
+    let committed = mrs
+        .db
+        .get_dummy(&make_ctr_key("test_ctr_addr", "channels_0"))
+        .unwrap();
+    assert_eq!(committed, Some("power".to_string()));
+
+    assert!(mrs.get_session(&session_id).await.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_expired_session_cannot_be_confirmed() {
+    let mrs = Arc::new(MRSTestUtils::mock_mrs_db().await);
+    let credential = {
+        let c = CredentialProfile::test_1();
+        Credential::new(&c.secret, &c.public_key_str).unwrap()
+    };
+
+    let mrs_clone = mrs.clone();
+    tokio::spawn(async move { mrs_clone.run().await });
+
+    let session_id = "session_expired".to_string();
+    mrs.add_session(make_session(&session_id, 0, &credential.public_key_str));
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    assert!(mrs.get_session(&session_id).await.is_none());
+
+    let sig = {
+        let signing_key = SigningKey::from(&credential.secret_key);
+        let s = sak_crypto::make_signature(signing_key, session_id.as_bytes());
+        s.to_der().to_bytes().to_vec()
+    };
+
+    let confirm_args = ConfirmSessionArgs {
+        session_id,
+        public_key: credential.public_key_str.to_string(),
+        sig,
+    };
+
+    assert!(mrs.confirm_session(confirm_args).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_session_cannot_be_confirmed_by_other_key() {
+    let mrs = Arc::new(MRSTestUtils::mock_mrs_db().await);
+    let owner = {
+        let c = CredentialProfile::test_1();
+        Credential::new(&c.secret, &c.public_key_str).unwrap()
+    };
+    let other = {
+        let c = CredentialProfile::test_3();
+        Credential::new(&c.secret, &c.public_key_str).unwrap()
+    };
+
+    let mrs_clone = mrs.clone();
+    tokio::spawn(async move { mrs_clone.run().await });
+
+    let session_id = "session_2".to_string();
+    mrs.add_session(make_session(
+        &session_id,
+        now_millis(),
+        &owner.public_key_str,
+    ));
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // Validly signed, but by a key the session was not opened for
+    let sig = {
+        let signing_key = SigningKey::from(&other.secret_key);
+        let s = sak_crypto::make_signature(signing_key, session_id.as_bytes());
+        s.to_der().to_bytes().to_vec()
+    };
+
+    let confirm_args = ConfirmSessionArgs {
+        session_id: session_id.to_string(),
+        public_key: other.public_key_str.to_string(),
+        sig,
+    };
+
+    assert!(mrs.confirm_session(confirm_args).await.is_err());
+
+    // The session is still there for its owner to confirm
+    assert!(mrs.get_session(&session_id).await.is_some());
+}
@@ -27,6 +27,7 @@ impl MRSTestUtils {
         let mrs = {
             let mrs_args = SakMRSArgs {
                 mrs_db_path: mrs_path,
+                session_ttl: None,
             };
 
             let m = SakMRS::init(mrs_args).await.unwrap();
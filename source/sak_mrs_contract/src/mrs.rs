@@ -31,7 +31,9 @@ pub fn execute(ctx: &ContractCtx, request: CtrRequest) -> Result<Vec<u8>, Contra
         "unimplemented" => {
             unimplemented!()
         }
-        _ => Err(("Wrong request type has been found in query").into()),
+        _ => Err(ContractError::InvalidRequest(
+            "Wrong request type has been found in query".into(),
+        )),
     }
 }
 
@@ -39,7 +41,9 @@ pub fn update(ctx: ContractCtx, request: CtrRequest) -> Result<InvokeResult, Con
     let mut storage = vec![];
     match request.req_type.as_ref() {
         RESERVE => reserve_slot(&mut storage, request.args),
-        _ => Err(("Wrong request type has been found in execution").into()),
+        _ => Err(ContractError::InvalidRequest(
+            "Wrong request type has been found in execution".into(),
+        )),
     }
 }
 
@@ -33,7 +33,10 @@ async fn test_call_ctr_mrs_fn_execute_reserve_slot() {
     };
 
     let mrs = {
-        let mrs_args = SakMRSArgs { mrs_db_path };
+        let mrs_args = SakMRSArgs {
+            mrs_db_path,
+            session_ttl: None,
+        };
 
         let m = SakMRS::init(mrs_args).await.unwrap();
         let m = Box::new(m) as MRSAccessor;
@@ -65,6 +68,7 @@ async fn test_call_ctr_mrs_fn_execute_reserve_slot() {
             req_type,
             args,
             ctr_call_type: CtrCallType::Execute,
+            session_owner: None,
         };
 
         let storage = get_test_mrs_state(test_mrs_vec);
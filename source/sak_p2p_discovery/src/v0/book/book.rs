@@ -0,0 +1,172 @@
+use super::AddrRecord;
+use crate::P2PDiscError;
+use chrono::Utc;
+use sak_kv_db::{
+    BoundColumnFamily, ColumnFamilyDescriptor, IteratorMode, KeyValueDatabase, Options, DB,
+};
+use sak_p2p_addr::KnownAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const ADDR_RECORD: &str = "addr_record";
+
+/// Addrs not seen for a week are aged out.
+pub(crate) const ADDR_BOOK_MAX_AGE_SEC: i64 = 7 * 24 * 3600;
+
+/// Addresses discovered so far, persisted so that a node can reach the
+/// network it has seen before after a restart, not only its bootstrap addrs.
+pub struct AddrBook {
+    db: DB,
+}
+
+impl AddrBook {
+    pub fn init(db_path: &PathBuf) -> Result<AddrBook, P2PDiscError> {
+        let options = {
+            let mut o = Options::default();
+            o.create_missing_column_families(true);
+            o.create_if_missing(true);
+
+            o
+        };
+
+        let cf_descriptors = vec![ColumnFamilyDescriptor::new(ADDR_RECORD, Options::default())];
+
+        let kv_db = match KeyValueDatabase::new(db_path, options, cf_descriptors) {
+            Ok(d) => d,
+            Err(err) => {
+                return Err(format!("Error initializing addr book, err: {}", err).into());
+            }
+        };
+
+        Ok(AddrBook {
+            db: kv_db.db_instance,
+        })
+    }
+
+    fn make_cf_handle(&self) -> Result<Arc<BoundColumnFamily>, String> {
+        match self.db.cf_handle(ADDR_RECORD) {
+            Some(h) => Ok(h),
+            None => Err(format!("Fail to open addr book column {}", ADDR_RECORD)),
+        }
+    }
+
+    pub fn get_record(&self, public_key_str: &String) -> Result<Option<AddrRecord>, P2PDiscError> {
+        let cf = self.make_cf_handle()?;
+
+        match self.db.get_cf(&cf, public_key_str)? {
+            Some(v) => Ok(Some(serde_json::from_slice(&v)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn put_record(&self, record: &AddrRecord) -> Result<(), P2PDiscError> {
+        let cf = self.make_cf_handle()?;
+
+        let v = serde_json::to_vec(record)?;
+
+        self.db.put_cf(&cf, &record.public_key_str, v)?;
+
+        Ok(())
+    }
+
+    /// Records in the order of score, the best first.
+    pub fn get_records(&self) -> Result<Vec<AddrRecord>, P2PDiscError> {
+        let cf = self.make_cf_handle()?;
+
+        let mut records = vec![];
+
+        for (_k, v) in self.db.iterator_cf(&cf, IteratorMode::Start) {
+            let r: AddrRecord = serde_json::from_slice(&v)?;
+
+            records.push(r);
+        }
+
+        records.sort_by(|a, b| b.score.cmp(&a.score));
+
+        Ok(records)
+    }
+
+    /// Updates the endpoint and the last seen time of the addr.
+    pub fn record_seen(&self, known_addr: &KnownAddr) -> Result<AddrRecord, P2PDiscError> {
+        let mut record = match self.get_record(&known_addr.public_key_str)? {
+            Some(r) => r,
+            None => AddrRecord {
+                public_key_str: known_addr.public_key_str.clone(),
+                ip: known_addr.ip.clone(),
+                disc_port: known_addr.disc_port,
+                p2p_port: known_addr.p2p_port,
+                last_seen: 0,
+                success_count: 0,
+                failure_count: 0,
+                score: 0,
+                banned_until: None,
+            },
+        };
+
+        record.ip = known_addr.ip.clone();
+        record.disc_port = known_addr.disc_port;
+        record.p2p_port = known_addr.p2p_port;
+        record.last_seen = Utc::now().timestamp();
+
+        self.put_record(&record)?;
+
+        Ok(record)
+    }
+
+    pub fn record_success(&self, known_addr: &KnownAddr) -> Result<(), P2PDiscError> {
+        let mut record = self.record_seen(known_addr)?;
+
+        record.success_count += 1;
+        record.update_score();
+
+        self.put_record(&record)
+    }
+
+    pub fn record_failure(&self, public_key_str: &String) -> Result<(), P2PDiscError> {
+        let mut record = match self.get_record(public_key_str)? {
+            Some(r) => r,
+            None => return Ok(()),
+        };
+
+        record.failure_count += 1;
+        record.update_score();
+
+        self.put_record(&record)
+    }
+
+    pub fn ban(&self, known_addr: &KnownAddr, duration_sec: i64) -> Result<(), P2PDiscError> {
+        let mut record = self.record_seen(known_addr)?;
+
+        record.banned_until = Some(Utc::now().timestamp() + duration_sec);
+
+        self.put_record(&record)
+    }
+
+    pub fn is_banned(&self, public_key_str: &String) -> Result<bool, P2PDiscError> {
+        match self.get_record(public_key_str)? {
+            Some(r) => Ok(r.is_banned(Utc::now().timestamp())),
+            None => Ok(false),
+        }
+    }
+
+    /// Deletes the records not seen within `max_age_sec` or scored too low.
+    /// Returns the number of records deleted.
+    pub fn prune(&self, max_age_sec: i64) -> Result<usize, P2PDiscError> {
+        let now = Utc::now().timestamp();
+
+        let expired: Vec<String> = self
+            .get_records()?
+            .into_iter()
+            .filter(|r| r.is_expired(now, max_age_sec))
+            .map(|r| r.public_key_str)
+            .collect();
+
+        let cf = self.make_cf_handle()?;
+
+        for public_key_str in expired.iter() {
+            self.db.delete_cf(&cf, public_key_str)?;
+        }
+
+        Ok(expired.len())
+    }
+}
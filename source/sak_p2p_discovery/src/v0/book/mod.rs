@@ -0,0 +1,5 @@
+mod book;
+mod record;
+
+pub use book::*;
+pub use record::*;
@@ -0,0 +1,57 @@
+use sak_p2p_addr::{AddrStatus, UnknownAddr};
+use serde::{Deserialize, Serialize};
+
+// A failure weighs more than a success, so that a flaky address sinks
+const FAILURE_WEIGHT: i64 = 2;
+
+/// Records below this score are dropped from the book.
+pub(crate) const MIN_SCORE: i64 = -10;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AddrRecord {
+    pub public_key_str: String,
+    pub ip: String,
+    pub disc_port: u16,
+    pub p2p_port: u16,
+    // Unix timestamp in seconds
+    pub last_seen: i64,
+    pub success_count: u32,
+    pub failure_count: u32,
+    pub score: i64,
+    // Unix timestamp in seconds until which the addr is not to be connected
+    #[serde(default)]
+    pub banned_until: Option<i64>,
+}
+
+impl AddrRecord {
+    pub(crate) fn update_score(&mut self) {
+        self.score = self.success_count as i64 - FAILURE_WEIGHT * self.failure_count as i64;
+    }
+
+    pub fn is_banned(&self, now: i64) -> bool {
+        match self.banned_until {
+            Some(t) => now < t,
+            None => false,
+        }
+    }
+
+    /// A banned record is kept until the ban is over.
+    pub fn is_expired(&self, now: i64, max_age_sec: i64) -> bool {
+        if self.is_banned(now) {
+            return false;
+        }
+
+        now - self.last_seen > max_age_sec || self.score < MIN_SCORE
+    }
+
+    pub fn to_unknown_addr(&self) -> UnknownAddr {
+        UnknownAddr {
+            ip: self.ip.clone(),
+            disc_port: self.disc_port,
+            p2p_port: Some(self.p2p_port),
+            sig: None,
+            public_key_str: Some(self.public_key_str.clone()),
+            status: AddrStatus::Initialized,
+        }
+    }
+}
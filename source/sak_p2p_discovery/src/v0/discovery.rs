@@ -1,12 +1,19 @@
 use super::dial_scheduler::{DialScheduler, DialSchedulerArgs};
 use super::server::{Server, ServerArgs};
 use super::task::runtime::DiscTaskRuntime;
-use crate::{AddrTable, Connection, DiscRuntime};
+use super::task::DiscoveryTask;
+use crate::{
+    find_default_gateway, AddrBook, AddrTable, Connection, DiscRuntime, ExternalAddr,
+    ExternalPorts, PortMapper, ADDR_BOOK_MAX_AGE_SEC, NAT_PMP_PORT, SSDP_MULTICAST_ADDR,
+};
+use chrono::Utc;
 use colored::Colorize;
-use sak_logger::info;
+use sak_logger::{info, warn};
 use sak_p2p_addr::UnknownAddr;
 use sak_p2p_id::Identity;
 use sak_task_queue::TaskQueue;
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::net::UdpSocket;
@@ -14,13 +21,19 @@ use tokio::net::UdpSocket;
 const DISC_TASK_QUEUE_CAPACITY: usize = 10;
 const ADDR_EXPIRE_DURATION: u64 = 3600;
 const ADDR_MONITOR_INTERVAL: u64 = 3000;
+// Max number of addrs of the addr book dialed at startup
+const ADDR_BOOK_DIAL_LIMIT: usize = 32;
 
 pub struct Discovery {
     disc_runtime: DiscRuntime,
     server: Server,
     dial_scheduler: DialScheduler,
     task_runtime: DiscTaskRuntime,
+    port_mapper: Option<PortMapper>,
+    disc_task_queue: Arc<TaskQueue<DiscoveryTask>>,
+    identity: Arc<Identity>,
     pub addr_table: Arc<AddrTable>,
+    pub external_addr: Arc<ExternalAddr>,
 }
 
 pub struct DiscoveryArgs {
@@ -34,10 +47,18 @@ pub struct DiscoveryArgs {
     pub bootstrap_addrs: Vec<UnknownAddr>,
     pub udp_socket: UdpSocket,
     pub identity: Arc<Identity>,
+    // Addr book is not persisted if not given
+    pub addr_book_path: Option<PathBuf>,
+    // Overrides the external ip and disc port detected
+    pub external_addr: Option<SocketAddr>,
+    // Maps the disc and p2p ports at the gateway with UPnP or NAT-PMP
+    pub port_mapping: bool,
 }
 
 impl Discovery {
     pub async fn init(disc_args: DiscoveryArgs) -> Result<(Discovery, u16), String> {
+        let external_addr = Arc::new(ExternalAddr::new(disc_args.external_addr));
+
         let (udp_conn, disc_port) = {
             let socket_addr = match disc_args.udp_socket.local_addr() {
                 Ok(a) => a,
@@ -46,7 +67,7 @@ impl Discovery {
                 }
             };
 
-            let udp_conn = Connection::new(disc_args.udp_socket);
+            let udp_conn = Connection::new(disc_args.udp_socket, external_addr.clone());
 
             (Arc::new(udp_conn), socket_addr.port())
         };
@@ -61,8 +82,28 @@ impl Discovery {
             None => Duration::from_millis(ADDR_MONITOR_INTERVAL),
         };
 
+        let addr_book = match &disc_args.addr_book_path {
+            Some(p) => match AddrBook::init(p) {
+                Ok(b) => Some(Arc::new(b)),
+                Err(err) => return Err(format!("Can't initialize addr book, err: {}", err)),
+            },
+            None => None,
+        };
+
+        let bootstrap_addrs = make_bootstrap_addrs(
+            disc_args.bootstrap_addrs,
+            &addr_book,
+            &disc_args.identity.credential.public_key_str,
+        );
+
         let addr_table = {
-            let t = match AddrTable::init(disc_args.disc_table_capacity).await {
+            let t = match AddrTable::init(
+                &disc_args.identity.credential.public_key_str,
+                disc_args.disc_table_capacity,
+                addr_book,
+            )
+            .await
+            {
                 Ok(t) => t,
                 Err(err) => return Err(format!("Can't initialize Table, err: {}", err).into()),
             };
@@ -82,7 +123,7 @@ impl Discovery {
 
         let dial_schd_args = DialSchedulerArgs {
             disc_dial_interval: disc_args.disc_dial_interval,
-            bootstrap_addrs: disc_args.bootstrap_addrs,
+            bootstrap_addrs,
             disc_task_queue: disc_task_queue.clone(),
         };
 
@@ -97,6 +138,7 @@ impl Discovery {
                 udp_conn: udp_conn.clone(),
                 identity: disc_args.identity.clone(),
                 addr_table: addr_table.clone(),
+                disc_task_queue: disc_task_queue.clone(),
                 addr_expire_duration,
             };
 
@@ -109,6 +151,7 @@ impl Discovery {
             let r = DiscRuntime {
                 addr_monitor_interval,
                 addr_table: addr_table.clone(),
+                disc_task_queue: disc_task_queue.clone(),
             };
 
             r
@@ -126,11 +169,40 @@ impl Discovery {
             h
         };
 
+        let port_mapper = match disc_args.port_mapping {
+            true => {
+                let ssdp_addr = match SSDP_MULTICAST_ADDR.parse::<SocketAddr>() {
+                    Ok(a) => a,
+                    Err(err) => return Err(format!("Invalid ssdp addr, err: {}", err)),
+                };
+
+                let nat_pmp_addr =
+                    find_default_gateway().map(|ip| SocketAddr::new(ip, NAT_PMP_PORT));
+
+                let local_ports = ExternalPorts {
+                    disc_port,
+                    p2p_port: disc_args.p2p_port,
+                };
+
+                Some(PortMapper::new(
+                    local_ports,
+                    external_addr.clone(),
+                    ssdp_addr,
+                    nat_pmp_addr,
+                ))
+            }
+            false => None,
+        };
+
         let disc = Discovery {
             server,
             task_runtime,
             dial_scheduler,
+            port_mapper,
+            disc_task_queue,
+            identity: disc_args.identity,
             addr_table,
+            external_addr,
             disc_runtime,
         };
 
@@ -148,6 +220,100 @@ impl Discovery {
             self.task_runtime.run(),
             self.dial_scheduler.run(),
             self.disc_runtime.run(),
+            self.run_port_mapper(),
         );
     }
+
+    /// Ip and ports of the node as reachable from outside of the NAT. The ip
+    /// is not known unless given, reported by the gateway or observed by the
+    /// peers.
+    pub async fn get_external_endpoint(&self) -> (Option<IpAddr>, ExternalPorts) {
+        let ip = self.external_addr.get_ip().await;
+        let ports = self
+            .external_addr
+            .get_advertised_ports(&self.identity)
+            .await;
+
+        (ip, ports)
+    }
+
+    /// Closes the task queue, dropping the tasks not handled yet. Returns
+    /// the number of the tasks dropped.
+    pub async fn close_task_queue(&self) -> usize {
+        let tasks = self.disc_task_queue.close().await;
+
+        tasks.len()
+    }
+
+    /// Removes the port mappings made at the gateway. Called on shutdown,
+    /// so that the ports are not left open.
+    pub async fn unmap_ports(&self) {
+        let port_mapper = match &self.port_mapper {
+            Some(m) => m,
+            None => return,
+        };
+
+        match port_mapper.unmap_ports().await {
+            Ok(_) => info!("Removed the port mappings at the gateway"),
+            Err(err) => warn!("Could not remove the port mappings, err: {}", err),
+        };
+    }
+
+    async fn run_port_mapper(&self) {
+        if let Some(m) = &self.port_mapper {
+            m.run().await;
+        }
+    }
+}
+
+// Bootstrap addrs come first, followed by the best scored addrs of the addr
+// book
+fn make_bootstrap_addrs(
+    bootstrap_addrs: Vec<UnknownAddr>,
+    addr_book: &Option<Arc<AddrBook>>,
+    my_public_key_str: &String,
+) -> Vec<UnknownAddr> {
+    let addr_book = match addr_book {
+        Some(b) => b,
+        None => return bootstrap_addrs,
+    };
+
+    if let Err(err) = addr_book.prune(ADDR_BOOK_MAX_AGE_SEC) {
+        warn!("Could not prune addr book, err: {}", err);
+    }
+
+    let records = match addr_book.get_records() {
+        Ok(r) => r,
+        Err(err) => {
+            warn!("Could not load addr book, err: {}", err);
+
+            return bootstrap_addrs;
+        }
+    };
+
+    let mut addrs = bootstrap_addrs;
+
+    let now = Utc::now().timestamp();
+
+    let known_records = records
+        .iter()
+        .filter(|r| r.public_key_str != *my_public_key_str)
+        .filter(|r| !r.is_banned(now))
+        .filter(|r| {
+            addrs
+                .iter()
+                .all(|a| a.disc_endpoint() != sak_utils_net::make_endpoint(&r.ip, r.disc_port))
+        })
+        .take(ADDR_BOOK_DIAL_LIMIT)
+        .map(|r| r.to_unknown_addr())
+        .collect::<Vec<UnknownAddr>>();
+
+    info!(
+        "Loaded addrs from the addr book, count: {}",
+        known_records.len().to_string().yellow(),
+    );
+
+    addrs.extend(known_records);
+
+    addrs
 }
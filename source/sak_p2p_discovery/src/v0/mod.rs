@@ -1,5 +1,7 @@
+mod book;
 mod dial_scheduler;
 mod discovery;
+mod nat;
 mod net;
 mod ops;
 mod runtime;
@@ -10,7 +12,9 @@ mod task;
 #[cfg(test)]
 mod tests;
 
+pub use book::*;
 pub use discovery::{Discovery, DiscoveryArgs};
+pub use nat::*;
 pub(crate) use net::*;
 pub(crate) use ops::*;
 pub(crate) use runtime::*;
@@ -0,0 +1,162 @@
+use colored::Colorize;
+use sak_logger::info;
+use sak_p2p_id::Identity;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::RwLock;
+
+/// An observed endpoint is trusted only after this many peers reported it.
+pub const MIN_EXTERNAL_ADDR_VOTES: usize = 2;
+
+// Max number of peers whose observations are kept
+pub(crate) const MAX_EXTERNAL_ADDR_VOTERS: usize = 64;
+
+/// Ports of the node as reachable from outside of the NAT.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExternalPorts {
+    pub disc_port: u16,
+    pub p2p_port: u16,
+}
+
+/// External address of the node. The endpoint given manually takes
+/// precedence over the one the gateway reports, which takes precedence over
+/// the one peers have observed.
+pub struct ExternalAddr {
+    // Ip and disc port, e.g. of a port forwarded by hand
+    manual_endpoint: Option<SocketAddr>,
+    gateway_ip: RwLock<Option<IpAddr>>,
+    mapped_ports: RwLock<Option<ExternalPorts>>,
+    // Endpoint of our disc socket as observed by peers, by their public key,
+    // along with the seq of the observation
+    observations: RwLock<HashMap<String, (SocketAddr, u64)>>,
+    // Increases with each observation, telling the oldest one
+    next_observation_seq: AtomicU64,
+}
+
+impl ExternalAddr {
+    pub fn new(manual_endpoint: Option<SocketAddr>) -> ExternalAddr {
+        ExternalAddr {
+            manual_endpoint,
+            gateway_ip: RwLock::new(None),
+            mapped_ports: RwLock::new(None),
+            observations: RwLock::new(HashMap::new()),
+            next_observation_seq: AtomicU64::new(0),
+        }
+    }
+
+    pub async fn record_observation(&self, reporter_public_key_str: &String, endpoint: SocketAddr) {
+        let mut observations = self.observations.write().await;
+
+        let prev_endpoint = get_majority_endpoint(&observations);
+
+        if observations.len() >= MAX_EXTERNAL_ADDR_VOTERS
+            && !observations.contains_key(reporter_public_key_str)
+        {
+            let oldest = observations
+                .iter()
+                .min_by_key(|(_, (_, seq))| *seq)
+                .map(|(k, _)| k.clone());
+
+            if let Some(k) = oldest {
+                observations.remove(&k);
+            }
+        }
+
+        let seq = self.next_observation_seq.fetch_add(1, Ordering::Relaxed);
+
+        observations.insert(reporter_public_key_str.clone(), (endpoint, seq));
+
+        let endpoint = get_majority_endpoint(&observations);
+
+        if endpoint != prev_endpoint {
+            if let Some(e) = endpoint {
+                info!(
+                    "External endpoint observed by peers has changed, endpoint: {}",
+                    e.to_string().yellow(),
+                );
+            }
+        }
+    }
+
+    pub async fn get_observed_endpoint(&self) -> Option<SocketAddr> {
+        let observations = self.observations.read().await;
+
+        get_majority_endpoint(&observations)
+    }
+
+    pub async fn set_mapping(&self, gateway_ip: Option<IpAddr>, ports: ExternalPorts) {
+        *self.gateway_ip.write().await = gateway_ip;
+        *self.mapped_ports.write().await = Some(ports);
+    }
+
+    pub async fn clear_mapping(&self) {
+        *self.gateway_ip.write().await = None;
+        *self.mapped_ports.write().await = None;
+    }
+
+    pub async fn get_ip(&self) -> Option<IpAddr> {
+        if let Some(e) = self.manual_endpoint {
+            return Some(e.ip());
+        }
+
+        if let Some(ip) = *self.gateway_ip.read().await {
+            return Some(ip);
+        }
+
+        self.get_observed_endpoint().await.map(|e| e.ip())
+    }
+
+    /// Ports advertised to peers. The disc port given manually comes first,
+    /// then the ports mapped at the gateway, then the disc port peers have
+    /// observed. A p2p port that is not mapped is assumed to be forwarded as
+    /// it is, as peers can only observe the disc one.
+    pub async fn get_advertised_ports(&self, identity: &Identity) -> ExternalPorts {
+        let mapped_ports = *self.mapped_ports.read().await;
+
+        if let Some(e) = self.manual_endpoint {
+            return ExternalPorts {
+                disc_port: e.port(),
+                p2p_port: mapped_ports.map_or(identity.p2p_port, |p| p.p2p_port),
+            };
+        }
+
+        if let Some(ports) = mapped_ports {
+            return ports;
+        }
+
+        let disc_port = match self.get_observed_endpoint().await {
+            Some(e) => e.port(),
+            None => identity.disc_port,
+        };
+
+        ExternalPorts {
+            disc_port,
+            p2p_port: identity.p2p_port,
+        }
+    }
+
+    pub async fn get_disc_endpoint(&self, identity: &Identity) -> Option<String> {
+        let ip = self.get_ip().await?;
+        let ports = self.get_advertised_ports(identity).await;
+
+        Some(SocketAddr::new(ip, ports.disc_port).to_string())
+    }
+}
+
+// An endpoint wins if it is reported by the majority of peers, and by no
+// fewer than MIN_EXTERNAL_ADDR_VOTES of them
+fn get_majority_endpoint(observations: &HashMap<String, (SocketAddr, u64)>) -> Option<SocketAddr> {
+    let mut votes: HashMap<SocketAddr, usize> = HashMap::new();
+
+    for (endpoint, _) in observations.values() {
+        *votes.entry(*endpoint).or_insert(0) += 1;
+    }
+
+    votes
+        .into_iter()
+        .filter(|(_, count)| *count >= MIN_EXTERNAL_ADDR_VOTES)
+        .filter(|(_, count)| count * 2 > observations.len())
+        .map(|(endpoint, _)| endpoint)
+        .next()
+}
@@ -0,0 +1,9 @@
+mod external;
+mod natpmp;
+mod port_map;
+mod upnp;
+
+pub use external::*;
+pub(crate) use natpmp::*;
+pub(crate) use port_map::*;
+pub(crate) use upnp::*;
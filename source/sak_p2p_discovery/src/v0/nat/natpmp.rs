@@ -0,0 +1,133 @@
+use super::PortMapProtocol;
+use crate::P2PDiscError;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+pub(crate) const NAT_PMP_PORT: u16 = 5351;
+
+const NAT_PMP_VERSION: u8 = 0;
+const OP_EXTERNAL_ADDR: u8 = 0;
+const OP_MAP_UDP: u8 = 1;
+const OP_MAP_TCP: u8 = 2;
+// Opcode of a response is the opcode of the request plus 128
+const OP_RESPONSE: u8 = 128;
+
+// Requests are retried, doubling the timeout each time (RFC 6886)
+const REQUEST_RETRY_COUNT: u32 = 4;
+const REQUEST_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Gateway speaking NAT-PMP (RFC 6886).
+pub(crate) struct NatPmpGateway {
+    pub(crate) gateway_addr: SocketAddr,
+}
+
+impl NatPmpGateway {
+    pub(crate) async fn get_external_ip(&self) -> Result<IpAddr, P2PDiscError> {
+        let res = self
+            .request(&[NAT_PMP_VERSION, OP_EXTERNAL_ADDR], OP_EXTERNAL_ADDR, 12)
+            .await?;
+
+        let ip = Ipv4Addr::new(res[8], res[9], res[10], res[11]);
+
+        Ok(IpAddr::V4(ip))
+    }
+
+    /// Returns the external port the gateway has actually mapped, which
+    /// may differ from the one suggested.
+    pub(crate) async fn add_port_mapping(
+        &self,
+        protocol: PortMapProtocol,
+        internal_port: u16,
+        external_port: u16,
+        lifetime_sec: u32,
+    ) -> Result<u16, P2PDiscError> {
+        let op = match protocol {
+            PortMapProtocol::Udp => OP_MAP_UDP,
+            PortMapProtocol::Tcp => OP_MAP_TCP,
+        };
+
+        let req = [
+            &[NAT_PMP_VERSION, op, 0, 0][..],
+            &internal_port.to_be_bytes()[..],
+            &external_port.to_be_bytes()[..],
+            &lifetime_sec.to_be_bytes()[..],
+        ]
+        .concat();
+
+        let res = self.request(&req, op, 16).await?;
+
+        let mapped_port = u16::from_be_bytes([res[10], res[11]]);
+
+        Ok(mapped_port)
+    }
+
+    /// Mapping is removed by asking for it again with no lifetime and no
+    /// external port.
+    pub(crate) async fn delete_port_mapping(
+        &self,
+        protocol: PortMapProtocol,
+        internal_port: u16,
+    ) -> Result<(), P2PDiscError> {
+        self.add_port_mapping(protocol, internal_port, 0, 0).await?;
+
+        Ok(())
+    }
+
+    async fn request(&self, req: &[u8], op: u8, res_len: usize) -> Result<Vec<u8>, P2PDiscError> {
+        let socket = UdpSocket::bind("0.0.0.0:0").await?;
+        socket.connect(self.gateway_addr).await?;
+
+        let mut buf = [0; 64];
+        let mut timeout = REQUEST_TIMEOUT;
+
+        for _ in 0..REQUEST_RETRY_COUNT {
+            socket.send(req).await?;
+
+            let len = match tokio::time::timeout(timeout, socket.recv(&mut buf)).await {
+                Ok(r) => r?,
+                Err(_) => {
+                    timeout *= 2;
+                    continue;
+                }
+            };
+
+            if len < res_len || buf[0] != NAT_PMP_VERSION || buf[1] != op + OP_RESPONSE {
+                return Err(format!("Malformed nat-pmp response, len: {}", len).into());
+            }
+
+            let result_code = u16::from_be_bytes([buf[2], buf[3]]);
+
+            if result_code != 0 {
+                return Err(format!("Nat-pmp request failed, result_code: {}", result_code).into());
+            }
+
+            return Ok(buf[..len].to_vec());
+        }
+
+        Err(format!(
+            "Nat-pmp gateway did not answer, gateway_addr: {}",
+            self.gateway_addr
+        )
+        .into())
+    }
+}
+
+/// Default gateway of the host, read from the routing table. Only Linux
+/// is supported.
+pub(crate) fn find_default_gateway() -> Option<IpAddr> {
+    let routes = std::fs::read_to_string("/proc/net/route").ok()?;
+
+    routes.lines().skip(1).find_map(|line| {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+
+        // Destination 0.0.0.0 is the default route
+        if cols.len() < 3 || cols[1] != "00000000" {
+            return None;
+        }
+
+        let gateway = u32::from_str_radix(cols[2], 16).ok()?;
+
+        Some(IpAddr::V4(Ipv4Addr::from(gateway.to_le_bytes())))
+    })
+}
@@ -0,0 +1,225 @@
+use super::{ExternalAddr, ExternalPorts, IgdGateway, NatPmpGateway};
+use crate::P2PDiscError;
+use colored::Colorize;
+use sak_logger::{info, warn};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+const PORT_MAPPING_LEASE_SEC: u32 = 3600;
+
+// Mappings are renewed well before their lease runs out
+const PORT_MAPPING_RENEW_INTERVAL: Duration = Duration::from_secs(1800);
+
+const SSDP_SEARCH_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum PortMapProtocol {
+    Tcp,
+    Udp,
+}
+
+impl std::fmt::Display for PortMapProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PortMapProtocol::Tcp => write!(f, "TCP"),
+            PortMapProtocol::Udp => write!(f, "UDP"),
+        }
+    }
+}
+
+// Gateway the ports have been mapped at
+pub(crate) enum MappedGateway {
+    Upnp(IgdGateway),
+    NatPmp(NatPmpGateway),
+}
+
+/// Maps the disc (udp) and p2p (tcp) ports at the gateway, trying UPnP
+/// first and NAT-PMP next.
+pub(crate) struct PortMapper {
+    pub(crate) local_ports: ExternalPorts,
+    pub(crate) external_addr: Arc<ExternalAddr>,
+    pub(crate) ssdp_addr: SocketAddr,
+    // Not known if the default gateway could not be found
+    pub(crate) nat_pmp_addr: Option<SocketAddr>,
+    pub(crate) mapped_gateway: RwLock<Option<MappedGateway>>,
+}
+
+impl PortMapper {
+    pub(crate) fn new(
+        local_ports: ExternalPorts,
+        external_addr: Arc<ExternalAddr>,
+        ssdp_addr: SocketAddr,
+        nat_pmp_addr: Option<SocketAddr>,
+    ) -> PortMapper {
+        PortMapper {
+            local_ports,
+            external_addr,
+            ssdp_addr,
+            nat_pmp_addr,
+            mapped_gateway: RwLock::new(None),
+        }
+    }
+
+    pub(crate) async fn map_ports(&self) -> Result<ExternalPorts, P2PDiscError> {
+        let upnp_err = match self.map_ports_upnp().await {
+            Ok(p) => return Ok(p),
+            Err(err) => err,
+        };
+
+        let nat_pmp_err = match self.map_ports_nat_pmp().await {
+            Ok(p) => return Ok(p),
+            Err(err) => err,
+        };
+
+        Err(format!(
+            "Could not map ports, upnp_err: {}, nat_pmp_err: {}",
+            upnp_err, nat_pmp_err,
+        )
+        .into())
+    }
+
+    async fn map_ports_upnp(&self) -> Result<ExternalPorts, P2PDiscError> {
+        let gateway = IgdGateway::search(self.ssdp_addr, SSDP_SEARCH_TIMEOUT).await?;
+
+        let ExternalPorts {
+            disc_port,
+            p2p_port,
+        } = self.local_ports;
+
+        gateway
+            .add_port_mapping(
+                PortMapProtocol::Udp,
+                disc_port,
+                disc_port,
+                PORT_MAPPING_LEASE_SEC,
+            )
+            .await?;
+
+        gateway
+            .add_port_mapping(
+                PortMapProtocol::Tcp,
+                p2p_port,
+                p2p_port,
+                PORT_MAPPING_LEASE_SEC,
+            )
+            .await?;
+
+        let gateway_ip = match gateway.get_external_ip().await {
+            Ok(ip) => Some(ip),
+            Err(err) => {
+                warn!("Could not get external ip from the gateway, err: {}", err);
+
+                None
+            }
+        };
+
+        self.external_addr
+            .set_mapping(gateway_ip, self.local_ports)
+            .await;
+
+        *self.mapped_gateway.write().await = Some(MappedGateway::Upnp(gateway));
+
+        Ok(self.local_ports)
+    }
+
+    async fn map_ports_nat_pmp(&self) -> Result<ExternalPorts, P2PDiscError> {
+        let gateway = match self.nat_pmp_addr {
+            Some(a) => NatPmpGateway { gateway_addr: a },
+            None => return Err("Default gateway is not known".into()),
+        };
+
+        let disc_port = gateway
+            .add_port_mapping(
+                PortMapProtocol::Udp,
+                self.local_ports.disc_port,
+                self.local_ports.disc_port,
+                PORT_MAPPING_LEASE_SEC,
+            )
+            .await?;
+
+        let p2p_port = gateway
+            .add_port_mapping(
+                PortMapProtocol::Tcp,
+                self.local_ports.p2p_port,
+                self.local_ports.p2p_port,
+                PORT_MAPPING_LEASE_SEC,
+            )
+            .await?;
+
+        let ports = ExternalPorts {
+            disc_port,
+            p2p_port,
+        };
+
+        let gateway_ip = match gateway.get_external_ip().await {
+            Ok(ip) => Some(ip),
+            Err(err) => {
+                warn!("Could not get external ip from the gateway, err: {}", err);
+
+                None
+            }
+        };
+
+        self.external_addr.set_mapping(gateway_ip, ports).await;
+
+        *self.mapped_gateway.write().await = Some(MappedGateway::NatPmp(gateway));
+
+        Ok(ports)
+    }
+
+    /// Removes the mappings made at the gateway, if any.
+    pub(crate) async fn unmap_ports(&self) -> Result<(), P2PDiscError> {
+        let mapped_gateway = match self.mapped_gateway.write().await.take() {
+            Some(g) => g,
+            None => return Ok(()),
+        };
+
+        self.external_addr.clear_mapping().await;
+
+        match mapped_gateway {
+            MappedGateway::Upnp(gateway) => {
+                gateway
+                    .delete_port_mapping(PortMapProtocol::Udp, self.local_ports.disc_port)
+                    .await?;
+
+                gateway
+                    .delete_port_mapping(PortMapProtocol::Tcp, self.local_ports.p2p_port)
+                    .await?;
+            }
+            MappedGateway::NatPmp(gateway) => {
+                gateway
+                    .delete_port_mapping(PortMapProtocol::Udp, self.local_ports.disc_port)
+                    .await?;
+
+                gateway
+                    .delete_port_mapping(PortMapProtocol::Tcp, self.local_ports.p2p_port)
+                    .await?;
+            }
+        };
+
+        Ok(())
+    }
+
+    pub(crate) async fn run(&self) {
+        loop {
+            match self.map_ports().await {
+                Ok(ports) => {
+                    info!(
+                        "Mapped ports at the gateway, disc_port: {}, p2p_port: {}",
+                        ports.disc_port.to_string().yellow(),
+                        ports.p2p_port.to_string().yellow(),
+                    );
+                }
+                Err(err) => {
+                    warn!("Port mapping failed, err: {}", err);
+
+                    self.external_addr.clear_mapping().await;
+                }
+            };
+
+            tokio::time::sleep(PORT_MAPPING_RENEW_INTERVAL).await;
+        }
+    }
+}
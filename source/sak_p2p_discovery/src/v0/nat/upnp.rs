@@ -0,0 +1,280 @@
+use super::PortMapProtocol;
+use crate::P2PDiscError;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, UdpSocket};
+
+pub(crate) const SSDP_MULTICAST_ADDR: &str = "239.255.255.250:1900";
+
+const IGD_SEARCH_TARGET: &str = "urn:schemas-upnp-org:device:InternetGatewayDevice:1";
+
+const WAN_SERVICE_TYPES: [&str; 2] = [
+    "urn:schemas-upnp-org:service:WANIPConnection:1",
+    "urn:schemas-upnp-org:service:WANPPPConnection:1",
+];
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+const PORT_MAPPING_DESCRIPTION: &str = "saksaha";
+
+/// Internet gateway device found through SSDP, controlled with SOAP calls
+/// to its WAN connection service.
+pub(crate) struct IgdGateway {
+    pub(crate) control_url: String,
+    pub(crate) service_type: String,
+    // Our ip in the network of the gateway
+    pub(crate) local_ip: IpAddr,
+}
+
+impl IgdGateway {
+    pub(crate) async fn search(
+        ssdp_addr: SocketAddr,
+        timeout: Duration,
+    ) -> Result<IgdGateway, P2PDiscError> {
+        let socket = UdpSocket::bind("0.0.0.0:0").await?;
+
+        let m_search = format!(
+            "M-SEARCH * HTTP/1.1\r\n\
+            HOST: {}\r\n\
+            MAN: \"ssdp:discover\"\r\n\
+            MX: 2\r\n\
+            ST: {}\r\n\r\n",
+            ssdp_addr, IGD_SEARCH_TARGET,
+        );
+
+        socket.send_to(m_search.as_bytes(), ssdp_addr).await?;
+
+        let mut buf = [0; 2048];
+
+        let (len, gateway_addr) =
+            match tokio::time::timeout(timeout, socket.recv_from(&mut buf)).await {
+                Ok(r) => r?,
+                Err(_) => return Err("No internet gateway device answered ssdp search".into()),
+            };
+
+        let res = String::from_utf8_lossy(&buf[..len]);
+
+        let location = match get_header(&res, "location") {
+            Some(l) => l,
+            None => return Err("ssdp response does not have location".into()),
+        };
+
+        let (_, desc) = http_request(&location, "GET", &[], "").await?;
+
+        let (service_type, control_path) = match find_wan_service(&desc) {
+            Some(s) => s,
+            None => return Err("Gateway does not have a WAN connection service".into()),
+        };
+
+        let control_url = if control_path.starts_with("http") {
+            control_path
+        } else {
+            let (host, _) = split_url(&location)?;
+
+            match control_path.starts_with('/') {
+                true => format!("http://{}{}", host, control_path),
+                false => format!("http://{}/{}", host, control_path),
+            }
+        };
+
+        let local_ip = {
+            let s = UdpSocket::bind("0.0.0.0:0").await?;
+            s.connect(gateway_addr).await?;
+            s.local_addr()?.ip()
+        };
+
+        Ok(IgdGateway {
+            control_url,
+            service_type,
+            local_ip,
+        })
+    }
+
+    pub(crate) async fn get_external_ip(&self) -> Result<IpAddr, P2PDiscError> {
+        let res = self.call("GetExternalIPAddress", &[]).await?;
+
+        match get_tag(&res, "NewExternalIPAddress") {
+            Some(ip) => Ok(ip.trim().parse()?),
+            None => Err("Gateway did not answer the external ip address".into()),
+        }
+    }
+
+    pub(crate) async fn add_port_mapping(
+        &self,
+        protocol: PortMapProtocol,
+        internal_port: u16,
+        external_port: u16,
+        lease_sec: u32,
+    ) -> Result<(), P2PDiscError> {
+        let args = [
+            ("NewRemoteHost", String::default()),
+            ("NewExternalPort", external_port.to_string()),
+            ("NewProtocol", protocol.to_string()),
+            ("NewInternalPort", internal_port.to_string()),
+            ("NewInternalClient", self.local_ip.to_string()),
+            ("NewEnabled", "1".to_string()),
+            (
+                "NewPortMappingDescription",
+                PORT_MAPPING_DESCRIPTION.to_string(),
+            ),
+            ("NewLeaseDuration", lease_sec.to_string()),
+        ];
+
+        self.call("AddPortMapping", &args).await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn delete_port_mapping(
+        &self,
+        protocol: PortMapProtocol,
+        external_port: u16,
+    ) -> Result<(), P2PDiscError> {
+        let args = [
+            ("NewRemoteHost", String::default()),
+            ("NewExternalPort", external_port.to_string()),
+            ("NewProtocol", protocol.to_string()),
+        ];
+
+        self.call("DeletePortMapping", &args).await?;
+
+        Ok(())
+    }
+
+    async fn call(&self, action: &str, args: &[(&str, String)]) -> Result<String, P2PDiscError> {
+        let args = args
+            .iter()
+            .map(|(k, v)| format!("<{}>{}</{}>", k, v, k))
+            .collect::<String>();
+
+        let body = format!(
+            "<?xml version=\"1.0\"?>\
+            <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+            s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+            <s:Body><u:{} xmlns:u=\"{}\">{}</u:{}></s:Body></s:Envelope>",
+            action, self.service_type, args, action,
+        );
+
+        let headers = [
+            ("Content-Type", "text/xml; charset=\"utf-8\"".to_string()),
+            (
+                "SOAPAction",
+                format!("\"{}#{}\"", self.service_type, action),
+            ),
+        ];
+
+        let (status, res) = http_request(&self.control_url, "POST", &headers, &body).await?;
+
+        if status != 200 {
+            let desc = get_tag(&res, "errorDescription").unwrap_or_default();
+
+            return Err(format!(
+                "Gateway refused {}, status: {}, err: {}",
+                action, status, desc,
+            )
+            .into());
+        }
+
+        Ok(res)
+    }
+}
+
+// Requests with HTTP/1.0 so that the response is never chunked
+async fn http_request(
+    url: &str,
+    method: &str,
+    headers: &[(&str, String)],
+    body: &str,
+) -> Result<(u16, String), P2PDiscError> {
+    let (host, path) = split_url(url)?;
+
+    let mut req = format!("{} {} HTTP/1.0\r\nHost: {}\r\n", method, path, host);
+
+    for (k, v) in headers {
+        req.push_str(&format!("{}: {}\r\n", k, v));
+    }
+
+    req.push_str(&format!("Content-Length: {}\r\n\r\n{}", body.len(), body));
+
+    let res = tokio::time::timeout(HTTP_TIMEOUT, async {
+        let mut stream = TcpStream::connect(&host).await?;
+        stream.write_all(req.as_bytes()).await?;
+
+        let mut res = vec![];
+        stream.read_to_end(&mut res).await?;
+
+        Ok::<_, std::io::Error>(res)
+    })
+    .await;
+
+    let res = match res {
+        Ok(r) => String::from_utf8_lossy(&r?).to_string(),
+        Err(_) => return Err(format!("Http request timed out, url: {}", url).into()),
+    };
+
+    let (head, body) = match res.split_once("\r\n\r\n") {
+        Some(r) => r,
+        None => return Err("Malformed http response".into()),
+    };
+
+    let status = match head.split_whitespace().nth(1) {
+        Some(s) => s.parse::<u16>()?,
+        None => return Err("Http response does not have a status".into()),
+    };
+
+    Ok((status, body.to_string()))
+}
+
+// Splits "http://host:port/path" into the host and the path
+fn split_url(url: &str) -> Result<(String, String), P2PDiscError> {
+    let rest = match url.strip_prefix("http://") {
+        Some(r) => r,
+        None => return Err(format!("Only http url is supported, url: {}", url).into()),
+    };
+
+    let (host, path) = match rest.find('/') {
+        Some(idx) => (&rest[..idx], &rest[idx..]),
+        None => (rest, "/"),
+    };
+
+    let host = match host.contains(':') {
+        true => host.to_string(),
+        false => format!("{}:80", host),
+    };
+
+    Ok((host, path.to_string()))
+}
+
+fn get_header(res: &str, name: &str) -> Option<String> {
+    res.lines().find_map(|line| {
+        let (k, v) = line.split_once(':')?;
+
+        match k.trim().eq_ignore_ascii_case(name) {
+            true => Some(v.trim().to_string()),
+            false => None,
+        }
+    })
+}
+
+fn get_tag(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+
+    let start = xml.find(&open)? + open.len();
+    let end = xml[start..].find(&close)? + start;
+
+    Some(xml[start..end].to_string())
+}
+
+// Finds the WAN connection service of the device description, returning
+// its service type and control url
+fn find_wan_service(desc: &str) -> Option<(String, String)> {
+    WAN_SERVICE_TYPES.iter().find_map(|service_type| {
+        let service = format!("<serviceType>{}</serviceType>", service_type);
+        let idx = desc.find(&service)?;
+        let control_url = get_tag(&desc[idx..], "controlURL")?;
+
+        Some((service_type.to_string(), control_url))
+    })
+}
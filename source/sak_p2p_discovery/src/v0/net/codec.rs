@@ -1,4 +1,8 @@
-use crate::{whoareyou::WhoAreYou, Msg, P2PDiscError};
+use crate::{
+    findnode::{FindNode, Nodes},
+    whoareyou::WhoAreYou,
+    Msg, P2PDiscError,
+};
 use bytes::BytesMut;
 use sak_p2p_frame::{frame_io, Parse};
 use std::error::Error;
@@ -47,6 +51,49 @@ impl Encoder<Msg> for UdpCodec {
                     }
                 };
 
+                return Ok(());
+            }
+            Msg::WhoAreYouFin(way) => {
+                let frame = match way.into_fin_frame() {
+                    Ok(f) => f,
+                    Err(err) => {
+                        return Err(format!("Error creating whoareyou frame, err: {}", err).into());
+                    }
+                };
+
+                match frame_io::write_frame(dst, &frame) {
+                    Ok(_) => (),
+                    Err(err) => {
+                        return Err(
+                            format!("Error writing who_are_you_fin_frame, err: {}", err).into()
+                        );
+                    }
+                };
+
+                return Ok(());
+            }
+            Msg::FindNode(find_node) => {
+                let frame = find_node.into_frame();
+
+                match frame_io::write_frame(dst, &frame) {
+                    Ok(_) => (),
+                    Err(err) => {
+                        return Err(format!("Error writing find_node_frame, err: {}", err).into());
+                    }
+                };
+
+                return Ok(());
+            }
+            Msg::Nodes(nodes) => {
+                let frame = nodes.into_frame();
+
+                match frame_io::write_frame(dst, &frame) {
+                    Ok(_) => (),
+                    Err(err) => {
+                        return Err(format!("Error writing nodes_frame, err: {}", err).into());
+                    }
+                };
+
                 return Ok(());
             }
         }
@@ -88,6 +135,36 @@ impl Decoder for UdpCodec {
 
                     return Ok(Some(Msg::WhoAreYouAck(way)));
                 }
+                "way_fin" => {
+                    let way = match WhoAreYou::parse_frames(&mut parse) {
+                        Ok(w) => w,
+                        Err(err) => {
+                            return Err(format!("Error creating who_are_you, err: {}", err).into());
+                        }
+                    };
+
+                    return Ok(Some(Msg::WhoAreYouFin(way)));
+                }
+                "find_node" => {
+                    let find_node = match FindNode::parse_frames(&mut parse) {
+                        Ok(f) => f,
+                        Err(err) => {
+                            return Err(format!("Error creating find_node, err: {}", err).into());
+                        }
+                    };
+
+                    return Ok(Some(Msg::FindNode(find_node)));
+                }
+                "nodes" => {
+                    let nodes = match Nodes::parse_frames(&mut parse) {
+                        Ok(n) => n,
+                        Err(err) => {
+                            return Err(format!("Error creating nodes, err: {}", err).into());
+                        }
+                    };
+
+                    return Ok(Some(Msg::Nodes(nodes)));
+                }
                 _ => {
                     return Err(format!(
                         "Msg type is unknown, cannot parse, msg_type: {}",
@@ -1,20 +1,30 @@
 use super::UdpCodec;
-use crate::Msg;
+use crate::{ExternalAddr, Msg};
+use chrono::{DateTime, Utc};
 use futures::{
     stream::{SplitSink, SplitStream},
     StreamExt,
 };
+use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tokio::{net::UdpSocket, sync::RwLock};
 use tokio_util::udp::UdpFramed;
 
 pub(crate) struct Connection {
     pub(crate) tx: RwLock<SplitSink<UdpFramed<UdpCodec>, (Msg, SocketAddr)>>,
     pub(crate) rx: RwLock<SplitStream<UdpFramed<UdpCodec>>>,
+    // Nonces of who_are_you_syn sent, by the disc endpoint, waiting for ack
+    pub(crate) way_challenges: RwLock<HashMap<String, [u8; 32]>>,
+    // Nonces of who_are_you_ack sent, by the socket addr, waiting for fin
+    pub(crate) way_acks: RwLock<HashMap<String, ([u8; 32], DateTime<Utc>)>>,
+    // find_node sent, by nonce, with the public key of the node asked
+    pub(crate) find_node_requests: RwLock<HashMap<[u8; 32], (String, DateTime<Utc>)>>,
+    pub(crate) external_addr: Arc<ExternalAddr>,
 }
 
 impl Connection {
-    pub(crate) fn new(socket: UdpSocket) -> Connection {
+    pub(crate) fn new(socket: UdpSocket, external_addr: Arc<ExternalAddr>) -> Connection {
         let udp_codec = UdpCodec {};
 
         let (tx, rx) = {
@@ -24,6 +34,13 @@ impl Connection {
             (RwLock::new(tx), RwLock::new(rx))
         };
 
-        Connection { tx, rx }
+        Connection {
+            tx,
+            rx,
+            way_challenges: RwLock::new(HashMap::new()),
+            way_acks: RwLock::new(HashMap::new()),
+            find_node_requests: RwLock::new(HashMap::new()),
+            external_addr,
+        }
     }
 }
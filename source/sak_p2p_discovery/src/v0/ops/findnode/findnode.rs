@@ -0,0 +1,236 @@
+use crate::{
+    v0::ops::msg_type::{FIND_NODE_TYPE, NODES_TYPE},
+    whoareyou::parse_nonce,
+    NodeId, P2PDiscError,
+};
+use bytes::Bytes;
+use sak_crypto::{Signature, SigningKey};
+use sak_p2p_frame::{Frame, Parse};
+use sak_p2p_id::Identity;
+
+const FIND_NODE_SIG_LABEL: &[u8] = b"saksaha_find_node";
+
+/// Max number of nodes in a single `nodes` msg, so that it fits in a
+/// datagram.
+pub(crate) const MAX_NODES_PER_MSG: usize = 8;
+
+pub(crate) struct FindNode {
+    pub(crate) src_public_key_str: String,
+    pub(crate) target: NodeId,
+    pub(crate) nonce: [u8; 32],
+    pub(crate) sig: Signature,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct NodeRecord {
+    pub(crate) public_key_str: String,
+    pub(crate) ip: String,
+    pub(crate) disc_port: u16,
+    pub(crate) p2p_port: u16,
+}
+
+pub(crate) struct Nodes {
+    pub(crate) src_public_key_str: String,
+    // Nonce of the find_node being answered
+    pub(crate) echo_nonce: [u8; 32],
+    pub(crate) records: Vec<NodeRecord>,
+    pub(crate) sig: Signature,
+}
+
+impl FindNode {
+    pub(crate) fn new_signed(identity: &Identity, target: NodeId) -> FindNode {
+        let src_public_key_str = identity.credential.public_key_str.clone();
+        let nonce = sak_crypto::rand_bytes_32();
+
+        let sig_data = [
+            FIND_NODE_SIG_LABEL,
+            FIND_NODE_TYPE.as_bytes(),
+            src_public_key_str.as_bytes(),
+            &target[..],
+            &nonce[..],
+        ]
+        .concat();
+
+        let sig = sign(identity, &sig_data);
+
+        FindNode {
+            src_public_key_str,
+            target,
+            nonce,
+            sig,
+        }
+    }
+
+    pub(crate) fn verify(&self) -> Result<(), String> {
+        let sig_data = [
+            FIND_NODE_SIG_LABEL,
+            FIND_NODE_TYPE.as_bytes(),
+            self.src_public_key_str.as_bytes(),
+            &self.target[..],
+            &self.nonce[..],
+        ]
+        .concat();
+
+        sak_crypto::verify_with_public_key_str(&self.src_public_key_str, &sig_data, &self.sig)
+    }
+
+    pub(crate) fn into_frame(&self) -> Frame {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from(FIND_NODE_TYPE.as_bytes()));
+        frame.push_bulk(Bytes::from(self.src_public_key_str.clone()));
+        frame.push_bulk(Bytes::copy_from_slice(&self.target));
+        frame.push_bulk(Bytes::copy_from_slice(&self.nonce));
+        frame.push_bulk(Bytes::copy_from_slice(self.sig.to_der().as_bytes()));
+
+        frame
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<FindNode, P2PDiscError> {
+        let src_public_key_str = parse_string(parse)?;
+
+        let target = parse_nonce(parse)?;
+
+        let nonce = parse_nonce(parse)?;
+
+        let sig = parse_sig(parse)?;
+
+        parse.finish()?;
+
+        Ok(FindNode {
+            src_public_key_str,
+            target,
+            nonce,
+            sig,
+        })
+    }
+}
+
+impl Nodes {
+    pub(crate) fn new_signed(
+        identity: &Identity,
+        echo_nonce: [u8; 32],
+        records: Vec<NodeRecord>,
+    ) -> Nodes {
+        let src_public_key_str = identity.credential.public_key_str.clone();
+
+        let sig_data = make_nodes_sig_data(&src_public_key_str, &echo_nonce, &records);
+
+        let sig = sign(identity, &sig_data);
+
+        Nodes {
+            src_public_key_str,
+            echo_nonce,
+            records,
+            sig,
+        }
+    }
+
+    pub(crate) fn verify(&self) -> Result<(), String> {
+        let sig_data =
+            make_nodes_sig_data(&self.src_public_key_str, &self.echo_nonce, &self.records);
+
+        sak_crypto::verify_with_public_key_str(&self.src_public_key_str, &sig_data, &self.sig)
+    }
+
+    pub(crate) fn into_frame(&self) -> Frame {
+        let mut frame = Frame::array();
+        frame.push_bulk(Bytes::from(NODES_TYPE.as_bytes()));
+        frame.push_bulk(Bytes::from(self.src_public_key_str.clone()));
+        frame.push_bulk(Bytes::copy_from_slice(&self.echo_nonce));
+        frame.push_int(self.records.len() as u128);
+
+        for r in self.records.iter() {
+            frame.push_bulk(Bytes::from(r.public_key_str.clone()));
+            frame.push_bulk(Bytes::from(r.ip.clone()));
+            frame.push_int(r.disc_port as u128);
+            frame.push_int(r.p2p_port as u128);
+        }
+
+        frame.push_bulk(Bytes::copy_from_slice(self.sig.to_der().as_bytes()));
+
+        frame
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> Result<Nodes, P2PDiscError> {
+        let src_public_key_str = parse_string(parse)?;
+
+        let echo_nonce = parse_nonce(parse)?;
+
+        let record_count = parse.next_int()? as usize;
+
+        if record_count > MAX_NODES_PER_MSG {
+            return Err(format!("Too many nodes in a msg, count: {}", record_count).into());
+        }
+
+        let mut records = Vec::with_capacity(record_count);
+
+        for _ in 0..record_count {
+            let public_key_str = parse_string(parse)?;
+            let ip = parse_string(parse)?;
+            let disc_port = parse.next_int()? as u16;
+            let p2p_port = parse.next_int()? as u16;
+
+            records.push(NodeRecord {
+                public_key_str,
+                ip,
+                disc_port,
+                p2p_port,
+            });
+        }
+
+        let sig = parse_sig(parse)?;
+
+        parse.finish()?;
+
+        Ok(Nodes {
+            src_public_key_str,
+            echo_nonce,
+            records,
+            sig,
+        })
+    }
+}
+
+fn sign(identity: &Identity, data: &[u8]) -> Signature {
+    let signing_key = SigningKey::from(&identity.credential.secret_key);
+
+    sak_crypto::make_signature(signing_key, data)
+}
+
+fn make_nodes_sig_data(
+    src_public_key_str: &String,
+    echo_nonce: &[u8; 32],
+    records: &Vec<NodeRecord>,
+) -> Vec<u8> {
+    let mut data = [
+        FIND_NODE_SIG_LABEL,
+        NODES_TYPE.as_bytes(),
+        src_public_key_str.as_bytes(),
+        &echo_nonce[..],
+    ]
+    .concat();
+
+    for r in records.iter() {
+        data.extend_from_slice(r.public_key_str.as_bytes());
+        data.extend_from_slice(r.ip.as_bytes());
+        data.extend_from_slice(&r.disc_port.to_be_bytes());
+        data.extend_from_slice(&r.p2p_port.to_be_bytes());
+    }
+
+    data
+}
+
+fn parse_string(parse: &mut Parse) -> Result<String, P2PDiscError> {
+    let b = parse.next_bytes()?;
+
+    Ok(String::from_utf8(b.to_vec())?)
+}
+
+fn parse_sig(parse: &mut Parse) -> Result<Signature, P2PDiscError> {
+    let b = parse.next_bytes()?;
+
+    match Signature::from_der(&b) {
+        Ok(s) => Ok(s),
+        Err(err) => Err(format!("Error parsing signature from byte array, err: {}", err).into()),
+    }
+}
@@ -0,0 +1,134 @@
+use super::{FindNode, Nodes, FIND_NODE_EXPIRATION_SEC};
+use crate::v0::task::DiscoveryTask;
+use crate::{AddrTable, Connection, DiscAddr, Msg, NodeId};
+use chrono::{Duration, Utc};
+use futures::SinkExt;
+use sak_logger::debug;
+use sak_p2p_addr::UnknownAddr;
+use sak_p2p_id::Identity;
+use sak_task_queue::TaskQueue;
+use std::{net::SocketAddr, sync::Arc};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub(crate) enum FindNodeInitError {
+    #[error("Can't send a message through udp socket, err: {err}")]
+    MsgSendFail { err: String },
+
+    #[error("Peer socket addr create fail, err: {err}")]
+    MalformedAddr { err: String },
+}
+
+pub(crate) async fn init_find_node(
+    addr: Arc<DiscAddr>,
+    target: NodeId,
+    identity: Arc<Identity>,
+    udp_conn: Arc<Connection>,
+) -> Result<(), FindNodeInitError> {
+    let her_disc_endpoint = addr.known_addr.get_disc_endpoint();
+
+    let her_socket_addr: SocketAddr = match her_disc_endpoint.parse() {
+        Ok(a) => a,
+        Err(err) => {
+            return Err(FindNodeInitError::MalformedAddr {
+                err: format!("{}, endpoint: {}", err, her_disc_endpoint),
+            });
+        }
+    };
+
+    let find_node = FindNode::new_signed(&identity, target);
+
+    {
+        let now = Utc::now();
+        let expiration = Duration::seconds(FIND_NODE_EXPIRATION_SEC);
+
+        let mut requests = udp_conn.find_node_requests.write().await;
+
+        requests.retain(|_, (_, at)| now.signed_duration_since(*at) < expiration);
+
+        requests.insert(
+            find_node.nonce,
+            (addr.known_addr.public_key_str.clone(), now),
+        );
+    }
+
+    let mut tx_lock = udp_conn.tx.write().await;
+
+    if let Err(err) = tx_lock
+        .send((Msg::FindNode(find_node), her_socket_addr))
+        .await
+    {
+        return Err(FindNodeInitError::MsgSendFail {
+            err: err.to_string(),
+        });
+    };
+
+    Ok(())
+}
+
+pub(crate) async fn handle_nodes(
+    nodes: Nodes,
+    udp_conn: Arc<Connection>,
+    identity: Arc<Identity>,
+    addr_table: Arc<AddrTable>,
+    disc_task_queue: Arc<TaskQueue<DiscoveryTask>>,
+) -> Result<(), String> {
+    // Nodes are valid only as an answer to the find_node we have sent
+    let (expected_public_key_str, _) = match udp_conn
+        .find_node_requests
+        .write()
+        .await
+        .remove(&nodes.echo_nonce)
+    {
+        Some(r) => r,
+        None => {
+            return Err(format!("nodes is not expected"));
+        }
+    };
+
+    if nodes.src_public_key_str != expected_public_key_str {
+        return Err(format!(
+            "nodes is sent by a node not asked, public_key: {}",
+            nodes.src_public_key_str
+        ));
+    }
+
+    nodes.verify()?;
+
+    // Answering proves liveness
+    addr_table.touch(&nodes.src_public_key_str).await;
+
+    let my_public_key_str = &identity.credential.public_key_str;
+
+    for record in nodes.records {
+        if record.public_key_str == *my_public_key_str {
+            continue;
+        }
+
+        if let Some(_) = addr_table.get_mapped_addr(&record.public_key_str).await {
+            continue;
+        }
+
+        let unknown_addr = UnknownAddr {
+            ip: record.ip,
+            disc_port: record.disc_port,
+            p2p_port: Some(record.p2p_port),
+            sig: None,
+            public_key_str: Some(record.public_key_str),
+            status: Default::default(),
+        };
+
+        debug!(
+            "Found a node through find_node, disc_endpoint: {}",
+            unknown_addr.disc_endpoint()
+        );
+
+        let task = DiscoveryTask::InitiateWhoAreYou { addr: unknown_addr };
+
+        if let Err(err) = disc_task_queue.push_back(task).await {
+            return Err(format!("Cannot enqueue a found node, err: {}", err));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,9 @@
+mod findnode;
+mod initiate;
+mod receive;
+
+pub(crate) use findnode::*;
+pub(crate) use initiate::*;
+pub(crate) use receive::*;
+
+pub(crate) const FIND_NODE_EXPIRATION_SEC: i64 = 30;
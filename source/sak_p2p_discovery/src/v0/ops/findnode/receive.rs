@@ -0,0 +1,62 @@
+use super::{FindNode, NodeRecord, Nodes, MAX_NODES_PER_MSG};
+use crate::{AddrTable, Connection, Msg};
+use futures::SinkExt;
+use sak_p2p_id::Identity;
+use std::{net::SocketAddr, sync::Arc};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub(crate) enum FindNodeRecvError {
+    #[error("Signature of find_node is invalid, err: {err}")]
+    InvalidSignature { err: String },
+
+    #[error("Can't send a message through udp socket, err: {err}")]
+    MsgSendFail { err: String },
+}
+
+pub(crate) async fn recv_find_node(
+    socket_addr: SocketAddr,
+    udp_conn: Arc<Connection>,
+    find_node: FindNode,
+    identity: Arc<Identity>,
+    addr_table: Arc<AddrTable>,
+) -> Result<(), FindNodeRecvError> {
+    if let Err(err) = find_node.verify() {
+        return Err(FindNodeRecvError::InvalidSignature { err });
+    }
+
+    let FindNode {
+        src_public_key_str: her_public_key_str,
+        target,
+        nonce: her_nonce,
+        ..
+    } = find_node;
+
+    addr_table.touch(&her_public_key_str).await;
+
+    let records = addr_table
+        .get_closest_addrs(&target, MAX_NODES_PER_MSG + 1)
+        .await
+        .iter()
+        .filter(|a| a.known_addr.public_key_str != her_public_key_str)
+        .take(MAX_NODES_PER_MSG)
+        .map(|a| NodeRecord {
+            public_key_str: a.known_addr.public_key_str.clone(),
+            ip: a.known_addr.ip.clone(),
+            disc_port: a.known_addr.disc_port,
+            p2p_port: a.known_addr.p2p_port,
+        })
+        .collect();
+
+    let nodes = Nodes::new_signed(&identity, her_nonce, records);
+
+    let mut tx_lock = udp_conn.tx.write().await;
+
+    if let Err(err) = tx_lock.send((Msg::Nodes(nodes), socket_addr)).await {
+        return Err(FindNodeRecvError::MsgSendFail {
+            err: err.to_string(),
+        });
+    }
+
+    Ok(())
+}
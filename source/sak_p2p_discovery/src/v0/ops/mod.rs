@@ -1,3 +1,4 @@
+pub(crate) mod findnode;
 mod msg;
 pub(crate) mod whoareyou;
 
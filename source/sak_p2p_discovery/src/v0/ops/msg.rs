@@ -1,11 +1,18 @@
+use super::findnode::{FindNode, Nodes};
 use super::whoareyou::WhoAreYou;
 
 pub(crate) mod msg_type {
     pub(crate) const WHO_ARE_YOU_SYN_TYPE: &str = "way_syn";
     pub(crate) const WHO_ARE_YOU_ACK_TYPE: &str = "way_ack";
+    pub(crate) const WHO_ARE_YOU_FIN_TYPE: &str = "way_fin";
+    pub(crate) const FIND_NODE_TYPE: &str = "find_node";
+    pub(crate) const NODES_TYPE: &str = "nodes";
 }
 
 pub(crate) enum Msg {
     WhoAreYouSyn(WhoAreYou),
     WhoAreYouAck(WhoAreYou),
+    WhoAreYouFin(WhoAreYou),
+    FindNode(FindNode),
+    Nodes(Nodes),
 }
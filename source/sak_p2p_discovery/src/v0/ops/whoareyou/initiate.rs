@@ -1,7 +1,9 @@
 use super::{check, WhoAreYou};
+use crate::v0::ops::msg_type::{WHO_ARE_YOU_ACK_TYPE, WHO_ARE_YOU_FIN_TYPE, WHO_ARE_YOU_SYN_TYPE};
 use crate::{AddrTable, Connection, DiscAddr, Msg};
 use chrono::Utc;
 use futures::SinkExt;
+use sak_logger::warn;
 use sak_p2p_addr::{AddrStatus, KnownAddr, UnknownAddr};
 use sak_p2p_id::Identity;
 use std::{net::SocketAddr, sync::Arc};
@@ -47,17 +49,21 @@ pub(crate) async fn init_who_are_you(
         }
     }
 
-    let src_disc_port = identity.disc_port;
-    let src_p2p_port = identity.p2p_port;
-    let src_sig = identity.credential.sig;
-    let src_public_key_str = identity.credential.public_key_str.clone();
+    let advertised_ports = udp_conn.external_addr.get_advertised_ports(&identity).await;
 
-    let way = WhoAreYou {
-        src_sig,
-        src_disc_port,
-        src_p2p_port,
-        src_public_key_str,
-    };
+    let way = WhoAreYou::new_signed(
+        &identity,
+        advertised_ports,
+        WHO_ARE_YOU_SYN_TYPE,
+        [0; 32],
+        String::default(),
+    );
+
+    udp_conn
+        .way_challenges
+        .write()
+        .await
+        .insert(her_disc_endpoint.to_string(), way.nonce);
 
     let mut tx_lock = udp_conn.tx.write().await;
 
@@ -85,16 +91,82 @@ pub(crate) async fn init_who_are_you(
 pub(crate) async fn handle_who_are_you_ack(
     way_ack: WhoAreYou,
     socket_addr: SocketAddr,
-    _udp_conn: Arc<Connection>,
+    udp_conn: Arc<Connection>,
+    identity: Arc<Identity>,
     addr_table: Arc<AddrTable>,
 ) -> Result<(), String> {
+    // Ack is valid only as an answer to the challenge we have sent
+    let challenge = match udp_conn
+        .way_challenges
+        .write()
+        .await
+        .remove(&socket_addr.to_string())
+    {
+        Some(c) => c,
+        None => {
+            return Err(format!(
+                "who_are_you_ack is not expected, socket_addr: {}",
+                socket_addr
+            ));
+        }
+    };
+
+    if way_ack.echo_nonce != challenge {
+        return Err(format!("who_are_you_ack does not answer the challenge"));
+    }
+
+    way_ack.verify(WHO_ARE_YOU_ACK_TYPE)?;
+
+    // She maps us only once we sign her nonce back, which proves that we
+    // own the key at this endpoint
+    {
+        let advertised_ports = udp_conn.external_addr.get_advertised_ports(&identity).await;
+
+        let way_fin = WhoAreYou::new_signed(
+            &identity,
+            advertised_ports,
+            WHO_ARE_YOU_FIN_TYPE,
+            way_ack.nonce,
+            socket_addr.to_string(),
+        );
+
+        let mut tx_lock = udp_conn.tx.write().await;
+
+        if let Err(err) = tx_lock
+            .send((Msg::WhoAreYouFin(way_fin), socket_addr))
+            .await
+        {
+            return Err(format!("Can't send who_are_you_fin, err: {}", err));
+        }
+    }
+
     let WhoAreYou {
         src_sig: her_sig,
         src_disc_port: her_disc_port,
         src_p2p_port: her_p2p_port,
         src_public_key_str: her_public_key_str,
+        observed_endpoint,
+        ..
     } = way_ack;
 
+    // She tells the endpoint our syn has come from, which is how we are
+    // seen from outside of the NAT
+    match observed_endpoint.parse::<SocketAddr>() {
+        Ok(e) => {
+            udp_conn
+                .external_addr
+                .record_observation(&her_public_key_str, e)
+                .await;
+        }
+        Err(_) => {
+            warn!(
+                "who_are_you_ack has malformed observed endpoint, \
+                observed_endpoint: {}",
+                observed_endpoint,
+            );
+        }
+    };
+
     if let Some(_) = addr_table.get_mapped_addr(&her_public_key_str).await {
         return Err(format!("Address is already mapped."));
     }
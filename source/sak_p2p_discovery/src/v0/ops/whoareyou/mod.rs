@@ -6,3 +6,5 @@ mod whoareyou;
 pub(crate) use initiate::*;
 pub(crate) use receive::*;
 pub(crate) use whoareyou::*;
+
+pub(crate) const WHO_ARE_YOU_EXPIRATION_SEC: i64 = 30;
@@ -1,6 +1,7 @@
-use super::{check, WhoAreYou};
+use super::{check, WhoAreYou, WHO_ARE_YOU_EXPIRATION_SEC};
+use crate::v0::ops::msg_type::{WHO_ARE_YOU_ACK_TYPE, WHO_ARE_YOU_FIN_TYPE, WHO_ARE_YOU_SYN_TYPE};
 use crate::{AddrTable, Connection, DiscAddr, Msg};
-use chrono::Utc;
+use chrono::{Duration, Utc};
 use futures::sink::SinkExt;
 use sak_logger::error;
 use sak_p2p_addr::{AddrStatus, KnownAddr};
@@ -9,6 +10,9 @@ use std::{net::SocketAddr, sync::Arc};
 use thiserror::Error;
 use tokio::sync::RwLock;
 
+// Handshakes answered with an ack and waiting for the fin
+const MAX_PENDING_WAY_ACKS: usize = 1024;
+
 #[derive(Error, Debug)]
 pub(crate) enum WhoAreYouRecvError {
     #[error("Will not proceed with disc_endpoint being myself")]
@@ -29,74 +33,69 @@ pub(crate) enum WhoAreYouRecvError {
     )]
     AddrAlreadyMapped { disc_endpoint: String },
 
+    #[error("Signature of who_are_you is invalid, err: {err}")]
+    InvalidSignature { err: String },
+
+    #[error("who_are_you_fin is not expected, socket_addr: {socket_addr}")]
+    FinNotExpected { socket_addr: String },
+
+    #[error("who_are_you_fin does not answer the challenge")]
+    ChallengeMismatch,
+
+    #[error("Too many who_are_you handshakes are pending")]
+    TooManyPendingHandshakes,
+
     #[error("Could not reserve addr slot")]
     AddrSlotReserveFail,
-
-    #[error("Could not parse her endpoint into SocketAddr, err: {err}")]
-    EndpointParseFail { err: String },
 }
 
+/// Answers the syn with an ack carrying a fresh nonce. Nothing is mapped
+/// until the peer signs that nonce back in the fin, as the syn itself could
+/// have been replayed from anywhere.
 pub(crate) async fn recv_who_are_you(
     socket_addr: SocketAddr,
     udp_conn: Arc<Connection>,
     way_syn: WhoAreYou,
-    // identity: Arc<DiscIdentity>,
     identity: Arc<Identity>,
     addr_table: Arc<AddrTable>,
 ) -> Result<(), WhoAreYouRecvError> {
-    let WhoAreYou {
-        src_sig: her_sig,
-        src_disc_port: her_disc_port,
-        src_p2p_port: her_p2p_port,
-        src_public_key_str: her_public_key_str,
-    } = way_syn;
+    if let Err(err) = way_syn.verify(WHO_ARE_YOU_SYN_TYPE) {
+        return Err(WhoAreYouRecvError::InvalidSignature { err });
+    }
 
-    let her_disc_endpoint =
-        sak_utils_net::make_endpoint(&socket_addr.ip().to_string(), her_disc_port);
+    check_peer(&socket_addr, &way_syn, &identity, &addr_table).await?;
 
-    if check::is_my_endpoint(identity.disc_port, &her_disc_endpoint) {
-        return Err(WhoAreYouRecvError::MyEndpoint);
-    }
+    let advertised_ports = udp_conn.external_addr.get_advertised_ports(&identity).await;
 
-    let slot_guard = match addr_table.get_mapped_addr(&her_public_key_str).await {
-        Some(_) => {
-            return Err(WhoAreYouRecvError::AddrAlreadyMapped {
-                disc_endpoint: her_disc_endpoint.to_string(),
-            });
-        }
-        None => match addr_table.get_empty_slot().await {
-            Ok(s) => s,
-            Err(_) => {
-                return Err(WhoAreYouRecvError::AddrSlotReserveFail);
-            }
-        },
-    };
+    // Ack goes back to where the syn has come from, as the NAT in front of
+    // her only lets that through
+    let way_ack = WhoAreYou::new_signed(
+        &identity,
+        advertised_ports,
+        WHO_ARE_YOU_ACK_TYPE,
+        way_syn.nonce,
+        socket_addr.to_string(),
+    );
 
-    let my_disc_port = identity.disc_port;
-    let my_p2p_port = identity.p2p_port;
-    let my_sig = identity.credential.sig;
-    let my_public_key_str = identity.credential.public_key_str.clone();
+    {
+        let now = Utc::now();
+        let expiration = Duration::seconds(WHO_ARE_YOU_EXPIRATION_SEC);
 
-    let way_ack = WhoAreYou {
-        src_sig: my_sig,
-        src_disc_port: my_disc_port,
-        src_p2p_port: my_p2p_port,
-        src_public_key_str: my_public_key_str,
-    };
+        let mut way_acks = udp_conn.way_acks.write().await;
 
-    let mut tx_lock = udp_conn.tx.write().await;
+        way_acks.retain(|_, (_, at)| now.signed_duration_since(*at) < expiration);
 
-    let her_disc_socket_addr: SocketAddr = match her_disc_endpoint.parse() {
-        Ok(a) => a,
-        Err(err) => {
-            return Err(WhoAreYouRecvError::EndpointParseFail {
-                err: err.to_string(),
-            });
+        if way_acks.len() >= MAX_PENDING_WAY_ACKS {
+            return Err(WhoAreYouRecvError::TooManyPendingHandshakes);
         }
-    };
+
+        way_acks.insert(socket_addr.to_string(), (way_ack.nonce, now));
+    }
+
+    let mut tx_lock = udp_conn.tx.write().await;
 
     if let Err(err) = tx_lock
-        .send((Msg::WhoAreYouAck(way_ack), her_disc_socket_addr))
+        .send((Msg::WhoAreYouAck(way_ack), socket_addr))
         .await
     {
         return Err(WhoAreYouRecvError::MsgSendFail {
@@ -104,6 +103,66 @@ pub(crate) async fn recv_who_are_you(
         });
     }
 
+    Ok(())
+}
+
+pub(crate) async fn handle_who_are_you_fin(
+    socket_addr: SocketAddr,
+    udp_conn: Arc<Connection>,
+    way_fin: WhoAreYou,
+    identity: Arc<Identity>,
+    addr_table: Arc<AddrTable>,
+) -> Result<(), WhoAreYouRecvError> {
+    // Fin is valid only as an answer to the ack we have sent to this addr
+    let challenge = match udp_conn
+        .way_acks
+        .write()
+        .await
+        .remove(&socket_addr.to_string())
+    {
+        Some((c, at)) => {
+            let expiration = Duration::seconds(WHO_ARE_YOU_EXPIRATION_SEC);
+
+            if Utc::now().signed_duration_since(at) >= expiration {
+                return Err(WhoAreYouRecvError::FinNotExpected {
+                    socket_addr: socket_addr.to_string(),
+                });
+            }
+
+            c
+        }
+        None => {
+            return Err(WhoAreYouRecvError::FinNotExpected {
+                socket_addr: socket_addr.to_string(),
+            });
+        }
+    };
+
+    if way_fin.echo_nonce != challenge {
+        return Err(WhoAreYouRecvError::ChallengeMismatch);
+    }
+
+    if let Err(err) = way_fin.verify(WHO_ARE_YOU_FIN_TYPE) {
+        return Err(WhoAreYouRecvError::InvalidSignature { err });
+    }
+
+    let her_disc_endpoint = check_peer(&socket_addr, &way_fin, &identity, &addr_table).await?;
+
+    let slot_guard = match addr_table.get_empty_slot().await {
+        Ok(s) => s,
+        Err(_) => {
+            return Err(WhoAreYouRecvError::AddrSlotReserveFail);
+        }
+    };
+
+    let WhoAreYou {
+        src_sig: her_sig,
+        src_disc_port: her_disc_port,
+        src_p2p_port: her_p2p_port,
+        src_public_key_str: her_public_key_str,
+        ..
+    } = way_fin;
+
     let her_public_key =
         match sak_crypto::convert_public_key_str_into_public_key(&her_public_key_str) {
             Ok(p) => p,
@@ -148,3 +207,27 @@ pub(crate) async fn recv_who_are_you(
 
     Ok(())
 }
+
+async fn check_peer(
+    socket_addr: &SocketAddr,
+    way: &WhoAreYou,
+    identity: &Identity,
+    addr_table: &AddrTable,
+) -> Result<String, WhoAreYouRecvError> {
+    let her_disc_endpoint =
+        sak_utils_net::make_endpoint(&socket_addr.ip().to_string(), way.src_disc_port);
+
+    if check::is_my_endpoint(identity.disc_port, &her_disc_endpoint)
+        || way.src_public_key_str == identity.credential.public_key_str
+    {
+        return Err(WhoAreYouRecvError::MyEndpoint);
+    }
+
+    if let Some(_) = addr_table.get_mapped_addr(&way.src_public_key_str).await {
+        return Err(WhoAreYouRecvError::AddrAlreadyMapped {
+            disc_endpoint: her_disc_endpoint,
+        });
+    }
+
+    Ok(her_disc_endpoint)
+}
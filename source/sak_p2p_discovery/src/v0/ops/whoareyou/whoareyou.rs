@@ -1,19 +1,84 @@
 use crate::{
-    v0::ops::msg_type::{WHO_ARE_YOU_ACK_TYPE, WHO_ARE_YOU_SYN_TYPE},
-    P2PDiscError,
+    v0::ops::msg_type::{WHO_ARE_YOU_ACK_TYPE, WHO_ARE_YOU_FIN_TYPE, WHO_ARE_YOU_SYN_TYPE},
+    ExternalPorts, P2PDiscError,
 };
 use bytes::{BufMut, Bytes, BytesMut};
-use sak_crypto::Signature;
+use sak_crypto::{Signature, SigningKey};
 use sak_p2p_frame::{Frame, Parse};
+use sak_p2p_id::Identity;
+use std::convert::TryInto;
+
+const WHO_ARE_YOU_SIG_LABEL: &[u8] = b"saksaha_who_are_you";
 
 pub(crate) struct WhoAreYou {
     pub(crate) src_sig: Signature,
     pub(crate) src_disc_port: u16,
     pub(crate) src_p2p_port: u16,
     pub(crate) src_public_key_str: String,
+    // Fresh challenge of the sender
+    pub(crate) nonce: [u8; 32],
+    // Challenge of the peer being answered, zeroed in syn
+    pub(crate) echo_nonce: [u8; 32],
+    // Endpoint the syn has been received from, empty in syn
+    pub(crate) observed_endpoint: String,
 }
 
 impl WhoAreYou {
+    /// Signs the message with the identity key. The signature covers the
+    /// nonces so that it can not be replayed as an answer to another
+    /// challenge. Ports advertised are the ones reachable from outside of
+    /// the NAT.
+    pub(crate) fn new_signed(
+        identity: &Identity,
+        advertised_ports: ExternalPorts,
+        msg_type: &'static str,
+        echo_nonce: [u8; 32],
+        observed_endpoint: String,
+    ) -> WhoAreYou {
+        let nonce = sak_crypto::rand_bytes_32();
+
+        let src_disc_port = advertised_ports.disc_port;
+        let src_p2p_port = advertised_ports.p2p_port;
+        let src_public_key_str = identity.credential.public_key_str.clone();
+
+        let sig_data = make_sig_data(
+            msg_type,
+            &nonce,
+            &echo_nonce,
+            src_disc_port,
+            src_p2p_port,
+            &src_public_key_str,
+            &observed_endpoint,
+        );
+
+        let signing_key = SigningKey::from(&identity.credential.secret_key);
+        let src_sig = sak_crypto::make_signature(signing_key, &sig_data);
+
+        WhoAreYou {
+            src_sig,
+            src_disc_port,
+            src_p2p_port,
+            src_public_key_str,
+            nonce,
+            echo_nonce,
+            observed_endpoint,
+        }
+    }
+
+    pub(crate) fn verify(&self, msg_type: &'static str) -> Result<(), String> {
+        let sig_data = make_sig_data(
+            msg_type,
+            &self.nonce,
+            &self.echo_nonce,
+            self.src_disc_port,
+            self.src_p2p_port,
+            &self.src_public_key_str,
+            &self.observed_endpoint,
+        );
+
+        sak_crypto::verify_with_public_key_str(&self.src_public_key_str, &sig_data, &self.src_sig)
+    }
+
     pub(crate) fn into_syn_frame(&self) -> Result<Frame, String> {
         self.into_frame(WHO_ARE_YOU_SYN_TYPE)
     }
@@ -22,6 +87,10 @@ impl WhoAreYou {
         self.into_frame(WHO_ARE_YOU_ACK_TYPE)
     }
 
+    pub(crate) fn into_fin_frame(&self) -> Result<Frame, String> {
+        self.into_frame(WHO_ARE_YOU_FIN_TYPE)
+    }
+
     fn into_frame(&self, msg_type: &'static str) -> Result<Frame, String> {
         let src_sig_bytes = {
             let mut b = BytesMut::new();
@@ -41,6 +110,9 @@ impl WhoAreYou {
         frame.push_bulk(src_sig_bytes.into());
         frame.push_int(self.src_disc_port as u128);
         frame.push_bulk(src_public_key_bytes.into());
+        frame.push_bulk(Bytes::copy_from_slice(&self.nonce));
+        frame.push_bulk(Bytes::copy_from_slice(&self.echo_nonce));
+        frame.push_bulk(Bytes::from(self.observed_endpoint.clone()));
 
         Ok(frame)
     }
@@ -79,6 +151,12 @@ impl WhoAreYou {
             s
         };
 
+        let nonce = parse_nonce(parse)?;
+
+        let echo_nonce = parse_nonce(parse)?;
+
+        let observed_endpoint = parse.next_string()?;
+
         parse.finish()?;
 
         let way = WhoAreYou {
@@ -86,8 +164,46 @@ impl WhoAreYou {
             src_sig,
             src_disc_port,
             src_public_key_str,
+            nonce,
+            echo_nonce,
+            observed_endpoint,
         };
 
         return Ok(way);
     }
 }
+
+pub(crate) fn parse_nonce(parse: &mut Parse) -> Result<[u8; 32], P2PDiscError> {
+    let b = parse.next_bytes()?;
+
+    let nonce: [u8; 32] = match b[..].try_into() {
+        Ok(n) => n,
+        Err(_) => {
+            return Err(format!("Nonce has invalid length, len: {}", b.len()).into());
+        }
+    };
+
+    Ok(nonce)
+}
+
+fn make_sig_data(
+    msg_type: &'static str,
+    nonce: &[u8; 32],
+    echo_nonce: &[u8; 32],
+    disc_port: u16,
+    p2p_port: u16,
+    public_key_str: &String,
+    observed_endpoint: &String,
+) -> Vec<u8> {
+    [
+        WHO_ARE_YOU_SIG_LABEL,
+        msg_type.as_bytes(),
+        &nonce[..],
+        &echo_nonce[..],
+        &disc_port.to_be_bytes()[..],
+        &p2p_port.to_be_bytes()[..],
+        public_key_str.as_bytes(),
+        observed_endpoint.as_bytes(),
+    ]
+    .concat()
+}
@@ -1,18 +1,35 @@
+use crate::v0::task::DiscoveryTask;
 use crate::AddrTable;
 use crate::PublicKey;
-use sak_logger::debug;
+use crate::ADDR_BOOK_MAX_AGE_SEC;
+use sak_logger::{debug, warn};
 use sak_p2p_addr::AddrStatus;
+use sak_task_queue::TaskQueue;
 use std::sync::Arc;
 use std::time::Duration;
 use std::time::SystemTime;
 
+const ROUTING_MAINTENANCE_INTERVAL: u64 = 5000;
+
+const BUCKET_REFRESH_INTERVAL_SEC: i64 = 60;
+
+const LIVENESS_TIMEOUT_SEC: i64 = 10;
+
+// Number of nodes asked at once in a lookup
+const LOOKUP_CONCURRENCY: usize = 3;
+
 pub(crate) struct DiscRuntime {
     pub(crate) addr_monitor_interval: Duration,
     pub(crate) addr_table: Arc<AddrTable>,
+    pub(crate) disc_task_queue: Arc<TaskQueue<DiscoveryTask>>,
 }
 
 impl DiscRuntime {
     pub async fn run(&self) {
+        tokio::join!(self.monitor_addrs(), self.maintain_routing_table());
+    }
+
+    async fn monitor_addrs(&self) {
         let rest_after_one_iteration = self.addr_monitor_interval * 5;
 
         loop {
@@ -31,9 +48,78 @@ impl DiscRuntime {
                     .await;
             }
 
+            match self
+                .addr_table
+                .update_addr_book(ADDR_BOOK_MAX_AGE_SEC)
+                .await
+            {
+                Ok(c) if c > 0 => debug!("Aged out addrs of the addr book, count: {}", c),
+                Ok(_) => (),
+                Err(err) => warn!("{}", err),
+            };
+
             sak_utils_time::wait_until_min_interval(time_since, rest_after_one_iteration).await;
         }
     }
+
+    // Checks liveness of the nodes that may be replaced, evicts the ones not
+    // answering, and refreshes the buckets with lookups
+    async fn maintain_routing_table(&self) {
+        let interval = Duration::from_millis(ROUTING_MAINTENANCE_INTERVAL);
+
+        loop {
+            let time_since = SystemTime::now();
+
+            let my_node_id = self.addr_table.get_my_node_id().await;
+
+            for addr in self.addr_table.take_liveness_checks().await {
+                self.enqueue_task(DiscoveryTask::FindNode {
+                    addr,
+                    target: my_node_id,
+                })
+                .await;
+            }
+
+            let evicted = self
+                .addr_table
+                .evict_unresponsive(chrono::Duration::seconds(LIVENESS_TIMEOUT_SEC))
+                .await;
+
+            if !evicted.is_empty() {
+                debug!("Evicted unresponsive addrs, count: {}", evicted.len());
+            }
+
+            let mut targets = self
+                .addr_table
+                .take_refresh_targets(chrono::Duration::seconds(BUCKET_REFRESH_INTERVAL_SEC))
+                .await;
+
+            // Looking up myself fills the buckets near me
+            if !targets.is_empty() {
+                targets.push(my_node_id);
+            }
+
+            for target in targets {
+                let addrs = self
+                    .addr_table
+                    .get_closest_addrs(&target, LOOKUP_CONCURRENCY)
+                    .await;
+
+                for addr in addrs {
+                    self.enqueue_task(DiscoveryTask::FindNode { addr, target })
+                        .await;
+                }
+            }
+
+            sak_utils_time::wait_until_min_interval(time_since, interval).await;
+        }
+    }
+
+    async fn enqueue_task(&self, task: DiscoveryTask) {
+        if let Err(err) = self.disc_task_queue.push_back(task).await {
+            warn!("Cannot enqueue a routing table task, err: {}", err);
+        }
+    }
 }
 
 async fn drop_address_if_necessary(
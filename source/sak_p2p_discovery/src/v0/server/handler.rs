@@ -1,9 +1,12 @@
+use crate::v0::task::DiscoveryTask;
 use crate::{
+    findnode,
     whoareyou::{self, WhoAreYouRecvError},
     AddrTable, Connection, Msg, P2PDiscError,
 };
 use sak_logger::warn;
 use sak_p2p_id::Identity;
+use sak_task_queue::TaskQueue;
 use std::{net::SocketAddr, sync::Arc, time::Duration};
 use tokio::sync::Semaphore;
 
@@ -19,6 +22,7 @@ impl Handler {
         udp_conn: Arc<Connection>,
         identity: Arc<Identity>,
         addr_table: Arc<AddrTable>,
+        disc_task_queue: Arc<TaskQueue<DiscoveryTask>>,
         _addr_expire_duration: Duration,
     ) -> Result<(), P2PDiscError> {
         match msg {
@@ -44,15 +48,49 @@ impl Handler {
 
                 Ok(())
             }
-            Msg::WhoAreYouAck(way_ack) => {
-                Ok(whoareyou::handle_who_are_you_ack(
-                    way_ack,
+            Msg::WhoAreYouAck(way_ack) => Ok(whoareyou::handle_who_are_you_ack(
+                way_ack,
+                socket_addr,
+                udp_conn,
+                identity,
+                addr_table,
+            )
+            .await?),
+            Msg::WhoAreYouFin(way_fin) => {
+                let res = whoareyou::handle_who_are_you_fin(
                     socket_addr,
                     udp_conn,
-                    // identity,
+                    way_fin,
+                    identity,
                     addr_table,
                 )
-                .await?)
+                .await;
+
+                match res {
+                    Ok(_) => return Ok(()),
+                    Err(way_recv_err) => match way_recv_err {
+                        WhoAreYouRecvError::AddrAlreadyMapped { .. } => {
+                            warn!("Error receiving whoareyou fin, err: {}", way_recv_err,);
+                        }
+                        _ => return Err(way_recv_err.into()),
+                    },
+                };
+
+                Ok(())
+            }
+            Msg::FindNode(find_node) => Ok(findnode::recv_find_node(
+                socket_addr,
+                udp_conn,
+                find_node,
+                identity,
+                addr_table,
+            )
+            .await?),
+            Msg::Nodes(nodes) => {
+                Ok(
+                    findnode::handle_nodes(nodes, udp_conn, identity, addr_table, disc_task_queue)
+                        .await?,
+                )
             }
         }
     }
@@ -1,8 +1,10 @@
 use super::handler::Handler;
+use crate::v0::task::DiscoveryTask;
 use crate::{AddrTable, Connection};
 use futures::StreamExt;
 use sak_logger::{error, info, warn};
 use sak_p2p_id::Identity;
+use sak_task_queue::TaskQueue;
 use std::{sync::Arc, time::Duration};
 use tokio::sync::Semaphore;
 
@@ -13,6 +15,7 @@ pub(crate) struct Server {
     conn_semaphore: Arc<Semaphore>,
     identity: Arc<Identity>,
     addr_table: Arc<AddrTable>,
+    disc_task_queue: Arc<TaskQueue<DiscoveryTask>>,
     addr_expire_duration: Duration,
 }
 
@@ -20,6 +23,7 @@ pub(crate) struct ServerArgs {
     pub(crate) udp_conn: Arc<Connection>,
     pub(crate) identity: Arc<Identity>,
     pub(crate) addr_table: Arc<AddrTable>,
+    pub(crate) disc_task_queue: Arc<TaskQueue<DiscoveryTask>>,
     pub(crate) addr_expire_duration: u64,
 }
 
@@ -34,6 +38,7 @@ impl Server {
             udp_conn: server_args.udp_conn,
             conn_semaphore,
             addr_table: server_args.addr_table,
+            disc_task_queue: server_args.disc_task_queue,
             addr_expire_duration,
         }
     }
@@ -59,6 +64,7 @@ impl Server {
                             let udp_conn = self.udp_conn.clone();
                             let identity = self.identity.clone();
                             let table = self.addr_table.clone();
+                            let disc_task_queue = self.disc_task_queue.clone();
                             let addr_expire_duration = self.addr_expire_duration;
 
                             tokio::spawn(async move {
@@ -69,6 +75,7 @@ impl Server {
                                         udp_conn,
                                         identity,
                                         table,
+                                        disc_task_queue,
                                         addr_expire_duration,
                                     )
                                     .await
@@ -1,11 +1,14 @@
 mod addr;
 mod iter;
+mod routing;
 mod slot;
 mod table;
 pub mod testing;
 
 pub use addr::DiscAddr;
 pub use iter::AddrsIterator;
+pub(crate) use routing::*;
+pub use routing::{make_node_id, NodeId};
 pub(crate) use slot::*;
 pub use table::AddrTable;
 pub(crate) use table::*;
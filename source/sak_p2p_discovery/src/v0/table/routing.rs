@@ -0,0 +1,311 @@
+use super::DiscAddr;
+use chrono::{DateTime, Duration, Utc};
+use sak_crypto::sha3::{Digest, Keccak256};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+pub type NodeId = [u8; 32];
+
+pub(crate) const K_BUCKET_SIZE: usize = 16;
+
+pub(crate) const REPLACEMENT_CACHE_SIZE: usize = 8;
+
+pub(crate) const BUCKET_COUNT: usize = 256;
+
+/// Node id is the keccak256 hash of the public key.
+pub fn make_node_id(public_key_str: &str) -> NodeId {
+    let mut hasher = Keccak256::default();
+    hasher.update(public_key_str.as_bytes());
+
+    let mut id = [0u8; 32];
+    id.copy_from_slice(&hasher.finalize());
+
+    id
+}
+
+pub(crate) fn xor_distance(a: &NodeId, b: &NodeId) -> NodeId {
+    let mut d = [0u8; 32];
+
+    for idx in 0..32 {
+        d[idx] = a[idx] ^ b[idx];
+    }
+
+    d
+}
+
+/// Index of the bucket `id` belongs to, i.e. the position of the highest
+/// bit of the distance. `None` for my own id.
+pub(crate) fn bucket_index(my_id: &NodeId, id: &NodeId) -> Option<usize> {
+    let d = xor_distance(my_id, id);
+
+    for (idx, byte) in d.iter().enumerate() {
+        if *byte != 0 {
+            return Some(BUCKET_COUNT - 1 - (idx * 8 + byte.leading_zeros() as usize));
+        }
+    }
+
+    None
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Liveness {
+    Alive,
+    CheckRequested,
+    Checking { since: DateTime<Utc> },
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct BucketEntry {
+    pub(crate) public_key_str: String,
+    pub(crate) node_id: NodeId,
+    pub(crate) liveness: Liveness,
+}
+
+pub(crate) struct KBucket {
+    // Least recently seen comes first
+    pub(crate) entries: VecDeque<BucketEntry>,
+    // Candidates waiting for a slot in the bucket, most recent comes last
+    pub(crate) replacements: VecDeque<Arc<DiscAddr>>,
+    pub(crate) last_refreshed: DateTime<Utc>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum BucketInsert {
+    Inserted,
+    Updated,
+    // Bucket is full. The candidate is kept as a replacement, and the least
+    // recently seen entry is asked to prove it is still alive.
+    Full { oldest_public_key_str: String },
+}
+
+pub(crate) struct RoutingTable {
+    my_id: NodeId,
+    buckets: Vec<KBucket>,
+}
+
+impl KBucket {
+    fn new() -> KBucket {
+        KBucket {
+            entries: VecDeque::new(),
+            replacements: VecDeque::new(),
+            last_refreshed: DateTime::<Utc>::from(UNIX_EPOCH),
+        }
+    }
+
+    fn position(&self, public_key_str: &String) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|e| e.public_key_str == *public_key_str)
+    }
+}
+
+impl RoutingTable {
+    pub(crate) fn new(my_public_key_str: &String) -> RoutingTable {
+        let buckets = (0..BUCKET_COUNT).map(|_| KBucket::new()).collect();
+
+        RoutingTable {
+            my_id: make_node_id(my_public_key_str),
+            buckets,
+        }
+    }
+
+    pub(crate) fn my_id(&self) -> &NodeId {
+        &self.my_id
+    }
+
+    pub(crate) fn insert(&mut self, addr: &Arc<DiscAddr>) -> Result<BucketInsert, String> {
+        let public_key_str = &addr.known_addr.public_key_str;
+        let node_id = make_node_id(public_key_str);
+
+        let bucket = match bucket_index(&self.my_id, &node_id) {
+            Some(idx) => &mut self.buckets[idx],
+            None => {
+                return Err(format!("Cannot put my own id into the routing table"));
+            }
+        };
+
+        if let Some(pos) = bucket.position(public_key_str) {
+            if let Some(mut e) = bucket.entries.remove(pos) {
+                e.liveness = Liveness::Alive;
+                bucket.entries.push_back(e);
+            }
+
+            return Ok(BucketInsert::Updated);
+        }
+
+        if bucket.entries.len() < K_BUCKET_SIZE {
+            bucket.entries.push_back(BucketEntry {
+                public_key_str: public_key_str.to_string(),
+                node_id,
+                liveness: Liveness::Alive,
+            });
+
+            return Ok(BucketInsert::Inserted);
+        }
+
+        bucket
+            .replacements
+            .retain(|r| r.known_addr.public_key_str != *public_key_str);
+
+        bucket.replacements.push_back(addr.clone());
+
+        if bucket.replacements.len() > REPLACEMENT_CACHE_SIZE {
+            bucket.replacements.pop_front();
+        }
+
+        let oldest = &mut bucket.entries[0];
+
+        if oldest.liveness == Liveness::Alive {
+            oldest.liveness = Liveness::CheckRequested;
+        }
+
+        Ok(BucketInsert::Full {
+            oldest_public_key_str: oldest.public_key_str.clone(),
+        })
+    }
+
+    /// Moves the node to the tail of its bucket as the most recently seen. Returns false if the node is not in
+    /// the table.
+    pub(crate) fn touch(&mut self, public_key_str: &String) -> bool {
+        let node_id = make_node_id(public_key_str);
+
+        let bucket = match bucket_index(&self.my_id, &node_id) {
+            Some(idx) => &mut self.buckets[idx],
+            None => return false,
+        };
+
+        match bucket.position(public_key_str) {
+            Some(pos) => {
+                if let Some(mut e) = bucket.entries.remove(pos) {
+                    e.liveness = Liveness::Alive;
+                    bucket.entries.push_back(e);
+                }
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the node, promoting the most recent replacement of its
+    /// bucket, which is returned so that it can be mapped.
+    pub(crate) fn remove(&mut self, public_key_str: &String) -> Option<Arc<DiscAddr>> {
+        let node_id = make_node_id(public_key_str);
+
+        let bucket = match bucket_index(&self.my_id, &node_id) {
+            Some(idx) => &mut self.buckets[idx],
+            None => return None,
+        };
+
+        bucket
+            .replacements
+            .retain(|r| r.known_addr.public_key_str != *public_key_str);
+
+        let pos = bucket.position(public_key_str)?;
+        bucket.entries.remove(pos);
+
+        let replacement = bucket.replacements.pop_back()?;
+
+        bucket.entries.push_back(BucketEntry {
+            public_key_str: replacement.known_addr.public_key_str.clone(),
+            node_id: make_node_id(&replacement.known_addr.public_key_str),
+            liveness: Liveness::Alive,
+        });
+
+        Some(replacement)
+    }
+
+    /// Nodes whose liveness has to be checked. They are marked as being
+    /// checked.
+    pub(crate) fn take_liveness_checks(&mut self) -> Vec<String> {
+        let now = Utc::now();
+        let mut public_keys = vec![];
+
+        for bucket in self.buckets.iter_mut() {
+            for e in bucket.entries.iter_mut() {
+                if e.liveness == Liveness::CheckRequested {
+                    e.liveness = Liveness::Checking { since: now };
+                    public_keys.push(e.public_key_str.clone());
+                }
+            }
+        }
+
+        public_keys
+    }
+
+    pub(crate) fn get_unresponsive(&self, timeout: Duration) -> Vec<String> {
+        let now = Utc::now();
+
+        self.buckets
+            .iter()
+            .flat_map(|b| b.entries.iter())
+            .filter(|e| match e.liveness {
+                Liveness::Checking { since } => now.signed_duration_since(since) > timeout,
+                _ => false,
+            })
+            .map(|e| e.public_key_str.clone())
+            .collect()
+    }
+
+    pub(crate) fn closest(&self, target: &NodeId, count: usize) -> Vec<String> {
+        let mut entries: Vec<&BucketEntry> =
+            self.buckets.iter().flat_map(|b| b.entries.iter()).collect();
+
+        entries.sort_by_key(|e| xor_distance(&e.node_id, target));
+
+        entries
+            .into_iter()
+            .take(count)
+            .map(|e| e.public_key_str.clone())
+            .collect()
+    }
+
+    /// Random lookup targets of the non-empty buckets that have not been
+    /// refreshed within `interval`. The buckets are marked as refreshed.
+    pub(crate) fn take_refresh_targets(&mut self, interval: Duration) -> Vec<NodeId> {
+        let now = Utc::now();
+        let mut targets = vec![];
+
+        for idx in 0..BUCKET_COUNT {
+            let bucket = &mut self.buckets[idx];
+
+            if bucket.entries.is_empty()
+                || now.signed_duration_since(bucket.last_refreshed) < interval
+            {
+                continue;
+            }
+
+            bucket.last_refreshed = now;
+            targets.push(self.random_id_in_bucket(idx));
+        }
+
+        targets
+    }
+
+    pub(crate) fn random_id_in_bucket(&self, idx: usize) -> NodeId {
+        let mut d = sak_crypto::rand_bytes_32();
+
+        let byte_idx = 31 - idx / 8;
+        let bit = idx % 8;
+
+        for b in d[..byte_idx].iter_mut() {
+            *b = 0;
+        }
+
+        d[byte_idx] &= ((1u16 << bit) - 1) as u8;
+        d[byte_idx] |= 1 << bit;
+
+        xor_distance(&self.my_id, &d)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.buckets.iter().map(|b| b.entries.len()).sum()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn get_bucket(&self, idx: usize) -> &KBucket {
+        &self.buckets[idx]
+    }
+}
@@ -1,10 +1,13 @@
 use super::{
     addr::DiscAddr,
+    routing::{BucketInsert, NodeId, RoutingTable},
     slot::{Slot, SlotGuard},
 };
-use crate::AddrsIterator;
+use crate::{AddrBook, AddrsIterator};
+use chrono::Duration;
 use colored::Colorize;
-use sak_logger::debug;
+use sak_logger::{debug, warn};
+use sak_p2p_addr::KnownAddr;
 use std::{collections::HashMap, sync::Arc};
 use tokio::sync::{
     mpsc::{self, Receiver, Sender, UnboundedReceiver, UnboundedSender},
@@ -14,12 +17,14 @@ use tokio::sync::{
 pub(crate) type PublicKey = String;
 pub(crate) type AddrMap = HashMap<PublicKey, Arc<DiscAddr>>;
 
-// const DISC_TABLE_CAPACITY: usize = 100;
-const DISC_TABLE_CAPACITY: usize = 5;
+const DISC_TABLE_CAPACITY: usize = 100;
 
-/// TODO Table shall have Kademlia flavored buckets later on
+/// Addresses are mapped by public key, and organized by the XOR distance of
+/// their node ids in the k-buckets of the routing table.
 pub struct AddrTable {
     addr_map: Arc<RwLock<AddrMap>>,
+    routing_table: RwLock<RoutingTable>,
+    addr_book: Option<Arc<AddrBook>>,
     slots_tx: Arc<UnboundedSender<Arc<Slot>>>,
     slots_rx: RwLock<UnboundedReceiver<Arc<Slot>>>,
     known_addrs_tx: Arc<Sender<Arc<DiscAddr>>>,
@@ -29,7 +34,11 @@ pub struct AddrTable {
 }
 
 impl AddrTable {
-    pub(crate) async fn init(disc_table_capacity: Option<u16>) -> Result<AddrTable, String> {
+    pub(crate) async fn init(
+        my_public_key_str: &String,
+        disc_table_capacity: Option<u16>,
+        addr_book: Option<Arc<AddrBook>>,
+    ) -> Result<AddrTable, String> {
         let addr_map = {
             let m = HashMap::new();
 
@@ -77,8 +86,12 @@ impl AddrTable {
 
         let addrs_it_mutex = Arc::new(Mutex::new(0));
 
+        let routing_table = RwLock::new(RoutingTable::new(my_public_key_str));
+
         let table = AddrTable {
             addr_map,
+            routing_table,
+            addr_book,
             slots_tx,
             slots_rx,
             known_addrs_tx,
@@ -152,17 +165,47 @@ impl AddrTable {
         &self,
         addr: Arc<DiscAddr>,
     ) -> Result<Option<Arc<DiscAddr>>, String> {
+        if self.is_banned(&addr.known_addr.public_key_str) {
+            debug!(
+                "Addr is banned, not inserting, key: {}",
+                addr.known_addr.get_public_ket_short(),
+            );
+
+            return Ok(None);
+        }
+
         // let mut addr_map = self.addr_map.write().await;
         let mut addr_map = self.get_addr_map_write().await;
+        let mut routing_table = self.routing_table.write().await;
 
         let key = &addr.known_addr.public_key_str;
 
+        if let BucketInsert::Full {
+            oldest_public_key_str,
+        } = routing_table.insert(&addr)?
+        {
+            debug!(
+                "Bucket is full, addr is kept as a replacement, key: {}, \
+                oldest: {}",
+                addr.known_addr.get_public_ket_short(),
+                &oldest_public_key_str[..6],
+            );
+
+            return Ok(None);
+        }
+
         debug!(
             "Disc table insert mapping! key: {}, value: (p2p_ep: {})",
             addr.known_addr.get_public_ket_short().green(),
             addr.known_addr.get_p2p_endpoint(),
         );
 
+        if let Some(addr_book) = &self.addr_book {
+            if let Err(err) = addr_book.record_success(&addr.known_addr) {
+                warn!("Could not record addr in the addr book, err: {}", err);
+            }
+        }
+
         match self.enqueue_known_addr(addr.clone()).await {
             Ok(_) => {}
             Err(err) => {
@@ -191,8 +234,160 @@ impl AddrTable {
 
     pub(crate) async fn remove_mapping(&self, public_key_str: &String) -> Option<Arc<DiscAddr>> {
         let mut addr_map = self.addr_map.write().await;
+        let mut routing_table = self.routing_table.write().await;
+
+        let removed = addr_map.remove(public_key_str);
+
+        let replacement = match routing_table.remove(public_key_str) {
+            Some(r) => r,
+            None => return removed,
+        };
+
+        debug!(
+            "Promoting a replacement into the bucket, key: {}",
+            replacement.known_addr.get_public_ket_short(),
+        );
+
+        addr_map.insert(
+            replacement.known_addr.public_key_str.clone(),
+            replacement.clone(),
+        );
+
+        drop(routing_table);
+        drop(addr_map);
+
+        if let Err(err) = self.enqueue_known_addr(replacement).await {
+            debug!("Could not enqueue a promoted addr, err: {}", err);
+        }
+
+        removed
+    }
+
+    /// Marks the node as seen just now, e.g. after it has answered.
+    pub(crate) async fn touch(&self, public_key_str: &String) -> bool {
+        let mut routing_table = self.routing_table.write().await;
+
+        routing_table.touch(public_key_str)
+    }
+
+    pub(crate) async fn get_closest_addrs(
+        &self,
+        target: &NodeId,
+        count: usize,
+    ) -> Vec<Arc<DiscAddr>> {
+        let addr_map = self.addr_map.read().await;
+        let routing_table = self.routing_table.read().await;
+
+        routing_table
+            .closest(target, count)
+            .iter()
+            .filter_map(|k| addr_map.get(k).map(|a| a.clone()))
+            .collect()
+    }
+
+    pub(crate) async fn take_liveness_checks(&self) -> Vec<Arc<DiscAddr>> {
+        let addr_map = self.addr_map.read().await;
+        let mut routing_table = self.routing_table.write().await;
+
+        routing_table
+            .take_liveness_checks()
+            .iter()
+            .filter_map(|k| addr_map.get(k).map(|a| a.clone()))
+            .collect()
+    }
+
+    /// Evicts the nodes that have not answered a liveness check within
+    /// `timeout`, promoting the replacements of their buckets.
+    pub(crate) async fn evict_unresponsive(&self, timeout: Duration) -> Vec<String> {
+        let public_keys = {
+            let routing_table = self.routing_table.read().await;
+
+            routing_table.get_unresponsive(timeout)
+        };
+
+        for public_key_str in public_keys.iter() {
+            self.remove_mapping(public_key_str).await;
+
+            if let Some(addr_book) = &self.addr_book {
+                if let Err(err) = addr_book.record_failure(public_key_str) {
+                    warn!("Could not record addr in the addr book, err: {}", err);
+                }
+            }
+        }
+
+        public_keys
+    }
+
+    /// Refreshes the last seen time of the mapped addrs in the addr book,
+    /// and ages out the ones not seen within `max_age_sec`.
+    pub(crate) async fn update_addr_book(&self, max_age_sec: i64) -> Result<usize, String> {
+        let addr_book = match &self.addr_book {
+            Some(b) => b,
+            None => return Ok(0),
+        };
+
+        {
+            let addr_map = self.addr_map.read().await;
+
+            for addr in addr_map.values() {
+                if let Err(err) = addr_book.record_seen(&addr.known_addr) {
+                    return Err(format!("Could not update addr book, err: {}", err));
+                }
+            }
+        }
+
+        match addr_book.prune(max_age_sec) {
+            Ok(c) => Ok(c),
+            Err(err) => Err(format!("Could not prune addr book, err: {}", err)),
+        }
+    }
+
+    /// Bans the addr for `duration_sec`. The ban is kept in the addr book;
+    /// without one, the addr is only dropped from the table.
+    pub async fn ban(&self, known_addr: &KnownAddr, duration_sec: i64) -> Result<(), String> {
+        self.remove_mapping(&known_addr.public_key_str).await;
+
+        let addr_book = match &self.addr_book {
+            Some(b) => b,
+            None => {
+                warn!("No addr book to keep the ban of an addr");
+
+                return Ok(());
+            }
+        };
+
+        match addr_book.ban(known_addr, duration_sec) {
+            Ok(_) => Ok(()),
+            Err(err) => Err(format!("Could not ban addr, err: {}", err)),
+        }
+    }
+
+    pub fn is_banned(&self, public_key_str: &String) -> bool {
+        let addr_book = match &self.addr_book {
+            Some(b) => b,
+            None => return false,
+        };
+
+        match addr_book.is_banned(public_key_str) {
+            Ok(b) => b,
+            Err(err) => {
+                warn!("Could not look up a ban in the addr book, err: {}", err);
+
+                false
+            }
+        }
+    }
+
+    pub(crate) async fn take_refresh_targets(&self, interval: Duration) -> Vec<NodeId> {
+        let mut routing_table = self.routing_table.write().await;
+
+        routing_table.take_refresh_targets(interval)
+    }
+
+    pub(crate) async fn get_my_node_id(&self) -> NodeId {
+        let routing_table = self.routing_table.read().await;
 
-        addr_map.remove(public_key_str)
+        *routing_table.my_id()
     }
 
     pub async fn get_status(&self) -> Vec<String> {
@@ -1,5 +1,5 @@
 use super::DiscoveryTask;
-use crate::{whoareyou, AddrTable, Connection};
+use crate::{findnode, whoareyou, AddrTable, Connection};
 use sak_logger::debug;
 use sak_p2p_id::Identity;
 use std::sync::Arc;
@@ -10,16 +10,22 @@ pub(crate) async fn run(
     addr_table: Arc<AddrTable>,
     udp_conn: Arc<Connection>,
 ) {
-    let result = match task {
+    match task {
         DiscoveryTask::InitiateWhoAreYou { addr } => {
-            whoareyou::init_who_are_you(addr, identity, addr_table, udp_conn).await
+            match whoareyou::init_who_are_you(addr, identity, addr_table, udp_conn).await {
+                Ok(_) => (),
+                Err(err) => {
+                    debug!("WhoAreYouInit stopped, err: {}", err,);
+                }
+            }
         }
-    };
-
-    match result {
-        Ok(_) => (),
-        Err(err) => {
-            debug!("WhoAreYouInit stopped, err: {}", err,);
+        DiscoveryTask::FindNode { addr, target } => {
+            match findnode::init_find_node(addr, target, identity, udp_conn).await {
+                Ok(_) => (),
+                Err(err) => {
+                    debug!("FindNodeInit stopped, err: {}", err,);
+                }
+            }
         }
-    }
+    };
 }
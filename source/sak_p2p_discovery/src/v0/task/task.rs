@@ -1,7 +1,10 @@
+use crate::{DiscAddr, NodeId};
 use sak_p2p_addr::UnknownAddr;
+use std::sync::Arc;
 
 pub(crate) enum DiscoveryTask {
     InitiateWhoAreYou { addr: UnknownAddr },
+    FindNode { addr: Arc<DiscAddr>, target: NodeId },
 }
 
 impl std::fmt::Display for DiscoveryTask {
@@ -10,6 +13,13 @@ impl std::fmt::Display for DiscoveryTask {
             Self::InitiateWhoAreYou { addr } => {
                 write!(f, "InitiateWhoAreYou [dest: {}]", addr.disc_endpoint())
             }
+            Self::FindNode { addr, .. } => {
+                write!(
+                    f,
+                    "FindNode [dest: {}]",
+                    addr.known_addr.get_disc_endpoint()
+                )
+            }
         }
     }
 }
@@ -0,0 +1,121 @@
+use crate::{AddrBook, DiscAddr};
+use sak_crypto::{SakKey, SigningKey};
+
+fn make_addr(public_key_str: &str, disc_port: u16) -> DiscAddr {
+    let (secret_key, public_key) = SakKey::generate();
+
+    let sig = sak_crypto::make_signature(SigningKey::from(&secret_key), b"test");
+
+    DiscAddr::new_dummy(
+        public_key,
+        public_key_str.to_string(),
+        sig,
+        disc_port,
+        disc_port + 1,
+    )
+}
+
+fn make_addr_book(name: &str) -> AddrBook {
+    let db_path = std::env::temp_dir()
+        .join("sak_p2p_discovery_test")
+        .join(name);
+
+    let _ = std::fs::remove_dir_all(&db_path);
+
+    AddrBook::init(&db_path).unwrap()
+}
+
+#[test]
+fn test_addr_book_records_are_ordered_by_score() {
+    let addr_book = make_addr_book("score");
+
+    let addr_1 = make_addr("node_1", 35101);
+    let addr_2 = make_addr("node_2", 35103);
+
+    addr_book.record_success(&addr_1.known_addr).unwrap();
+    addr_book.record_success(&addr_2.known_addr).unwrap();
+    addr_book.record_success(&addr_2.known_addr).unwrap();
+    addr_book.record_failure(&"node_1".to_string()).unwrap();
+
+    let records = addr_book.get_records().unwrap();
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].public_key_str, "node_2");
+    assert_eq!(records[0].success_count, 2);
+    assert_eq!(records[0].score, 2);
+    assert_eq!(records[1].public_key_str, "node_1");
+    assert_eq!(records[1].failure_count, 1);
+    assert_eq!(records[1].score, -1);
+
+    let unknown_addr = records[0].to_unknown_addr();
+
+    assert_eq!(unknown_addr.disc_port, 35103);
+    assert_eq!(unknown_addr.p2p_port, Some(35104));
+    assert_eq!(unknown_addr.public_key_str, Some("node_2".to_string()));
+}
+
+#[test]
+fn test_addr_book_ages_out_records() {
+    let addr_book = make_addr_book("prune");
+
+    let addr_1 = make_addr("node_1", 35101);
+    let addr_2 = make_addr("node_2", 35103);
+
+    addr_book.record_success(&addr_1.known_addr).unwrap();
+    addr_book.record_success(&addr_2.known_addr).unwrap();
+
+    let mut record = addr_book
+        .get_record(&"node_1".to_string())
+        .unwrap()
+        .unwrap();
+    record.last_seen -= 3600;
+    addr_book.put_record(&record).unwrap();
+
+    assert_eq!(addr_book.prune(600).unwrap(), 1);
+
+    let records = addr_book.get_records().unwrap();
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].public_key_str, "node_2");
+}
+
+#[test]
+fn test_addr_book_drops_low_scored_records() {
+    let addr_book = make_addr_book("low_score");
+
+    let addr_1 = make_addr("node_1", 35101);
+
+    addr_book.record_success(&addr_1.known_addr).unwrap();
+
+    for _ in 0..6 {
+        addr_book.record_failure(&"node_1".to_string()).unwrap();
+    }
+
+    assert_eq!(addr_book.prune(3600).unwrap(), 1);
+    assert!(addr_book.get_records().unwrap().is_empty());
+}
+
+#[test]
+fn test_addr_book_keeps_banned_records() {
+    let addr_book = make_addr_book("ban");
+
+    let addr_1 = make_addr("node_1", 35101);
+
+    addr_book.ban(&addr_1.known_addr, 3600).unwrap();
+
+    assert!(addr_book.is_banned(&"node_1".to_string()).unwrap());
+    assert!(!addr_book.is_banned(&"node_2".to_string()).unwrap());
+
+    let mut record = addr_book
+        .get_record(&"node_1".to_string())
+        .unwrap()
+        .unwrap();
+    record.last_seen -= 7200;
+    addr_book.put_record(&record).unwrap();
+
+    assert_eq!(addr_book.prune(600).unwrap(), 0);
+
+    addr_book.ban(&addr_1.known_addr, -1).unwrap();
+
+    assert!(!addr_book.is_banned(&"node_1".to_string()).unwrap());
+}
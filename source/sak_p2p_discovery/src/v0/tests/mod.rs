@@ -4,6 +4,18 @@ mod utils;
 #[cfg(test)]
 mod test_multiple_agents;
 
+#[cfg(test)]
+mod book;
+
+#[cfg(test)]
+mod routing;
+
+#[cfg(test)]
+mod nat;
+
+#[cfg(test)]
+mod whoareyou;
+
 #[cfg(test)]
 mod test {
     use super::utils;
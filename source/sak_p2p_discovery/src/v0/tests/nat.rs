@@ -0,0 +1,418 @@
+use super::utils;
+use crate::{
+    ExternalAddr, ExternalPorts, IgdGateway, PortMapProtocol, PortMapper, MAX_EXTERNAL_ADDR_VOTERS,
+};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::sync::Mutex;
+
+const GATEWAY_EXTERNAL_IP: &str = "203.0.113.7";
+
+const IGD_DESC: &str = "<?xml version=\"1.0\"?>\
+    <root><device><deviceList><device><deviceList><device><serviceList><service>\
+    <serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>\
+    <controlURL>/ctl/IPConn</controlURL>\
+    </service></serviceList></device></deviceList></device></deviceList></device></root>";
+
+struct IgdStub {
+    ssdp_addr: SocketAddr,
+    // Http requests received, in order
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+// Internet gateway device answering ssdp search, description and SOAP
+// requests on localhost
+async fn run_igd_stub() -> IgdStub {
+    let ssdp_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let ssdp_addr = ssdp_socket.local_addr().unwrap();
+
+    let http_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let http_addr = http_listener.local_addr().unwrap();
+
+    let requests = Arc::new(Mutex::new(vec![]));
+
+    tokio::spawn(async move {
+        let mut buf = [0; 2048];
+
+        loop {
+            let (_, from) = ssdp_socket.recv_from(&mut buf).await.unwrap();
+
+            let res = format!(
+                "HTTP/1.1 200 OK\r\n\
+                ST: urn:schemas-upnp-org:device:InternetGatewayDevice:1\r\n\
+                LOCATION: http://{}/desc.xml\r\n\r\n",
+                http_addr,
+            );
+
+            ssdp_socket.send_to(res.as_bytes(), from).await.unwrap();
+        }
+    });
+
+    let requests_clone = requests.clone();
+
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = http_listener.accept().await.unwrap();
+
+            let req = read_http_request(&mut stream).await;
+
+            let body = if req.starts_with("GET") {
+                IGD_DESC.to_string()
+            } else if req.contains("#GetExternalIPAddress") {
+                format!(
+                    "<s:Envelope><s:Body><u:GetExternalIPAddressResponse>\
+                    <NewExternalIPAddress>{}</NewExternalIPAddress>\
+                    </u:GetExternalIPAddressResponse></s:Body></s:Envelope>",
+                    GATEWAY_EXTERNAL_IP,
+                )
+            } else {
+                "<s:Envelope><s:Body><u:AddPortMappingResponse/></s:Body></s:Envelope>".to_string()
+            };
+
+            requests_clone.lock().await.push(req);
+
+            let res = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/xml\r\n\
+                Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body,
+            );
+
+            stream.write_all(res.as_bytes()).await.unwrap();
+            stream.shutdown().await.unwrap();
+        }
+    });
+
+    IgdStub {
+        ssdp_addr,
+        requests,
+    }
+}
+
+async fn read_http_request(stream: &mut TcpStream) -> String {
+    let mut req = vec![];
+    let mut buf = [0; 1024];
+
+    loop {
+        let len = stream.read(&mut buf).await.unwrap();
+        req.extend_from_slice(&buf[..len]);
+
+        let s = String::from_utf8_lossy(&req).to_string();
+
+        if len == 0 {
+            return s;
+        }
+
+        if let Some((head, body)) = s.split_once("\r\n\r\n") {
+            let content_length = head
+                .lines()
+                .find_map(|l| l.strip_prefix("Content-Length: "))
+                .map(|l| l.parse::<usize>().unwrap())
+                .unwrap_or(0);
+
+            if body.len() >= content_length {
+                return s;
+            }
+        }
+    }
+}
+
+// NAT-PMP gateway which maps every port to the suggested one plus 1000
+async fn run_nat_pmp_stub() -> SocketAddr {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = socket.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let mut buf = [0; 64];
+
+        loop {
+            let (_, from) = socket.recv_from(&mut buf).await.unwrap();
+
+            let op = buf[1];
+            let epoch = 1u32.to_be_bytes();
+
+            let res = match op {
+                0 => [&[0, 128, 0, 0][..], &epoch[..], &[203, 0, 113, 7][..]].concat(),
+                _ => {
+                    let external_port = u16::from_be_bytes([buf[6], buf[7]]) + 1000;
+
+                    [
+                        &[0, 128 + op, 0, 0][..],
+                        &epoch[..],
+                        &buf[4..6],
+                        &external_port.to_be_bytes()[..],
+                        &buf[8..12],
+                    ]
+                    .concat()
+                }
+            };
+
+            socket.send_to(&res, from).await.unwrap();
+        }
+    });
+
+    addr
+}
+
+fn make_endpoint(endpoint: &str) -> SocketAddr {
+    endpoint.parse().unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_upnp_igd_stub_maps_port() {
+    let stub = run_igd_stub().await;
+
+    let gateway = IgdGateway::search(stub.ssdp_addr, Duration::from_secs(1))
+        .await
+        .unwrap();
+
+    assert!(gateway.control_url.ends_with("/ctl/IPConn"));
+    assert_eq!(
+        gateway.service_type,
+        "urn:schemas-upnp-org:service:WANIPConnection:1"
+    );
+
+    let external_ip = gateway.get_external_ip().await.unwrap();
+
+    assert_eq!(external_ip.to_string(), GATEWAY_EXTERNAL_IP);
+
+    gateway
+        .add_port_mapping(PortMapProtocol::Udp, 35001, 35001, 3600)
+        .await
+        .unwrap();
+
+    let requests = stub.requests.lock().await;
+    let add_port_mapping = requests.last().unwrap();
+
+    assert!(add_port_mapping.contains("#AddPortMapping"));
+    assert!(add_port_mapping.contains("<NewExternalPort>35001</NewExternalPort>"));
+    assert!(add_port_mapping.contains("<NewProtocol>UDP</NewProtocol>"));
+    assert!(add_port_mapping.contains("<NewInternalClient>127.0.0.1</NewInternalClient>"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_port_mapper_maps_disc_and_p2p_ports_with_upnp() {
+    let stub = run_igd_stub().await;
+
+    let identity = utils::make_identity(35001, 35002);
+    let external_addr = Arc::new(ExternalAddr::new(None));
+
+    let port_mapper = PortMapper::new(
+        ExternalPorts {
+            disc_port: 35001,
+            p2p_port: 35002,
+        },
+        external_addr.clone(),
+        stub.ssdp_addr,
+        None,
+    );
+
+    port_mapper.map_ports().await.unwrap();
+
+    {
+        let requests = stub.requests.lock().await;
+
+        assert!(requests
+            .iter()
+            .any(|r| r.contains("<NewProtocol>TCP</NewProtocol>")
+                && r.contains("<NewInternalPort>35002</NewInternalPort>")));
+    }
+
+    assert_eq!(
+        external_addr.get_ip().await,
+        Some(GATEWAY_EXTERNAL_IP.parse().unwrap())
+    );
+    assert_eq!(
+        external_addr.get_disc_endpoint(&identity).await,
+        Some(format!("{}:35001", GATEWAY_EXTERNAL_IP))
+    );
+
+    port_mapper.unmap_ports().await.unwrap();
+
+    let requests = stub.requests.lock().await;
+
+    let delete_port_mappings = requests
+        .iter()
+        .filter(|r| r.contains("#DeletePortMapping"))
+        .collect::<Vec<_>>();
+
+    assert_eq!(delete_port_mappings.len(), 2);
+    assert!(delete_port_mappings
+        .iter()
+        .any(|r| r.contains("<NewProtocol>UDP</NewProtocol>")
+            && r.contains("<NewExternalPort>35001</NewExternalPort>")));
+    assert_eq!(external_addr.get_ip().await, None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_port_mapper_falls_back_to_nat_pmp() {
+    // Gateway not answering ssdp search
+    let silent_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+    let nat_pmp_addr = run_nat_pmp_stub().await;
+
+    let identity = utils::make_identity(35001, 35002);
+    let external_addr = Arc::new(ExternalAddr::new(None));
+
+    let port_mapper = PortMapper::new(
+        ExternalPorts {
+            disc_port: 35001,
+            p2p_port: 35002,
+        },
+        external_addr.clone(),
+        silent_socket.local_addr().unwrap(),
+        Some(nat_pmp_addr),
+    );
+
+    let ports = port_mapper.map_ports().await.unwrap();
+
+    let expected_ports = ExternalPorts {
+        disc_port: 36001,
+        p2p_port: 36002,
+    };
+
+    assert_eq!(ports, expected_ports);
+    assert_eq!(
+        external_addr.get_advertised_ports(&identity).await,
+        expected_ports
+    );
+    assert_eq!(
+        external_addr.get_ip().await,
+        Some(GATEWAY_EXTERNAL_IP.parse().unwrap())
+    );
+
+    // Asked again with no lifetime
+    port_mapper.unmap_ports().await.unwrap();
+
+    assert_eq!(external_addr.get_ip().await, None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_external_addr_is_decided_by_majority_vote() {
+    let identity = utils::make_identity(35001, 35002);
+    let external_addr = ExternalAddr::new(None);
+
+    let endpoint_1 = make_endpoint("198.51.100.1:40001");
+    let endpoint_2 = make_endpoint("198.51.100.1:40002");
+
+    external_addr
+        .record_observation(&"node_1".to_string(), endpoint_1)
+        .await;
+
+    // A single peer is not trusted
+    assert_eq!(external_addr.get_observed_endpoint().await, None);
+    assert_eq!(
+        external_addr.get_advertised_ports(&identity).await,
+        ExternalPorts {
+            disc_port: 35001,
+            p2p_port: 35002,
+        }
+    );
+
+    external_addr
+        .record_observation(&"node_2".to_string(), endpoint_1)
+        .await;
+
+    assert_eq!(
+        external_addr.get_observed_endpoint().await,
+        Some(endpoint_1)
+    );
+    assert_eq!(
+        external_addr
+            .get_advertised_ports(&identity)
+            .await
+            .disc_port,
+        40001
+    );
+
+    external_addr
+        .record_observation(&"node_3".to_string(), endpoint_2)
+        .await;
+    external_addr
+        .record_observation(&"node_4".to_string(), endpoint_2)
+        .await;
+
+    // Tie, no majority
+    assert_eq!(external_addr.get_observed_endpoint().await, None);
+
+    // Peer changing her mind is counted once
+    external_addr
+        .record_observation(&"node_1".to_string(), endpoint_2)
+        .await;
+
+    assert_eq!(
+        external_addr.get_observed_endpoint().await,
+        Some(endpoint_2)
+    );
+    assert_eq!(
+        external_addr.get_ip().await,
+        Some("198.51.100.1".parse().unwrap())
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_external_addr_manual_endpoint_overrides_detected_one() {
+    let identity = utils::make_identity(35001, 35002);
+
+    let manual_endpoint = make_endpoint("192.0.2.10:45001");
+    let external_addr = ExternalAddr::new(Some(manual_endpoint));
+
+    let endpoint = make_endpoint("198.51.100.1:40001");
+
+    external_addr
+        .record_observation(&"node_1".to_string(), endpoint)
+        .await;
+    external_addr
+        .record_observation(&"node_2".to_string(), endpoint)
+        .await;
+
+    assert_eq!(external_addr.get_ip().await, Some(manual_endpoint.ip()));
+    assert_eq!(
+        external_addr.get_disc_endpoint(&identity).await,
+        Some("192.0.2.10:45001".to_string())
+    );
+    assert_eq!(
+        external_addr.get_advertised_ports(&identity).await,
+        ExternalPorts {
+            disc_port: 45001,
+            p2p_port: 35002,
+        }
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_external_addr_evicts_the_oldest_observation() {
+    let external_addr = ExternalAddr::new(None);
+
+    let endpoint_1 = make_endpoint("198.51.100.1:40001");
+    let endpoint_2 = make_endpoint("198.51.100.1:40002");
+
+    let half = MAX_EXTERNAL_ADDR_VOTERS / 2;
+
+    for idx in 0..MAX_EXTERNAL_ADDR_VOTERS {
+        let endpoint = match idx < half {
+            true => endpoint_1,
+            false => endpoint_2,
+        };
+
+        external_addr
+            .record_observation(&format!("node_{}", idx), endpoint)
+            .await;
+    }
+
+    // Tie, no majority
+    assert_eq!(external_addr.get_observed_endpoint().await, None);
+
+    // Table is full, so the observation of node_0 goes
+    external_addr
+        .record_observation(&"node_new".to_string(), endpoint_2)
+        .await;
+
+    assert_eq!(
+        external_addr.get_observed_endpoint().await,
+        Some(endpoint_2)
+    );
+}
@@ -0,0 +1,124 @@
+use crate::{
+    bucket_index, make_node_id, xor_distance, BucketInsert, DiscAddr, RoutingTable, BUCKET_COUNT,
+    K_BUCKET_SIZE,
+};
+use sak_crypto::{SakKey, SigningKey};
+use std::sync::Arc;
+
+const MY_PUBLIC_KEY_STR: &str = "my_node";
+
+fn make_addr(public_key_str: &String) -> Arc<DiscAddr> {
+    let (secret_key, public_key) = SakKey::generate();
+
+    let sig = sak_crypto::make_signature(SigningKey::from(&secret_key), b"test");
+
+    Arc::new(DiscAddr::new_dummy(
+        public_key,
+        public_key_str.to_string(),
+        sig,
+        35001,
+        35002,
+    ))
+}
+
+// Public keys that fall into the given bucket of `MY_PUBLIC_KEY_STR`
+fn make_public_keys_in_bucket(idx: usize, count: usize) -> Vec<String> {
+    let my_id = make_node_id(MY_PUBLIC_KEY_STR);
+
+    (0..)
+        .map(|i| format!("node_{}", i))
+        .filter(|k| bucket_index(&my_id, &make_node_id(k)) == Some(idx))
+        .take(count)
+        .collect()
+}
+
+#[test]
+fn test_random_id_falls_into_its_bucket() {
+    let table = RoutingTable::new(&MY_PUBLIC_KEY_STR.to_string());
+
+    for idx in [0, 1, 7, 8, 100, 254, BUCKET_COUNT - 1] {
+        let id = table.random_id_in_bucket(idx);
+
+        assert_eq!(bucket_index(table.my_id(), &id), Some(idx));
+    }
+
+    assert_eq!(bucket_index(table.my_id(), table.my_id()), None);
+}
+
+#[test]
+fn test_closest_is_ordered_by_xor_distance() {
+    let mut table = RoutingTable::new(&MY_PUBLIC_KEY_STR.to_string());
+
+    let public_keys: Vec<String> = (0..10).map(|i| format!("node_{}", i)).collect();
+
+    for k in public_keys.iter() {
+        table.insert(&make_addr(k)).unwrap();
+    }
+
+    let target = make_node_id("target");
+
+    let closest = table.closest(&target, 3);
+
+    let mut expected = public_keys.clone();
+    expected.sort_by_key(|k| xor_distance(&make_node_id(k), &target));
+
+    assert_eq!(closest, expected[..3].to_vec());
+}
+
+#[test]
+fn test_full_bucket_keeps_replacement_until_oldest_is_evicted() {
+    let mut table = RoutingTable::new(&MY_PUBLIC_KEY_STR.to_string());
+
+    let idx = BUCKET_COUNT - 1;
+    let public_keys = make_public_keys_in_bucket(idx, K_BUCKET_SIZE + 1);
+
+    for k in public_keys[..K_BUCKET_SIZE].iter() {
+        assert_eq!(table.insert(&make_addr(k)).unwrap(), BucketInsert::Inserted);
+    }
+
+    let oldest = public_keys[0].clone();
+    let candidate = public_keys[K_BUCKET_SIZE].clone();
+
+    assert_eq!(
+        table.insert(&make_addr(&candidate)).unwrap(),
+        BucketInsert::Full {
+            oldest_public_key_str: oldest.clone(),
+        }
+    );
+    assert_eq!(table.len(), K_BUCKET_SIZE);
+    assert_eq!(table.get_bucket(idx).replacements.len(), 1);
+
+    assert_eq!(table.take_liveness_checks(), vec![oldest.clone()]);
+    assert!(table.take_liveness_checks().is_empty());
+
+    let promoted = table.remove(&oldest).unwrap();
+
+    assert_eq!(promoted.known_addr.public_key_str, candidate);
+    assert_eq!(table.len(), K_BUCKET_SIZE);
+    assert!(table.get_bucket(idx).replacements.is_empty());
+}
+
+#[test]
+fn test_touch_keeps_responsive_node() {
+    let mut table = RoutingTable::new(&MY_PUBLIC_KEY_STR.to_string());
+
+    let idx = BUCKET_COUNT - 1;
+    let public_keys = make_public_keys_in_bucket(idx, K_BUCKET_SIZE + 1);
+
+    for k in public_keys.iter() {
+        table.insert(&make_addr(k)).unwrap();
+    }
+
+    let oldest = public_keys[0].clone();
+
+    assert_eq!(table.take_liveness_checks(), vec![oldest.clone()]);
+
+    assert!(table.touch(&oldest));
+
+    let bucket = table.get_bucket(idx);
+
+    assert_eq!(bucket.entries.back().unwrap().public_key_str, oldest);
+    assert!(table
+        .get_unresponsive(chrono::Duration::seconds(-1))
+        .is_empty());
+}
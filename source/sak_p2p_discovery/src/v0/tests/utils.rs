@@ -326,11 +326,9 @@ lazy_static! {
     ];
 }
 
-async fn make_disc_args(test_disc_args: &TestDiscArgs) -> DiscoveryArgs {
+async fn make_disc_args(test_disc_args: &TestDiscArgs, disc_port: Option<u16>) -> DiscoveryArgs {
     let (disc_socket, disc_port) = {
-        let (socket, socket_addr) = sak_utils_net::setup_udp_socket(Some(test_disc_args.disc_port))
-            .await
-            .unwrap();
+        let (socket, socket_addr) = sak_utils_net::setup_udp_socket(disc_port).await.unwrap();
 
         info!(
             "Bound udp socket for P2P discovery, addr: {}",
@@ -363,6 +361,9 @@ async fn make_disc_args(test_disc_args: &TestDiscArgs) -> DiscoveryArgs {
         identity,
         p2p_port: 1,
         bootstrap_addrs: test_disc_args.bootstrap_addrs.clone(),
+        addr_book_path: None,
+        external_addr: None,
+        port_mapping: false,
     };
 
     args
@@ -387,7 +388,7 @@ pub(super) async fn create_disc(disc_idx: u16) -> (Arc<Discovery>, String) {
         .get(idx)
         .expect("Discovery arg should be provided");
 
-    let disc_args = make_disc_args(test_disc_args).await;
+    let disc_args = make_disc_args(test_disc_args, Some(test_disc_args.disc_port)).await;
 
     let public_key_str = disc_args.identity.credential.public_key_str.clone();
 
@@ -397,3 +398,32 @@ pub(super) async fn create_disc(disc_idx: u16) -> (Arc<Discovery>, String) {
 
     (Arc::new(disc), public_key_str)
 }
+
+// Discovery bound to a port picked by the os, returned along
+pub(super) async fn create_disc_on_any_port(disc_idx: u16) -> (Arc<Discovery>, u16) {
+    let test_disc_args = TEST_DISC_ARGS
+        .get(disc_idx as usize)
+        .expect("Discovery arg should be provided");
+
+    let disc_args = make_disc_args(test_disc_args, None).await;
+
+    let disc_port = disc_args.identity.disc_port;
+
+    let (disc, _) = Discovery::init(disc_args)
+        .await
+        .expect("Discovery should be initialized");
+
+    (Arc::new(disc), disc_port)
+}
+
+pub(super) fn make_identity(disc_port: u16, p2p_port: u16) -> Identity {
+    let test_disc_args = &TEST_DISC_ARGS[0];
+
+    Identity::new(
+        &test_disc_args.secret,
+        &test_disc_args.public_key_str,
+        p2p_port,
+        disc_port,
+    )
+    .unwrap()
+}
@@ -0,0 +1,79 @@
+use super::utils;
+use crate::v0::ops::msg_type::{WHO_ARE_YOU_FIN_TYPE, WHO_ARE_YOU_SYN_TYPE};
+use crate::{whoareyou::WhoAreYou, ExternalPorts, Msg, UdpCodec};
+use futures::{SinkExt, StreamExt};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio_util::udp::UdpFramed;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_syn_alone_does_not_map_the_sender() {
+    let (disc, disc_port) = utils::create_disc_on_any_port(1).await;
+    utils::discovery_run(disc.clone());
+
+    let disc_addr: SocketAddr = format!("127.0.0.1:{}", disc_port).parse().unwrap();
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let my_port = socket.local_addr().unwrap().port();
+    let mut framed = UdpFramed::new(socket, UdpCodec {});
+
+    let identity = utils::make_identity(my_port, 1);
+    let public_key_str = identity.credential.public_key_str.clone();
+    let ports = ExternalPorts {
+        disc_port: my_port,
+        p2p_port: 1,
+    };
+
+    // Syn signed by the key holder can be replayed by anyone, from anywhere
+    let way_syn = WhoAreYou::new_signed(
+        &identity,
+        ports,
+        WHO_ARE_YOU_SYN_TYPE,
+        [0; 32],
+        String::default(),
+    );
+
+    framed
+        .send((Msg::WhoAreYouSyn(way_syn), disc_addr))
+        .await
+        .unwrap();
+
+    let way_ack = match tokio::time::timeout(Duration::from_secs(3), framed.next()).await {
+        Ok(Some(Ok((Msg::WhoAreYouAck(way_ack), _)))) => way_ack,
+        _ => panic!("who_are_you_ack should have been received"),
+    };
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    assert!(
+        disc.addr_table
+            .get_mapped_addr(&public_key_str)
+            .await
+            .is_none(),
+        "Addr should not be mapped before the fin"
+    );
+
+    let way_fin = WhoAreYou::new_signed(
+        &identity,
+        ports,
+        WHO_ARE_YOU_FIN_TYPE,
+        way_ack.nonce,
+        disc_addr.to_string(),
+    );
+
+    framed
+        .send((Msg::WhoAreYouFin(way_fin), disc_addr))
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    assert!(
+        disc.addr_table
+            .get_mapped_addr(&public_key_str)
+            .await
+            .is_some(),
+        "Addr should be mapped once the nonce is signed back"
+    );
+}
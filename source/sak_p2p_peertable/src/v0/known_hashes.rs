@@ -0,0 +1,68 @@
+use std::collections::{BTreeMap, HashMap};
+
+// Enough for the txs and blocks announced in a few minutes
+pub const KNOWN_HASHES_CAPACITY: usize = 4096;
+
+/// Tx and block hashes a peer is known to have, as she announced or was
+/// sent them. The least recently seen hash is dropped once full.
+pub struct KnownHashes {
+    capacity: usize,
+    tick: u64,
+    hashes: HashMap<String, u64>,
+    order: BTreeMap<u64, String>,
+}
+
+impl KnownHashes {
+    pub fn new(capacity: usize) -> KnownHashes {
+        KnownHashes {
+            capacity,
+            tick: 0,
+            hashes: HashMap::new(),
+            order: BTreeMap::new(),
+        }
+    }
+
+    /// Inserts the hash, or makes it the most recently seen one.
+    pub fn insert(&mut self, hash: &str) {
+        self.tick += 1;
+
+        if let Some(t) = self.hashes.get_mut(hash) {
+            self.order.remove(t);
+            *t = self.tick;
+            self.order.insert(self.tick, hash.to_string());
+
+            return;
+        }
+
+        if self.hashes.len() >= self.capacity {
+            let oldest_tick = self.order.keys().next().copied();
+
+            if let Some(t) = oldest_tick {
+                if let Some(oldest) = self.order.remove(&t) {
+                    self.hashes.remove(&oldest);
+                }
+            }
+        }
+
+        self.hashes.insert(hash.to_string(), self.tick);
+        self.order.insert(self.tick, hash.to_string());
+    }
+
+    pub fn contains(&self, hash: &str) -> bool {
+        self.hashes.contains_key(hash)
+    }
+
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+}
+
+impl Default for KnownHashes {
+    fn default() -> KnownHashes {
+        KnownHashes::new(KNOWN_HASHES_CAPACITY)
+    }
+}
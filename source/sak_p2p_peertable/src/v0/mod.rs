@@ -1,12 +1,23 @@
 mod iter;
+mod known_hashes;
 mod peer;
+mod policy;
+mod rate_limit;
 mod runtime;
+mod score;
 mod slot;
 mod table;
 
+#[cfg(test)]
+mod tests;
+
 pub use iter::*;
+pub use known_hashes::*;
 pub use peer::*;
+pub use policy::*;
+pub use rate_limit::*;
 pub(crate) use runtime::*;
+pub use score::*;
 pub use slot::*;
 pub use table::*;
 
@@ -1,9 +1,12 @@
-use crate::SlotGuard;
+use crate::{KnownHashes, Misbehavior, PeerScore, RateLimiter, SlotGuard, SlotKind};
 use chrono::{DateTime, Utc};
+use sak_logger::debug;
 use sak_p2p_addr::AddrStatus;
 use sak_p2p_discovery::DiscAddr;
 use sak_p2p_transport::Transport;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
 pub struct Peer {
@@ -12,6 +15,18 @@ pub struct Peer {
     peer_status: RwLock<PeerStatus>,
     addr: Arc<DiscAddr>,
     peer_slot_guard: SlotGuard,
+    score: RwLock<PeerScore>,
+    rate_limiter: RwLock<RateLimiter>,
+    rtt: RwLock<Option<Duration>>,
+    chain_status: RwLock<Option<PeerChainStatus>>,
+    known_hashes: RwLock<KnownHashes>,
+}
+
+/// Chain status of a peer, as told in her hello and updated along the sync.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PeerChainStatus {
+    pub best_height: u128,
+    pub capabilities: u64,
 }
 
 pub enum PeerStatus {
@@ -37,6 +52,11 @@ impl Peer {
             addr,
             peer_slot_guard,
             is_initiator,
+            score: RwLock::new(PeerScore::default()),
+            rate_limiter: RwLock::new(RateLimiter::default()),
+            rtt: RwLock::new(None),
+            chain_status: RwLock::new(None),
+            known_hashes: RwLock::new(KnownHashes::default()),
         }
     }
 
@@ -60,10 +80,111 @@ impl Peer {
         &self.addr
     }
 
+    pub fn get_slot_kind(&self) -> SlotKind {
+        self.peer_slot_guard.slot.kind
+    }
+
     pub fn get_disc_endpoint(&self) -> String {
         self.addr.known_addr.get_disc_endpoint().to_string()
     }
 
+    /// Round trip time measured by the latest ping.
+    pub async fn get_rtt(&self) -> Option<Duration> {
+        *self.rtt.read().await
+    }
+
+    pub async fn set_rtt(&self, rtt: Duration) {
+        let mut rtt_lock = self.rtt.write().await;
+        *rtt_lock = Some(rtt);
+    }
+
+    pub async fn get_chain_status(&self) -> Option<PeerChainStatus> {
+        self.chain_status.read().await.clone()
+    }
+
+    pub async fn set_chain_status(&self, chain_status: PeerChainStatus) {
+        let mut chain_status_lock = self.chain_status.write().await;
+        *chain_status_lock = Some(chain_status);
+    }
+
+    /// Raises the best height of the peer, e.g. when she announces new blocks.
+    pub async fn update_best_height(&self, height: u128) {
+        let mut chain_status_lock = self.chain_status.write().await;
+
+        if let Some(s) = chain_status_lock.as_mut() {
+            s.best_height = s.best_height.max(height);
+        }
+    }
+
+    /// Whether the peer told she has `capability` in her hello.
+    pub async fn has_capability(&self, capability: u64) -> bool {
+        match self.chain_status.read().await.as_ref() {
+            Some(s) => s.capabilities & capability == capability,
+            None => false,
+        }
+    }
+
+    /// Remembers that the peer has these tx or block hashes, so that they
+    /// are not announced to her again.
+    pub async fn mark_known<'a>(&self, hashes: impl IntoIterator<Item = &'a String>) {
+        let mut known_hashes = self.known_hashes.write().await;
+
+        for h in hashes {
+            known_hashes.insert(h);
+        }
+    }
+
+    /// Hashes out of `hashes` the peer is not known to have.
+    pub async fn filter_unknown(&self, hashes: &[String]) -> Vec<String> {
+        let known_hashes = self.known_hashes.read().await;
+
+        hashes
+            .iter()
+            .filter(|h| !known_hashes.contains(h))
+            .cloned()
+            .collect()
+    }
+
+    pub async fn is_disconnected(&self) -> bool {
+        let peer_status = self.peer_status.read().await;
+
+        matches!(*peer_status, PeerStatus::Disconnected)
+    }
+
+    pub async fn get_score(&self) -> PeerScore {
+        self.score.read().await.clone()
+    }
+
+    /// Whether the peer is still within the rate limit of the msg type at
+    /// `now`, which is given by the clock of the node.
+    pub async fn allow_msg(&self, msg_type: &'static str, now: Instant) -> bool {
+        let mut rate_limiter = self.rate_limiter.write().await;
+
+        rate_limiter.try_take_at(msg_type, now)
+    }
+
+    pub async fn reward(&self) {
+        let mut score = self.score.write().await;
+
+        score.reward();
+    }
+
+    /// Lowers the score of the peer and returns the updated one.
+    pub async fn penalize(&self, misbehavior: Misbehavior) -> PeerScore {
+        let mut score = self.score.write().await;
+
+        score.penalize(misbehavior);
+
+        debug!(
+            "Peer misbehaved, her_public_key: {}, misbehavior: {}, score: {}",
+            self.get_public_key_short(),
+            misbehavior,
+            score.score,
+        );
+
+        score.clone()
+    }
+
     pub async fn set_peer_status(&self, peer_status: PeerStatus) {
         match &peer_status {
             PeerStatus::Disconnected => {
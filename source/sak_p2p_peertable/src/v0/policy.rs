@@ -0,0 +1,72 @@
+use crate::PeerTableError;
+use std::net::IpAddr;
+
+/// Max number of inbound peers of the same subnet (/24 for ipv4, /64 for
+/// ipv6). Loopback is not limited.
+pub const MAX_INBOUND_PEERS_PER_SUBNET: usize = 2;
+
+/// Capacity of the peer table split into pools of slots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlotQuota {
+    pub inbound: isize,
+    pub outbound: isize,
+    pub reserved: isize,
+}
+
+impl SlotQuota {
+    /// Reserved peers get up to half of the capacity. Outbound peers get a
+    /// third of the rest unless told otherwise, inbound peers the remainder.
+    pub fn new(
+        capacity: isize,
+        outbound_capacity: Option<isize>,
+        reserved_peer_count: usize,
+    ) -> Result<SlotQuota, PeerTableError> {
+        let reserved = (reserved_peer_count as isize).min(capacity / 2);
+        let unreserved = capacity - reserved;
+
+        let outbound = match outbound_capacity {
+            Some(c) => c,
+            None => (unreserved / 3).max(1).min(unreserved),
+        };
+
+        if outbound < 0 || outbound > unreserved {
+            return Err(format!(
+                "Outbound capacity should be within the unreserved \
+                capacity, outbound: {}, unreserved: {}",
+                outbound, unreserved,
+            )
+            .into());
+        }
+
+        Ok(SlotQuota {
+            inbound: unreserved - outbound,
+            outbound,
+            reserved,
+        })
+    }
+}
+
+/// Subnet the ip belongs to, `None` if the ip is not subject to the subnet
+/// limit.
+pub fn make_subnet(ip: &str) -> Option<String> {
+    let ip: IpAddr = ip.parse().ok()?;
+
+    if ip.is_loopback() {
+        return None;
+    }
+
+    let subnet = match ip {
+        IpAddr::V4(v4) => {
+            let o = v4.octets();
+
+            format!("{}.{}.{}.0/24", o[0], o[1], o[2])
+        }
+        IpAddr::V6(v6) => {
+            let s = v6.segments();
+
+            format!("{:x}:{:x}:{:x}:{:x}::/64", s[0], s[1], s[2], s[3])
+        }
+    };
+
+    Some(subnet)
+}
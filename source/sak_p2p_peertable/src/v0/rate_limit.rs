@@ -0,0 +1,88 @@
+use sak_p2p_transport::MsgType;
+use std::collections::HashMap;
+use std::time::Instant;
+
+// (burst capacity, refill per second)
+const TX_RATE: (f64, f64) = (20.0, 5.0);
+const BLOCK_RATE: (f64, f64) = (10.0, 2.0);
+const LIGHT_REQ_RATE: (f64, f64) = (20.0, 4.0);
+const DEFAULT_RATE: (f64, f64) = (50.0, 10.0);
+
+pub struct TokenBucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// `now` is read from the clock the bucket is later taken from, which
+    /// is a virtual one in a simulation.
+    pub fn new(capacity: f64, refill_per_sec: f64, now: Instant) -> TokenBucket {
+        TokenBucket {
+            capacity,
+            refill_per_sec,
+            tokens: capacity,
+            last_refill: now,
+        }
+    }
+
+    pub fn try_take(&mut self) -> bool {
+        self.try_take_at(Instant::now())
+    }
+
+    pub(crate) fn try_take_at(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Token buckets of a peer, one per msg type.
+#[derive(Default)]
+pub struct RateLimiter {
+    buckets: HashMap<&'static str, TokenBucket>,
+}
+
+impl RateLimiter {
+    pub fn try_take(&mut self, msg_type: &'static str) -> bool {
+        self.try_take_at(msg_type, Instant::now())
+    }
+
+    pub fn try_take_at(&mut self, msg_type: &'static str, now: Instant) -> bool {
+        self.buckets
+            .entry(msg_type)
+            .or_insert_with(|| {
+                let (capacity, refill_per_sec) = get_rate(msg_type);
+
+                TokenBucket::new(capacity, refill_per_sec, now)
+            })
+            .try_take_at(now)
+    }
+}
+
+fn get_rate(msg_type: &str) -> (f64, f64) {
+    match msg_type {
+        MsgType::TX_SYN | MsgType::TX_HASH_SYN => TX_RATE,
+        MsgType::BLOCK_SYN
+        | MsgType::BLOCK_HASH_SYN
+        | MsgType::COMPACT_BLOCK_SYN
+        | MsgType::BLOCK_TXS
+        | MsgType::HEADERS => BLOCK_RATE,
+        MsgType::HEADER_REQ
+        | MsgType::AUTH_PATH_REQ
+        | MsgType::CM_IDX_REQ
+        | MsgType::CTR_QUERY_REQ => LIGHT_REQ_RATE,
+        _ => DEFAULT_RATE,
+    }
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+pub const MAX_PEER_SCORE: i64 = 100;
+
+/// A peer scored this low gets disconnected and banned.
+pub const BAN_PEER_SCORE: i64 = -100;
+
+// Score recovered for each message that turned out valid
+const VALID_MSG_REWARD: i64 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Misbehavior {
+    ParseError,
+    RateLimited,
+    InvalidTx,
+    InvalidBlock,
+    UnknownBlock,
+    UnexpectedMsg,
+    InvalidProof,
+}
+
+impl Misbehavior {
+    pub fn penalty(&self) -> i64 {
+        match self {
+            Misbehavior::ParseError => 20,
+            Misbehavior::RateLimited => 5,
+            Misbehavior::InvalidTx => 10,
+            Misbehavior::InvalidBlock => 50,
+            Misbehavior::UnknownBlock => 5,
+            Misbehavior::UnexpectedMsg => 10,
+            Misbehavior::InvalidProof => 50,
+        }
+    }
+}
+
+impl std::fmt::Display for Misbehavior {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Misbehavior::ParseError => write!(f, "parse_error"),
+            Misbehavior::RateLimited => write!(f, "rate_limited"),
+            Misbehavior::InvalidTx => write!(f, "invalid_tx"),
+            Misbehavior::InvalidBlock => write!(f, "invalid_block"),
+            Misbehavior::UnknownBlock => write!(f, "unknown_block"),
+            Misbehavior::UnexpectedMsg => write!(f, "unexpected_msg"),
+            Misbehavior::InvalidProof => write!(f, "invalid_proof"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PeerScore {
+    pub score: i64,
+    pub valid_msg_count: u64,
+    pub misbehavior_count: u64,
+}
+
+impl PeerScore {
+    pub fn reward(&mut self) {
+        self.valid_msg_count += 1;
+        self.score = (self.score + VALID_MSG_REWARD).min(MAX_PEER_SCORE);
+    }
+
+    pub fn penalize(&mut self, misbehavior: Misbehavior) {
+        self.misbehavior_count += 1;
+        self.score -= misbehavior.penalty();
+    }
+
+    pub fn should_ban(&self) -> bool {
+        self.score <= BAN_PEER_SCORE
+    }
+}
@@ -1,19 +1,34 @@
 use sak_logger::error;
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc::UnboundedSender;
 
+/// Pool a slot belongs to. Reserved slots are only given to the reserved
+/// peers (e.g. bootstrap peers).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SlotKind {
+    Inbound,
+    Outbound,
+    Reserved,
+}
+
 pub struct Slot {
     pub idx: isize,
+    pub kind: SlotKind,
 }
 
 pub struct SlotGuard {
     pub slot: Slot,
     pub slots_tx: Arc<UnboundedSender<Slot>>,
+    pub(crate) _subnet_guard: Option<SubnetGuard>,
 }
 
 impl Drop for SlotGuard {
     fn drop(&mut self) {
-        let slot = std::mem::replace(&mut self.slot, Slot { idx: -1 });
+        let kind = self.slot.kind;
+
+        let slot = std::mem::replace(&mut self.slot, Slot { idx: -1, kind });
 
         match self.slots_tx.send(slot) {
             Ok(_) => (),
@@ -27,3 +42,27 @@ impl Drop for SlotGuard {
         }
     }
 }
+
+/// Counts an inbound peer against her subnet for as long as she holds the
+/// slot.
+pub(crate) struct SubnetGuard {
+    pub(crate) subnet: String,
+    pub(crate) inbound_subnets: Arc<Mutex<HashMap<String, usize>>>,
+}
+
+impl Drop for SubnetGuard {
+    fn drop(&mut self) {
+        let mut inbound_subnets = match self.inbound_subnets.lock() {
+            Ok(s) => s,
+            Err(err) => err.into_inner(),
+        };
+
+        if let Some(count) = inbound_subnets.get_mut(&self.subnet) {
+            *count -= 1;
+
+            if *count == 0 {
+                inbound_subnets.remove(&self.subnet);
+            }
+        }
+    }
+}
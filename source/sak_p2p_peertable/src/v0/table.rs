@@ -1,10 +1,14 @@
-use crate::{Peer, PeerIterator, PeerTableError, Runtime, Slot, SlotGuard};
+use crate::{
+    make_subnet, Peer, PeerChainStatus, PeerIterator, PeerScore, PeerTableError, Runtime, Slot,
+    SlotGuard, SlotKind, SlotQuota, SubnetGuard, MAX_INBOUND_PEERS_PER_SUBNET,
+};
 use colored::Colorize;
 use sak_logger::{debug, error, info};
 use sak_p2p_addr::UnknownAddr;
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::{hash_map::Values, HashMap},
-    sync::Arc,
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
 };
 use tokio::sync::{
     mpsc::{self, UnboundedReceiver, UnboundedSender},
@@ -16,38 +20,85 @@ const PEER_TABLE_CAPACITY: isize = 30;
 pub type PublicKey = String;
 pub type PeerMap = HashMap<PublicKey, Arc<Peer>>;
 
-pub struct PeerTable {
-    peer_map: Arc<RwLock<PeerMap>>,
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeerInfo {
+    pub public_key_str: String,
+    pub p2p_endpoint: String,
+    pub score: PeerScore,
+    pub rtt_ms: Option<u64>,
+    pub chain_status: Option<PeerChainStatus>,
+    pub slot_kind: SlotKind,
+    // Whether she has dialed us
+    pub is_inbound: bool,
+}
+
+struct SlotPool {
     slots_rx: RwLock<UnboundedReceiver<Slot>>,
     slots_tx: Arc<UnboundedSender<Slot>>,
+}
+
+pub struct PeerTable {
+    peer_map: Arc<RwLock<PeerMap>>,
+    slot_pools: HashMap<SlotKind, SlotPool>,
+    slot_quota: SlotQuota,
+    // Peers given reserved slots, e.g. bootstrap peers
+    reserved_public_keys: HashSet<PublicKey>,
+    // Number of inbound peers by subnet
+    inbound_subnets: Arc<Mutex<HashMap<String, usize>>>,
     peer_queue_tx: Arc<UnboundedSender<Arc<Peer>>>,
     peer_queue_iter: Arc<RwLock<PeerIterator>>,
 }
 
 impl PeerTable {
-    pub async fn init(peer_table_capacity: Option<i16>) -> Result<PeerTable, PeerTableError> {
+    pub async fn init(
+        peer_table_capacity: Option<i16>,
+        outbound_capacity: Option<i16>,
+        reserved_public_keys: Vec<PublicKey>,
+    ) -> Result<PeerTable, PeerTableError> {
         let capacity = match peer_table_capacity {
             Some(c) => c.into(),
             None => PEER_TABLE_CAPACITY,
         };
 
-        let (slots_tx, slots_rx) = {
-            let (tx, rx) = mpsc::unbounded_channel();
-            let slots_tx = Arc::new(tx);
-            let slots_rx = RwLock::new(rx);
-
-            for idx in 0..capacity {
-                let s = Slot { idx };
-
-                match slots_tx.send(s) {
-                    Ok(_) => (),
-                    Err(err) => {
-                        error!("slots channel has been closed, err: {}", err,);
-                    }
+        let reserved_public_keys: HashSet<PublicKey> = reserved_public_keys.into_iter().collect();
+
+        let slot_quota = SlotQuota::new(
+            capacity,
+            outbound_capacity.map(|c| c.into()),
+            reserved_public_keys.len(),
+        )?;
+
+        let slot_pools = {
+            let mut pools = HashMap::new();
+            let mut idx = 0;
+
+            for (kind, count) in [
+                (SlotKind::Inbound, slot_quota.inbound),
+                (SlotKind::Outbound, slot_quota.outbound),
+                (SlotKind::Reserved, slot_quota.reserved),
+            ] {
+                let (tx, rx) = mpsc::unbounded_channel();
+
+                for _ in 0..count {
+                    match tx.send(Slot { idx, kind }) {
+                        Ok(_) => (),
+                        Err(err) => {
+                            error!("slots channel has been closed, err: {}", err,);
+                        }
+                    };
+
+                    idx += 1;
+                }
+
+                let pool = SlotPool {
+                    slots_rx: RwLock::new(rx),
+                    slots_tx: Arc::new(tx),
                 };
+
+                pools.insert(kind, pool);
             }
 
-            (slots_tx, slots_rx)
+            pools
         };
 
         let (peer_queue_tx, peer_queue_iter) = {
@@ -75,13 +126,18 @@ impl PeerTable {
 
         let ps = PeerTable {
             peer_map,
-            slots_rx,
-            slots_tx,
+            slot_pools,
+            slot_quota,
+            reserved_public_keys,
+            inbound_subnets: Arc::new(Mutex::new(HashMap::new())),
             peer_queue_tx,
             peer_queue_iter,
         };
 
-        debug!("Initialized peer table, capacity: {}", capacity);
+        debug!(
+            "Initialized peer table, capacity: {}, quota: {:?}",
+            capacity, ps.slot_quota,
+        );
 
         Ok(ps)
     }
@@ -114,21 +170,114 @@ impl PeerTable {
         &self.peer_map
     }
 
-    pub async fn get_empty_slot(&self) -> Result<SlotGuard, PeerTableError> {
-        let mut slots_rx = self.slots_rx.write().await;
+    pub fn get_slot_quota(&self) -> &SlotQuota {
+        &self.slot_quota
+    }
 
-        match slots_rx.recv().await {
-            Some(s) => {
-                let slot_guard = SlotGuard {
-                    slot: s,
-                    slots_tx: self.slots_tx.clone(),
-                };
+    pub fn is_reserved(&self, public_key: &PublicKey) -> bool {
+        self.reserved_public_keys.contains(public_key)
+    }
+
+    /// Slot for a peer we dial. Reserved peers may take a reserved slot if
+    /// outbound slots are all taken.
+    pub async fn get_outbound_slot(
+        &self,
+        public_key: &PublicKey,
+    ) -> Result<SlotGuard, PeerTableError> {
+        if let Some(s) = self.take_slot(SlotKind::Outbound, None).await {
+            return Ok(s);
+        }
 
-                return Ok(slot_guard);
+        if self.is_reserved(public_key) {
+            if let Some(s) = self.take_slot(SlotKind::Reserved, None).await {
+                return Ok(s);
             }
-            None => {
-                return Err(format!("Peer slots have beeen closed. Critical error").into());
+        }
+
+        Err("Outbound slots are all taken".into())
+    }
+
+    /// Whether a peer who has dialed us might be given a slot. Checked
+    /// before the handshake, when it is not known yet whether she is a
+    /// reserved peer.
+    pub async fn has_inbound_capacity(&self) -> bool {
+        for kind in [SlotKind::Inbound, SlotKind::Reserved] {
+            if let Some(pool) = self.slot_pools.get(&kind) {
+                if !pool.slots_rx.read().await.is_empty() {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Slot for a peer who has dialed us. Peers other than the reserved
+    /// ones are limited in number by their subnet.
+    pub async fn get_inbound_slot(
+        &self,
+        public_key: &PublicKey,
+        ip: &str,
+    ) -> Result<SlotGuard, PeerTableError> {
+        if self.is_reserved(public_key) {
+            if let Some(s) = self.take_slot(SlotKind::Reserved, None).await {
+                return Ok(s);
+            }
+
+            if let Some(s) = self.take_slot(SlotKind::Inbound, None).await {
+                return Ok(s);
             }
+
+            return Err("Inbound and reserved slots are all taken".into());
+        }
+
+        let subnet_guard = match make_subnet(ip) {
+            Some(subnet) => Some(self.reserve_subnet(subnet)?),
+            None => None,
+        };
+
+        match self.take_slot(SlotKind::Inbound, subnet_guard).await {
+            Some(s) => Ok(s),
+            None => Err("Inbound slots are all taken".into()),
+        }
+    }
+
+    fn reserve_subnet(&self, subnet: String) -> Result<SubnetGuard, PeerTableError> {
+        let mut inbound_subnets = match self.inbound_subnets.lock() {
+            Ok(s) => s,
+            Err(err) => err.into_inner(),
+        };
+
+        let count = inbound_subnets.entry(subnet.clone()).or_insert(0);
+
+        if *count >= MAX_INBOUND_PEERS_PER_SUBNET {
+            return Err(format!("Too many inbound peers of the subnet, subnet: {}", subnet).into());
+        }
+
+        *count += 1;
+
+        Ok(SubnetGuard {
+            subnet,
+            inbound_subnets: self.inbound_subnets.clone(),
+        })
+    }
+
+    async fn take_slot(
+        &self,
+        kind: SlotKind,
+        subnet_guard: Option<SubnetGuard>,
+    ) -> Option<SlotGuard> {
+        let pool = self.slot_pools.get(&kind)?;
+
+        let mut slots_rx = pool.slots_rx.write().await;
+
+        match slots_rx.try_recv() {
+            Ok(s) => Some(SlotGuard {
+                slot: s,
+                slots_tx: pool.slots_tx.clone(),
+                _subnet_guard: subnet_guard,
+            }),
+            Err(_) => None,
         }
     }
 
@@ -156,6 +305,14 @@ impl PeerTable {
         Ok(peer_map.insert(public_key_str, peer))
     }
 
+    /// The slot of the peer is put back in the queue once the last reference
+    /// to the peer is dropped.
+    pub async fn remove_mapping(&self, public_key: &PublicKey) -> Option<Arc<Peer>> {
+        let mut peer_map = self.peer_map.write().await;
+
+        peer_map.remove(public_key)
+    }
+
     pub async fn get_status(&self) -> Vec<String> {
         let mut peer_vec = Vec::new();
         let peer_map = self.peer_map.read().await;
@@ -167,6 +324,30 @@ impl PeerTable {
         peer_vec
     }
 
+    pub async fn get_peer_infos(&self) -> Vec<PeerInfo> {
+        let peers: Vec<Arc<Peer>> = {
+            let peer_map = self.peer_map.read().await;
+
+            peer_map.values().cloned().collect()
+        };
+
+        let mut peer_infos = Vec::with_capacity(peers.len());
+
+        for peer in peers {
+            peer_infos.push(PeerInfo {
+                public_key_str: peer.get_public_key().to_string(),
+                p2p_endpoint: peer.get_addr().known_addr.get_p2p_endpoint(),
+                score: peer.get_score().await,
+                rtt_ms: peer.get_rtt().await.map(|d| d.as_millis() as u64),
+                chain_status: peer.get_chain_status().await,
+                slot_kind: peer.get_slot_kind(),
+                is_inbound: peer.is_initiator,
+            });
+        }
+
+        peer_infos
+    }
+
     pub fn peer_queue_iter(&self) -> Arc<RwLock<PeerIterator>> {
         self.peer_queue_iter.clone()
     }
@@ -0,0 +1,34 @@
+use crate::KnownHashes;
+
+#[test]
+fn test_known_hashes_drops_the_least_recently_seen() {
+    let mut known_hashes = KnownHashes::new(3);
+
+    known_hashes.insert("a");
+    known_hashes.insert("b");
+    known_hashes.insert("c");
+
+    // "a" is seen again, so "b" becomes the oldest
+    known_hashes.insert("a");
+    known_hashes.insert("d");
+
+    assert_eq!(known_hashes.len(), 3);
+    assert!(known_hashes.contains("a"));
+    assert!(!known_hashes.contains("b"));
+    assert!(known_hashes.contains("c"));
+    assert!(known_hashes.contains("d"));
+}
+
+#[test]
+fn test_known_hashes_stays_bounded() {
+    let mut known_hashes = KnownHashes::new(100);
+
+    for i in 0..1000 {
+        known_hashes.insert(&i.to_string());
+    }
+
+    assert_eq!(known_hashes.len(), 100);
+    assert!(known_hashes.contains("999"));
+    assert!(known_hashes.contains("900"));
+    assert!(!known_hashes.contains("899"));
+}
@@ -0,0 +1,4 @@
+mod known_hashes;
+mod rate_limit;
+mod score;
+mod slot;
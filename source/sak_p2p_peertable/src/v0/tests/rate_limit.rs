@@ -0,0 +1,53 @@
+use crate::{RateLimiter, TokenBucket};
+use sak_p2p_transport::MsgType;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_token_bucket_refills_over_time() {
+    let now = Instant::now();
+
+    let mut bucket = TokenBucket::new(2.0, 1.0, now);
+
+    assert!(bucket.try_take_at(now));
+    assert!(bucket.try_take_at(now));
+    assert!(!bucket.try_take_at(now));
+
+    assert!(bucket.try_take_at(now + Duration::from_secs(1)));
+    assert!(!bucket.try_take_at(now + Duration::from_secs(1)));
+
+    // Tokens do not pile up beyond the capacity
+    let later = now + Duration::from_secs(60);
+
+    assert!(bucket.try_take_at(later));
+    assert!(bucket.try_take_at(later));
+    assert!(!bucket.try_take_at(later));
+}
+
+#[test]
+fn test_token_bucket_refills_on_a_clock_behind_the_wall_clock() {
+    let now = Instant::now()
+        .checked_sub(Duration::from_secs(60))
+        .expect("Instant should go back a minute");
+
+    let mut rate_limiter = RateLimiter::default();
+
+    assert!(rate_limiter.try_take_at(MsgType::PING, now));
+
+    // Drained
+    assert!((0..100).any(|_| !rate_limiter.try_take_at(MsgType::PING, now)));
+
+    assert!(rate_limiter.try_take_at(MsgType::PING, now + Duration::from_secs(1)));
+}
+
+#[test]
+fn test_rate_limiter_keeps_a_bucket_per_msg_type() {
+    let mut rate_limiter = RateLimiter::default();
+
+    let allowed = (0..100)
+        .filter(|_| rate_limiter.try_take(MsgType::BLOCK_SYN))
+        .count();
+
+    assert!(allowed < 100);
+
+    assert!(rate_limiter.try_take(MsgType::TX_SYN));
+}
@@ -0,0 +1,27 @@
+use crate::{Misbehavior, PeerScore, MAX_PEER_SCORE};
+
+#[test]
+fn test_peer_score_is_banned_after_repeated_misbehavior() {
+    let mut score = PeerScore::default();
+
+    score.penalize(Misbehavior::InvalidBlock);
+
+    assert!(!score.should_ban());
+
+    score.penalize(Misbehavior::InvalidBlock);
+
+    assert!(score.should_ban());
+    assert_eq!(score.misbehavior_count, 2);
+}
+
+#[test]
+fn test_peer_score_reward_is_capped() {
+    let mut score = PeerScore::default();
+
+    for _ in 0..(MAX_PEER_SCORE * 2) {
+        score.reward();
+    }
+
+    assert_eq!(score.score, MAX_PEER_SCORE);
+    assert_eq!(score.valid_msg_count, (MAX_PEER_SCORE * 2) as u64);
+}
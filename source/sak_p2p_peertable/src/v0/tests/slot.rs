@@ -0,0 +1,136 @@
+use crate::{make_subnet, PeerTable, SlotKind, SlotQuota, MAX_INBOUND_PEERS_PER_SUBNET};
+
+#[test]
+fn test_slot_quota_splits_capacity() {
+    let quota = SlotQuota::new(30, None, 2).unwrap();
+
+    assert_eq!(
+        quota,
+        SlotQuota {
+            inbound: 19,
+            outbound: 9,
+            reserved: 2,
+        }
+    );
+
+    // Reserved slots are at most half of the capacity
+    let quota = SlotQuota::new(4, Some(1), 10).unwrap();
+
+    assert_eq!(quota.reserved, 2);
+    assert_eq!(quota.inbound, 1);
+
+    assert!(SlotQuota::new(4, Some(5), 0).is_err());
+}
+
+#[test]
+fn test_make_subnet() {
+    assert_eq!(
+        make_subnet("203.0.113.7"),
+        Some("203.0.113.0/24".to_string())
+    );
+    assert_eq!(
+        make_subnet("2001:db8:1:2:3:4:5:6"),
+        Some("2001:db8:1:2::/64".to_string())
+    );
+    assert_eq!(make_subnet("127.0.0.1"), None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_outbound_slots_are_limited() {
+    let peer_table = PeerTable::init(Some(6), Some(1), vec!["friend".to_string()])
+        .await
+        .unwrap();
+
+    let slot_1 = peer_table
+        .get_outbound_slot(&"node_1".to_string())
+        .await
+        .unwrap();
+
+    assert_eq!(slot_1.slot.kind, SlotKind::Outbound);
+    assert!(peer_table
+        .get_outbound_slot(&"node_2".to_string())
+        .await
+        .is_err());
+
+    // Reserved peer is not locked out by the others
+    let slot_2 = peer_table
+        .get_outbound_slot(&"friend".to_string())
+        .await
+        .unwrap();
+
+    assert_eq!(slot_2.slot.kind, SlotKind::Reserved);
+
+    drop(slot_1);
+
+    assert!(peer_table
+        .get_outbound_slot(&"node_2".to_string())
+        .await
+        .is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_inbound_slots_are_limited_by_subnet() {
+    let peer_table = PeerTable::init(Some(10), Some(1), vec![]).await.unwrap();
+
+    let mut slots = vec![];
+
+    for idx in 0..MAX_INBOUND_PEERS_PER_SUBNET {
+        let ip = format!("203.0.113.{}", idx + 1);
+
+        let s = peer_table
+            .get_inbound_slot(&format!("node_{}", idx), &ip)
+            .await
+            .unwrap();
+
+        slots.push(s);
+    }
+
+    assert!(peer_table
+        .get_inbound_slot(&"node_a".to_string(), "203.0.113.100")
+        .await
+        .is_err());
+
+    assert!(peer_table
+        .get_inbound_slot(&"node_b".to_string(), "198.51.100.1")
+        .await
+        .is_ok());
+
+    // Subnet is counted only while the slot is held
+    slots.pop();
+
+    assert!(peer_table
+        .get_inbound_slot(&"node_a".to_string(), "203.0.113.100")
+        .await
+        .is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_inbound_capacity_counts_reserved_slots() {
+    let peer_table = PeerTable::init(Some(4), Some(1), vec!["friend".to_string()])
+        .await
+        .unwrap();
+
+    let slot_1 = peer_table
+        .get_inbound_slot(&"node_1".to_string(), "203.0.113.1")
+        .await
+        .unwrap();
+
+    let _slot_2 = peer_table
+        .get_inbound_slot(&"node_2".to_string(), "198.51.100.1")
+        .await
+        .unwrap();
+
+    // She might be the reserved peer, which is not known before the handshake
+    assert!(peer_table.has_inbound_capacity().await);
+
+    let _slot_3 = peer_table
+        .get_inbound_slot(&"friend".to_string(), "192.0.2.1")
+        .await
+        .unwrap();
+
+    assert!(!peer_table.has_inbound_capacity().await);
+
+    drop(slot_1);
+
+    assert!(peer_table.has_inbound_capacity().await);
+}
@@ -1,9 +1,11 @@
 mod msg_codec;
+mod msg_stream;
 mod msg_wrap;
 mod plain;
 mod upgraded;
 
 pub(crate) use msg_codec::*;
+pub use msg_stream::*;
 pub use msg_wrap::*;
 pub use plain::*;
 pub use upgraded::*;
@@ -1,97 +1,73 @@
 use crate::{
-    BlockAckMsg, BlockHashSyncMsg, BlockSynMsg, ErrorMsg, HandshakeMsg, HelloMsg, Msg, MsgType,
-    PingMsg, TrptError, TxAckMsg, TxHashSyncMsg, TxSynMsg,
+    decode_exact, AuthPathMsg, AuthPathReqMsg, BlockAckMsg, BlockHashSyncMsg, BlockSynMsg,
+    BlockTxsMsg, BlockTxsReqMsg, CmIdxMsg, CmIdxReqMsg, CompactBlockSynMsg, CtrQueryMsg,
+    CtrQueryReqMsg, ErrorMsg, HandshakeFinMsg, HandshakeMsg, HeaderReqMsg, HeadersMsg, HelloMsg,
+    Msg, MsgCode, PingMsg, TrptError, TxAckMsg, TxHashSyncMsg, TxSynMsg, WIRE_VERSION,
 };
-use bytes::BytesMut;
-use sak_p2p_frame::{frame_io, Parse};
+use bytes::Bytes;
 
-pub(crate) fn decode_into_msg(src: &mut BytesMut) -> Result<Option<Msg>, TrptError> {
-    let maybe_frame = match frame_io::parse_frame(src) {
-        Ok(f) => f,
-        Err(err) => return Err(format!("Error parsing the frame, original_err: {}", err).into()),
-    };
+/// Decodes a msg out of `src` which holds exactly one msg.
+pub(crate) fn decode_from_msg_bytes(mut src: Bytes) -> Result<Msg, TrptError> {
+    if src.len() < 2 {
+        return Err(format!("Msg is too short, len: {}", src.len()).into());
+    }
 
-    if let Some(frame) = maybe_frame {
-        let mut parse = Parse::new(frame)?;
+    let header = src.split_to(2);
+    let (wire_version, msg_code) = (header[0], header[1]);
 
-        let msg_type = parse.next_string()?.to_lowercase();
+    if wire_version != WIRE_VERSION {
+        return Err(format!(
+            "Wire version mismatch, mine: {}, hers: {}",
+            WIRE_VERSION, wire_version,
+        )
+        .into());
+    }
 
-        let msg = match parse_msg(&msg_type, parse) {
-            Ok(m) => m,
-            Err(err) => {
-                return Err(
-                    format!("Error parsing msg, msg_type: {}, err: {}", msg_type, err).into(),
-                );
-            }
-        };
+    let msg = match parse_msg(msg_code, src) {
+        Ok(m) => m,
+        Err(err) => {
+            return Err(format!("Error parsing msg, msg_code: {}, err: {}", msg_code, err).into());
+        }
+    };
 
-        return Ok(Some(msg));
-    } else {
-        return Ok(None);
-    }
+    Ok(msg)
 }
 
 #[inline]
-fn parse_msg(msg_type: &String, mut parse: Parse) -> Result<Msg, TrptError> {
-    let msg = match msg_type.as_str() {
-        MsgType::HELLO_SYN => {
-            let hello = HelloMsg::from_parse(&mut parse)?;
-            Msg::HelloSyn(hello)
-        }
-        MsgType::HELLO_ACK => {
-            let hello = HelloMsg::from_parse(&mut parse)?;
-            Msg::HelloAck(hello)
-        }
-        MsgType::HANDSHAKE_SYN => {
-            let handshake = HandshakeMsg::from_parse(&mut parse)?;
-            Msg::HandshakeSyn(handshake)
-        }
-        MsgType::HANDSHAKE_ACK => {
-            let handshake = HandshakeMsg::from_parse(&mut parse)?;
-            Msg::HandshakeAck(handshake)
-        }
-        MsgType::TX_HASH_SYN => {
-            let tx_hash_sync = TxHashSyncMsg::from_parse(&mut parse)?;
-            Msg::TxHashSyn(tx_hash_sync)
-        }
-        MsgType::TX_HASH_ACK => {
-            let tx_hash_sync = TxHashSyncMsg::from_parse(&mut parse)?;
-            Msg::TxHashAck(tx_hash_sync)
-        }
-        MsgType::TX_SYN => {
-            let tx_syn = TxSynMsg::from_parse(&mut parse)?;
-            Msg::TxSyn(tx_syn)
-        }
-        MsgType::TX_ACK => {
-            let tx_ack = TxAckMsg::from_parse(&mut parse)?;
-            Msg::TxAck(tx_ack)
-        }
-        MsgType::BLOCK_HASH_SYN => {
-            let block_hash_sync = BlockHashSyncMsg::from_parse(&mut parse)?;
-            Msg::BlockHashSyn(block_hash_sync)
-        }
-        MsgType::BLOCK_HASH_ACK => {
-            let block_hash_ack = BlockHashSyncMsg::from_parse(&mut parse)?;
-            Msg::BlockHashAck(block_hash_ack)
-        }
-        MsgType::BLOCK_SYN => {
-            let block_syn = BlockSynMsg::from_parse(&mut parse)?;
-            Msg::BlockSyn(block_syn)
-        }
-        MsgType::BLOCK_ACK => {
-            let block_ack = BlockAckMsg::from_parse(&mut parse)?;
-            Msg::BlockAck(block_ack)
-        }
-        MsgType::PING => {
-            let ping = PingMsg::from_parse(&mut parse)?;
-            Msg::Ping(ping)
-        }
-        MsgType::ERROR => {
-            let error = ErrorMsg::from_parse(&mut parse)?;
-            Msg::Error(error)
+fn parse_msg(msg_code: u8, src: Bytes) -> Result<Msg, TrptError> {
+    let msg = match msg_code {
+        MsgCode::HELLO_SYN => Msg::HelloSyn(decode_exact::<HelloMsg>(src)?),
+        MsgCode::HELLO_ACK => Msg::HelloAck(decode_exact::<HelloMsg>(src)?),
+        MsgCode::HANDSHAKE_SYN => Msg::HandshakeSyn(decode_exact::<HandshakeMsg>(src)?),
+        MsgCode::HANDSHAKE_ACK => Msg::HandshakeAck(decode_exact::<HandshakeMsg>(src)?),
+        MsgCode::HANDSHAKE_FIN => Msg::HandshakeFin(decode_exact::<HandshakeFinMsg>(src)?),
+        MsgCode::TX_HASH_SYN => Msg::TxHashSyn(decode_exact::<TxHashSyncMsg>(src)?),
+        MsgCode::TX_HASH_ACK => Msg::TxHashAck(decode_exact::<TxHashSyncMsg>(src)?),
+        MsgCode::TX_SYN => Msg::TxSyn(decode_exact::<TxSynMsg>(src)?),
+        MsgCode::TX_ACK => Msg::TxAck(decode_exact::<TxAckMsg>(src)?),
+        MsgCode::BLOCK_HASH_SYN => Msg::BlockHashSyn(decode_exact::<BlockHashSyncMsg>(src)?),
+        MsgCode::BLOCK_HASH_ACK => Msg::BlockHashAck(decode_exact::<BlockHashSyncMsg>(src)?),
+        MsgCode::BLOCK_SYN => Msg::BlockSyn(decode_exact::<BlockSynMsg>(src)?),
+        MsgCode::BLOCK_ACK => Msg::BlockAck(decode_exact::<BlockAckMsg>(src)?),
+        MsgCode::COMPACT_BLOCK_SYN => {
+            Msg::CompactBlockSyn(decode_exact::<CompactBlockSynMsg>(src)?)
         }
+        MsgCode::BLOCK_TXS_REQ => Msg::BlockTxsReq(decode_exact::<BlockTxsReqMsg>(src)?),
+        MsgCode::BLOCK_TXS => Msg::BlockTxs(decode_exact::<BlockTxsMsg>(src)?),
+        MsgCode::FULL_BLOCK_REQ => Msg::FullBlockReq(decode_exact::<BlockHashSyncMsg>(src)?),
+        MsgCode::HEADER_REQ => Msg::HeaderReq(decode_exact::<HeaderReqMsg>(src)?),
+        MsgCode::HEADERS => Msg::Headers(decode_exact::<HeadersMsg>(src)?),
+        MsgCode::AUTH_PATH_REQ => Msg::AuthPathReq(decode_exact::<AuthPathReqMsg>(src)?),
+        MsgCode::AUTH_PATH => Msg::AuthPath(decode_exact::<AuthPathMsg>(src)?),
+        MsgCode::CM_IDX_REQ => Msg::CmIdxReq(decode_exact::<CmIdxReqMsg>(src)?),
+        MsgCode::CM_IDX => Msg::CmIdx(decode_exact::<CmIdxMsg>(src)?),
+        MsgCode::CTR_QUERY_REQ => Msg::CtrQueryReq(decode_exact::<CtrQueryReqMsg>(src)?),
+        MsgCode::CTR_QUERY => Msg::CtrQuery(decode_exact::<CtrQueryMsg>(src)?),
+        MsgCode::PING => Msg::Ping(decode_exact::<PingMsg>(src)?),
+        MsgCode::PONG => Msg::Pong(decode_exact::<PingMsg>(src)?),
+        MsgCode::ERROR => Msg::Error(decode_exact::<ErrorMsg>(src)?),
         _ => {
-            return Err(format!("Frame does have invalid msg_type, type: {}", msg_type).into());
+            return Err(format!("Msg has an invalid msg_code, code: {}", msg_code).into());
         }
     };
 
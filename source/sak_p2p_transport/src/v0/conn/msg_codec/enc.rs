@@ -1,35 +1,47 @@
-use crate::{Msg, MsgType, TrptError};
-use bytes::BytesMut;
-use sak_p2p_frame::frame_io;
+use crate::{Encode, Msg, MsgCode, TrptError, WIRE_VERSION};
+use bytes::{BufMut, BytesMut};
 
-pub(crate) fn encode_into_frame(item: Msg, dst: &mut BytesMut) -> Result<&'static str, TrptError> {
-    let (frame, msg_type) = match item {
-        Msg::Ping(ping) => (ping.into_frame(), MsgType::PING),
-        Msg::HelloSyn(hello) => (hello.into_syn_frame(), MsgType::HELLO_SYN),
-        Msg::HelloAck(hello) => (hello.into_ack_frame(), MsgType::HELLO_SYN),
-        Msg::HandshakeSyn(handshake) => (handshake.into_syn_frame(), MsgType::HANDSHAKE_SYN),
-        Msg::HandshakeAck(handshake) => (handshake.into_ack_frame(), MsgType::HANDSHAKE_ACK),
-        Msg::TxSyn(sync) => (sync.into_frame(), MsgType::TX_SYN),
-        Msg::TxAck(m) => (m.into_frame(), MsgType::TX_ACK),
-        Msg::TxHashSyn(sync_tx_hash) => (sync_tx_hash.into_syn_frame(), MsgType::TX_HASH_SYN),
-        Msg::TxHashAck(sync_tx_hash) => (sync_tx_hash.into_ack_frame(), MsgType::TX_HASH_ACK),
-        Msg::BlockHashSyn(block_hash_sync) => {
-            (block_hash_sync.into_syn_frame(), MsgType::BLOCK_HASH_SYN)
-        }
-        Msg::BlockHashAck(block_hash_sync) => {
-            (block_hash_sync.into_ack_frame(), MsgType::BLOCK_HASH_ACK)
-        }
-        Msg::BlockSyn(sync_block) => (sync_block.into_frame(), MsgType::BLOCK_SYN),
-        Msg::BlockAck(m) => (m.into_frame(), MsgType::BLOCK_ACK),
-        Msg::Error(error) => (error.into_frame(), MsgType::ERROR),
-    };
+pub(crate) fn encode_into_msg_bytes(
+    item: Msg,
+    dst: &mut BytesMut,
+) -> Result<&'static str, TrptError> {
+    let msg_type = item.get_type();
 
-    match frame_io::write_frame(dst, &frame) {
-        Ok(_) => (),
-        Err(err) => {
-            return Err(format!("Error writing who_are_you_syn_frame, err: {}", err).into());
-        }
+    let (msg_code, msg_body): (u8, &dyn Encode) = match &item {
+        Msg::HelloSyn(hello) => (MsgCode::HELLO_SYN, hello),
+        Msg::HelloAck(hello) => (MsgCode::HELLO_ACK, hello),
+        Msg::HandshakeSyn(handshake) => (MsgCode::HANDSHAKE_SYN, handshake),
+        Msg::HandshakeAck(handshake) => (MsgCode::HANDSHAKE_ACK, handshake),
+        Msg::HandshakeFin(handshake) => (MsgCode::HANDSHAKE_FIN, handshake),
+        Msg::TxHashSyn(sync_tx_hash) => (MsgCode::TX_HASH_SYN, sync_tx_hash),
+        Msg::TxHashAck(sync_tx_hash) => (MsgCode::TX_HASH_ACK, sync_tx_hash),
+        Msg::TxSyn(sync) => (MsgCode::TX_SYN, sync),
+        Msg::TxAck(m) => (MsgCode::TX_ACK, m),
+        Msg::BlockHashSyn(block_hash_sync) => (MsgCode::BLOCK_HASH_SYN, block_hash_sync),
+        Msg::BlockHashAck(block_hash_sync) => (MsgCode::BLOCK_HASH_ACK, block_hash_sync),
+        Msg::BlockSyn(sync_block) => (MsgCode::BLOCK_SYN, sync_block),
+        Msg::BlockAck(m) => (MsgCode::BLOCK_ACK, m),
+        Msg::CompactBlockSyn(compact_block) => (MsgCode::COMPACT_BLOCK_SYN, compact_block),
+        Msg::BlockTxsReq(block_txs_req) => (MsgCode::BLOCK_TXS_REQ, block_txs_req),
+        Msg::BlockTxs(block_txs) => (MsgCode::BLOCK_TXS, block_txs),
+        Msg::FullBlockReq(block_hash_sync) => (MsgCode::FULL_BLOCK_REQ, block_hash_sync),
+        Msg::HeaderReq(header_req) => (MsgCode::HEADER_REQ, header_req),
+        Msg::Headers(headers) => (MsgCode::HEADERS, headers),
+        Msg::AuthPathReq(auth_path_req) => (MsgCode::AUTH_PATH_REQ, auth_path_req),
+        Msg::AuthPath(auth_path) => (MsgCode::AUTH_PATH, auth_path),
+        Msg::CmIdxReq(cm_idx_req) => (MsgCode::CM_IDX_REQ, cm_idx_req),
+        Msg::CmIdx(cm_idx) => (MsgCode::CM_IDX, cm_idx),
+        Msg::CtrQueryReq(ctr_query_req) => (MsgCode::CTR_QUERY_REQ, ctr_query_req),
+        Msg::CtrQuery(ctr_query) => (MsgCode::CTR_QUERY, ctr_query),
+        Msg::Error(error) => (MsgCode::ERROR, error),
+        Msg::Ping(ping) => (MsgCode::PING, ping),
+        Msg::Pong(pong) => (MsgCode::PONG, pong),
     };
 
+    dst.put_u8(WIRE_VERSION);
+    dst.put_u8(msg_code);
+
+    msg_body.encode(dst);
+
     Ok(msg_type)
 }
@@ -0,0 +1,60 @@
+use crate::{Msg, TrptError};
+use futures::{Sink, Stream};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+
+/// Carries the msgs of an upgraded conn. A framed TCP socket in a node, and
+/// an in-memory link a simulator schedules in tests.
+pub trait MsgStream:
+    Stream<Item = Result<Msg, TrptError>> + Sink<Msg, Error = TrptError> + Send + Sync + Unpin
+{
+}
+
+impl<T> MsgStream for T where
+    T: Stream<Item = Result<Msg, TrptError>> + Sink<Msg, Error = TrptError> + Send + Sync + Unpin
+{
+}
+
+/// In-memory end of a link. Msgs sent are handed to `msg_tx`, and the ones
+/// received are read from `msg_rx`, whoever is at the other side of them.
+pub struct MemStream {
+    msg_tx: UnboundedSender<Msg>,
+    msg_rx: UnboundedReceiver<Msg>,
+}
+
+impl MemStream {
+    pub fn new(msg_tx: UnboundedSender<Msg>, msg_rx: UnboundedReceiver<Msg>) -> MemStream {
+        MemStream { msg_tx, msg_rx }
+    }
+}
+
+impl Stream for MemStream {
+    type Item = Result<Msg, TrptError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.msg_rx.poll_recv(cx).map(|m| m.map(Ok))
+    }
+}
+
+impl Sink<Msg> for MemStream {
+    type Error = TrptError;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), TrptError>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, msg: Msg) -> Result<(), TrptError> {
+        self.msg_tx
+            .send(msg)
+            .map_err(|_| "In-memory link has been closed".into())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), TrptError>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), TrptError>> {
+        Poll::Ready(Ok(()))
+    }
+}
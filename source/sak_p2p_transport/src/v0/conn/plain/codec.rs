@@ -1,14 +1,32 @@
-use crate::{dec, enc, Msg, TrptError};
-use bytes::BytesMut;
+use crate::{dec, enc, Msg, TrptError, MAX_MSG_LEN};
+use bytes::{Buf, BufMut, BytesMut};
 use tokio_util::codec::{Decoder, Encoder};
 
+// Each msg is prefixed with its length, u32 big endian
+const LEN_PREFIX_LEN: usize = 4;
+
 pub struct P2PCodec {}
 
 impl Encoder<Msg> for P2PCodec {
     type Error = TrptError;
 
     fn encode(&mut self, item: Msg, dst: &mut BytesMut) -> Result<(), TrptError> {
-        let _msg_type = enc::encode_into_frame(item, dst)?;
+        let mut msg_part = BytesMut::new();
+
+        let _msg_type = enc::encode_into_msg_bytes(item, &mut msg_part)?;
+
+        if msg_part.len() > MAX_MSG_LEN {
+            return Err(format!(
+                "Message is too large, msg_len: {}, max: {}",
+                msg_part.len(),
+                MAX_MSG_LEN,
+            )
+            .into());
+        }
+
+        dst.reserve(LEN_PREFIX_LEN + msg_part.len());
+        dst.put_u32(msg_part.len() as u32);
+        dst.extend_from_slice(&msg_part);
 
         return Ok(());
     }
@@ -19,6 +37,32 @@ impl Decoder for P2PCodec {
     type Error = TrptError;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, TrptError> {
-        return dec::decode_into_msg(src);
+        if src.len() < LEN_PREFIX_LEN {
+            return Ok(None);
+        }
+
+        let msg_len = u32::from_be_bytes([src[0], src[1], src[2], src[3]]) as usize;
+
+        if msg_len > MAX_MSG_LEN {
+            return Err(format!(
+                "Message is too large, msg_len: {}, max: {}",
+                msg_len, MAX_MSG_LEN,
+            )
+            .into());
+        }
+
+        if src.len() < LEN_PREFIX_LEN + msg_len {
+            src.reserve(LEN_PREFIX_LEN + msg_len - src.len());
+
+            return Ok(None);
+        }
+
+        src.advance(LEN_PREFIX_LEN);
+
+        let msg_part = src.split_to(msg_len).freeze();
+
+        let msg = dec::decode_from_msg_bytes(msg_part)?;
+
+        return Ok(Some(msg));
     }
 }
@@ -1,10 +1,5 @@
 use super::codec::P2PCodec;
-use crate::{TrptError, UpgradedConn, UpgradedP2PCodec};
-use chacha20::{cipher::KeyIvInit, ChaCha20};
-use sak_crypto::{
-    sha3::{Digest, Keccak256},
-    SharedSecret,
-};
+use crate::{handshake::SessionKeys, TrptError, UpgradedConn, UpgradedP2PCodec};
 use std::net::SocketAddr;
 use tokio::net::TcpStream;
 use tokio_util::codec::Framed;
@@ -34,41 +29,18 @@ impl Conn {
 
     pub async fn upgrade(
         self,
-        shared_secret: SharedSecret,
-        nonce: &[u8],
+        session_keys: SessionKeys,
         her_public_key: &String,
     ) -> Result<UpgradedConn, TrptError> {
-        // Initialize message authentication code (MAC)
-        let (out_mac, in_mac) = {
-            let mut out_mac = Keccak256::default();
-            out_mac.update(shared_secret.as_bytes());
-
-            let mut in_mac = Keccak256::default();
-            in_mac.update(shared_secret.as_bytes());
-
-            (out_mac, in_mac)
-        };
-
-        let out_cipher = ChaCha20::new(shared_secret.as_bytes().into(), nonce.into());
-
-        let in_cipher = ChaCha20::new(shared_secret.as_bytes().into(), nonce.into());
-
         let conn_id = format!(
             "{}-{}",
             "me",
             sak_p2p_id::make_public_key_short(&her_public_key)?
         );
 
-        let socket = self.socket.map_codec(|_| UpgradedP2PCodec {
-            out_cipher,
-            in_cipher,
-            out_mac,
-            in_mac,
-            conn_id: conn_id.to_string(),
-            parsed_msg_len: None,
-            in_count: 0,
-            out_count: 0,
-        });
+        let socket = self
+            .socket
+            .map_codec(|_| UpgradedP2PCodec::new(session_keys, conn_id.to_string()));
 
         let upgraded_conn = UpgradedConn::init(socket, conn_id, self.public_key).await;
 
@@ -1,4 +1,5 @@
 mod codec;
 mod conn;
 
+pub(crate) use codec::*;
 pub use conn::*;
@@ -1,35 +1,127 @@
-use crate::{dec, enc, Msg, TrptError};
-use bytes::{Buf, BytesMut};
-use chacha20::cipher::StreamCipher;
-use chacha20::ChaCha20;
-use sak_crypto::sha3::digest::core_api::CoreWrapper;
-use sak_crypto::sha3::digest::FixedOutput;
-use sak_crypto::sha3::{Digest, Keccak256, Keccak256Core};
-use std::convert::TryInto;
-use tokio_util::codec::{Decoder, Encoder};
+use crate::{handshake::SessionKeys, TrptError};
+use bytes::BytesMut;
+use chacha20poly1305::aead::{AeadInPlace, NewAead};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce, Tag};
 
 //
-//              header-portion                    msg-portion
-// |-----------------------------------------|--------------------|
-//    header-ciphertext(5)    header-mac(15)   msg-ciphertext(n)
-// |-----------------------|-----------------|--------------------|
+// A msg is sent in one or more chunks. Each chunk is two AEAD sealed parts,
+// the header and the body, each with its own sequence-number nonce.
 //
+//              header-portion                       body-portion
+// |-----------------------------------------|---------------------------|
+//    header-ciphertext(3)  header-tag(16)     body-ciphertext(n)  tag(16)
+// |----------------------|------------------|-------------------|-------|
+//
+// header plaintext: body_len(2, big endian) | flags(1)
+//
+
+pub(crate) const CHUNK_LEN: usize = 65_535; // 2^16 - 1
 
-pub(crate) const MSG_LEN: usize = 65_536; // 2^16
+pub(crate) const MAX_MSG_LEN: usize = 32 * 1024 * 1024;
 
-pub(crate) const HEADER_CIPHERTEXT_LEN: usize = 5;
+pub(crate) const HEADER_PLAINTEXT_LEN: usize = 3;
 
-pub(crate) const HEADER_MAC_LEN: usize = 15;
+pub(crate) const TAG_LEN: usize = 16;
 
-pub(crate) const HEADER_TOTAL_LEN: usize = HEADER_CIPHERTEXT_LEN + HEADER_MAC_LEN;
+pub(crate) const HEADER_TOTAL_LEN: usize = HEADER_PLAINTEXT_LEN + TAG_LEN;
+
+/// Set on the last chunk of a msg.
+pub(crate) const FLAG_LAST_CHUNK: u8 = 1;
 
 pub struct UpgradedP2PCodec {
-    pub(crate) out_cipher: ChaCha20,
-    pub(crate) in_cipher: ChaCha20,
-    pub(crate) out_mac: CoreWrapper<Keccak256Core>,
-    pub(crate) in_mac: CoreWrapper<Keccak256Core>,
+    pub(crate) out_cipher: ChaCha20Poly1305,
+    pub(crate) in_cipher: ChaCha20Poly1305,
+    pub(crate) out_nonce: [u8; 12],
+    pub(crate) in_nonce: [u8; 12],
+    pub(crate) out_seq: u64,
+    pub(crate) in_seq: u64,
     pub(crate) conn_id: String,
-    pub(crate) parsed_msg_len: Option<u16>,
+    pub(crate) parsed_header: Option<ChunkHeader>,
+    // Bodies of the chunks received so far, of a msg not yet complete
+    pub(crate) in_msg: BytesMut,
+    // Bytes on the wire of the chunks in `in_msg`
+    pub(crate) in_msg_wire_len: usize,
     pub(crate) in_count: usize,
     pub(crate) out_count: usize,
 }
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ChunkHeader {
+    pub(crate) body_len: usize,
+    pub(crate) is_last: bool,
+}
+
+impl UpgradedP2PCodec {
+    pub(crate) fn new(session_keys: SessionKeys, conn_id: String) -> UpgradedP2PCodec {
+        let SessionKeys {
+            out_key,
+            out_nonce,
+            in_key,
+            in_nonce,
+        } = session_keys;
+
+        UpgradedP2PCodec {
+            out_cipher: ChaCha20Poly1305::new(&out_key.into()),
+            in_cipher: ChaCha20Poly1305::new(&in_key.into()),
+            out_nonce,
+            in_nonce,
+            out_seq: 0,
+            in_seq: 0,
+            conn_id,
+            parsed_header: None,
+            in_msg: BytesMut::new(),
+            in_msg_wire_len: 0,
+            in_count: 0,
+            out_count: 0,
+        }
+    }
+
+    pub(crate) fn seal(&mut self, buf: &mut [u8]) -> Result<Tag, TrptError> {
+        let nonce = make_seq_nonce(&self.out_nonce, self.out_seq)?;
+        self.out_seq += 1;
+
+        match self
+            .out_cipher
+            .encrypt_in_place_detached(Nonce::from_slice(&nonce), &[], buf)
+        {
+            Ok(t) => Ok(t),
+            Err(err) => Err(format!("Cannot seal a chunk, err: {}", err).into()),
+        }
+    }
+
+    pub(crate) fn open(&mut self, buf: &mut [u8], tag: &[u8]) -> Result<(), TrptError> {
+        let nonce = make_seq_nonce(&self.in_nonce, self.in_seq)?;
+        self.in_seq += 1;
+
+        match self.in_cipher.decrypt_in_place_detached(
+            Nonce::from_slice(&nonce),
+            &[],
+            buf,
+            Tag::from_slice(tag),
+        ) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(format!(
+                "Chunk cannot be authenticated. Buffer might have been \
+                tampered, conn_id: {}, in_seq: {}",
+                self.conn_id, self.in_seq,
+            )
+            .into()),
+        }
+    }
+}
+
+/// Nonce of the `seq`-th sealing, the sequence number XORed into the last
+/// 8 bytes of the base nonce.
+pub(crate) fn make_seq_nonce(base_nonce: &[u8; 12], seq: u64) -> Result<[u8; 12], TrptError> {
+    if seq == u64::MAX {
+        return Err(format!("Nonce sequence is exhausted, connection has to be renewed").into());
+    }
+
+    let mut nonce = *base_nonce;
+
+    for (n, s) in nonce[4..].iter_mut().zip(seq.to_be_bytes().iter()) {
+        *n ^= *s;
+    }
+
+    Ok(nonce)
+}
@@ -1,4 +1,4 @@
-use crate::{Msg, TrptError, UpgradedP2PCodec};
+use crate::{Msg, MsgStream, TrptError, UpgradedP2PCodec};
 use futures::{SinkExt, StreamExt};
 use sak_logger::{debug, info, warn};
 use tokio::net::TcpStream;
@@ -6,7 +6,7 @@ use tokio_util::codec::Framed;
 
 pub struct UpgradedConn {
     conn_id: String,
-    socket: Framed<TcpStream, UpgradedP2PCodec>,
+    socket: Box<dyn MsgStream>,
     public_key: String,
 }
 
@@ -16,13 +16,20 @@ impl UpgradedConn {
         conn_id: String,
         public_key: String,
     ) -> UpgradedConn {
-        let upgraded_conn = UpgradedConn {
+        UpgradedConn::from_stream(Box::new(socket), conn_id, public_key)
+    }
+
+    /// Conn over any msg stream, such as the in-memory links of a simulator.
+    pub fn from_stream(
+        socket: Box<dyn MsgStream>,
+        conn_id: String,
+        public_key: String,
+    ) -> UpgradedConn {
+        UpgradedConn {
             socket,
             conn_id,
             public_key,
-        };
-
-        upgraded_conn
+        }
     }
 
     pub fn get_conn_id(&self) -> &String {
@@ -38,6 +45,12 @@ impl UpgradedConn {
             "\nsend msg(), conn_id: {}, msg: {}", self.conn_id, msg_type
         );
 
+        sak_metrics::metrics()
+            .p2p
+            .msgs_sent
+            .with_labels(&[msg.get_type()])
+            .inc();
+
         self.socket.send(msg).await
     }
 
@@ -45,6 +58,14 @@ impl UpgradedConn {
     pub async fn next_msg(&mut self) -> Option<Result<Msg, TrptError>> {
         let msg = self.socket.next().await;
 
+        if let Some(Ok(m)) = &msg {
+            sak_metrics::metrics()
+                .p2p
+                .msgs_received
+                .with_labels(&[m.get_type()])
+                .inc();
+        }
+
         // println!("\n 33 next_msg: conn_id: {}, msg: {:?}", self.conn_id, msg);
 
         msg
@@ -1,12 +1,8 @@
 use crate::{
-    dec, Msg, TrptError, UpgradedP2PCodec, HEADER_CIPHERTEXT_LEN, HEADER_MAC_LEN, HEADER_TOTAL_LEN,
+    dec, ChunkHeader, Msg, TrptError, UpgradedP2PCodec, FLAG_LAST_CHUNK, HEADER_PLAINTEXT_LEN,
+    HEADER_TOTAL_LEN, MAX_MSG_LEN, TAG_LEN,
 };
 use bytes::{Buf, BytesMut};
-use chacha20::cipher::StreamCipher;
-use chacha20::ChaCha20;
-use sak_crypto::sha3::digest::core_api::CoreWrapper;
-use sak_crypto::sha3::{Digest, Keccak256Core};
-use std::convert::TryInto;
 use tokio_util::codec::Decoder;
 
 impl Decoder for UpgradedP2PCodec {
@@ -14,133 +10,97 @@ impl Decoder for UpgradedP2PCodec {
     type Error = TrptError;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, TrptError> {
-        // println!(
-        //     "\ndecoding!! conn_id: {}, src({}): {:?}",
-        //     self.conn_id,
-        //     src.len(),
-        //     src.to_vec()
-        // );
-
-        if src.len() <= HEADER_TOTAL_LEN {
-            return Ok(None);
-        }
+        loop {
+            let header = match self.parsed_header {
+                Some(h) => h,
+                None => {
+                    if src.len() < HEADER_TOTAL_LEN {
+                        return Ok(None);
+                    }
 
-        let msg_len = if let Some(l) = self.parsed_msg_len {
-            l
-        } else {
-            let l = parse_header_portion(
-                src,
-                &mut self.in_cipher,
-                &mut self.in_mac,
-                &mut self.in_count,
-            )?;
+                    let h = parse_header_portion(self, src)?;
 
-            self.parsed_msg_len = Some(l);
+                    self.parsed_header = Some(h);
 
-            l
-        };
+                    h
+                }
+            };
 
-        if src.len() < msg_len as usize {
-            // println!(
-            //     "\nMsg not fully arrived!, src len: {}, msg_len: {}",
-            //     src.len(),
-            //     msg_len
-            // );
+            if src.len() < header.body_len + TAG_LEN {
+                src.reserve(header.body_len + TAG_LEN - src.len());
 
-            return Ok(None);
-        } else {
-            // println!("resetting incomplete msg len to none");
+                return Ok(None);
+            }
 
-            self.parsed_msg_len = None;
-        }
+            self.parsed_header = None;
 
-        let msg = parse_msg_portion(src, msg_len, &mut self.in_cipher)?;
+            parse_body_portion(self, src, header.body_len)?;
 
-        // println!("decode complete, conn_id: {}, msg: {:?}", self.conn_id, msg);
+            self.in_msg_wire_len += HEADER_TOTAL_LEN + header.body_len + TAG_LEN;
 
-        Ok(msg)
-    }
-}
-
-fn parse_header_portion(
-    src: &mut BytesMut,
-    in_cipher: &mut ChaCha20,
-    in_mac: &mut CoreWrapper<Keccak256Core>,
-    in_count: &mut usize,
-) -> Result<u16, TrptError> {
-    *in_count += 1;
+            if header.is_last {
+                break;
+            }
+        }
 
-    let digest = &mut in_mac.finalize_reset()[..HEADER_MAC_LEN];
+        let msg_part = self.in_msg.split().freeze();
 
-    // XORing
-    for idx in 0..HEADER_CIPHERTEXT_LEN {
-        digest[idx] = digest[idx] ^ src[idx];
-    }
+        self.in_count += 1;
 
-    let mac: &[u8; 15] = src[5..20].try_into()?;
+        let wire_len = std::mem::take(&mut self.in_msg_wire_len);
 
-    // println!(
-    //     "\nparsing, mac: {:?}, digest: {:?}, in_count: {}, src ({}): {:?}",
-    //     mac.to_vec(),
-    //     digest.to_vec(),
-    //     in_count,
-    //     src.len(),
-    //     src.to_vec(),
-    // );
+        let msg = match dec::decode_from_msg_bytes(msg_part) {
+            Ok(m) => m,
+            Err(err) => {
+                return Err(format!("Error decoding a msg body, err: {}", err).into());
+            }
+        };
 
-    // println!("digest after XORing: {:?}, mac: {:?}", digest.to_vec(), mac);
+        sak_metrics::metrics()
+            .p2p
+            .bytes_received
+            .with_labels(&[msg.get_type()])
+            .inc_by(wire_len as u64);
 
-    if digest != mac {
-        return Err(format!(
-            "Header mac invalid. Buffer might have been tampered, \
-            mac: {:?}, digest: {:?}, in_count: {}",
-            mac, digest, in_count,
-        )
-        .into());
+        Ok(Some(msg))
     }
+}
 
-    in_mac.update(&digest);
-
-    in_cipher.apply_keystream(&mut src[..HEADER_CIPHERTEXT_LEN]);
+fn parse_header_portion(
+    codec: &mut UpgradedP2PCodec,
+    src: &mut BytesMut,
+) -> Result<ChunkHeader, TrptError> {
+    let mut header = src.split_to(HEADER_TOTAL_LEN);
+    let tag = header.split_off(HEADER_PLAINTEXT_LEN);
 
-    // println!(
-    //     "src after cipher: {:?}",
-    //     &src.to_vec()[..HEADER_CIPHERTEXT_LEN]
-    // );
+    codec.open(&mut header, &tag)?;
 
-    let msg_len = {
-        let b: &[u8; 2] = src[0..2].try_into()?;
-        u16::from_be_bytes(*b)
-    };
+    let body_len = u16::from_be_bytes([header[0], header[1]]) as usize;
+    let is_last = header[2] & FLAG_LAST_CHUNK != 0;
 
-    Ok(msg_len)
+    Ok(ChunkHeader { body_len, is_last })
 }
 
-fn parse_msg_portion(
+fn parse_body_portion(
+    codec: &mut UpgradedP2PCodec,
     src: &mut BytesMut,
-    msg_len: u16,
-    in_cipher: &mut ChaCha20,
-) -> Result<Option<Msg>, TrptError> {
-    src.advance(HEADER_TOTAL_LEN);
-
-    // println!(
-    //     "\n>> parsing msg portion, src_len: {}, msg_len: {}",
-    //     src.len(),
-    //     msg_len,
-    // );
+    body_len: usize,
+) -> Result<(), TrptError> {
+    let mut body = src.split_to(body_len);
+    let tag = src.split_to(TAG_LEN);
 
-    let msg_body_len = msg_len as usize - HEADER_TOTAL_LEN;
+    codec.open(&mut body, &tag)?;
 
-    in_cipher.apply_keystream(&mut src[..msg_body_len]);
-
-    // println!("\ndecrypt: msg_portion: {:?}", src.to_vec());
+    if codec.in_msg.len() + body.len() > MAX_MSG_LEN {
+        return Err(format!(
+            "Message is too large, msg_len: {}, max: {}",
+            codec.in_msg.len() + body.len(),
+            MAX_MSG_LEN,
+        )
+        .into());
+    }
 
-    let msg = match dec::decode_into_msg(src) {
-        Ok(m) => m,
-        Err(err) => {
-            return Err(format!("Error decoding a msg body, err: {}", err).into());
-        }
-    };
+    codec.in_msg.unsplit(body);
 
-    Ok(msg)
+    Ok(())
 }
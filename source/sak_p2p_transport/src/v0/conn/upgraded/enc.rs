@@ -1,111 +1,69 @@
-use crate::{
-    dec, enc, Msg, TrptError, UpgradedP2PCodec, HEADER_CIPHERTEXT_LEN, HEADER_MAC_LEN,
-    HEADER_TOTAL_LEN, MSG_LEN,
-};
-use bytes::{Buf, BufMut, BytesMut};
-use chacha20::cipher::StreamCipher;
-use chacha20::ChaCha20;
-use sak_crypto::sha3::digest::core_api::CoreWrapper;
-use sak_crypto::sha3::{Digest, Keccak256Core};
+use crate::{enc, Msg, TrptError, UpgradedP2PCodec, CHUNK_LEN, FLAG_LAST_CHUNK, MAX_MSG_LEN};
+use bytes::BytesMut;
 use tokio_util::codec::Encoder;
 
 impl Encoder<Msg> for UpgradedP2PCodec {
     type Error = TrptError;
 
     fn encode(&mut self, item: Msg, dst: &mut BytesMut) -> Result<(), TrptError> {
-        // let msg = item.to_string();
-
-        // println!("encoding, item: {}", &item);
-
         let mut msg_part = BytesMut::new();
 
-        enc::encode_into_frame(item, &mut msg_part)?;
+        let msg_type = enc::encode_into_msg_bytes(item, &mut msg_part)?;
+
+        if msg_part.len() > MAX_MSG_LEN {
+            return Err(format!(
+                "Message is too large, msg_len: {}, max: {}",
+                msg_part.len(),
+                MAX_MSG_LEN,
+            )
+            .into());
+        }
 
-        write_header_and_header_mac(dst, msg_part.len(), &mut self.out_mac, &mut self.out_cipher)?;
+        let dst_len = dst.len();
 
-        // println!(
-        //     "\nencode(): before enc (msg_part), conn_id: {}, msg({}): {}, \
-        //         dst: {:?}, msg_part: {:?}",
-        //     self.conn_id,
-        //     dst.len(),
-        //     msg,
-        //     dst.to_vec(),
-        //     msg_part.to_vec(),
-        // );
+        let chunk_count = std::cmp::max(1, (msg_part.len() + CHUNK_LEN - 1) / CHUNK_LEN);
 
-        self.out_cipher.apply_keystream(&mut msg_part);
+        for idx in 0..chunk_count {
+            let body_len = std::cmp::min(CHUNK_LEN, msg_part.len());
+            let body = msg_part.split_to(body_len);
+            let is_last = idx == chunk_count - 1;
 
-        dst.unsplit(msg_part);
+            write_chunk(self, dst, body, is_last)?;
+        }
 
         self.out_count += 1;
 
-        // println!(
-        //     "\nencode(): dst, conn_id: {}, _after enc ({}): {:?}, \
-        //     out_count: {}, msg: {}",
-        //     self.conn_id,
-        //     dst.len(),
-        //     dst.to_vec(),
-        //     self.out_count,
-        //     msg,
-        // );
-
-        return Ok(());
+        sak_metrics::metrics()
+            .p2p
+            .bytes_sent
+            .with_labels(&[msg_type])
+            .inc_by((dst.len() - dst_len) as u64);
+
+        Ok(())
     }
 }
 
-fn write_header_and_header_mac(
+fn write_chunk(
+    codec: &mut UpgradedP2PCodec,
     dst: &mut BytesMut,
-    msg_part_len: usize,
-    out_mac: &mut CoreWrapper<Keccak256Core>,
-    out_cipher: &mut ChaCha20,
+    mut body: BytesMut,
+    is_last: bool,
 ) -> Result<(), TrptError> {
-    let msg_len = msg_part_len + HEADER_TOTAL_LEN;
-
-    if msg_len > MSG_LEN {
-        return Err(format!(
-            "Message is too large, >2^16 not permitted, msg_len: {}",
-            msg_len,
-        )
-        .into());
-    }
+    let flags = if is_last { FLAG_LAST_CHUNK } else { 0 };
 
-    write_msg_len(dst, msg_len);
+    let mut header = {
+        let len_be_bytes = (body.len() as u16).to_be_bytes();
 
-    // Write Empty header portion (reserve for future use)
-    dst.extend_from_slice(&[0u8; 3]);
+        [len_be_bytes[0], len_be_bytes[1], flags]
+    };
 
-    // Encrypt header
-    out_cipher.apply_keystream(&mut dst[0..5]);
+    let header_tag = codec.seal(&mut header)?;
+    let body_tag = codec.seal(&mut body)?;
 
-    write_header_mac(dst, out_mac);
-
-    // println!("header portion: {:?}", dst.to_vec());
+    dst.extend_from_slice(&header);
+    dst.extend_from_slice(&header_tag);
+    dst.extend_from_slice(&body);
+    dst.extend_from_slice(&body_tag);
 
     Ok(())
 }
-
-#[inline]
-fn write_msg_len(dst: &mut BytesMut, msg_len: usize) {
-    let len_be_bytes = msg_len.to_be_bytes();
-    let len = len_be_bytes.len();
-
-    dst.extend_from_slice(&[len_be_bytes[len - 2], len_be_bytes[len - 1]]);
-}
-
-#[inline]
-fn write_header_mac<'a>(dst: &mut BytesMut, out_mac: &mut CoreWrapper<Keccak256Core>) {
-    let digest = &mut out_mac.finalize_reset()[..HEADER_MAC_LEN];
-
-    // println!("digest: {:?}", digest.to_vec());
-
-    // XORing
-    for idx in 0..HEADER_CIPHERTEXT_LEN {
-        digest[idx] = digest[idx] ^ dst[idx];
-    }
-
-    // println!("digest after: {:?}", digest.to_vec());
-
-    out_mac.update(&digest);
-
-    dst.extend_from_slice(&digest);
-}
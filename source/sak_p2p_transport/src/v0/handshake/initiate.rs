@@ -1,5 +1,9 @@
-use crate::HandshakeMsg;
+use super::{
+    make_transcript_hash, sign_transcript, verify_transcript, EphemeralKey, SessionKeys,
+    ROLE_INITIATOR, ROLE_RESPONDER,
+};
 use crate::{Conn, Msg, Transport};
+use crate::{HandshakeFinMsg, HandshakeMsg};
 use futures::SinkExt;
 use futures::StreamExt;
 use sak_p2p_id::Identity;
@@ -54,6 +58,12 @@ pub enum HandshakeInitError {
     )]
     PeerNodeAlreadyInUse { public_key: String, err: String },
 
+    #[error("Handshake ack is not from the peer intended, public_key: {public_key}")]
+    UnexpectedPeer { public_key: String },
+
+    #[error("Peer could not prove her identity, err: {err}")]
+    InvalidSignature { err: String },
+
     #[error("Peer node is empty.")]
     EmptyPeerNode,
 
@@ -82,10 +92,16 @@ pub async fn initiate_handshake(
         public_key_str,
     } = handshake_init_args;
 
+    let my_public_key_str = identity.credential.public_key_str.clone();
+    let my_eph_key = EphemeralKey::generate();
+    let my_nonce = sak_crypto::rand_bytes_32();
+
     let handshake_msg = match HandshakeMsg::new(
         identity.p2p_port,
-        identity.credential.public_key_str.clone(),
-        public_key_str,
+        my_public_key_str.clone(),
+        public_key_str.clone(),
+        my_eph_key.public_key_str.clone(),
+        my_nonce,
     ) {
         Ok(h) => h,
         Err(err) => {
@@ -122,25 +138,86 @@ pub async fn initiate_handshake(
         }
     };
 
-    let her_public_key_str = handshake_ack.src_public_key_str;
-    let my_secret_key = &identity.credential.secret_key;
-    let her_public_key =
-        match sak_crypto::convert_public_key_str_into_public_key(&her_public_key_str) {
+    let HandshakeMsg {
+        src_public_key_str: her_public_key_str,
+        dst_public_key_str,
+        eph_public_key_str: her_eph_public_key_str,
+        nonce: her_nonce,
+        sig: her_sig,
+        ..
+    } = handshake_ack;
+
+    if her_public_key_str != public_key_str || dst_public_key_str != my_public_key_str {
+        return Err(HandshakeInitError::UnexpectedPeer {
+            public_key: her_public_key_str,
+        });
+    }
+
+    let transcript_hash = make_transcript_hash(
+        &my_public_key_str,
+        &her_public_key_str,
+        &my_eph_key.public_key_str,
+        &my_nonce,
+        &her_eph_public_key_str,
+        &her_nonce,
+    );
+
+    let her_sig = her_sig.ok_or(HandshakeInitError::InvalidSignature {
+        err: "Handshake ack is not signed".to_string(),
+    })?;
+
+    if let Err(err) = verify_transcript(
+        &her_public_key_str,
+        &transcript_hash,
+        ROLE_RESPONDER,
+        &her_sig,
+    ) {
+        return Err(HandshakeInitError::InvalidSignature { err });
+    }
+
+    let handshake_fin = HandshakeFinMsg {
+        sig: sign_transcript(
+            &identity.credential.secret_key,
+            &transcript_hash,
+            ROLE_INITIATOR,
+        ),
+    };
+
+    match conn.socket.send(Msg::HandshakeFin(handshake_fin)).await {
+        Ok(_) => (),
+        Err(err) => {
+            return Err(HandshakeInitError::FrameWriteFail {
+                err: err.to_string(),
+            });
+        }
+    };
+
+    let her_eph_public_key =
+        match sak_crypto::convert_public_key_str_into_public_key(&her_eph_public_key_str) {
             Ok(pk) => pk,
             Err(err) => {
                 return Err(HandshakeInitError::PublicKeyCreateFail {
-                    public_key: her_public_key_str.clone(),
+                    public_key: her_eph_public_key_str.clone(),
                     err,
                 })
             }
         };
 
-    let shared_secret = sak_crypto::make_shared_secret(my_secret_key, her_public_key);
+    let session_keys = match SessionKeys::derive(
+        &my_eph_key.secret_key,
+        her_eph_public_key,
+        &transcript_hash,
+        true,
+    ) {
+        Ok(k) => k,
+        Err(err) => {
+            return Err(HandshakeInitError::ConnectionCreateFail {
+                err: err.to_string(),
+            });
+        }
+    };
 
-    let upgraded_conn = match conn
-        .upgrade(shared_secret, &[0; 12], &her_public_key_str)
-        .await
-    {
+    let upgraded_conn = match conn.upgrade(session_keys, &her_public_key_str).await {
         Ok(c) => c,
         Err(err) => {
             return Err(HandshakeInitError::ConnectionCreateFail {
@@ -1,5 +1,7 @@
 mod initiate;
 mod receive;
+mod session;
 
 pub use initiate::*;
 pub use receive::*;
+pub use session::*;
@@ -1,3 +1,7 @@
+use super::{
+    make_transcript_hash, sign_transcript, verify_transcript, EphemeralKey, SessionKeys,
+    ROLE_INITIATOR, ROLE_RESPONDER,
+};
 use crate::{Conn, Msg, Transport};
 use crate::{HandshakeMsg, TrptError};
 use futures::SinkExt;
@@ -32,6 +36,9 @@ pub enum HandshakeRecvError {
     )]
     PublicKeyCreateFail { public_key: String, err: String },
 
+    #[error("Peer could not prove her identity, err: {err}")]
+    InvalidSignature { err: String },
+
     #[error("Failed to send handshake ack frame, err: {err}")]
     AckSendFail { err: String },
 
@@ -91,6 +98,9 @@ pub async fn receive_handshake(
         src_p2p_port: _,
         src_public_key_str: her_public_key_str,
         dst_public_key_str: my_public_key_str,
+        eph_public_key_str: her_eph_public_key_str,
+        nonce: her_nonce,
+        sig: _,
     } = handshake_syn;
 
     if my_public_key_str != identity.credential.public_key_str {
@@ -99,11 +109,30 @@ pub async fn receive_handshake(
         });
     }
 
+    let my_eph_key = EphemeralKey::generate();
+    let my_nonce = sak_crypto::rand_bytes_32();
+
+    let transcript_hash = make_transcript_hash(
+        &her_public_key_str,
+        &my_public_key_str,
+        &her_eph_public_key_str,
+        &her_nonce,
+        &my_eph_key.public_key_str,
+        &my_nonce,
+    );
+
     let handshake_msg = HandshakeMsg {
         instance_id: instance_id.clone(),
         src_p2p_port: identity.p2p_port,
         src_public_key_str: my_public_key_str.clone(),
         dst_public_key_str: her_public_key_str.clone(),
+        eph_public_key_str: my_eph_key.public_key_str.clone(),
+        nonce: my_nonce,
+        sig: Some(sign_transcript(
+            &identity.credential.secret_key,
+            &transcript_hash,
+            ROLE_RESPONDER,
+        )),
     };
 
     match conn.socket.send(Msg::HandshakeAck(handshake_msg)).await {
@@ -115,25 +144,60 @@ pub async fn receive_handshake(
         }
     };
 
-    let my_secret_key = &identity.credential.secret_key;
+    let handshake_fin = match conn.socket.next().await {
+        Some(maybe_msg) => match maybe_msg {
+            Ok(msg) => match msg {
+                Msg::HandshakeFin(handshake) => handshake,
+                _ => {
+                    return Err(HandshakeRecvError::InvalidMsgType);
+                }
+            },
+            Err(err) => {
+                return Err(HandshakeRecvError::MsgParseError { err });
+            }
+        },
+        None => {
+            return Err(HandshakeRecvError::PeerEndedConnection {
+                socket_addr: conn.socket_addr,
+            });
+        }
+    };
+
+    if let Err(err) = verify_transcript(
+        &her_public_key_str,
+        &transcript_hash,
+        ROLE_INITIATOR,
+        &handshake_fin.sig,
+    ) {
+        return Err(HandshakeRecvError::InvalidSignature { err });
+    }
 
-    let her_public_key =
-        match sak_crypto::convert_public_key_str_into_public_key(&her_public_key_str) {
+    let her_eph_public_key =
+        match sak_crypto::convert_public_key_str_into_public_key(&her_eph_public_key_str) {
             Ok(pk) => pk,
             Err(err) => {
                 return Err(HandshakeRecvError::PublicKeyCreateFail {
-                    public_key: her_public_key_str,
+                    public_key: her_eph_public_key_str,
                     err,
                 })
             }
         };
 
-    let shared_secret = sak_crypto::make_shared_secret(my_secret_key, her_public_key);
+    let session_keys = match SessionKeys::derive(
+        &my_eph_key.secret_key,
+        her_eph_public_key,
+        &transcript_hash,
+        false,
+    ) {
+        Ok(k) => k,
+        Err(err) => {
+            return Err(HandshakeRecvError::ConnectionCreateFail {
+                err: err.to_string(),
+            });
+        }
+    };
 
-    let upgraded_conn = match conn
-        .upgrade(shared_secret, &[0; 12], &her_public_key_str)
-        .await
-    {
+    let upgraded_conn = match conn.upgrade(session_keys, &her_public_key_str).await {
         Ok(c) => c,
         Err(err) => {
             return Err(HandshakeRecvError::ConnectionCreateFail {
@@ -0,0 +1,163 @@
+use crate::TrptError;
+use sak_crypto::sha3::{Digest, Keccak256};
+use sak_crypto::{PublicKey, SakKey, SecretKey, Signature, SigningKey, ToEncodedPoint};
+
+//
+// Handshake, XX pattern
+//
+//   initiator                                responder
+//       ---- syn (s_i, e_i, nonce_i) ------------>
+//       <--- ack (s_r, e_r, nonce_r, sig_r(h)) ---
+//       ---- fin (sig_i(h)) --------------------->
+//
+// `h` is the hash of the transcript. Session keys are derived from the
+// ephemeral ECDH shared secret only, which gives forward secrecy. Static keys
+// are authenticated by signing `h`, which contains the nonces of both sides.
+//
+
+const PROTOCOL_NAME: &[u8] = b"saksaha_handshake_xx_secp256k1_keccak256_chacha20";
+
+pub(crate) const ROLE_INITIATOR: &[u8] = b"initiator";
+
+pub(crate) const ROLE_RESPONDER: &[u8] = b"responder";
+
+pub(crate) struct EphemeralKey {
+    pub(crate) secret_key: SecretKey,
+    pub(crate) public_key_str: String,
+}
+
+impl EphemeralKey {
+    pub(crate) fn generate() -> EphemeralKey {
+        let (secret_key, public_key) = SakKey::generate();
+
+        let public_key_str = sak_crypto::encode_hex(&public_key.to_encoded_point(false).to_bytes());
+
+        EphemeralKey {
+            secret_key,
+            public_key_str,
+        }
+    }
+}
+
+pub(crate) struct Transcript {
+    hasher: Keccak256,
+}
+
+impl Transcript {
+    pub(crate) fn new() -> Transcript {
+        let mut t = Transcript {
+            hasher: Keccak256::default(),
+        };
+
+        t.mix(PROTOCOL_NAME);
+
+        t
+    }
+
+    /// Every element is length-prefixed so that the boundaries between
+    /// elements are part of the hash.
+    pub(crate) fn mix(&mut self, data: &[u8]) {
+        self.hasher.update(&(data.len() as u64).to_be_bytes());
+        self.hasher.update(data);
+    }
+
+    pub(crate) fn hash(&self) -> [u8; 32] {
+        let mut h = [0u8; 32];
+        h.copy_from_slice(&self.hasher.clone().finalize());
+
+        h
+    }
+}
+
+pub struct SessionKeys {
+    pub(crate) out_key: [u8; 32],
+    pub(crate) out_nonce: [u8; 12],
+    pub(crate) in_key: [u8; 32],
+    pub(crate) in_nonce: [u8; 12],
+}
+
+impl SessionKeys {
+    pub(crate) fn derive(
+        my_eph_secret_key: &SecretKey,
+        her_eph_public_key: PublicKey,
+        transcript_hash: &[u8; 32],
+        is_initiator: bool,
+    ) -> Result<SessionKeys, TrptError> {
+        let shared_secret = sak_crypto::make_shared_secret(my_eph_secret_key, her_eph_public_key);
+        let ikm = shared_secret.as_bytes();
+
+        let i2r_key = sak_crypto::derive_key(ikm, transcript_hash, b"i2r_key")?;
+        let r2i_key = sak_crypto::derive_key(ikm, transcript_hash, b"r2i_key")?;
+        let i2r_nonce = make_nonce(sak_crypto::derive_key(ikm, transcript_hash, b"i2r_nonce")?);
+        let r2i_nonce = make_nonce(sak_crypto::derive_key(ikm, transcript_hash, b"r2i_nonce")?);
+
+        let keys = if is_initiator {
+            SessionKeys {
+                out_key: i2r_key,
+                out_nonce: i2r_nonce,
+                in_key: r2i_key,
+                in_nonce: r2i_nonce,
+            }
+        } else {
+            SessionKeys {
+                out_key: r2i_key,
+                out_nonce: r2i_nonce,
+                in_key: i2r_key,
+                in_nonce: i2r_nonce,
+            }
+        };
+
+        Ok(keys)
+    }
+}
+
+pub(crate) fn sign_transcript(
+    secret_key: &SecretKey,
+    transcript_hash: &[u8; 32],
+    role: &[u8],
+) -> Signature {
+    let signing_key = SigningKey::from(secret_key);
+
+    sak_crypto::make_signature(signing_key, &[&transcript_hash[..], role].concat())
+}
+
+pub(crate) fn verify_transcript(
+    public_key_str: &String,
+    transcript_hash: &[u8; 32],
+    role: &[u8],
+    sig: &Signature,
+) -> Result<(), String> {
+    sak_crypto::verify_with_public_key_str(
+        public_key_str,
+        &[&transcript_hash[..], role].concat(),
+        sig,
+    )
+}
+
+/// Transcript of the syn and ack, as both sides see it.
+pub(crate) fn make_transcript_hash(
+    initiator_public_key_str: &String,
+    responder_public_key_str: &String,
+    initiator_eph_public_key_str: &String,
+    initiator_nonce: &[u8; 32],
+    responder_eph_public_key_str: &String,
+    responder_nonce: &[u8; 32],
+) -> [u8; 32] {
+    let mut t = Transcript::new();
+
+    t.mix(initiator_public_key_str.as_bytes());
+    t.mix(responder_public_key_str.as_bytes());
+    t.mix(initiator_eph_public_key_str.as_bytes());
+    t.mix(initiator_nonce);
+    t.mix(responder_eph_public_key_str.as_bytes());
+    t.mix(responder_nonce);
+
+    t.hash()
+}
+
+fn make_nonce(b: [u8; 32]) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce.copy_from_slice(&b[..12]);
+
+    nonce
+}
@@ -2,7 +2,7 @@ mod conn;
 pub mod handshake;
 mod msg;
 mod transport;
-pub(crate) mod utils;
+mod wire;
 
 #[cfg(test)]
 mod tests;
@@ -10,5 +10,6 @@ mod tests;
 pub use conn::*;
 pub use msg::*;
 pub use transport::Transport;
+pub use wire::*;
 
 pub(crate) type TrptError = Box<dyn std::error::Error + Send + Sync>;
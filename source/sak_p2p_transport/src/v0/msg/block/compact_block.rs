@@ -0,0 +1,62 @@
+use crate::{impl_wire, Decode, Encode, TrptError};
+use bytes::{Bytes, BytesMut};
+use sak_types::{Block, BlockHash, TxCandidate, TxHash};
+
+/// Blocks with their header and tx hashes only. The receiver rebuilds them
+/// out of the txs in her pool.
+#[derive(Debug)]
+pub struct CompactBlockSynMsg {
+    pub blocks: Vec<Block>,
+}
+
+impl_wire!(CompactBlockSynMsg { blocks });
+
+/// Asks for the txs of a compact block missing in the pool.
+#[derive(Debug)]
+pub struct BlockTxsReqMsg {
+    pub block_hash: BlockHash,
+    pub tx_hashes: Vec<TxHash>,
+}
+
+impl_wire!(BlockTxsReqMsg {
+    block_hash,
+    tx_hashes,
+});
+
+/// The txs asked by `BlockTxsReqMsg`, along with the block they belong to.
+#[derive(Debug)]
+pub struct BlockTxsMsg {
+    pub block: Block,
+    pub tx_candidates: Vec<TxCandidate>,
+}
+
+impl Encode for BlockTxsMsg {
+    fn encode(&self, dst: &mut BytesMut) {
+        self.block.encode(dst);
+        self.tx_candidates.encode(dst);
+    }
+}
+
+impl Decode for BlockTxsMsg {
+    fn decode(src: &mut Bytes) -> Result<BlockTxsMsg, TrptError> {
+        let block = Block::decode(src)?;
+        let tx_candidates: Vec<TxCandidate> = Decode::decode(src)?;
+
+        // Only the txs of the block may come with it
+        for tc in tx_candidates.iter() {
+            if !block.tx_hashes.contains(tc.get_tx_hash()) {
+                return Err(format!(
+                    "Tx is not in the block, block_hash: {}, tx_hash: {}",
+                    block.get_block_hash(),
+                    tc.get_tx_hash(),
+                )
+                .into());
+            }
+        }
+
+        Ok(BlockTxsMsg {
+            block,
+            tx_candidates,
+        })
+    }
+}
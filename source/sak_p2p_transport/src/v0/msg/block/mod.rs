@@ -1,7 +1,9 @@
 mod block_ack;
 mod block_hash_sync;
 mod block_syn;
+mod compact_block;
 
 pub use block_ack::*;
 pub use block_hash_sync::*;
 pub use block_syn::*;
+pub use compact_block::*;
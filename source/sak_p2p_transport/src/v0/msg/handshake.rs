@@ -1,6 +1,5 @@
-use crate::{MsgType, TrptError};
-use bytes::{BufMut, Bytes, BytesMut};
-use sak_p2p_frame::{Frame, Parse};
+use crate::impl_wire;
+use sak_crypto::Signature;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Debug)]
@@ -9,6 +8,15 @@ pub struct HandshakeMsg {
     pub src_p2p_port: u16,
     pub src_public_key_str: String,
     pub dst_public_key_str: String,
+    pub eph_public_key_str: String,
+    pub nonce: [u8; 32],
+    // Given only in the ack, signed over the transcript by the responder
+    pub sig: Option<Signature>,
+}
+
+#[derive(Debug)]
+pub struct HandshakeFinMsg {
+    pub sig: Signature,
 }
 
 impl HandshakeMsg {
@@ -16,6 +24,8 @@ impl HandshakeMsg {
         src_p2p_port: u16,
         src_public_key_str: String,
         dst_public_key_str: String,
+        eph_public_key_str: String,
+        nonce: [u8; 32],
     ) -> Result<HandshakeMsg, String> {
         let since_the_epoch = match SystemTime::now().duration_since(UNIX_EPOCH) {
             Ok(s) => s,
@@ -33,74 +43,21 @@ impl HandshakeMsg {
             src_p2p_port,
             src_public_key_str,
             dst_public_key_str,
+            eph_public_key_str,
+            nonce,
+            sig: None,
         })
     }
-
-    pub fn from_parse(parse: &mut Parse) -> Result<HandshakeMsg, TrptError> {
-        let src_p2p_port = {
-            let p = parse.next_int()? as u16;
-            p
-        };
-
-        let instance_id = {
-            let k = parse.next_bytes()?;
-            std::str::from_utf8(k.as_ref())?.into()
-        };
-
-        let src_public_key_str: String = {
-            let k = parse.next_bytes()?;
-            std::str::from_utf8(k.as_ref())?.into()
-        };
-
-        let dst_public_key_str: String = {
-            let k = parse.next_bytes()?;
-            std::str::from_utf8(k.as_ref())?.into()
-        };
-
-        let h = HandshakeMsg {
-            instance_id,
-            src_p2p_port,
-            src_public_key_str,
-            dst_public_key_str,
-        };
-
-        Ok(h)
-    }
-
-    fn into_frame(&self, msg_type: &'static str) -> Frame {
-        let mut frame = Frame::array();
-
-        let instance_id_bytes = {
-            let mut b = BytesMut::new();
-            b.put(self.instance_id.as_bytes());
-            b
-        };
-
-        let src_public_key_bytes = {
-            let mut b = BytesMut::new();
-            b.put(self.src_public_key_str.as_bytes());
-            b
-        };
-
-        let dst_public_key_bytes = {
-            let mut b = BytesMut::new();
-            b.put(self.dst_public_key_str.as_bytes());
-            b
-        };
-
-        frame.push_bulk(Bytes::from(msg_type));
-        frame.push_int(self.src_p2p_port as u128);
-        frame.push_bulk(instance_id_bytes.into());
-        frame.push_bulk(src_public_key_bytes.into());
-        frame.push_bulk(dst_public_key_bytes.into());
-        frame
-    }
-
-    pub fn into_syn_frame(&self) -> Frame {
-        self.into_frame(MsgType::HANDSHAKE_SYN)
-    }
-
-    pub fn into_ack_frame(&self) -> Frame {
-        self.into_frame(MsgType::HANDSHAKE_ACK)
-    }
 }
+
+impl_wire!(HandshakeMsg {
+    instance_id,
+    src_p2p_port,
+    src_public_key_str,
+    dst_public_key_str,
+    eph_public_key_str,
+    nonce,
+    sig,
+});
+
+impl_wire!(HandshakeFinMsg { sig });
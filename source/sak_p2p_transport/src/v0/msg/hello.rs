@@ -1,75 +1,80 @@
-use crate::{MsgType, TrptError};
-use bytes::{BufMut, Bytes, BytesMut};
+use crate::impl_wire;
 use sak_p2p_addr::UnknownAddr;
-use sak_p2p_frame::{Frame, Parse};
-use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Bumped whenever the p2p msgs change incompatibly.
+pub const P2P_PROTOCOL_VERSION: u32 = 2;
+
+// Bits of `HelloMsg::capabilities`
+pub const CAP_TX_RELAY: u64 = 1 << 0;
+pub const CAP_BLOCK_RELAY: u64 = 1 << 1;
+pub const CAP_COMPACT_BLOCK_RELAY: u64 = 1 << 2;
+pub const CAP_LIGHT_SERVE: u64 = 1 << 3;
 
 #[derive(Debug)]
 pub struct HelloMsg {
+    pub protocol_version: u32,
+    pub network_id: String,
+    pub genesis_hash: String,
+    pub best_height: u128,
+    pub capabilities: u64,
     pub unknown_addrs: Vec<UnknownAddr>,
 }
 
 impl HelloMsg {
-    pub fn new(unknown_addrs: Vec<UnknownAddr>) -> Result<HelloMsg, String> {
-        Ok(HelloMsg { unknown_addrs })
-    }
-
-    pub fn from_parse(parse: &mut Parse) -> Result<HelloMsg, TrptError> {
-        let addr_count = parse.next_int()? as u16;
-
-        let mut v = Vec::with_capacity(addr_count as usize);
-
-        for _ in 0..addr_count {
-            let ip: String = {
-                let ip = parse.next_bytes()?;
-
-                std::str::from_utf8(ip.as_ref())?.into()
-            };
-
-            let disc_port = parse.next_int()? as u16;
-
-            let unknown_addr = UnknownAddr::new_from_endpoint(&ip, disc_port);
-
-            v.push(unknown_addr)
+    pub fn new(
+        network_id: String,
+        genesis_hash: String,
+        best_height: u128,
+        capabilities: u64,
+        unknown_addrs: Vec<UnknownAddr>,
+    ) -> HelloMsg {
+        HelloMsg {
+            protocol_version: P2P_PROTOCOL_VERSION,
+            network_id,
+            genesis_hash,
+            best_height,
+            capabilities,
+            unknown_addrs,
         }
-
-        let h = HelloMsg { unknown_addrs: v };
-
-        Ok(h)
     }
 
-    fn into_frame(&self, msg_type: &'static str) -> Frame {
-        let mut frame = Frame::array();
-
-        frame.push_bulk(Bytes::from(msg_type));
-
-        let addr_count = self.unknown_addrs.len();
-        frame.push_int(addr_count as u128);
-
-        for idx in 0..addr_count {
-            let unknown_addr = &self.unknown_addrs[idx];
-
-            let ip = {
-                let mut ip = BytesMut::new();
-
-                ip.put(unknown_addr.ip.as_bytes());
-
-                ip
-            };
-
-            frame.push_bulk(ip.into());
+    /// Whether the node that sent `other` is on the same network and speaks
+    /// the same protocol. Returns the reason of the mismatch otherwise.
+    pub fn check_compatible(&self, other: &HelloMsg) -> Result<(), String> {
+        if self.protocol_version != other.protocol_version {
+            return Err(format!(
+                "Protocol version mismatch, mine: {}, hers: {}",
+                self.protocol_version, other.protocol_version,
+            ));
+        }
 
-            frame.push_int(unknown_addr.disc_port as u128);
+        if self.network_id != other.network_id {
+            return Err(format!(
+                "Network id mismatch, mine: {}, hers: {}",
+                self.network_id, other.network_id,
+            ));
         }
 
-        frame
-    }
+        if self.genesis_hash != other.genesis_hash {
+            return Err(format!(
+                "Genesis block mismatch, mine: {}, hers: {}",
+                self.genesis_hash, other.genesis_hash,
+            ));
+        }
 
-    pub fn into_syn_frame(&self) -> Frame {
-        self.into_frame(MsgType::HELLO_SYN)
+        Ok(())
     }
 
-    pub fn into_ack_frame(&self) -> Frame {
-        self.into_frame(MsgType::HELLO_ACK)
+    pub fn has_capability(&self, capability: u64) -> bool {
+        self.capabilities & capability == capability
     }
 }
+
+impl_wire!(HelloMsg {
+    protocol_version,
+    network_id,
+    genesis_hash,
+    best_height,
+    capabilities,
+    unknown_addrs,
+});
@@ -0,0 +1,52 @@
+use crate::impl_wire;
+use sak_types::{BlockHeight, Cm, CmIdx};
+
+/// Asks a full node for the merkle auth path of a cm.
+#[derive(Debug)]
+pub struct AuthPathReqMsg {
+    pub req_id: u64,
+    pub cm_idx: CmIdx,
+}
+
+impl_wire!(AuthPathReqMsg { req_id, cm_idx });
+
+/// Auth path of the cm in the tree whose root is the merkle root of the
+/// block at `block_height`.
+#[derive(Debug)]
+pub struct AuthPath {
+    pub block_height: BlockHeight,
+    pub cm: Cm,
+    pub path: Vec<(Cm, bool)>,
+}
+
+impl_wire!(AuthPath {
+    block_height,
+    cm,
+    path,
+});
+
+/// `auth_path` is `None` if the cm idx is not in the tree yet.
+#[derive(Debug)]
+pub struct AuthPathMsg {
+    pub req_id: u64,
+    pub auth_path: Option<AuthPath>,
+}
+
+impl_wire!(AuthPathMsg { req_id, auth_path });
+
+/// Asks a full node for the idx of a cm.
+#[derive(Debug)]
+pub struct CmIdxReqMsg {
+    pub req_id: u64,
+    pub cm: Cm,
+}
+
+impl_wire!(CmIdxReqMsg { req_id, cm });
+
+#[derive(Debug)]
+pub struct CmIdxMsg {
+    pub req_id: u64,
+    pub cm_idx: Option<CmIdx>,
+}
+
+impl_wire!(CmIdxMsg { req_id, cm_idx });
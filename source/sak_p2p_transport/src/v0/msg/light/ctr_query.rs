@@ -0,0 +1,31 @@
+use crate::impl_wire;
+
+/// Asks a full node to run a contract query.
+#[derive(Debug)]
+pub struct CtrQueryReqMsg {
+    pub req_id: u64,
+    pub ctr_addr: String,
+    pub req_type: String,
+    pub args: Vec<u8>,
+}
+
+impl_wire!(CtrQueryReqMsg {
+    req_id,
+    ctr_addr,
+    req_type,
+    args,
+});
+
+/// Result of the query, or the error it has ended with.
+#[derive(Debug)]
+pub struct CtrQueryMsg {
+    pub req_id: u64,
+    pub result: Vec<u8>,
+    pub error: Option<String>,
+}
+
+impl_wire!(CtrQueryMsg {
+    req_id,
+    result,
+    error,
+});
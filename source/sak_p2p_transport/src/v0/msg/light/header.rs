@@ -0,0 +1,43 @@
+use crate::{impl_wire, Decode, Encode, TrptError};
+use bytes::{Bytes, BytesMut};
+use sak_types::{Block, BlockHeight};
+
+// Headers sent at once. A light node asks for more until she is caught up
+pub const MAX_HEADERS_PER_MSG: usize = 128;
+
+/// Asks for the block headers from `from_height` on.
+#[derive(Debug)]
+pub struct HeaderReqMsg {
+    pub from_height: BlockHeight,
+}
+
+impl_wire!(HeaderReqMsg { from_height });
+
+/// Blocks without their txs, in the height order.
+#[derive(Debug)]
+pub struct HeadersMsg {
+    pub blocks: Vec<Block>,
+}
+
+impl Encode for HeadersMsg {
+    fn encode(&self, dst: &mut BytesMut) {
+        self.blocks.encode(dst);
+    }
+}
+
+impl Decode for HeadersMsg {
+    fn decode(src: &mut Bytes) -> Result<HeadersMsg, TrptError> {
+        let blocks: Vec<Block> = Decode::decode(src)?;
+
+        if blocks.len() > MAX_HEADERS_PER_MSG {
+            return Err(format!(
+                "Too many headers in a msg, count: {}, max: {}",
+                blocks.len(),
+                MAX_HEADERS_PER_MSG,
+            )
+            .into());
+        }
+
+        Ok(HeadersMsg { blocks })
+    }
+}
@@ -0,0 +1,7 @@
+mod auth_path;
+mod ctr_query;
+mod header;
+
+pub use auth_path::*;
+pub use ctr_query::*;
+pub use header::*;
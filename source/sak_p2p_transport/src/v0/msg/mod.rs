@@ -2,16 +2,17 @@ mod block;
 mod error;
 mod handshake;
 mod hello;
+mod light;
 mod msg;
 mod msg_type;
 mod ping;
 mod tx;
-pub(crate) mod tx_utils;
 
 pub use block::*;
 pub use error::*;
 pub use handshake::*;
 pub use hello::*;
+pub use light::*;
 pub use msg::Msg;
 pub use msg_type::*;
 pub use ping::*;
@@ -1,5 +1,7 @@
 use crate::{
-    BlockAckMsg, BlockHashSyncMsg, BlockSynMsg, ErrorMsg, HandshakeMsg, HelloMsg, PingMsg,
+    AuthPathMsg, AuthPathReqMsg, BlockAckMsg, BlockHashSyncMsg, BlockSynMsg, BlockTxsMsg,
+    BlockTxsReqMsg, CmIdxMsg, CmIdxReqMsg, CompactBlockSynMsg, CtrQueryMsg, CtrQueryReqMsg,
+    ErrorMsg, HandshakeFinMsg, HandshakeMsg, HeaderReqMsg, HeadersMsg, HelloMsg, MsgType, PingMsg,
     TxAckMsg, TxHashSyncMsg, TxSynMsg,
 };
 
@@ -13,6 +15,8 @@ pub enum Msg {
 
     HandshakeAck(HandshakeMsg),
 
+    HandshakeFin(HandshakeFinMsg),
+
     TxHashSyn(TxHashSyncMsg),
 
     TxHashAck(TxHashSyncMsg),
@@ -29,9 +33,70 @@ pub enum Msg {
 
     BlockAck(BlockAckMsg),
 
+    CompactBlockSyn(CompactBlockSynMsg),
+
+    BlockTxsReq(BlockTxsReqMsg),
+
+    BlockTxs(BlockTxsMsg),
+
+    FullBlockReq(BlockHashSyncMsg),
+
+    HeaderReq(HeaderReqMsg),
+
+    Headers(HeadersMsg),
+
+    AuthPathReq(AuthPathReqMsg),
+
+    AuthPath(AuthPathMsg),
+
+    CmIdxReq(CmIdxReqMsg),
+
+    CmIdx(CmIdxMsg),
+
+    CtrQueryReq(CtrQueryReqMsg),
+
+    CtrQuery(CtrQueryMsg),
+
     Error(ErrorMsg),
 
     Ping(PingMsg),
+
+    Pong(PingMsg),
+}
+
+impl Msg {
+    pub fn get_type(&self) -> &'static str {
+        match &self {
+            Msg::HelloSyn(_) => MsgType::HELLO_SYN,
+            Msg::HelloAck(_) => MsgType::HELLO_ACK,
+            Msg::HandshakeSyn(_) => MsgType::HANDSHAKE_SYN,
+            Msg::HandshakeAck(_) => MsgType::HANDSHAKE_ACK,
+            Msg::HandshakeFin(_) => MsgType::HANDSHAKE_FIN,
+            Msg::TxHashSyn(_) => MsgType::TX_HASH_SYN,
+            Msg::TxHashAck(_) => MsgType::TX_HASH_ACK,
+            Msg::TxSyn(_) => MsgType::TX_SYN,
+            Msg::TxAck(_) => MsgType::TX_ACK,
+            Msg::BlockHashSyn(_) => MsgType::BLOCK_HASH_SYN,
+            Msg::BlockHashAck(_) => MsgType::BLOCK_HASH_ACK,
+            Msg::BlockSyn(_) => MsgType::BLOCK_SYN,
+            Msg::BlockAck(_) => MsgType::BLOCK_ACK,
+            Msg::CompactBlockSyn(_) => MsgType::COMPACT_BLOCK_SYN,
+            Msg::BlockTxsReq(_) => MsgType::BLOCK_TXS_REQ,
+            Msg::BlockTxs(_) => MsgType::BLOCK_TXS,
+            Msg::FullBlockReq(_) => MsgType::FULL_BLOCK_REQ,
+            Msg::HeaderReq(_) => MsgType::HEADER_REQ,
+            Msg::Headers(_) => MsgType::HEADERS,
+            Msg::AuthPathReq(_) => MsgType::AUTH_PATH_REQ,
+            Msg::AuthPath(_) => MsgType::AUTH_PATH,
+            Msg::CmIdxReq(_) => MsgType::CM_IDX_REQ,
+            Msg::CmIdx(_) => MsgType::CM_IDX,
+            Msg::CtrQueryReq(_) => MsgType::CTR_QUERY_REQ,
+            Msg::CtrQuery(_) => MsgType::CTR_QUERY,
+            Msg::Error(_) => MsgType::ERROR,
+            Msg::Ping(_) => MsgType::PING,
+            Msg::Pong(_) => MsgType::PONG,
+        }
+    }
 }
 
 impl std::fmt::Display for Msg {
@@ -41,6 +106,7 @@ impl std::fmt::Display for Msg {
             Msg::HelloAck(_) => write!(f, "hello_ack"),
             Msg::HandshakeSyn(_) => write!(f, "handshake_syn"),
             Msg::HandshakeAck(_) => write!(f, "handshake_ack"),
+            Msg::HandshakeFin(_) => write!(f, "handshake_fin"),
             Msg::TxHashSyn(tx_hash_sync) => {
                 write!(f, "tx_hash_syn, tx count: {}", tx_hash_sync.tx_hashes.len())
             }
@@ -58,7 +124,38 @@ impl std::fmt::Display for Msg {
             Msg::BlockHashAck(_) => write!(f, "block_hash_ack"),
             Msg::BlockSyn(_) => write!(f, "block_syn"),
             Msg::BlockAck(_) => write!(f, "block_ack"),
+            Msg::CompactBlockSyn(compact_block_syn) => {
+                write!(
+                    f,
+                    "compact_block_syn, block count: {}",
+                    compact_block_syn.blocks.len()
+                )
+            }
+            Msg::BlockTxsReq(block_txs_req) => {
+                write!(
+                    f,
+                    "block_txs_req, tx count: {}",
+                    block_txs_req.tx_hashes.len()
+                )
+            }
+            Msg::BlockTxs(block_txs) => {
+                write!(f, "block_txs, tx count: {}", block_txs.tx_candidates.len())
+            }
+            Msg::FullBlockReq(_) => write!(f, "full_block_req"),
+            Msg::HeaderReq(header_req) => {
+                write!(f, "header_req, from_height: {}", header_req.from_height)
+            }
+            Msg::Headers(headers) => {
+                write!(f, "headers, block count: {}", headers.blocks.len())
+            }
+            Msg::AuthPathReq(_) => write!(f, "auth_path_req"),
+            Msg::AuthPath(_) => write!(f, "auth_path"),
+            Msg::CmIdxReq(_) => write!(f, "cm_idx_req"),
+            Msg::CmIdx(_) => write!(f, "cm_idx"),
+            Msg::CtrQueryReq(_) => write!(f, "ctr_query_req"),
+            Msg::CtrQuery(_) => write!(f, "ctr_query"),
             Msg::Ping(_) => write!(f, "ping"),
+            Msg::Pong(_) => write!(f, "pong"),
         }
     }
 }
@@ -7,6 +7,8 @@ pub mod MsgType {
 
     pub const HANDSHAKE_ACK: &str = "hs_ack";
 
+    pub const HANDSHAKE_FIN: &str = "hs_fin";
+
     pub const TX_SYN: &str = "tx_syn";
 
     pub const TX_ACK: &str = "tx_ack";
@@ -25,5 +27,90 @@ pub mod MsgType {
 
     pub const PING: &str = "ping";
 
+    pub const PONG: &str = "pong";
+
     pub const ERROR: &str = "error";
+
+    pub const COMPACT_BLOCK_SYN: &str = "compact_block_syn";
+
+    pub const BLOCK_TXS_REQ: &str = "block_txs_req";
+
+    pub const BLOCK_TXS: &str = "block_txs";
+
+    pub const FULL_BLOCK_REQ: &str = "full_block_req";
+
+    pub const HEADER_REQ: &str = "header_req";
+
+    pub const HEADERS: &str = "headers";
+
+    pub const AUTH_PATH_REQ: &str = "auth_path_req";
+
+    pub const AUTH_PATH: &str = "auth_path";
+
+    pub const CM_IDX_REQ: &str = "cm_idx_req";
+
+    pub const CM_IDX: &str = "cm_idx";
+
+    pub const CTR_QUERY_REQ: &str = "ctr_query_req";
+
+    pub const CTR_QUERY: &str = "ctr_query";
+}
+
+/// Code of each msg type on the wire. Codes are never reused.
+pub mod MsgCode {
+    pub const HELLO_SYN: u8 = 1;
+
+    pub const HELLO_ACK: u8 = 2;
+
+    pub const HANDSHAKE_SYN: u8 = 3;
+
+    pub const HANDSHAKE_ACK: u8 = 4;
+
+    pub const HANDSHAKE_FIN: u8 = 5;
+
+    pub const TX_SYN: u8 = 6;
+
+    pub const TX_ACK: u8 = 7;
+
+    pub const TX_HASH_SYN: u8 = 8;
+
+    pub const TX_HASH_ACK: u8 = 9;
+
+    pub const BLOCK_HASH_SYN: u8 = 10;
+
+    pub const BLOCK_HASH_ACK: u8 = 11;
+
+    pub const BLOCK_SYN: u8 = 12;
+
+    pub const BLOCK_ACK: u8 = 13;
+
+    pub const PING: u8 = 14;
+
+    pub const PONG: u8 = 15;
+
+    pub const ERROR: u8 = 16;
+
+    pub const COMPACT_BLOCK_SYN: u8 = 17;
+
+    pub const BLOCK_TXS_REQ: u8 = 18;
+
+    pub const BLOCK_TXS: u8 = 19;
+
+    pub const FULL_BLOCK_REQ: u8 = 20;
+
+    pub const HEADER_REQ: u8 = 21;
+
+    pub const HEADERS: u8 = 22;
+
+    pub const AUTH_PATH_REQ: u8 = 23;
+
+    pub const AUTH_PATH: u8 = 24;
+
+    pub const CM_IDX_REQ: u8 = 25;
+
+    pub const CM_IDX: u8 = 26;
+
+    pub const CTR_QUERY_REQ: u8 = 27;
+
+    pub const CTR_QUERY: u8 = 28;
 }
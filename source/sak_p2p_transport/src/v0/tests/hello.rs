@@ -0,0 +1,69 @@
+use crate::handshake::SessionKeys;
+use crate::{HelloMsg, Msg, UpgradedP2PCodec, CAP_BLOCK_RELAY, CAP_TX_RELAY};
+use bytes::BytesMut;
+use sak_p2p_addr::UnknownAddr;
+use tokio_util::codec::{Decoder, Encoder};
+
+fn make_hello(network_id: &str, genesis_hash: &str) -> HelloMsg {
+    HelloMsg::new(
+        network_id.to_string(),
+        genesis_hash.to_string(),
+        7,
+        CAP_TX_RELAY,
+        vec![UnknownAddr::new_from_endpoint(
+            &"127.0.0.1".to_string(),
+            35518,
+        )],
+    )
+}
+
+#[test]
+fn test_hello_ack_round_trip() {
+    let keys = SessionKeys {
+        out_key: [1; 32],
+        out_nonce: [11; 12],
+        in_key: [1; 32],
+        in_nonce: [11; 12],
+    };
+
+    let mut codec = UpgradedP2PCodec::new(keys, "conn_1".to_string());
+
+    let mut buf = BytesMut::new();
+
+    codec
+        .encode(Msg::HelloAck(make_hello("net_1", "genesis_1")), &mut buf)
+        .unwrap();
+
+    match codec.decode(&mut buf).unwrap() {
+        Some(Msg::HelloAck(h)) => {
+            assert_eq!(h.network_id, "net_1");
+            assert_eq!(h.genesis_hash, "genesis_1");
+            assert_eq!(h.best_height, 7);
+            assert!(h.has_capability(CAP_TX_RELAY));
+            assert!(!h.has_capability(CAP_BLOCK_RELAY));
+            assert_eq!(h.unknown_addrs.len(), 1);
+            assert_eq!(h.unknown_addrs[0].disc_port, 35518);
+        }
+        m => panic!("Unexpected msg: {:?}", m),
+    }
+}
+
+#[test]
+fn test_hello_of_another_network_is_incompatible() {
+    let hello = make_hello("net_1", "genesis_1");
+
+    assert!(hello
+        .check_compatible(&make_hello("net_1", "genesis_1"))
+        .is_ok());
+    assert!(hello
+        .check_compatible(&make_hello("net_2", "genesis_1"))
+        .is_err());
+    assert!(hello
+        .check_compatible(&make_hello("net_1", "genesis_2"))
+        .is_err());
+
+    let mut other = make_hello("net_1", "genesis_1");
+    other.protocol_version += 1;
+
+    assert!(hello.check_compatible(&other).is_err());
+}
@@ -1,2 +1,6 @@
 mod cipher;
+mod codec;
 mod handshake;
+mod hello;
+mod session;
+mod wire;
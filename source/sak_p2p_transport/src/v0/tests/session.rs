@@ -0,0 +1,86 @@
+use crate::handshake::{
+    make_transcript_hash, sign_transcript, verify_transcript, EphemeralKey, SessionKeys,
+    ROLE_INITIATOR, ROLE_RESPONDER,
+};
+use sak_p2p_id::Identity;
+
+fn make_identity() -> Identity {
+    Identity::new(
+        &String::from(
+            "\
+            7297b903877a957748b74068d63d6d566\
+            148197524099fc1df5cd9e8814c66c7",
+        ),
+        &String::from(
+            "\
+            045739d074b8722891c307e8e75c9607e\
+            0b55a80778b42ef5f4640d4949dbf3992\
+            f6083b729baef9e9545c4e95590616fd3\
+            82662a09653f2a966ff524989ae8c0f",
+        ),
+        35601,
+        35602,
+    )
+    .unwrap()
+}
+
+fn make_keys(
+    eph_i: &EphemeralKey,
+    eph_r: &EphemeralKey,
+    transcript_hash: &[u8; 32],
+) -> (SessionKeys, SessionKeys) {
+    let pk_i = sak_crypto::convert_public_key_str_into_public_key(&eph_i.public_key_str).unwrap();
+    let pk_r = sak_crypto::convert_public_key_str_into_public_key(&eph_r.public_key_str).unwrap();
+
+    let keys_i = SessionKeys::derive(&eph_i.secret_key, pk_r, transcript_hash, true).unwrap();
+    let keys_r = SessionKeys::derive(&eph_r.secret_key, pk_i, transcript_hash, false).unwrap();
+
+    (keys_i, keys_r)
+}
+
+#[test]
+fn test_session_keys_are_symmetric_and_fresh_per_handshake() {
+    let transcript_hash = [7u8; 32];
+
+    let (eph_i, eph_r) = (EphemeralKey::generate(), EphemeralKey::generate());
+    let (keys_i, keys_r) = make_keys(&eph_i, &eph_r, &transcript_hash);
+
+    assert_eq!(keys_i.out_key, keys_r.in_key);
+    assert_eq!(keys_i.out_nonce, keys_r.in_nonce);
+    assert_eq!(keys_i.in_key, keys_r.out_key);
+    assert_ne!(keys_i.out_key, keys_i.in_key);
+
+    let (eph_i_2, eph_r_2) = (EphemeralKey::generate(), EphemeralKey::generate());
+    let (keys_i_2, _) = make_keys(&eph_i_2, &eph_r_2, &transcript_hash);
+
+    assert_ne!(keys_i.out_key, keys_i_2.out_key);
+}
+
+#[test]
+fn test_transcript_sig_is_bound_to_role_and_nonces() {
+    let identity = make_identity();
+    let public_key_str = &identity.credential.public_key_str;
+
+    let make_hash = |nonce: [u8; 32]| {
+        make_transcript_hash(
+            public_key_str,
+            &"her_public_key".to_string(),
+            &"eph_i".to_string(),
+            &nonce,
+            &"eph_r".to_string(),
+            &[2u8; 32],
+        )
+    };
+
+    let h = make_hash([1u8; 32]);
+    let sig = sign_transcript(&identity.credential.secret_key, &h, ROLE_RESPONDER);
+
+    assert!(verify_transcript(public_key_str, &h, ROLE_RESPONDER, &sig).is_ok());
+
+    // Reflected to the other role
+    assert!(verify_transcript(public_key_str, &h, ROLE_INITIATOR, &sig).is_err());
+
+    // Replayed in a handshake having a different challenge nonce
+    let h_2 = make_hash([3u8; 32]);
+    assert!(verify_transcript(public_key_str, &h_2, ROLE_RESPONDER, &sig).is_err());
+}
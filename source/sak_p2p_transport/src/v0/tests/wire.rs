@@ -0,0 +1,710 @@
+use crate::{
+    decode_exact, encode_to_bytes, get_uvarint, put_uvarint, AuthPath, AuthPathMsg, AuthPathReqMsg,
+    BlockAckMsg, BlockHashSyncMsg, BlockSynMsg, BlockTxsMsg, BlockTxsReqMsg, CmIdxMsg, CmIdxReqMsg,
+    CompactBlockSynMsg, CtrQueryMsg, CtrQueryReqMsg, Decode, Encode, ErrorMsg, HandshakeFinMsg,
+    HandshakeMsg, HeaderReqMsg, HeadersMsg, HelloMsg, Msg, P2PCodec, PingMsg, TxAckMsg,
+    TxHashSyncMsg, TxSynMsg, MAX_HEADERS_PER_MSG, WIRE_VERSION,
+};
+use bytes::{Bytes, BytesMut};
+use proptest::collection::vec;
+use proptest::option;
+use proptest::prelude::*;
+use proptest::strategy::ValueTree;
+use proptest::test_runner::TestRunner;
+use sak_crypto::{rand_bytes_32, SecretKey, Signer, SigningKey};
+use sak_p2p_addr::UnknownAddr;
+use sak_p2p_frame::{frame_io, Frame, Parse};
+use sak_types::{Block, MintTx, MintTxCandidate, PourTx, PourTxCandidate, Tx, TxCandidate};
+use std::time::Instant;
+use tokio_util::codec::{Decoder, Encoder};
+
+// Txs and blocks are hashed on every case, so fewer cases than the default
+const ROUND_TRIP_CASES: u32 = 64;
+
+// Draws a single value, for the tests that need a fixture rather than a
+// property
+fn sample<S: Strategy>(strategy: S) -> S::Value {
+    strategy
+        .new_tree(&mut TestRunner::default())
+        .unwrap()
+        .current()
+}
+
+// Mixes small and full width values so that every varint length shows up
+fn arb_u128() -> impl Strategy<Value = u128> {
+    (any::<u128>(), 0..128u32).prop_map(|(v, shift)| v >> shift)
+}
+
+fn arb_string(max_len: usize) -> impl Strategy<Value = String> {
+    vec(any::<char>(), 0..=max_len).prop_map(|chars| chars.into_iter().collect())
+}
+
+// Hex string, as tx and block hashes are
+fn arb_hash() -> impl Strategy<Value = String> {
+    any::<[u8; 32]>().prop_map(|b| to_hex(&b))
+}
+
+fn to_hex(b: &[u8]) -> String {
+    b.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn arb_bytes(max_len: usize) -> impl Strategy<Value = Vec<u8>> {
+    vec(any::<u8>(), 0..=max_len)
+}
+
+fn arb_arrs(max_len: usize) -> impl Strategy<Value = Vec<[u8; 32]>> {
+    vec(any::<[u8; 32]>(), 0..=max_len)
+}
+
+fn arb_sig() -> impl Strategy<Value = sak_crypto::Signature> {
+    (any::<[u8; 32]>(), arb_bytes(64)).prop_filter_map(
+        "secret should be a valid scalar",
+        |(secret, data)| {
+            let secret_key = SecretKey::from_bytes(&secret).ok()?;
+
+            Some(SigningKey::from(&secret_key).sign(&data))
+        },
+    )
+}
+
+fn arb_mint_tc() -> impl Strategy<Value = MintTxCandidate> {
+    (
+        arb_string(20),
+        arb_bytes(300),
+        arb_string(20),
+        option::of(arb_string(20)),
+        arb_arrs(3),
+        any::<[u8; 32]>(),
+        any::<[u8; 32]>(),
+        any::<[u8; 32]>(),
+    )
+        .prop_map(|(created_at, data, author_sig, ctr_addr, cms, v, k, s)| {
+            MintTxCandidate::new(created_at, data, author_sig, ctr_addr, cms, v, k, s)
+        })
+}
+
+fn arb_pour_tc() -> impl Strategy<Value = PourTxCandidate> {
+    (
+        arb_string(20),
+        arb_bytes(300),
+        arb_string(20),
+        option::of(arb_string(20)),
+        arb_bytes(200),
+        arb_arrs(3),
+        arb_arrs(3),
+        arb_arrs(3),
+    )
+        .prop_map(
+            |(created_at, data, author_sig, ctr_addr, pi, sns, cms, merkle_rts)| {
+                PourTxCandidate::new(
+                    created_at, data, author_sig, ctr_addr, pi, sns, cms, merkle_rts,
+                )
+            },
+        )
+}
+
+fn arb_tc() -> impl Strategy<Value = TxCandidate> {
+    prop_oneof![
+        arb_mint_tc().prop_map(TxCandidate::Mint),
+        arb_pour_tc().prop_map(TxCandidate::Pour),
+    ]
+}
+
+fn arb_tx() -> impl Strategy<Value = Tx> {
+    let cm_idxes = || vec(arb_u128(), 0..=3);
+
+    prop_oneof![
+        (arb_mint_tc(), cm_idxes()).prop_map(|(tc, idxes)| Tx::Mint(MintTx::new(tc, idxes))),
+        (arb_pour_tc(), cm_idxes()).prop_map(|(tc, idxes)| Tx::Pour(PourTx::new(tc, idxes))),
+    ]
+}
+
+fn arb_block_with_txs() -> impl Strategy<Value = (Block, Vec<Tx>)> {
+    (
+        vec(arb_tx(), 0..=3),
+        vec(arb_string(20), 0..=3),
+        arb_string(20),
+        arb_string(20),
+        arb_u128(),
+        any::<[u8; 32]>(),
+    )
+        .prop_map(
+            |(txs, witness_sigs, validator_sig, created_at, block_height, merkle_rt)| {
+                let block = Block::new(
+                    validator_sig,
+                    txs.iter().map(|tx| tx.get_tx_hash().to_string()).collect(),
+                    witness_sigs,
+                    created_at,
+                    block_height,
+                    merkle_rt,
+                );
+
+                (block, txs)
+            },
+        )
+}
+
+fn arb_block_with_tcs() -> impl Strategy<Value = (Block, Vec<TxCandidate>)> {
+    arb_block_with_txs()
+        .prop_map(|(block, txs)| (block, txs.into_iter().map(|tx| tx.downgrade()).collect()))
+}
+
+fn arb_hello() -> impl Strategy<Value = HelloMsg> {
+    (
+        any::<u32>(),
+        arb_string(10),
+        arb_string(64),
+        arb_u128(),
+        any::<u64>(),
+        vec((arb_string(15), any::<u16>()), 0..=5),
+    )
+        .prop_map(
+            |(protocol_version, network_id, genesis_hash, best_height, capabilities, addrs)| {
+                HelloMsg {
+                    protocol_version,
+                    network_id,
+                    genesis_hash,
+                    best_height,
+                    capabilities,
+                    unknown_addrs: addrs
+                        .iter()
+                        .map(|(ip, port)| UnknownAddr::new_from_endpoint(ip, *port))
+                        .collect(),
+                }
+            },
+        )
+}
+
+fn arb_handshake() -> impl Strategy<Value = HandshakeMsg> {
+    (
+        arb_string(20),
+        any::<u16>(),
+        arb_string(130),
+        arb_string(130),
+        arb_string(130),
+        any::<[u8; 32]>(),
+        option::of(arb_sig()),
+    )
+        .prop_map(
+            |(instance_id, src_p2p_port, src_key, dst_key, eph_key, nonce, sig)| HandshakeMsg {
+                instance_id,
+                src_p2p_port,
+                src_public_key_str: src_key,
+                dst_public_key_str: dst_key,
+                eph_public_key_str: eph_key,
+                nonce,
+                sig,
+            },
+        )
+}
+
+fn arb_block_hash_sync() -> impl Strategy<Value = BlockHashSyncMsg> {
+    vec((arb_u128(), arb_string(64)), 0..=5).prop_map(|new_blocks| BlockHashSyncMsg { new_blocks })
+}
+
+fn arb_tx_hash_sync() -> impl Strategy<Value = TxHashSyncMsg> {
+    vec(arb_string(64), 0..=5).prop_map(|tx_hashes| TxHashSyncMsg { tx_hashes })
+}
+
+fn arb_auth_path() -> impl Strategy<Value = AuthPath> {
+    (
+        arb_u128(),
+        any::<[u8; 32]>(),
+        vec((any::<[u8; 32]>(), any::<bool>()), 0..=5),
+    )
+        .prop_map(|(block_height, cm, path)| AuthPath {
+            block_height,
+            cm,
+            path,
+        })
+}
+
+// Every msg type, so that a type left out of the codec is caught. Msg is
+// not Clone, hence the msgs without fields are mapped out of `Just(())`
+fn arb_msg() -> impl Strategy<Value = Msg> {
+    prop_oneof![
+        arb_hello().prop_map(Msg::HelloSyn),
+        arb_hello().prop_map(Msg::HelloAck),
+        arb_handshake().prop_map(Msg::HandshakeSyn),
+        arb_handshake().prop_map(Msg::HandshakeAck),
+        arb_sig().prop_map(|sig| Msg::HandshakeFin(HandshakeFinMsg { sig })),
+        arb_tx_hash_sync().prop_map(Msg::TxHashSyn),
+        arb_tx_hash_sync().prop_map(Msg::TxHashAck),
+        vec(arb_tc(), 0..=3).prop_map(|tx_candidates| Msg::TxSyn(TxSynMsg { tx_candidates })),
+        Just(()).prop_map(|_| Msg::TxAck(TxAckMsg {})),
+        arb_block_hash_sync().prop_map(Msg::BlockHashSyn),
+        arb_block_hash_sync().prop_map(Msg::BlockHashAck),
+        vec(arb_block_with_txs(), 0..=2).prop_map(|blocks| Msg::BlockSyn(BlockSynMsg { blocks })),
+        Just(()).prop_map(|_| Msg::BlockAck(BlockAckMsg {})),
+        vec(arb_block_with_txs(), 0..=2).prop_map(|blocks| {
+            Msg::CompactBlockSyn(CompactBlockSynMsg {
+                blocks: blocks.into_iter().map(|(b, _)| b).collect(),
+            })
+        }),
+        (arb_hash(), vec(arb_hash(), 0..=5)).prop_map(|(block_hash, tx_hashes)| {
+            Msg::BlockTxsReq(BlockTxsReqMsg {
+                block_hash,
+                tx_hashes,
+            })
+        }),
+        arb_block_with_tcs().prop_map(|(block, tx_candidates)| {
+            Msg::BlockTxs(BlockTxsMsg {
+                block,
+                tx_candidates,
+            })
+        }),
+        arb_block_hash_sync().prop_map(Msg::FullBlockReq),
+        arb_u128().prop_map(|from_height| Msg::HeaderReq(HeaderReqMsg { from_height })),
+        vec(arb_block_with_txs(), 0..=3).prop_map(|blocks| {
+            Msg::Headers(HeadersMsg {
+                blocks: blocks.into_iter().map(|(b, _)| b).collect(),
+            })
+        }),
+        (any::<u64>(), arb_u128())
+            .prop_map(|(req_id, cm_idx)| Msg::AuthPathReq(AuthPathReqMsg { req_id, cm_idx })),
+        (any::<u64>(), option::of(arb_auth_path()))
+            .prop_map(|(req_id, auth_path)| Msg::AuthPath(AuthPathMsg { req_id, auth_path })),
+        (any::<u64>(), any::<[u8; 32]>())
+            .prop_map(|(req_id, cm)| Msg::CmIdxReq(CmIdxReqMsg { req_id, cm })),
+        (any::<u64>(), option::of(arb_u128()))
+            .prop_map(|(req_id, cm_idx)| Msg::CmIdx(CmIdxMsg { req_id, cm_idx })),
+        (any::<u64>(), arb_string(20), arb_string(20), arb_bytes(100)).prop_map(
+            |(req_id, ctr_addr, req_type, args)| {
+                Msg::CtrQueryReq(CtrQueryReqMsg {
+                    req_id,
+                    ctr_addr,
+                    req_type,
+                    args,
+                })
+            }
+        ),
+        (any::<u64>(), arb_bytes(100), option::of(arb_string(50))).prop_map(
+            |(req_id, result, error)| Msg::CtrQuery(CtrQueryMsg {
+                req_id,
+                result,
+                error,
+            })
+        ),
+        arb_string(50).prop_map(|error| Msg::Error(ErrorMsg { error })),
+        arb_u128().prop_map(|nonce| Msg::Ping(PingMsg { nonce })),
+        arb_u128().prop_map(|nonce| Msg::Pong(PingMsg { nonce })),
+    ]
+}
+
+fn encode_msg(msg: Msg) -> BytesMut {
+    let mut buf = BytesMut::new();
+
+    P2PCodec {}.encode(msg, &mut buf).unwrap();
+
+    buf
+}
+
+fn decode_msg(buf: &mut BytesMut) -> Result<Option<Msg>, crate::TrptError> {
+    P2PCodec {}.decode(buf)
+}
+
+#[test]
+fn test_varint_edge_values_round_trip() {
+    let values = [
+        0,
+        1,
+        127,
+        128,
+        300,
+        u16::MAX as u128,
+        u64::MAX as u128,
+        u128::MAX - 1,
+        u128::MAX,
+    ];
+
+    for v in values {
+        let mut buf = BytesMut::new();
+        put_uvarint(&mut buf, v);
+
+        let mut src = buf.freeze();
+
+        assert_eq!(get_uvarint(&mut src).unwrap(), v);
+        assert!(src.is_empty());
+    }
+
+    let mut buf = BytesMut::new();
+    put_uvarint(&mut buf, 127);
+    assert_eq!(buf.len(), 1);
+
+    let mut buf = BytesMut::new();
+    put_uvarint(&mut buf, u128::MAX);
+    assert_eq!(buf.len(), 19);
+}
+
+#[test]
+fn test_varint_rejects_overflow_and_truncation() {
+    // 19th group carrying more than the 2 bits left of a u128
+    let mut overflow = vec![0xff; 18];
+    overflow.push(0x04);
+
+    assert!(get_uvarint(&mut Bytes::from(overflow)).is_err());
+
+    // Continuation bit set on the last byte
+    assert!(get_uvarint(&mut Bytes::from(vec![0x80, 0x80])).is_err());
+
+    // Value too large for the type
+    let bytes = encode_to_bytes(&(u16::MAX as u32 + 1));
+    assert!(decode_exact::<u16>(bytes).is_err());
+}
+
+#[test]
+fn test_huge_claimed_list_len_is_rejected() {
+    let mut buf = BytesMut::new();
+    put_uvarint(&mut buf, 1 << 40);
+    buf.extend_from_slice(&[0u8; 32]);
+
+    assert!(decode_exact::<Vec<[u8; 32]>>(buf.freeze()).is_err());
+
+    // Fails on running out of bytes, instead of allocating for the claim
+    let mut src = Bytes::from(vec![0u8; 64]);
+    assert!(<[u8; 32]>::decode_vec(&mut src, usize::MAX / 32).is_err());
+
+    let mut src = Bytes::from(vec![0u8; 64]);
+    assert!(String::decode_vec(&mut src, usize::MAX / 64).is_err());
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(ROUND_TRIP_CASES))]
+
+    #[test]
+    fn test_varint_round_trips(v in arb_u128()) {
+        let mut buf = BytesMut::new();
+        put_uvarint(&mut buf, v);
+
+        let mut src = buf.freeze();
+
+        prop_assert_eq!(get_uvarint(&mut src).unwrap(), v);
+        prop_assert!(src.is_empty());
+
+        prop_assert_eq!(decode_exact::<u128>(encode_to_bytes(&v)).unwrap(), v);
+    }
+
+    #[test]
+    fn test_narrow_uints_round_trip(a in any::<u16>(), b in any::<u32>(), c in any::<u64>()) {
+        prop_assert_eq!(decode_exact::<u16>(encode_to_bytes(&a)).unwrap(), a);
+        prop_assert_eq!(decode_exact::<u32>(encode_to_bytes(&b)).unwrap(), b);
+        prop_assert_eq!(decode_exact::<u64>(encode_to_bytes(&c)).unwrap(), c);
+    }
+
+    #[test]
+    fn test_string_round_trips(s in arb_string(300)) {
+        prop_assert_eq!(decode_exact::<String>(encode_to_bytes(&s)).unwrap(), s);
+    }
+
+    #[test]
+    fn test_lists_round_trip(
+        strings in vec(arb_string(20), 0..20),
+        arrs in arb_arrs(20),
+        bytes in arb_bytes(300),
+        pairs in vec((arb_u128(), arb_hash()), 0..20),
+        opts in vec(option::of(any::<bool>()), 0..20),
+    ) {
+        prop_assert_eq!(decode_exact::<Vec<String>>(encode_to_bytes(&strings)).unwrap(), strings);
+        prop_assert_eq!(decode_exact::<Vec<[u8; 32]>>(encode_to_bytes(&arrs)).unwrap(), arrs);
+        prop_assert_eq!(decode_exact::<Vec<u8>>(encode_to_bytes(&bytes)).unwrap(), bytes);
+        prop_assert_eq!(
+            decode_exact::<Vec<(u128, String)>>(encode_to_bytes(&pairs)).unwrap(),
+            pairs
+        );
+        prop_assert_eq!(decode_exact::<Vec<Option<bool>>>(encode_to_bytes(&opts)).unwrap(), opts);
+    }
+
+    // Msgs have no PartialEq, so a decoded msg is compared by its encoding
+    #[test]
+    fn test_every_msg_round_trips(msg in arb_msg()) {
+        let msg_type = msg.get_type();
+
+        let mut buf = encode_msg(msg);
+        let encoded = buf.clone();
+
+        let decoded = decode_msg(&mut buf).unwrap().unwrap();
+
+        prop_assert!(buf.is_empty());
+        prop_assert_eq!(decoded.get_type(), msg_type);
+        prop_assert_eq!(encode_msg(decoded), encoded, "msg_type: {}", msg_type);
+    }
+
+    #[test]
+    fn test_tx_and_block_round_trip_keep_hashes(
+        tc in arb_tc(),
+        tx in arb_tx(),
+        (block, _) in arb_block_with_txs(),
+    ) {
+        let decoded: TxCandidate = decode_exact(encode_to_bytes(&tc)).unwrap();
+
+        prop_assert_eq!(decoded.get_tx_hash(), tc.get_tx_hash());
+        prop_assert_eq!(decoded, tc);
+
+        let decoded: Tx = decode_exact(encode_to_bytes(&tx)).unwrap();
+
+        prop_assert_eq!(decoded.get_tx_hash(), tx.get_tx_hash());
+        prop_assert_eq!(decoded, tx);
+
+        let decoded: Block = decode_exact(encode_to_bytes(&block)).unwrap();
+
+        prop_assert_eq!(decoded.get_block_hash(), block.get_block_hash());
+        prop_assert_eq!(decoded, block);
+    }
+
+    #[test]
+    fn test_codec_decodes_msgs_arriving_in_pieces(
+        msgs in vec(arb_msg(), 1..10),
+        piece_len in 1..64usize,
+    ) {
+        let msg_count = msgs.len();
+
+        let mut buf = BytesMut::new();
+
+        for msg in msgs {
+            buf.unsplit(encode_msg(msg));
+        }
+
+        let mut src = BytesMut::new();
+        let mut decoded_count = 0;
+
+        while !buf.is_empty() {
+            let n = std::cmp::min(piece_len, buf.len());
+            src.unsplit(buf.split_to(n));
+
+            while decode_msg(&mut src).unwrap().is_some() {
+                decoded_count += 1;
+            }
+        }
+
+        prop_assert_eq!(decoded_count, msg_count);
+    }
+}
+
+#[test]
+fn test_decode_rejects_malformed_msgs() {
+    let ping = encode_msg(Msg::Ping(PingMsg { nonce: 300 }));
+
+    // Other wire version
+    let mut buf = ping.clone();
+    buf[4] = WIRE_VERSION + 1;
+    assert!(decode_msg(&mut buf).is_err());
+
+    // Unknown msg code
+    let mut buf = ping.clone();
+    buf[5] = 200;
+    assert!(decode_msg(&mut buf).is_err());
+
+    // Body shorter than the msg type needs
+    let mut buf = BytesMut::new();
+    buf.extend_from_slice(&3u32.to_be_bytes());
+    buf.extend_from_slice(&[WIRE_VERSION, ping[5], 0x80]);
+    assert!(decode_msg(&mut buf).is_err());
+
+    // Trailing bytes after the body
+    let mut buf = BytesMut::new();
+    buf.extend_from_slice(&((ping.len() - 4 + 1) as u32).to_be_bytes());
+    buf.extend_from_slice(&ping[4..]);
+    buf.extend_from_slice(&[0]);
+    assert!(decode_msg(&mut buf).is_err());
+
+    // List longer than the msg
+    let mut body = BytesMut::new();
+    put_uvarint(&mut body, 1_000_000);
+    assert!(decode_exact::<TxHashSyncMsg>(body.freeze()).is_err());
+}
+
+#[test]
+fn test_block_syn_rejects_txs_not_in_the_block() {
+    let (block, _) = sample(arb_block_with_txs());
+    let txs = vec![sample(arb_tx())];
+
+    let msg = BlockSynMsg {
+        blocks: vec![(block, txs)],
+    };
+
+    assert!(decode_exact::<BlockSynMsg>(encode_to_bytes(&msg)).is_err());
+}
+
+#[test]
+fn test_block_txs_rejects_txs_not_in_the_block() {
+    let (block, _) = sample(arb_block_with_tcs());
+
+    let msg = BlockTxsMsg {
+        block,
+        tx_candidates: vec![sample(arb_tc())],
+    };
+
+    assert!(decode_exact::<BlockTxsMsg>(encode_to_bytes(&msg)).is_err());
+}
+
+#[test]
+fn test_compact_block_is_smaller_than_full_block() {
+    let txs: Vec<Tx> = sample(vec(arb_tx(), 10));
+
+    let block = Block::new(
+        "validator_sig".to_string(),
+        txs.iter().map(|tx| tx.get_tx_hash().to_string()).collect(),
+        vec!["witness_sig".to_string()],
+        "created_at".to_string(),
+        0,
+        rand_bytes_32(),
+    );
+
+    let compact_block_syn = CompactBlockSynMsg {
+        blocks: vec![block],
+    };
+
+    let compact_len = encode_to_bytes(&compact_block_syn).len();
+
+    let block = compact_block_syn.blocks.into_iter().next().unwrap();
+
+    let full_len = encode_to_bytes(&BlockSynMsg {
+        blocks: vec![(block, txs)],
+    })
+    .len();
+
+    assert!(compact_len * 5 < full_len);
+}
+
+// The frames the msgs were sent in before
+fn make_tx_hash_frame(msg: &TxHashSyncMsg) -> BytesMut {
+    let mut frame = Frame::array();
+
+    frame.push_bulk(Bytes::from("tx_hash_syn"));
+    frame.push_int(msg.tx_hashes.len() as u128);
+
+    for tx_hash in msg.tx_hashes.iter() {
+        frame.push_bulk(Bytes::from(tx_hash.clone()));
+    }
+
+    let mut buf = BytesMut::new();
+    frame_io::write_frame(&mut buf, &frame).unwrap();
+
+    buf
+}
+
+fn make_block_hash_frame(msg: &BlockHashSyncMsg) -> BytesMut {
+    let mut frame = Frame::array();
+
+    frame.push_bulk(Bytes::from("block_hash_syn"));
+    frame.push_int(msg.new_blocks.len() as u128);
+
+    for (height, block_hash) in msg.new_blocks.iter() {
+        frame.push_int(*height);
+        frame.push_bulk(Bytes::from(block_hash.clone()));
+    }
+
+    let mut buf = BytesMut::new();
+    frame_io::write_frame(&mut buf, &frame).unwrap();
+
+    buf
+}
+
+fn make_tx_hash_sync_msg(count: usize) -> TxHashSyncMsg {
+    TxHashSyncMsg {
+        tx_hashes: (0..count).map(|_| to_hex(&rand_bytes_32())).collect(),
+    }
+}
+
+fn make_block_hash_sync_msg(count: usize) -> BlockHashSyncMsg {
+    BlockHashSyncMsg {
+        new_blocks: (0..count)
+            .map(|idx| (idx as u128, to_hex(&rand_bytes_32())))
+            .collect(),
+    }
+}
+
+#[test]
+fn test_wire_is_smaller_than_frame() {
+    let tx_hash_sync = make_tx_hash_sync_msg(100);
+    let frame_len = make_tx_hash_frame(&tx_hash_sync).len();
+    let wire_len = encode_msg(Msg::TxHashSyn(tx_hash_sync)).len();
+
+    assert!(
+        wire_len < frame_len,
+        "wire: {}, frame: {}",
+        wire_len,
+        frame_len
+    );
+
+    let block_hash_sync = make_block_hash_sync_msg(100);
+    let frame_len = make_block_hash_frame(&block_hash_sync).len();
+    let wire_len = encode_msg(Msg::BlockHashSyn(block_hash_sync)).len();
+
+    assert!(
+        wire_len < frame_len,
+        "wire: {}, frame: {}",
+        wire_len,
+        frame_len
+    );
+}
+
+// cargo test -p sak_p2p_transport -- --ignored --nocapture bench_wire
+#[test]
+#[ignore]
+fn bench_wire_against_frame() {
+    const ITER: usize = 2_000;
+
+    let block_hash_sync = make_block_hash_sync_msg(500);
+
+    let frame_bytes = make_block_hash_frame(&block_hash_sync);
+    let wire_bytes = encode_to_bytes(&block_hash_sync);
+
+    let now = Instant::now();
+
+    for _ in 0..ITER {
+        let mut buf = frame_bytes.clone();
+        let frame = frame_io::parse_frame(&mut buf).unwrap().unwrap();
+        let mut parse = Parse::new(frame).unwrap();
+
+        parse.next_string().unwrap();
+        let count = parse.next_int().unwrap();
+
+        for _ in 0..count {
+            parse.next_int().unwrap();
+            parse.next_string().unwrap();
+        }
+    }
+
+    let frame_elapsed = now.elapsed();
+
+    let now = Instant::now();
+
+    for _ in 0..ITER {
+        decode_exact::<BlockHashSyncMsg>(wire_bytes.clone()).unwrap();
+    }
+
+    let wire_elapsed = now.elapsed();
+
+    let now = Instant::now();
+
+    for _ in 0..ITER {
+        let mut buf = BytesMut::new();
+        block_hash_sync.encode(&mut buf);
+    }
+
+    let wire_enc_elapsed = now.elapsed();
+
+    println!(
+        "block_hash_syn x500, size frame: {}, wire: {}\n\
+        decode x{} frame: {:?}, wire: {:?}, encode wire: {:?}",
+        frame_bytes.len(),
+        wire_bytes.len(),
+        ITER,
+        frame_elapsed,
+        wire_elapsed,
+        wire_enc_elapsed,
+    );
+}
+
+#[test]
+fn test_headers_rejects_too_many_blocks() {
+    let msg = HeadersMsg {
+        blocks: sample(vec(arb_block_with_txs(), MAX_HEADERS_PER_MSG + 1))
+            .into_iter()
+            .map(|(b, _)| b)
+            .collect(),
+    };
+
+    assert!(decode_exact::<HeadersMsg>(encode_to_bytes(&msg)).is_err());
+}
@@ -0,0 +1,100 @@
+//
+// Binary encoding of the p2p msgs.
+//
+// Integers are LEB128 varints, strings and lists are prefixed with their
+// varint length and fixed size arrays are written as they are. A struct is
+// its fields in the order they are listed in `impl_wire!`, so the encoder
+// and the decoder of a type share a single field list.
+//
+// A msg on the wire is
+//
+//   wire_version(1) | msg_code(1) | msg_body(n)
+//
+
+mod primitive;
+mod types;
+
+pub use primitive::*;
+
+use crate::TrptError;
+use bytes::{Bytes, BytesMut};
+
+/// Bumped whenever the encoding of any type changes. A msg of another
+/// version is rejected.
+pub const WIRE_VERSION: u8 = 1;
+
+/// Most items a list is allocated for ahead of decoding them, as its length
+/// prefix is given by the peer.
+const MAX_PREALLOC_LEN: usize = 1024;
+
+pub trait Encode {
+    fn encode(&self, dst: &mut BytesMut);
+
+    /// Encodes the items of a list, without the length prefix.
+    fn encode_slice(items: &[Self], dst: &mut BytesMut)
+    where
+        Self: Sized,
+    {
+        for item in items {
+            item.encode(dst);
+        }
+    }
+}
+
+pub trait Decode: Sized {
+    fn decode(src: &mut Bytes) -> Result<Self, TrptError>;
+
+    /// Decodes `len` items of a list whose length prefix is already read.
+    fn decode_vec(src: &mut Bytes, len: usize) -> Result<Vec<Self>, TrptError> {
+        let mut v = Vec::with_capacity(len.min(MAX_PREALLOC_LEN));
+
+        for _ in 0..len {
+            v.push(Self::decode(src)?);
+        }
+
+        Ok(v)
+    }
+}
+
+/// Implements `Encode` and `Decode` for a struct out of the list of its
+/// fields. Fields are written in the order given.
+macro_rules! impl_wire {
+    ($ty:ident { $($field:ident),* $(,)? }) => {
+        impl $crate::Encode for $ty {
+            #[allow(unused_variables)]
+            fn encode(&self, dst: &mut bytes::BytesMut) {
+                $($crate::Encode::encode(&self.$field, dst);)*
+            }
+        }
+
+        impl $crate::Decode for $ty {
+            #[allow(unused_variables)]
+            fn decode(src: &mut bytes::Bytes) -> Result<Self, $crate::TrptError> {
+                $(let $field = $crate::Decode::decode(src)?;)*
+
+                Ok($ty { $($field),* })
+            }
+        }
+    };
+}
+
+pub(crate) use impl_wire;
+
+/// Decodes a value which should take up the whole `src`.
+pub fn decode_exact<T: Decode>(mut src: Bytes) -> Result<T, TrptError> {
+    let v = T::decode(&mut src)?;
+
+    if !src.is_empty() {
+        return Err(format!("Trailing bytes after the value, len: {}", src.len()).into());
+    }
+
+    Ok(v)
+}
+
+pub fn encode_to_bytes<T: Encode>(v: &T) -> Bytes {
+    let mut dst = BytesMut::new();
+
+    v.encode(&mut dst);
+
+    dst.freeze()
+}
@@ -0,0 +1,247 @@
+use super::{Decode, Encode};
+use crate::TrptError;
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use sak_crypto::Signature;
+use std::convert::TryFrom;
+
+// A u128 takes at most 19 groups of 7 bits
+const MAX_VARINT_LEN: usize = 19;
+
+pub fn put_uvarint(dst: &mut BytesMut, mut v: u128) {
+    loop {
+        let b = (v & 0x7f) as u8;
+        v >>= 7;
+
+        if v == 0 {
+            dst.put_u8(b);
+            return;
+        }
+
+        dst.put_u8(b | 0x80);
+    }
+}
+
+pub fn get_uvarint(src: &mut Bytes) -> Result<u128, TrptError> {
+    let mut v: u128 = 0;
+
+    for idx in 0..MAX_VARINT_LEN {
+        let b = get_u8(src)?;
+        let part = (b & 0x7f) as u128;
+        let shift = idx * 7;
+
+        if part.leading_zeros() < shift as u32 {
+            return Err(format!("Varint overflows u128").into());
+        }
+
+        v |= part << shift;
+
+        if b & 0x80 == 0 {
+            return Ok(v);
+        }
+    }
+
+    Err(format!("Varint is too long").into())
+}
+
+fn get_u8(src: &mut Bytes) -> Result<u8, TrptError> {
+    if !src.has_remaining() {
+        return Err(format!("Unexpected end of the msg").into());
+    }
+
+    Ok(src.get_u8())
+}
+
+// Every item takes at least a byte, so a list cannot be longer than what is
+// left of the msg.
+fn get_len(src: &mut Bytes) -> Result<usize, TrptError> {
+    let len = get_uvarint(src)?;
+
+    if len > src.remaining() as u128 {
+        return Err(format!(
+            "Length exceeds the msg, len: {}, remaining: {}",
+            len,
+            src.remaining(),
+        )
+        .into());
+    }
+
+    Ok(len as usize)
+}
+
+fn get_slice(src: &mut Bytes, len: usize) -> Result<Bytes, TrptError> {
+    if len > src.remaining() {
+        return Err(format!("Unexpected end of the msg").into());
+    }
+
+    Ok(src.split_to(len))
+}
+
+impl Encode for u8 {
+    fn encode(&self, dst: &mut BytesMut) {
+        dst.put_u8(*self);
+    }
+
+    fn encode_slice(items: &[u8], dst: &mut BytesMut) {
+        dst.put_slice(items);
+    }
+}
+
+impl Decode for u8 {
+    fn decode(src: &mut Bytes) -> Result<u8, TrptError> {
+        get_u8(src)
+    }
+
+    fn decode_vec(src: &mut Bytes, len: usize) -> Result<Vec<u8>, TrptError> {
+        Ok(get_slice(src, len)?.to_vec())
+    }
+}
+
+macro_rules! impl_wire_uint {
+    ($($ty:ty),*) => {
+        $(
+            impl Encode for $ty {
+                fn encode(&self, dst: &mut BytesMut) {
+                    put_uvarint(dst, *self as u128);
+                }
+            }
+
+            impl Decode for $ty {
+                fn decode(src: &mut Bytes) -> Result<$ty, TrptError> {
+                    let v = get_uvarint(src)?;
+
+                    match <$ty>::try_from(v) {
+                        Ok(v) => Ok(v),
+                        Err(_) => Err(format!(
+                            "Integer is out of range, v: {}, type: {}",
+                            v,
+                            stringify!($ty),
+                        )
+                        .into()),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_wire_uint!(u16, u32, u64, u128);
+
+impl Encode for bool {
+    fn encode(&self, dst: &mut BytesMut) {
+        dst.put_u8(*self as u8);
+    }
+}
+
+impl Decode for bool {
+    fn decode(src: &mut Bytes) -> Result<bool, TrptError> {
+        match get_u8(src)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            b => Err(format!("Invalid bool, b: {}", b).into()),
+        }
+    }
+}
+
+impl Encode for [u8; 32] {
+    fn encode(&self, dst: &mut BytesMut) {
+        dst.put_slice(self);
+    }
+}
+
+impl Decode for [u8; 32] {
+    fn decode(src: &mut Bytes) -> Result<[u8; 32], TrptError> {
+        let b = get_slice(src, 32)?;
+
+        let mut arr = [0u8; 32];
+        arr.copy_from_slice(&b);
+
+        Ok(arr)
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, dst: &mut BytesMut) {
+        put_uvarint(dst, self.len() as u128);
+        dst.put_slice(self.as_bytes());
+    }
+}
+
+impl Decode for String {
+    fn decode(src: &mut Bytes) -> Result<String, TrptError> {
+        let len = get_len(src)?;
+        let b = get_slice(src, len)?;
+
+        let s = std::str::from_utf8(&b)?.to_string();
+
+        Ok(s)
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, dst: &mut BytesMut) {
+        put_uvarint(dst, self.len() as u128);
+
+        T::encode_slice(self, dst);
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(src: &mut Bytes) -> Result<Vec<T>, TrptError> {
+        let len = get_len(src)?;
+
+        T::decode_vec(src, len)
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, dst: &mut BytesMut) {
+        match self {
+            Some(v) => {
+                dst.put_u8(1);
+                v.encode(dst);
+            }
+            None => dst.put_u8(0),
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode(src: &mut Bytes) -> Result<Option<T>, TrptError> {
+        match bool::decode(src)? {
+            true => Ok(Some(T::decode(src)?)),
+            false => Ok(None),
+        }
+    }
+}
+
+impl<A: Encode, B: Encode> Encode for (A, B) {
+    fn encode(&self, dst: &mut BytesMut) {
+        self.0.encode(dst);
+        self.1.encode(dst);
+    }
+}
+
+impl<A: Decode, B: Decode> Decode for (A, B) {
+    fn decode(src: &mut Bytes) -> Result<(A, B), TrptError> {
+        Ok((A::decode(src)?, B::decode(src)?))
+    }
+}
+
+impl Encode for Signature {
+    fn encode(&self, dst: &mut BytesMut) {
+        let der = self.to_der();
+        let der = der.as_bytes();
+
+        put_uvarint(dst, der.len() as u128);
+        dst.put_slice(der);
+    }
+}
+
+impl Decode for Signature {
+    fn decode(src: &mut Bytes) -> Result<Signature, TrptError> {
+        let len = get_len(src)?;
+        let b = get_slice(src, len)?;
+
+        Ok(Signature::from_der(&b)?)
+    }
+}
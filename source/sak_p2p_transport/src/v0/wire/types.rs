@@ -0,0 +1,197 @@
+use super::{Decode, Encode};
+use crate::TrptError;
+use bytes::{Bytes, BytesMut};
+use sak_p2p_addr::UnknownAddr;
+use sak_types::{Block, MintTx, MintTxCandidate, PourTx, PourTxCandidate, Tx, TxCandidate, TxType};
+
+// Types carrying a hash are built through their constructors so that the
+// hash is computed by the receiver, never taken from the wire.
+
+impl Encode for MintTxCandidate {
+    fn encode(&self, dst: &mut BytesMut) {
+        self.created_at.encode(dst);
+        self.data.encode(dst);
+        self.author_sig.encode(dst);
+        self.ctr_addr.encode(dst);
+        self.cms.encode(dst);
+        self.v.encode(dst);
+        self.k.encode(dst);
+        self.s.encode(dst);
+    }
+}
+
+impl Decode for MintTxCandidate {
+    fn decode(src: &mut Bytes) -> Result<MintTxCandidate, TrptError> {
+        let created_at = String::decode(src)?;
+        let data = Vec::decode(src)?;
+        let author_sig = String::decode(src)?;
+        let ctr_addr = String::decode(src)?;
+        let cms = Vec::decode(src)?;
+        let v = Decode::decode(src)?;
+        let k = Decode::decode(src)?;
+        let s = Decode::decode(src)?;
+
+        let tc = MintTxCandidate::new(created_at, data, author_sig, Some(ctr_addr), cms, v, k, s);
+
+        Ok(tc)
+    }
+}
+
+impl Encode for PourTxCandidate {
+    fn encode(&self, dst: &mut BytesMut) {
+        self.created_at.encode(dst);
+        self.data.encode(dst);
+        self.author_sig.encode(dst);
+        self.ctr_addr.encode(dst);
+        self.pi.encode(dst);
+        self.sns.encode(dst);
+        self.cms.encode(dst);
+        self.merkle_rts.encode(dst);
+    }
+}
+
+impl Decode for PourTxCandidate {
+    fn decode(src: &mut Bytes) -> Result<PourTxCandidate, TrptError> {
+        let created_at = String::decode(src)?;
+        let data = Vec::decode(src)?;
+        let author_sig = String::decode(src)?;
+        let ctr_addr = String::decode(src)?;
+        let pi = Vec::decode(src)?;
+        let sns = Vec::decode(src)?;
+        let cms = Vec::decode(src)?;
+        let merkle_rts = Vec::decode(src)?;
+
+        let tc = PourTxCandidate::new(
+            created_at,
+            data,
+            author_sig,
+            Some(ctr_addr),
+            pi,
+            sns,
+            cms,
+            merkle_rts,
+        );
+
+        Ok(tc)
+    }
+}
+
+impl Encode for TxCandidate {
+    fn encode(&self, dst: &mut BytesMut) {
+        match self {
+            TxCandidate::Mint(tc) => {
+                (TxType::Mint as u8).encode(dst);
+                tc.encode(dst);
+            }
+            TxCandidate::Pour(tc) => {
+                (TxType::Pour as u8).encode(dst);
+                tc.encode(dst);
+            }
+        }
+    }
+}
+
+impl Decode for TxCandidate {
+    fn decode(src: &mut Bytes) -> Result<TxCandidate, TrptError> {
+        let tx_type = TxType::from(u8::decode(src)?);
+
+        let tc = match tx_type {
+            TxType::Mint => TxCandidate::Mint(MintTxCandidate::decode(src)?),
+            TxType::Pour => TxCandidate::Pour(PourTxCandidate::decode(src)?),
+            _ => return Err(format!("tx candidate type is invalid, {:?}", tx_type).into()),
+        };
+
+        Ok(tc)
+    }
+}
+
+impl Encode for Tx {
+    fn encode(&self, dst: &mut BytesMut) {
+        match self {
+            Tx::Mint(t) => {
+                (TxType::Mint as u8).encode(dst);
+                t.tx_candidate.encode(dst);
+                t.cm_idxes.encode(dst);
+            }
+            Tx::Pour(t) => {
+                (TxType::Pour as u8).encode(dst);
+                t.tx_candidate.encode(dst);
+                t.cm_idxes.encode(dst);
+            }
+        }
+    }
+}
+
+impl Decode for Tx {
+    fn decode(src: &mut Bytes) -> Result<Tx, TrptError> {
+        let tx_type = TxType::from(u8::decode(src)?);
+
+        let tx = match tx_type {
+            TxType::Mint => {
+                let tx_candidate = MintTxCandidate::decode(src)?;
+                let cm_idxes = Vec::decode(src)?;
+
+                Tx::Mint(MintTx::new(tx_candidate, cm_idxes))
+            }
+            TxType::Pour => {
+                let tx_candidate = PourTxCandidate::decode(src)?;
+                let cm_idxes = Vec::decode(src)?;
+
+                Tx::Pour(PourTx::new(tx_candidate, cm_idxes))
+            }
+            _ => return Err(format!("Invalid tx type to parse, tx_type: {:?}", tx_type).into()),
+        };
+
+        Ok(tx)
+    }
+}
+
+impl Encode for Block {
+    fn encode(&self, dst: &mut BytesMut) {
+        self.validator_sig.encode(dst);
+        self.tx_hashes.encode(dst);
+        self.witness_sigs.encode(dst);
+        self.created_at.encode(dst);
+        self.block_height.encode(dst);
+        self.merkle_rt.encode(dst);
+    }
+}
+
+impl Decode for Block {
+    fn decode(src: &mut Bytes) -> Result<Block, TrptError> {
+        let validator_sig = String::decode(src)?;
+        let tx_hashes = Vec::decode(src)?;
+        let witness_sigs = Vec::decode(src)?;
+        let created_at = String::decode(src)?;
+        let block_height = u128::decode(src)?;
+        let merkle_rt = Decode::decode(src)?;
+
+        let block = Block::new(
+            validator_sig,
+            tx_hashes,
+            witness_sigs,
+            created_at,
+            block_height,
+            merkle_rt,
+        );
+
+        Ok(block)
+    }
+}
+
+// Only the endpoint is shared, the rest is learned through discovery
+impl Encode for UnknownAddr {
+    fn encode(&self, dst: &mut BytesMut) {
+        self.ip.encode(dst);
+        self.disc_port.encode(dst);
+    }
+}
+
+impl Decode for UnknownAddr {
+    fn decode(src: &mut Bytes) -> Result<UnknownAddr, TrptError> {
+        let ip = String::decode(src)?;
+        let disc_port = u16::decode(src)?;
+
+        Ok(UnknownAddr::new_from_endpoint(&ip, disc_port))
+    }
+}
@@ -1,28 +1,138 @@
+mod params;
+mod subscription;
+#[cfg(test)]
+mod tests;
 mod tx;
 
+pub use params::*;
+pub use subscription::*;
 pub use tx::*;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
 
 pub const JSON_RPC_2: &'static str = "2.0";
 
+// Error codes defined by JSON-RPC 2.0
+pub const PARSE_ERROR: i32 = -32700;
+pub const INVALID_REQUEST: i32 = -32600;
+pub const METHOD_NOT_FOUND: i32 = -32601;
+pub const INVALID_PARAMS: i32 = -32602;
+pub const INTERNAL_ERROR: i32 = -32603;
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct JsonResponse<R: Serialize> {
     pub jsonrpc: String,
+    // A missing option reads as None. `default` is not put on them, as it
+    // would ask a generic `R` to be Default.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<JsonRPCError>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub result: Option<R>,
-    pub id: String,
+    pub id: JsonRpcId,
 }
 
+impl<R: Serialize> JsonResponse<R> {
+    pub fn new_result(id: JsonRpcId, result: R) -> JsonResponse<R> {
+        JsonResponse {
+            jsonrpc: JSON_RPC_2.into(),
+            error: None,
+            result: Some(result),
+            id,
+        }
+    }
+
+    pub fn new_error(id: JsonRpcId, error: JsonRPCError) -> JsonResponse<R> {
+        JsonResponse {
+            jsonrpc: JSON_RPC_2.into(),
+            error: Some(error),
+            result: None,
+            id,
+        }
+    }
+}
+
+/// A request without an `id` is a notification, which is not replied to.
+/// `"id": null` is still a request, and is replied to with a null id.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct JsonRequest {
     pub jsonrpc: String,
     pub method: String,
-    pub params: Option<Vec<u8>>,
-    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<serde_json::Value>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_id",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub id: Option<JsonRpcId>,
+}
+
+impl JsonRequest {
+    pub fn is_notification(&self) -> bool {
+        self.id.is_none()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(untagged)]
+pub enum JsonRpcId {
+    Number(serde_json::Number),
+    String(String),
+    Null,
+}
+
+impl fmt::Display for JsonRpcId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonRpcId::Number(n) => write!(f, "{}", n),
+            JsonRpcId::String(s) => write!(f, "{}", s),
+            JsonRpcId::Null => write!(f, "null"),
+        }
+    }
+}
+
+impl From<u64> for JsonRpcId {
+    fn from(n: u64) -> JsonRpcId {
+        JsonRpcId::Number(n.into())
+    }
+}
+
+impl From<String> for JsonRpcId {
+    fn from(s: String) -> JsonRpcId {
+        JsonRpcId::String(s)
+    }
+}
+
+impl From<&str> for JsonRpcId {
+    fn from(s: &str) -> JsonRpcId {
+        JsonRpcId::String(s.to_string())
+    }
+}
+
+// A present id, null included, is `Some`, so that it is told apart from a
+// missing one
+fn deserialize_id<'de, D>(deserializer: D) -> Result<Option<JsonRpcId>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    JsonRpcId::deserialize(deserializer).map(Some)
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct JsonRPCError {
-    pub msg: String,
+    pub code: i32,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+impl JsonRPCError {
+    pub fn new(code: i32, message: String) -> JsonRPCError {
+        JsonRPCError {
+            code,
+            message,
+            data: None,
+        }
+    }
 }
@@ -0,0 +1,25 @@
+use serde_json::Value;
+use std::convert::TryFrom;
+
+/// Clients not yet migrated send the params as the bytes of the serialized
+/// JSON, e.g. `[123, 125]` for `{}`. Such params are decoded back as long as
+/// the bytes make a JSON object or array, and the others are left as they are.
+pub fn decode_legacy_params(params: Value) -> Value {
+    let bytes: Option<Vec<u8>> = match &params {
+        Value::Array(arr) if !arr.is_empty() => arr
+            .iter()
+            .map(|v| v.as_u64().and_then(|n| u8::try_from(n).ok()))
+            .collect(),
+        _ => None,
+    };
+
+    if let Some(b) = bytes {
+        if let Ok(v) = serde_json::from_slice::<Value>(&b) {
+            if v.is_object() || v.is_array() {
+                return v;
+            }
+        }
+    }
+
+    params
+}
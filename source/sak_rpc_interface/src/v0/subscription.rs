@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+
+/// Method of the notifications a node pushes to its subscribers.
+pub const SUBSCRIPTION_METHOD: &'static str = "subscription";
+
+/// Request without an id, which is not replied to.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct JsonNotification<P: Serialize> {
+    pub jsonrpc: String,
+    pub method: String,
+    pub params: P,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SubscriptionParams<R: Serialize> {
+    pub subscription: String,
+    pub result: R,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SubscribeTxStatusRequest {
+    pub tx_hash: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SubscribeCtrEventsRequest {
+    pub ctr_addr: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UnsubscribeRequest {
+    pub subscription: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct NewBlockEvent {
+    pub block_hash: String,
+    pub block_height: u128,
+    pub tx_hashes: Vec<String>,
+    pub created_at: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PendingTxEvent {
+    pub tx_hash: String,
+    pub ctr_addr: String,
+    pub created_at: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TxStatus {
+    // Neither in the tx pool nor in the ledger
+    Unknown,
+    Pending,
+    Confirmed,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TxStatusEvent {
+    pub tx_hash: String,
+    pub status: TxStatus,
+    // Block of a confirmed tx, not known for the ones confirmed before
+    // subscribing
+    pub block_height: Option<u128>,
+    pub block_hash: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CtrEventKind {
+    Deploy,
+    Call,
+}
+
+/// A tx of a block written that deploys or calls a contract.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CtrEvent {
+    pub ctr_addr: String,
+    pub kind: CtrEventKind,
+    pub tx_hash: String,
+    pub block_height: u128,
+    pub block_hash: String,
+}
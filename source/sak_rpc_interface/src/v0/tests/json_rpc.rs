@@ -0,0 +1,35 @@
+use crate::{decode_legacy_params, JsonRequest, JsonRpcId};
+use serde_json::json;
+
+#[test]
+fn test_request_ids_tell_notifications_apart() {
+    let req: JsonRequest =
+        serde_json::from_str(r#"{"jsonrpc":"2.0","method":"get_status","id":7}"#).unwrap();
+    assert_eq!(req.id, Some(JsonRpcId::from(7)));
+
+    let req: JsonRequest =
+        serde_json::from_str(r#"{"jsonrpc":"2.0","method":"get_status","id":"a"}"#).unwrap();
+    assert_eq!(req.id, Some(JsonRpcId::from("a")));
+
+    let req: JsonRequest =
+        serde_json::from_str(r#"{"jsonrpc":"2.0","method":"get_status","id":null}"#).unwrap();
+    assert_eq!(req.id, Some(JsonRpcId::Null));
+    assert!(!req.is_notification());
+
+    let req: JsonRequest =
+        serde_json::from_str(r#"{"jsonrpc":"2.0","method":"get_status"}"#).unwrap();
+    assert!(req.is_notification());
+}
+
+#[test]
+fn test_legacy_params_are_decoded() {
+    let params = json!({ "tx_hash": "0x1" });
+    let legacy = serde_json::to_value(serde_json::to_vec(&params).unwrap()).unwrap();
+
+    assert_eq!(decode_legacy_params(legacy), params);
+
+    // Positional params are left as they are
+    assert_eq!(decode_legacy_params(json!([1, 2, 3])), json!([1, 2, 3]));
+    assert_eq!(decode_legacy_params(json!(["0x1"])), json!(["0x1"]));
+    assert_eq!(decode_legacy_params(json!(params)), params);
+}
@@ -0,0 +1,20 @@
+/// Prefix of every key a contract stores. The address is length prefixed, as
+/// it may have `_` in it (e.g. "validator_contract_addr"), which would
+/// otherwise let `a` + `b_x` and `a_b` + `x` end up as the same key.
+pub fn ctr_key_prefix(ctr_addr: &str) -> String {
+    format!("{}:{}_", ctr_addr.len(), ctr_addr)
+}
+
+pub fn make_ctr_key(ctr_addr: &str, key: &str) -> String {
+    format!("{}{}", ctr_key_prefix(ctr_addr), key)
+}
+
+// Digits of `u64::MAX`
+const LIST_IDX_WIDTH: usize = 20;
+
+/// Key of the element at `idx` of the list `name`. Indices are zero-padded,
+/// so that the keys sort in the order of the indices and a range of them is
+/// a range of keys. Both the contracts and the host write list keys with it.
+pub fn make_list_key(name: &str, idx: u64) -> String {
+    format!("{}_{:0width$}", name, idx, width = LIST_IDX_WIDTH)
+}
@@ -1,23 +1,61 @@
+use crate::{RangeRequest, RangeResponse};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 pub type MRSAccessor = Box<dyn MRSInterface + Send + Sync>;
 
+pub type LedgerAccessor = Arc<dyn LedgerInterface + Send + Sync>;
+
 pub type StoreInterfaceError = Box<dyn std::error::Error + Send + Sync>;
 
+#[async_trait]
 pub trait MRSInterface {
-    fn get_mrs_data(&self, key: &String) -> Result<Option<String>, StoreInterfaceError>;
+    fn get_mrs_data(&self, key: &String) -> Result<Option<Vec<u8>>, StoreInterfaceError>;
+
+    fn get_mrs_data_range(
+        &self,
+        range: &RangeRequest,
+    ) -> Result<RangeResponse, StoreInterfaceError>;
 
     fn put_mrs_data(&self, key: &String, value: &String) -> Result<(), StoreInterfaceError>;
 
-    // async fn get_session(&self, session_id: String) -> Session;
     fn add_session(&self, session: Session);
+
+    async fn get_session(&self, session_id: &String) -> Option<Session>;
+
+    async fn confirm_session(
+        &self,
+        confirm_args: ConfirmSessionArgs,
+    ) -> Result<(), StoreInterfaceError>;
+
+    async fn run(&self);
+
+    /// Writes what the store holds in memory to disk.
+    fn flush(&self) -> Result<(), StoreInterfaceError>;
+
+    fn get_stats(&self) -> Result<StoreStats, StoreInterfaceError>;
+
+    /// Approximate number of the slots reserved.
+    fn get_slot_count(&self) -> Result<u64, StoreInterfaceError>;
 }
 
-// pub trait LedgerInterface {
-//     fn get_ctr_state(&self) -> Result<Option<Vec<u8>>, StoreInterfaceError>;
-// }
+pub trait LedgerInterface {
+    fn get_ctr_state(&self, key: &String) -> Result<Option<Vec<u8>>, StoreInterfaceError>;
+
+    fn get_ctr_state_range(
+        &self,
+        range: &RangeRequest,
+    ) -> Result<RangeResponse, StoreInterfaceError>;
+}
+
+/// Approximate size of a store and number of the records it holds.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+pub struct StoreStats {
+    pub size_bytes: u64,
+    pub record_count: u64,
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct PreflightResponse {
@@ -25,8 +63,22 @@ pub struct PreflightResponse {
     pub data: Vec<u8>,
 }
 
-#[derive(Serialize, Deserialize)]
+/// Pending MRS receipt produced by a contract execution. It is committed
+/// only once the client confirms it, otherwise it expires.
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Session {
     pub id: String,
+    pub ctr_addr: String,
     pub receipt: HashMap<String, Vec<u8>>,
+    pub created_at: u64,
+    // Only this key can confirm the session
+    pub owner_public_key: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ConfirmSessionArgs {
+    pub session_id: String,
+    pub public_key: String,
+    // DER encoded signature over the session id
+    pub sig: Vec<u8>,
 }
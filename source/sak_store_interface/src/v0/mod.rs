@@ -1,5 +1,9 @@
+mod ctr_key;
 mod interface;
+mod range;
 
+pub use ctr_key::*;
 pub use interface::*;
+pub use range::*;
 
 pub type StoreInterfaceError = Box<dyn std::error::Error + Send + Sync>;
@@ -0,0 +1,96 @@
+use crate::{ctr_key_prefix, make_ctr_key};
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of entries a single range scan returns, regardless of
+/// the limit requested.
+pub const RANGE_LIMIT_MAX: usize = 128;
+
+/// Bounded scan over the keys sharing `prefix`. `start` is inclusive and
+/// `end` is exclusive. `cursor` is the last key of the previous page, so
+/// scanning resumes right after it.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RangeRequest {
+    pub prefix: String,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub cursor: Option<String>,
+    pub limit: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RangeResponse {
+    pub items: Vec<(String, Vec<u8>)>,
+    pub cursor: Option<String>,
+}
+
+/// What a range host function hands back to the contract. A range the host
+/// fails to scan is an error, not an empty response.
+pub type RangeResult = Result<RangeResponse, String>;
+
+impl RangeRequest {
+    /// Scopes the range to the keys of the contract at `ctr_addr`.
+    pub fn with_namespace(&self, ctr_addr: &String) -> RangeRequest {
+        let ns = |k: &String| make_ctr_key(ctr_addr, k);
+
+        RangeRequest {
+            prefix: ns(&self.prefix),
+            start: self.start.as_ref().map(ns),
+            end: self.end.as_ref().map(ns),
+            cursor: self.cursor.as_ref().map(ns),
+            limit: self.limit.min(RANGE_LIMIT_MAX),
+        }
+    }
+
+    /// Key from which a forward iterator should be positioned.
+    pub fn seek_key(&self) -> &String {
+        match (&self.cursor, &self.start) {
+            (Some(c), _) => c,
+            (None, Some(s)) => s,
+            (None, None) => &self.prefix,
+        }
+    }
+
+    /// Returns `true` once `key` falls out of the requested range, after
+    /// which no further key can match.
+    pub fn is_past_end(&self, key: &String) -> bool {
+        if !key.starts_with(&self.prefix) {
+            return true;
+        }
+
+        match &self.end {
+            Some(e) => key >= e,
+            None => false,
+        }
+    }
+
+    pub fn is_before_start(&self, key: &String) -> bool {
+        if let Some(c) = &self.cursor {
+            if key <= c {
+                return true;
+            }
+        }
+
+        if let Some(s) = &self.start {
+            if key < s {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+impl RangeResponse {
+    pub fn strip_namespace(self, ctr_addr: &String) -> RangeResponse {
+        let ns_prefix = ctr_key_prefix(ctr_addr);
+        let strip = |k: String| match k.strip_prefix(&ns_prefix) {
+            Some(s) => s.to_string(),
+            None => k,
+        };
+
+        RangeResponse {
+            items: self.items.into_iter().map(|(k, v)| (strip(k), v)).collect(),
+            cursor: self.cursor.map(strip),
+        }
+    }
+}
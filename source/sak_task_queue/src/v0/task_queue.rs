@@ -48,4 +48,20 @@ where
             .await
             .ok_or(format!("Cannot receive tasks any more. Task queue is closed.",).into())
     }
+
+    /// Closes the queue and takes out the tasks not popped yet. Pushing a
+    /// task fails afterwards, so does popping one once the queue is empty.
+    pub async fn close(&self) -> Vec<T> {
+        let mut rx = self.rx.lock().await;
+
+        rx.close();
+
+        let mut tasks = vec![];
+
+        while let Ok(t) = rx.try_recv() {
+            tasks.push(t);
+        }
+
+        tasks
+    }
 }
@@ -32,6 +32,20 @@ impl Tx {
         }
     }
 
+    pub fn get_ctr_addr(&self) -> &String {
+        match &self {
+            Tx::Mint(t) => &t.tx_candidate.ctr_addr,
+            Tx::Pour(t) => &t.tx_candidate.ctr_addr,
+        }
+    }
+
+    pub fn get_ctr_op(&self) -> TxCtrOp {
+        match &self {
+            Tx::Mint(t) => t.tx_candidate.get_ctr_op(),
+            Tx::Pour(t) => t.tx_candidate.get_ctr_op(),
+        }
+    }
+
     pub fn get_cm_count(&self) -> usize {
         match &self {
             Tx::Mint(t) => t.tx_candidate.cms.len(),
@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+
+pub type Sleep = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// Source of time of a node. Timing dependent routines go through it, so
+/// that a simulator can run them on virtual time.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+
+    fn sleep(&self, duration: Duration) -> Sleep;
+}
+
+/// Wall clock, backed by the tokio timer.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+pub fn system_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
+
+/// Virtual clock. Time stands still until the simulator advances it, and
+/// sleeps are woken in the order of their deadlines, the earlier registered
+/// first on a tie.
+pub struct SimClock {
+    start: Instant,
+    state: Mutex<SimClockState>,
+}
+
+struct SimClockState {
+    elapsed: Duration,
+    next_seq: u64,
+    timers: BTreeMap<(Duration, u64), oneshot::Sender<()>>,
+}
+
+impl SimClock {
+    pub fn new() -> SimClock {
+        SimClock {
+            start: Instant::now(),
+            state: Mutex::new(SimClockState {
+                elapsed: Duration::ZERO,
+                next_seq: 0,
+                timers: BTreeMap::new(),
+            }),
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.lock_state().elapsed
+    }
+
+    /// Moves the time to the earliest pending sleep and wakes it. Returns
+    /// false if nothing sleeps. Sleeps whose futures have been dropped are
+    /// discarded without moving the time.
+    pub fn advance(&self) -> bool {
+        let mut state = self.lock_state();
+
+        loop {
+            let key = match state.timers.keys().next() {
+                Some(k) => *k,
+                None => return false,
+            };
+
+            let wake_tx = match state.timers.remove(&key) {
+                Some(t) => t,
+                None => return false,
+            };
+
+            if wake_tx.is_closed() {
+                continue;
+            }
+
+            state.elapsed = key.0;
+
+            let _ = wake_tx.send(());
+
+            return true;
+        }
+    }
+
+    /// Wakes the sleeps ending within `duration`, in order, and moves the
+    /// time by `duration`.
+    pub fn advance_by(&self, duration: Duration) {
+        let until = self.elapsed() + duration;
+
+        loop {
+            let mut state = self.lock_state();
+
+            let key = match state.timers.keys().next() {
+                Some(k) if k.0 <= until => *k,
+                _ => {
+                    state.elapsed = until;
+                    return;
+                }
+            };
+
+            if let Some(wake_tx) = state.timers.remove(&key) {
+                state.elapsed = key.0;
+
+                let _ = wake_tx.send(());
+            }
+        }
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, SimClockState> {
+        match self.state.lock() {
+            Ok(s) => s,
+            Err(err) => err.into_inner(),
+        }
+    }
+}
+
+impl Default for SimClock {
+    fn default() -> SimClock {
+        SimClock::new()
+    }
+}
+
+impl Clock for SimClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        if duration.is_zero() {
+            return Box::pin(async {});
+        }
+
+        let (wake_tx, wake_rx) = oneshot::channel();
+
+        {
+            let mut state = self.lock_state();
+
+            let deadline = state.elapsed + duration;
+            let seq = state.next_seq;
+
+            state.next_seq += 1;
+            state.timers.insert((deadline, seq), wake_tx);
+        }
+
+        Box::pin(async move {
+            let _ = wake_rx.await;
+        })
+    }
+}
@@ -1,3 +1,7 @@
+mod clock;
+
+pub use clock::*;
+
 use sak_logger::error;
 use std::time::{Duration, SystemTime};
 
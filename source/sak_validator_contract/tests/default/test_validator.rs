@@ -82,7 +82,10 @@ async fn test_call_ctr_validator_fn_init() {
     };
 
     let mrs = {
-        let mrs_args = SakMRSArgs { mrs_db_path };
+        let mrs_args = SakMRSArgs {
+            mrs_db_path,
+            session_ttl: None,
+        };
 
         let m = SakMRS::init(mrs_args).await.unwrap();
         let m = Box::new(m) as MRSAccessor;
@@ -107,7 +110,10 @@ async fn test_call_ctr_validator_fn_init() {
     let mrs = {
         let mrs_db_path = { test_dir.join("mrs") };
 
-        let mrs_args = SakMRSArgs { mrs_db_path };
+        let mrs_args = SakMRSArgs {
+            mrs_db_path,
+            session_ttl: None,
+        };
 
         let m = SakMRS::init(mrs_args).await.unwrap();
     };
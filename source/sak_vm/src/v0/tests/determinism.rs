@@ -0,0 +1,121 @@
+use super::utils::MockStore;
+use crate::v0::wasm::Wasmtime;
+use crate::SakVM;
+use sak_vm_interface::ContractProcess;
+use std::collections::HashMap;
+
+const CTR_EXPORTS: &str = r#"
+    (memory (export "memory") 1)
+    (func (export "CTR__alloc") (param i32) (result i32) i32.const 0)
+    (func (export "CTR__dealloc") (param i32 i32))
+    (func (export "CTR__init") (result i32 i32 i32 i32)
+        i32.const 0 i32.const 0 i32.const 0 i32.const 0)
+    (func (export "CTR__execute") (param i32 i32) (result i32 i32 i32 i32)
+        i32.const 0 i32.const 0 i32.const 0 i32.const 0)
+    (func (export "CTR__update") (param i32 i32) (result i32 i32 i32 i32)
+        i32.const 0 i32.const 0 i32.const 0 i32.const 0)
+"#;
+
+fn make_module(body: &str) -> Vec<u8> {
+    wat::parse_str(format!("(module {} {})", body, CTR_EXPORTS)).unwrap()
+}
+
+#[test]
+fn test_wasm_importing_host_fns_is_valid() {
+    let wasm = make_module(
+        r#"(import "host" "HOST__get_mrs_data" (func (param i32 i32 i32) (result i32)))"#,
+    );
+
+    assert!(SakVM::is_valid_wasm(wasm));
+}
+
+#[test]
+fn test_wasm_importing_wasi_is_rejected() {
+    let wasm = make_module(
+        r#"(import "wasi_snapshot_preview1" "fd_write"
+            (func (param i32 i32 i32 i32) (result i32)))"#,
+    );
+
+    assert!(!SakVM::is_valid_wasm(wasm));
+}
+
+#[test]
+fn test_wasm_importing_unknown_host_fn_is_rejected() {
+    let wasm = make_module(r#"(import "host" "HOST__time" (func (result i64)))"#);
+
+    assert!(!SakVM::is_valid_wasm(wasm));
+}
+
+#[test]
+fn test_wasm_using_simd_is_rejected() {
+    let wasm = make_module(
+        r#"(func (result i32)
+            v128.const i32x4 0 0 0 0
+            f32x4.sqrt
+            i32x4.extract_lane 0)"#,
+    );
+
+    assert!(!SakVM::is_valid_wasm(wasm));
+}
+
+#[test]
+fn test_wasm_without_ctr_fns_is_rejected() {
+    let wasm = wat::parse_str(r#"(module (memory (export "memory") 1))"#).unwrap();
+
+    assert!(!SakVM::is_valid_wasm(wasm));
+}
+
+#[test]
+fn test_wasm_in_text_format_is_rejected() {
+    let wasm = format!("(module {})", CTR_EXPORTS);
+
+    assert!(!SakVM::is_valid_wasm(wasm));
+}
+
+#[test]
+fn test_wasm_using_floats_is_valid() {
+    let wasm = make_module(
+        r#"(func (param i32) (result i32)
+            local.get 0
+            f64.convert_i32_s
+            f64.const 0.5
+            f64.mul
+            i32.trunc_f64_s)"#,
+    );
+
+    assert!(SakVM::is_valid_wasm(&wasm));
+
+    let (mrs, _) = MockStore::make_accessors(HashMap::new());
+    let vm = SakVM::init(mrs).unwrap();
+
+    assert!(vm.validate_wasm(&wasm).is_ok());
+}
+
+// Contracts built on contract_std use floats, e.g. in the number parsing of
+// serde_json
+#[test]
+fn test_genesis_contracts_are_valid() {
+    let genesis_ctrs: [(&str, &[u8]); 3] = [
+        (
+            "validator",
+            include_bytes!("../../../../prebuild/sak_validator_contract.postprocess.wasm"),
+        ),
+        (
+            "envelope",
+            include_bytes!("../../../../prebuild/envelope_contract.postprocess.wasm"),
+        ),
+        (
+            "mrs",
+            include_bytes!("../../../../prebuild/sak_mrs_contract.postprocess.wasm"),
+        ),
+    ];
+
+    for (name, wasm) in genesis_ctrs {
+        if let Err(err) = Wasmtime::validate_wasm(wasm) {
+            panic!(
+                "Genesis contract should be valid, ctr: {}, err: {}",
+                name, err
+            );
+        }
+    }
+}
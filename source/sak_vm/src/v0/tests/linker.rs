@@ -0,0 +1,196 @@
+use super::utils::MockStore;
+use crate::v0::wasm::Wasmtime;
+use sak_store_interface::{make_ctr_key, make_list_key, RangeRequest, RangeResult};
+use std::collections::HashMap;
+
+const CTR_ADDR: &str = "test_ctr_addr";
+
+// Calls `get_ctr_state` with the arg at offset 16 and has the contract
+// allocate every return value at offset 64
+const CALLER_WASM: &str = r#"
+    (module
+        (import "host" "HOST__get_ctr_state" (func $get_ctr_state (param i32 i32 i32) (result i32)))
+        (import "host" "HOST__get_ctr_state_range"
+            (func $get_ctr_state_range (param i32 i32 i32) (result i32)))
+        (memory (export "memory") 1)
+        (data (i32.const 16) "field_key")
+        (func (export "call") (param i32 i32 i32) (result i32)
+            local.get 0 local.get 1 local.get 2
+            call $get_ctr_state)
+        (func (export "call_range") (param i32)
+            (local $i i32)
+            (loop $l
+                i32.const 16 i32.const 9 i32.const 0
+                call $get_ctr_state_range
+                drop
+                local.get $i i32.const 1 i32.add local.tee $i
+                local.get 0 i32.lt_s
+                br_if $l))
+        (func (export "CTR__alloc") (param i32) (result i32) i32.const 64)
+        (func (export "CTR__dealloc") (param i32 i32))
+        (func (export "CTR__init") (result i32 i32 i32 i32)
+            i32.const 0 i32.const 0 i32.const 0 i32.const 0)
+        (func (export "CTR__execute") (param i32 i32) (result i32 i32 i32 i32)
+            i32.const 0 i32.const 0 i32.const 0 i32.const 0)
+        (func (export "CTR__update") (param i32 i32) (result i32 i32 i32 i32)
+            i32.const 0 i32.const 0 i32.const 0 i32.const 0))
+"#;
+
+fn caller_wasm() -> Vec<u8> {
+    wat::parse_str(CALLER_WASM).unwrap()
+}
+
+fn call_get_ctr_state(ptr: i32, len: i32, ptr_ret_len: i32) -> Result<Vec<u8>, String> {
+    let data = HashMap::from([(make_ctr_key(CTR_ADDR, "field_key"), b"power".to_vec())]);
+    let (mrs, ledger) = MockStore::make_accessors(data);
+
+    let (instance, mut store) =
+        Wasmtime::make_instance(caller_wasm(), &CTR_ADDR.to_string(), &mrs, &ledger).unwrap();
+
+    let call = instance
+        .get_typed_func::<(i32, i32, i32), i32, _>(&mut store, "call")
+        .unwrap();
+
+    let ret_ptr = call
+        .call(&mut store, (ptr, len, ptr_ret_len))
+        .map_err(|err| err.to_string())?;
+
+    let memory = instance.get_memory(&mut store, "memory").unwrap();
+    let mem = memory.data(&store);
+
+    let ret_len = {
+        let b = &mem[ptr_ret_len as usize..ptr_ret_len as usize + 4];
+        u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize
+    };
+
+    Ok(mem[ret_ptr as usize..ret_ptr as usize + ret_len].to_vec())
+}
+
+#[test]
+fn test_host_fn_returns_value_into_contract_memory() {
+    let ret = call_get_ctr_state(16, 9, 0).unwrap();
+
+    assert_eq!(ret, b"power");
+}
+
+#[test]
+fn test_host_fn_traps_on_out_of_bounds_pointer() {
+    // Arg running past the end of the memory
+    let err = call_get_ctr_state(65536 - 4, 9, 0).unwrap_err();
+    assert!(err.contains("out of the memory bounds"), "err: {}", err);
+
+    // Arg whose ptr + len overflows
+    assert!(call_get_ctr_state(16, -1, 0).is_err());
+
+    // Return length written past the end of the memory
+    let err = call_get_ctr_state(16, 9, 65536 - 2).unwrap_err();
+    assert!(err.contains("out of the memory bounds"), "err: {}", err);
+}
+
+fn call_range_n_times(n: i32, gas_limit: usize) -> Result<usize, String> {
+    let (mrs, ledger) = MockStore::make_accessors(HashMap::new());
+
+    let (instance, mut store) =
+        Wasmtime::make_instance(caller_wasm(), &CTR_ADDR.to_string(), &mrs, &ledger).unwrap();
+
+    store.data_mut().gas_limit = gas_limit;
+
+    let call = instance
+        .get_typed_func::<i32, (), _>(&mut store, "call_range")
+        .unwrap();
+
+    call.call(&mut store, n).map_err(|err| err.to_string())?;
+
+    Ok(store.data().gas_charged)
+}
+
+#[test]
+fn test_contract_traps_once_out_of_gas() {
+    // An empty range is charged the fee of a call only
+    assert_eq!(call_range_n_times(10, 1_000).unwrap(), 1_000);
+
+    let err = call_range_n_times(11, 1_000).unwrap_err();
+    assert!(err.contains("Out of gas"), "err: {}", err);
+}
+
+// Passes its params through to `put_mrs_data` and `get_mrs_data_range`, and
+// has the contract allocate every return value at offset 8192
+const LIST_WASM: &str = r#"
+    (module
+        (import "host" "HOST__put_mrs_data"
+            (func $put_mrs_data (param i32 i32 i32 i32 i32)))
+        (import "host" "HOST__get_mrs_data_range"
+            (func $get_mrs_data_range (param i32 i32 i32) (result i32)))
+        (memory (export "memory") 1)
+        (func (export "put") (param i32 i32 i32 i32)
+            local.get 0 local.get 1 local.get 2 local.get 3 i32.const 0
+            call $put_mrs_data)
+        (func (export "range") (param i32 i32) (result i32)
+            local.get 0 local.get 1 i32.const 0
+            call $get_mrs_data_range)
+        (func (export "CTR__alloc") (param i32) (result i32) i32.const 8192)
+        (func (export "CTR__dealloc") (param i32 i32))
+        (func (export "CTR__init") (result i32 i32 i32 i32)
+            i32.const 0 i32.const 0 i32.const 0 i32.const 0)
+        (func (export "CTR__execute") (param i32 i32) (result i32 i32 i32 i32)
+            i32.const 0 i32.const 0 i32.const 0 i32.const 0)
+        (func (export "CTR__update") (param i32 i32) (result i32 i32 i32 i32)
+            i32.const 0 i32.const 0 i32.const 0 i32.const 0))
+"#;
+
+#[test]
+fn test_list_elements_put_by_host_are_read_back_by_list_keys() {
+    let (mrs, ledger) = MockStore::make_accessors(HashMap::new());
+    let list_wasm = wat::parse_str(LIST_WASM).unwrap();
+
+    let (instance, mut store) =
+        Wasmtime::make_instance(list_wasm, &CTR_ADDR.to_string(), &mrs, &ledger).unwrap();
+
+    let memory = instance.get_memory(&mut store, "memory").unwrap();
+    let put = instance
+        .get_typed_func::<(i32, i32, i32, i32), (), _>(&mut store, "put")
+        .unwrap();
+    let range = instance
+        .get_typed_func::<(i32, i32), i32, _>(&mut store, "range")
+        .unwrap();
+
+    memory.write(&mut store, 16, b"list").unwrap();
+    for val in ["v0", "v1", "v2"] {
+        memory.write(&mut store, 32, val.as_bytes()).unwrap();
+        put.call(&mut store, (16, 4, 32, 2)).unwrap();
+    }
+
+    // Built the way `List::range(1, 3)` builds it
+    let req = RangeRequest {
+        prefix: "list_".to_string(),
+        start: Some(make_list_key("list", 1)),
+        end: Some(make_list_key("list", 3)),
+        cursor: None,
+        limit: 2,
+    };
+
+    let mut call_range = |req: &[u8]| -> RangeResult {
+        memory.write(&mut store, 1024, req).unwrap();
+        let ret_ptr = range.call(&mut store, (1024, req.len() as i32)).unwrap() as usize;
+
+        let mem = memory.data(&store);
+        let ret_len = u32::from_be_bytes([mem[0], mem[1], mem[2], mem[3]]) as usize;
+
+        serde_json::from_slice(&mem[ret_ptr..ret_ptr + ret_len]).unwrap()
+    };
+
+    let res = call_range(&serde_json::to_vec(&req).unwrap()).unwrap();
+
+    assert_eq!(
+        res.items,
+        vec![
+            (make_list_key("list", 1), b"v1".to_vec()),
+            (make_list_key("list", 2), b"v2".to_vec()),
+        ]
+    );
+
+    // A request the host can't parse comes back as an error, not as an
+    // empty range
+    let err = call_range(b"not a range").unwrap_err();
+    assert!(err.contains("malformed"), "err: {}", err);
+}
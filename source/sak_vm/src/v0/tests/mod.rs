@@ -1,2 +1,5 @@
+mod determinism;
+mod linker;
+mod result;
 mod utils;
 mod vm;
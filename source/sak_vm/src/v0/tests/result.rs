@@ -0,0 +1,50 @@
+use sak_contract_std::{make_result_vec, ContractError, ContractRevert};
+use sak_vm_interface::InvokeReceipt;
+
+#[test]
+fn test_result_envelope_ok_is_unwrapped() {
+    let result = make_result_vec(Ok(vec![1, 2, 3]));
+
+    let receipt = InvokeReceipt::from_execute(result, None).unwrap();
+
+    assert_eq!(receipt.result, vec![1, 2, 3]);
+}
+
+#[test]
+fn test_result_envelope_err_is_downcastable_revert() {
+    let err = ContractError::Custom {
+        code: 1001,
+        kind: "insufficient_slot".to_string(),
+        msg: "No slot is left".to_string(),
+        data: Some(vec![7]),
+    };
+
+    let result = make_result_vec(Err(err));
+
+    let err = match InvokeReceipt::from_execute(result, None) {
+        Ok(_) => panic!("reverted call should not produce a receipt"),
+        Err(err) => err,
+    };
+
+    let revert = err.downcast_ref::<ContractRevert>().unwrap();
+
+    assert_eq!(revert.status, 1001);
+    assert_eq!(revert.kind, "insufficient_slot");
+    assert_eq!(revert.msg, "No slot is left");
+    assert_eq!(revert.data, Some(vec![7]));
+}
+
+#[test]
+fn test_result_envelope_maps_serde_error_to_serialization_kind() {
+    let err: ContractError = serde_json::from_slice::<String>(b"not json")
+        .unwrap_err()
+        .into();
+
+    let result = make_result_vec(Err(err));
+
+    let err = InvokeReceipt::from_execute(result, None).err().unwrap();
+    let revert = err.downcast_ref::<ContractRevert>().unwrap();
+
+    assert_eq!(revert.status, 422);
+    assert_eq!(revert.kind, "serialization");
+}
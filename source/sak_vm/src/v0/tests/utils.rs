@@ -1,4 +1,11 @@
+use async_trait::async_trait;
 use sak_logger::SakLogger;
+use sak_store_interface::{
+    ConfirmSessionArgs, LedgerAccessor, LedgerInterface, MRSAccessor, MRSInterface, RangeRequest,
+    RangeResponse, Session, StoreInterfaceError, StoreStats,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
 
 pub(crate) struct VMTestUtils;
 
@@ -7,3 +14,112 @@ impl VMTestUtils {
         SakLogger::init_test_console().unwrap();
     }
 }
+
+/// In-memory store, standing in for both the MRS and the ledger so that
+/// host functions can be called without a db.
+pub(crate) struct MockStore {
+    pub data: Mutex<BTreeMap<String, Vec<u8>>>,
+}
+
+#[async_trait]
+impl MRSInterface for MockStore {
+    fn get_mrs_data(&self, key: &String) -> Result<Option<Vec<u8>>, StoreInterfaceError> {
+        Ok(self.data.lock().unwrap().get(key).cloned())
+    }
+
+    fn get_mrs_data_range(
+        &self,
+        range: &RangeRequest,
+    ) -> Result<RangeResponse, StoreInterfaceError> {
+        Ok(self.get_range(range))
+    }
+
+    fn put_mrs_data(&self, key: &String, value: &String) -> Result<(), StoreInterfaceError> {
+        self.data
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), value.as_bytes().to_vec());
+
+        Ok(())
+    }
+
+    fn add_session(&self, _session: Session) {}
+
+    async fn get_session(&self, _session_id: &String) -> Option<Session> {
+        None
+    }
+
+    async fn confirm_session(
+        &self,
+        _confirm_args: ConfirmSessionArgs,
+    ) -> Result<(), StoreInterfaceError> {
+        Ok(())
+    }
+
+    async fn run(&self) {}
+
+    fn flush(&self) -> Result<(), StoreInterfaceError> {
+        Ok(())
+    }
+
+    fn get_stats(&self) -> Result<StoreStats, StoreInterfaceError> {
+        Ok(StoreStats::default())
+    }
+
+    fn get_slot_count(&self) -> Result<u64, StoreInterfaceError> {
+        Ok(0)
+    }
+}
+
+impl LedgerInterface for MockStore {
+    fn get_ctr_state(&self, key: &String) -> Result<Option<Vec<u8>>, StoreInterfaceError> {
+        Ok(self.data.lock().unwrap().get(key).cloned())
+    }
+
+    fn get_ctr_state_range(
+        &self,
+        range: &RangeRequest,
+    ) -> Result<RangeResponse, StoreInterfaceError> {
+        Ok(self.get_range(range))
+    }
+}
+
+impl MockStore {
+    pub fn make_accessors(data: HashMap<String, Vec<u8>>) -> (Arc<MRSAccessor>, LedgerAccessor) {
+        let data: BTreeMap<_, _> = data.into_iter().collect();
+
+        let mrs: MRSAccessor = Box::new(MockStore {
+            data: Mutex::new(data.clone()),
+        });
+        let ledger: LedgerAccessor = Arc::new(MockStore {
+            data: Mutex::new(data),
+        });
+
+        (Arc::new(mrs), ledger)
+    }
+
+    // Scans the way the ledger and the MRS scan their column families
+    fn get_range(&self, range: &RangeRequest) -> RangeResponse {
+        let data = self.data.lock().unwrap();
+        let mut res = RangeResponse::default();
+
+        for (key, value) in data.range(range.seek_key().to_string()..) {
+            if range.is_past_end(key) {
+                break;
+            }
+
+            if range.is_before_start(key) {
+                continue;
+            }
+
+            if res.items.len() >= range.limit {
+                res.cursor = res.items.last().map(|(k, _)| k.to_string());
+                break;
+            }
+
+            res.items.push((key.to_string(), value.to_vec()));
+        }
+
+        res
+    }
+}
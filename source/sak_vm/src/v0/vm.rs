@@ -4,13 +4,14 @@ use async_trait::async_trait;
 use sak_contract_std::{symbols, ContractFn, CtrRequest, Storage};
 use sak_crypto::rand;
 use sak_logger::{error, info};
-use sak_store_interface::{MRSAccessor, Session};
+use sak_store_interface::{LedgerAccessor, MRSAccessor, Session};
 use sak_vm_interface::wasmtime::{Instance, Memory, Store, TypedFunc};
 use sak_vm_interface::{
     ContractProcess, CtrExecuteFn, CtrInitFn, InstanceState, InvokeReceipt, VMInterfaceError,
 };
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 pub struct SakVM {
     mrs: Arc<MRSAccessor>,
@@ -19,24 +20,36 @@ pub struct SakVM {
 impl ContractProcess for SakVM {
     fn invoke(
         &self,
-        _ctr_addr: &String,
+        ctr_addr: &String,
         contract_wasm: &[u8],
         ctr_fn: ContractFn,
+        ledger: LedgerAccessor,
     ) -> Result<InvokeReceipt, VMInterfaceError> {
         println!("333");
+        let ctr_fn_label = match &ctr_fn {
+            ContractFn::Init => "init",
+            ContractFn::Execute(_) => "execute",
+            ContractFn::Update(_) => "update",
+        };
+
+        let started_at = Instant::now();
+
         let res = match ctr_fn {
             ContractFn::Init => {
-                let (instance, store, memory) = Self::init_module(contract_wasm, &self.mrs)?;
+                let (instance, store, memory) =
+                    Self::init_module(contract_wasm, ctr_addr, &self.mrs, &ledger)?;
 
                 self.invoke_init(instance, store, memory)
             }
             ContractFn::Execute(request) => {
-                let (instance, store, memory) = Self::init_module(contract_wasm, &self.mrs)?;
+                let (instance, store, memory) =
+                    Self::init_module(contract_wasm, ctr_addr, &self.mrs, &ledger)?;
 
-                self.invoke_execute(instance, store, memory, request)
+                self.invoke_execute(ctr_addr, instance, store, memory, request)
             }
             ContractFn::Update(request) => {
-                let (instance, store, memory) = Self::init_module(contract_wasm, &self.mrs)?;
+                let (instance, store, memory) =
+                    Self::init_module(contract_wasm, ctr_addr, &self.mrs, &ledger)?;
 
                 self.invoke_update(instance, store, memory, request)
             }
@@ -44,8 +57,22 @@ impl ContractProcess for SakVM {
 
         // println!("res: {:?}", res.as_ref().unwrap().result);
 
+        let metrics = &sak_metrics::metrics().vm;
+
+        metrics
+            .invoke_seconds
+            .with_labels(&[ctr_fn_label])
+            .observe_duration(started_at.elapsed());
+
+        if res.is_err() {
+            metrics.invoke_failures.with_labels(&[ctr_fn_label]).inc();
+        }
+
         res
     }
+    fn validate_wasm(&self, contract_wasm: &[u8]) -> Result<(), VMInterfaceError> {
+        Wasmtime::validate_wasm(contract_wasm)
+    }
 }
 
 impl SakVM {
@@ -82,16 +109,17 @@ impl SakVM {
             String::from_utf8_lossy(&receipt_bytes)
         );
 
-        let receipt: HashMap<String, Vec<u8>> = serde_json::from_slice(&receipt_bytes)?;
+        let receipt = parse_receipt(&receipt_bytes)?;
 
-        let invoke_receipt = InvokeReceipt::from_init(Some(receipt))?;
+        let mut invoke_receipt = InvokeReceipt::from_init(result_bytes, Some(receipt))?;
+        invoke_receipt.gas_charged = store.data().gas_charged;
 
         Ok(invoke_receipt)
     }
 
     fn invoke_execute(
         &self,
-        // ctr_addr: &String,
+        ctr_addr: &String,
         instance: Instance,
         mut store: Store<InstanceState>,
         memory: Memory,
@@ -100,6 +128,8 @@ impl SakVM {
         let contract_fn: CtrExecuteFn =
             { instance.get_typed_func(&mut store, symbols::CTR__EXECUTE)? };
 
+        let session_owner = request.session_owner.clone();
+
         let (request_bytes, request_len) = {
             let str = serde_json::to_value(request)?.to_string();
 
@@ -139,20 +169,33 @@ impl SakVM {
             String::from_utf8_lossy(&receipt_bytes)
         );
 
-        let receipt: HashMap<String, Vec<u8>> = serde_json::from_slice(&receipt_bytes)?;
+        let receipt = parse_receipt(&receipt_bytes)?;
 
-        // println!("power11: {:?}", receipt);
-        // let session_id = format!("{}_{}", ctr_addr, rand());
-        // let session = Session {
-        //     id: session_id,
-        //     receipt,
-        // };
+        // A reverted call fails here, before any session is opened
+        let mut invoke_receipt = InvokeReceipt::from_execute(result_bytes, None)?;
+        invoke_receipt.gas_charged = store.data().gas_charged;
 
-        // self.mrs.add_session(session);
+        if !receipt.is_empty() {
+            let owner_public_key = session_owner
+                .ok_or("Execution opens a session, but the request has no session owner")?;
 
-        let receipt = InvokeReceipt::from_execute(result_bytes)?;
+            let created_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+            let session_id = format!("{}_{}_{}", ctr_addr, created_at, rand());
 
-        Ok(receipt)
+            let session = Session {
+                id: session_id.to_string(),
+                ctr_addr: ctr_addr.to_string(),
+                receipt,
+                created_at,
+                owner_public_key,
+            };
+
+            self.mrs.add_session(session);
+
+            invoke_receipt.session_id = Some(session_id);
+        }
+
+        Ok(invoke_receipt)
     }
 
     fn invoke_update(
@@ -198,21 +241,25 @@ impl SakVM {
             result = Wasmtime::read_memory(&store, &memory, result_ptr as u32, result_len as u32)?
         }
 
-        let receipt = InvokeReceipt::from_update(result, storage)?;
+        let mut receipt = InvokeReceipt::from_update(result, storage)?;
+        receipt.gas_charged = store.data().gas_charged;
 
         Ok(receipt)
     }
 
     fn init_module(
         contract_wasm: impl AsRef<[u8]>,
+        ctr_addr: &String,
         mrs: &Arc<MRSAccessor>,
+        ledger: &LedgerAccessor,
     ) -> Result<(Instance, Store<InstanceState>, Memory), VMError> {
-        let (instance, mut store) = match Wasmtime::make_instance(contract_wasm, mrs) {
-            Ok(r) => r,
-            Err(err) => {
-                return Err(format!("Error creating an instance, err: {}", err).into());
-            }
-        };
+        let (instance, mut store) =
+            match Wasmtime::make_instance(contract_wasm, ctr_addr, mrs, ledger) {
+                Ok(r) => r,
+                Err(err) => {
+                    return Err(format!("Error creating an instance, err: {}", err).into());
+                }
+            };
 
         let memory = instance
             .get_memory(&mut store, symbols::MEMORY)
@@ -222,6 +269,17 @@ impl SakVM {
     }
 
     pub fn is_valid_wasm(wasm: impl AsRef<[u8]>) -> bool {
-        Wasmtime::is_valid_wasm(wasm)
+        Wasmtime::validate_wasm(wasm).is_ok()
+    }
+}
+
+// A contract that has reverted returns an empty receipt
+fn parse_receipt(receipt_bytes: &[u8]) -> Result<HashMap<String, Vec<u8>>, VMError> {
+    if receipt_bytes.is_empty() {
+        return Ok(HashMap::new());
     }
+
+    let receipt = serde_json::from_slice(receipt_bytes)?;
+
+    Ok(receipt)
 }
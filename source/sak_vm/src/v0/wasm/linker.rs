@@ -1,15 +1,18 @@
-use crate::{v0::wasm::Wasmtime, VMError};
+use crate::VMError;
 use sak_contract_std::symbols;
 use sak_logger::{error, info};
-use sak_store_interface::{MRSAccessor, PreflightResponse};
-use sak_vm_interface::wasmtime::{
-    Caller, Config, Engine, Instance, Linker, Module, Store, TypedFunc,
+use sak_store_interface::{
+    make_ctr_key, make_list_key, LedgerAccessor, MRSAccessor, RangeRequest, RangeResult,
 };
+use sak_vm_interface::wasmtime::{Caller, Engine, Linker, Memory, Trap, TypedFunc};
 use sak_vm_interface::InstanceState;
 use serde::{Deserialize, Serialize};
-use std::mem::size_of;
 use std::sync::Arc;
 
+const GAS_PER_RANGE_CALL: usize = 100;
+
+const GAS_PER_RANGE_ITEM: usize = 10;
+
 #[derive(Serialize, Deserialize)]
 pub struct Data {
     d: usize,
@@ -18,10 +21,14 @@ pub struct Data {
 pub(crate) fn make_linker(
     engine: Engine,
     mrs: &Arc<MRSAccessor>,
+    ledger: &LedgerAccessor,
 ) -> Result<Linker<InstanceState>, VMError> {
     let mut linker = Linker::new(&engine);
     let mrs_get = mrs.clone();
     let mrs_put = mrs.clone();
+    let ledger_get = ledger.clone();
+    let mrs_range = mrs.clone();
+    let ledger_range = ledger.clone();
 
     linker.func_wrap(
         "host",
@@ -36,194 +43,146 @@ pub(crate) fn make_linker(
     linker.func_wrap(
         "host",
         symbols::HOST__GET_MRS_DATA,
-        move |mut caller: Caller<InstanceState>, ptr_arg: u32, len_arg: u32, ptr_ret_len: u32| {
-            let state = caller.data_mut();
-            println!(
-                "get_mrs_data(): state: {:?}, params: {}, {}",
-                state, ptr_arg, len_arg
-            );
+        move |mut caller: Caller<InstanceState>,
+              ptr_arg: u32,
+              len_arg: u32,
+              ptr_ret_len: u32|
+              -> Result<i32, Trap> {
+            let ctr_addr = caller.data().ctr_addr.to_string();
 
-            let maybe_memory = caller.get_export(symbols::MEMORY).unwrap();
-            let memory = maybe_memory.into_memory().unwrap();
+            let memory = get_memory(&mut caller)?;
 
-            let maybe_arg = memory
-                .data(&caller)
-                .get(ptr_arg as usize..)
-                .and_then(|arr| arr.get(..len_arg as usize));
+            let arg = read_string_arg(&caller, &memory, ptr_arg, len_arg)?;
+
+            // arg == {field}_{key}
+            let key: String = make_ctr_key(&ctr_addr, &arg);
+
+            let data_bytes = match mrs_get.get_mrs_data(&key) {
+                Ok(d) => d.unwrap_or(vec![]),
+                Err(err) => {
+                    error!("Error getting mrs data, key: {}, err: {}", key, err);
 
-            let arg = {
-                let maybe_arg = maybe_arg.ok_or("arg should be given").unwrap();
-                String::from_utf8(maybe_arg.to_vec()).expect("arg should be parsable string")
+                    vec![]
+                }
             };
 
-            // arg == {field}_{key}
-            let key: String = format!("{}_{}", "ctr_address", arg);
+            info!(
+                "get_mrs_data(): key: {}, data len: {}",
+                key,
+                data_bytes.len()
+            );
 
-            println!("test key: {:?}", key);
+            write_return_value(&mut caller, &memory, data_bytes, ptr_ret_len)
+        },
+    )?;
+
+    linker.func_wrap(
+        "host",
+        symbols::HOST__GET_CTR_STATE,
+        move |mut caller: Caller<InstanceState>,
+              ptr_arg: u32,
+              len_arg: u32,
+              ptr_ret_len: u32|
+              -> Result<i32, Trap> {
+            let ctr_addr = caller.data().ctr_addr.to_string();
 
-            // MRS init
-            // let mrs = mrs_get.clone();
-            let a = mrs_get
-                .get_mrs_data(&key)
-                .unwrap_or(Some("Fail".to_string()));
-            println!("real mrs data!!: {:?}", a);
+            let memory = get_memory(&mut caller)?;
 
-            println!("get_mrs_data(): arg: {}", arg);
+            let arg = read_string_arg(&caller, &memory, ptr_arg, len_arg)?;
 
-            //----------------------------------------------
-            // dummy_data should be replaced by `a`
-            let dummy_data = PreflightResponse {
-                request_id: 10000,
-                data: vec![1, 2, 3],
-            };
-            //----------------------------------------------
+            // arg == {field}_{key}
+            let key: String = make_ctr_key(&ctr_addr, &arg);
 
-            let data_bytes = match serde_json::to_vec(&dummy_data) {
-                Ok(b) => b,
+            let data_bytes = match ledger_get.get_ctr_state(&key) {
+                Ok(d) => d.unwrap_or(vec![]),
                 Err(err) => {
-                    error!("Error serializing mrs data, err: {}", err);
+                    error!("Error getting ctr state, key: {}, err: {}", key, err);
 
                     vec![]
                 }
             };
 
-            let data_len = data_bytes.len() as u32;
-            let data_len_bytes = data_len.to_be_bytes();
-            let data_len_ptr = data_len_bytes.as_ptr();
+            info!(
+                "get_ctr_state(): key: {}, data len: {}",
+                key,
+                data_bytes.len()
+            );
+
+            write_return_value(&mut caller, &memory, data_bytes, ptr_ret_len)
+        },
+    )?;
+
+    linker.func_wrap(
+        "host",
+        symbols::HOST__GET_MRS_DATA_RANGE,
+        move |mut caller: Caller<InstanceState>,
+              ptr_arg: u32,
+              len_arg: u32,
+              ptr_ret_len: u32|
+              -> Result<i32, Trap> {
+            let ctr_addr = caller.data().ctr_addr.to_string();
+
+            let memory = get_memory(&mut caller)?;
 
-            unsafe {
-                let raw = memory.data_ptr(&caller).offset(ptr_ret_len as isize);
-                raw.copy_from(data_len_ptr, size_of::<u32>());
-            }
+            let res: RangeResult =
+                read_range_arg(&caller, &memory, ptr_arg, len_arg)?.and_then(|range| {
+                    let range = range.with_namespace(&ctr_addr);
 
-            println!(
-                "get_mrs_data(): data: {:?}, len: {}, getting memoy allocation",
-                &String::from_utf8(data_bytes.clone()),
-                &data_bytes.len(),
-            );
+                    mrs_range
+                        .get_mrs_data_range(&range)
+                        .map(|r| r.strip_namespace(&ctr_addr))
+                        .map_err(|err| {
+                            error!(
+                                "Error getting mrs data range, prefix: {}, err: {}",
+                                range.prefix, err
+                            );
 
-            let alloc = caller
-                .get_export(symbols::CTR__ALLOC)
-                .unwrap()
-                .into_func()
-                .unwrap();
-            let alloc: TypedFunc<i32, i32> = alloc.typed(&caller).unwrap();
+                            err.to_string()
+                        })
+                });
 
-            let ptr_offset = alloc.call(&mut caller, data_bytes.len() as i32).unwrap() as isize;
+            charge_range_gas(&mut caller, &res)?;
 
-            unsafe {
-                let raw = memory.data_ptr(&caller).offset(ptr_offset);
-                raw.copy_from(data_bytes.as_ptr(), data_len as usize);
-            }
+            let data_bytes = serde_json::to_vec(&res).unwrap_or(vec![]);
 
-            ptr_offset as i32
+            write_return_value(&mut caller, &memory, data_bytes, ptr_ret_len)
         },
     )?;
 
     linker.func_wrap(
         "host",
-        symbols::HOST__GET_CTR_STATE,
-        move |mut caller: Caller<InstanceState>, ptr_arg: u32, len_arg: u32, ptr_ret_len: u32| {
-            let state = caller.data_mut();
-            println!(
-                "get_ctr_state(): state: {:?}, params: {}, {}",
-                state, ptr_arg, len_arg
-            );
-
-            let maybe_memory = caller.get_export(symbols::MEMORY).unwrap();
-            let memory = maybe_memory.into_memory().unwrap();
-
-            let maybe_arg = memory
-                .data(&caller)
-                .get(ptr_arg as usize..)
-                .and_then(|arr| arr.get(..len_arg as usize));
-
-            let arg = {
-                let maybe_arg = maybe_arg.ok_or("arg should be given").unwrap();
-                String::from_utf8(maybe_arg.to_vec()).expect("arg should be parsable string")
-            };
+        symbols::HOST__GET_CTR_STATE_RANGE,
+        move |mut caller: Caller<InstanceState>,
+              ptr_arg: u32,
+              len_arg: u32,
+              ptr_ret_len: u32|
+              -> Result<i32, Trap> {
+            let ctr_addr = caller.data().ctr_addr.to_string();
 
-            // arg == {field}_{key}
-            let key: String = format!("{}_{}", "validator_contract_addr", arg);
-
-            println!("test key: {:?}", key);
-
-            // ctr_state init
-            // let ctr_state = ctr_state_get
-            //     .get_ctr_state_data(&key)
-            //     .unwrap_or(Some("Fail".to_string()));
-            // println!("real ctr_state data!!: {:?}", a);
-
-            println!("get_ctr_state_data(): arg: {}", arg);
-
-            //----------------------------------------------
-            // TODO dummy_data should be replaced by `ctr_state`
-            // let dummy_data = PreflightResponse {
-            //     request_id: 10000,
-            //     data: vec![
-            //         48, 52, 53, 55, 51, 57, 100, 48, 55, 52, 98, 56, 55, 50, 50, 56, 57, 49, 99,
-            //         51, 48, 55, 101, 56, 101, 55, 53, 99, 57, 54, 48, 55, 101, 48, 98, 53, 53, 97,
-            //         56, 48, 55, 55, 56, 98, 52, 50, 101, 102, 53, 102, 52, 54, 52, 48, 100, 52, 57,
-            //         52, 57, 100, 98, 102, 51, 57, 57, 50, 102, 54, 48, 56, 51, 98, 55, 50, 57, 98,
-            //         97, 101, 102, 57, 101, 57, 53, 52, 53, 99, 52, 101, 57, 53, 53, 57, 48, 54, 49,
-            //         54, 102, 100, 51, 56, 50, 54, 54, 50, 97, 48, 57, 54, 53, 51, 102, 50, 97, 57,
-            //         54, 54, 102, 102, 53, 50, 52, 57, 56, 57, 97, 101, 56, 99, 48, 102,
-            //     ],
-            // };
-            //----------------------------------------------
-
-            // let data_bytes = match serde_json::to_vec(&dummy_data) {
-            //     Ok(b) => b,
-            //     Err(err) => {
-            //         error!("Error serializing ctr_state data, err: {}", err);
-
-            //         vec![]
-            //     }
-            // };
-
-            let data_bytes = vec![
-                48, 52, 53, 55, 51, 57, 100, 48, 55, 52, 98, 56, 55, 50, 50, 56, 57, 49, 99, 51,
-                48, 55, 101, 56, 101, 55, 53, 99, 57, 54, 48, 55, 101, 48, 98, 53, 53, 97, 56, 48,
-                55, 55, 56, 98, 52, 50, 101, 102, 53, 102, 52, 54, 52, 48, 100, 52, 57, 52, 57,
-                100, 98, 102, 51, 57, 57, 50, 102, 54, 48, 56, 51, 98, 55, 50, 57, 98, 97, 101,
-                102, 57, 101, 57, 53, 52, 53, 99, 52, 101, 57, 53, 53, 57, 48, 54, 49, 54, 102,
-                100, 51, 56, 50, 54, 54, 50, 97, 48, 57, 54, 53, 51, 102, 50, 97, 57, 54, 54, 102,
-                102, 53, 50, 52, 57, 56, 57, 97, 101, 56, 99, 48, 102,
-            ];
-
-            let data_len = data_bytes.len() as u32;
-            let data_len_bytes = data_len.to_be_bytes();
-            let data_len_ptr = data_len_bytes.as_ptr();
-
-            unsafe {
-                let raw = memory.data_ptr(&caller).offset(ptr_ret_len as isize);
-                raw.copy_from(data_len_ptr, size_of::<u32>());
-            }
-
-            println!(
-                "get_ctr_state_data(): data: {:?}, len: {}, getting memoy allocation",
-                &String::from_utf8(data_bytes.clone()),
-                &data_bytes.len(),
-            );
+            let memory = get_memory(&mut caller)?;
 
-            let alloc = caller
-                .get_export(symbols::CTR__ALLOC)
-                .unwrap()
-                .into_func()
-                .unwrap();
+            let res: RangeResult =
+                read_range_arg(&caller, &memory, ptr_arg, len_arg)?.and_then(|range| {
+                    let range = range.with_namespace(&ctr_addr);
 
-            let alloc: TypedFunc<i32, i32> = alloc.typed(&caller).unwrap();
+                    ledger_range
+                        .get_ctr_state_range(&range)
+                        .map(|r| r.strip_namespace(&ctr_addr))
+                        .map_err(|err| {
+                            error!(
+                                "Error getting ctr state range, prefix: {}, err: {}",
+                                range.prefix, err
+                            );
 
-            let ptr_offset = alloc.call(&mut caller, data_bytes.len() as i32).unwrap() as isize;
+                            err.to_string()
+                        })
+                });
 
-            unsafe {
-                let raw = memory.data_ptr(&caller).offset(ptr_offset);
-                raw.copy_from(data_bytes.as_ptr(), data_len as usize);
-            }
+            charge_range_gas(&mut caller, &res)?;
 
-            println!("[! aaron] ptr_offset: {:?}", ptr_offset);
+            let data_bytes = serde_json::to_vec(&res).unwrap_or(vec![]);
 
-            ptr_offset as i32
+            write_return_value(&mut caller, &memory, data_bytes, ptr_ret_len)
         },
     )?;
 
@@ -235,55 +194,144 @@ pub(crate) fn make_linker(
               arg_len: u32,
               arg2_ptr: u32,
               arg2_len: u32,
-              ptr_ret_len: u32| {
-            let state = caller.data_mut();
-            println!(
-                "put_mrs_data(): state: {:?}, params: {}, {}, {}, {}",
-                state, arg_ptr, arg_len, arg2_ptr, arg2_len,
-            );
+              ptr_ret_len: u32|
+              -> Result<(), Trap> {
+            let ctr_addr = caller.data().ctr_addr.to_string();
 
-            let k_maybe_memory = caller.get_export(symbols::MEMORY).unwrap();
-            let k_memory = k_maybe_memory.into_memory().unwrap();
-            let k_maybe_arg = k_memory
-                .data(&caller)
-                .get(arg_ptr as usize..)
-                .and_then(|arr| arr.get(..arg_len as usize));
-            let key_arg = {
-                let k_maybe_arg = k_maybe_arg.ok_or("arg should be given").unwrap();
-                String::from_utf8(k_maybe_arg.to_vec()).expect("arg should be parsable string")
-            };
+            let memory = get_memory(&mut caller)?;
 
-            // let mrs = mrs_put.clone();
-            let latest_idx_key = String::from("latest_idx");
-            let cur_idx = mrs_put
-                .get_mrs_data(&latest_idx_key)
-                .unwrap_or(Some("0".to_string()))
-                .unwrap();
-            let latest_idx = (cur_idx.parse::<i32>().unwrap() + 1).to_string();
-
-            let ctr_address = "ctr_address";
-            let key: String = format!("{}_{}_{}", ctr_address, key_arg, cur_idx);
-
-            //
-
-            let v_maybe_memory = caller.get_export(symbols::MEMORY).unwrap();
-            let v_memory = v_maybe_memory.into_memory().unwrap();
-            let v_maybe_arg = v_memory
-                .data(&caller)
-                .get(arg2_ptr as usize..)
-                .and_then(|arr| arr.get(..arg2_len as usize));
-            let value = {
-                let v_maybe_arg = v_maybe_arg.ok_or("arg should be given").unwrap();
-                String::from_utf8(v_maybe_arg.to_vec()).expect("arg should be parsable string")
+            let key_arg = read_string_arg(&caller, &memory, arg_ptr, arg_len)?;
+
+            let latest_idx_key = make_ctr_key(&ctr_addr, "latest_idx");
+            let cur_idx = match mrs_put.get_mrs_data(&latest_idx_key) {
+                Ok(Some(i)) => String::from_utf8(i)
+                    .ok()
+                    .and_then(|i| i.parse::<u64>().ok())
+                    .unwrap_or(0),
+                _ => 0,
             };
+            let latest_idx = (cur_idx + 1).to_string();
+
+            // The key_arg is the name of a list, whose elements are read back
+            // with the keys `List` makes
+            let key: String = make_ctr_key(&ctr_addr, &make_list_key(&key_arg, cur_idx));
 
-            println!("put_mrs_data(), key: {:?}", key);
-            println!("put_mrs_data(), value: {:?}", value);
+            let value = read_string_arg(&caller, &memory, arg2_ptr, arg2_len)?;
 
-            mrs_put.put_mrs_data(&key, &value).unwrap();
-            mrs_put.put_mrs_data(&latest_idx_key, &latest_idx).unwrap();
+            info!("put_mrs_data(), key: {:?}", key);
+
+            mrs_put.put_mrs_data(&key, &value)?;
+            mrs_put.put_mrs_data(&latest_idx_key, &latest_idx)?;
+
+            Ok(())
         },
     )?;
 
     Ok(linker)
 }
+
+fn get_memory(caller: &mut Caller<InstanceState>) -> Result<Memory, Trap> {
+    caller
+        .get_export(symbols::MEMORY)
+        .and_then(|m| m.into_memory())
+        .ok_or_else(|| Trap::new("Contract should export memory"))
+}
+
+/// Reads a utf8 string off the guest memory. Both `ptr` and `len` are given
+/// by the contract, so they are bounds checked.
+fn read_string_arg(
+    caller: &Caller<InstanceState>,
+    memory: &Memory,
+    ptr: u32,
+    len: u32,
+) -> Result<String, Trap> {
+    let arg = memory
+        .data(caller)
+        .get(ptr as usize..)
+        .and_then(|arr| arr.get(..len as usize))
+        .ok_or_else(|| {
+            Trap::new(format!(
+                "Arg is out of the memory bounds, ptr: {}, len: {}",
+                ptr, len
+            ))
+        })?;
+
+    String::from_utf8(arg.to_vec())
+        .map_err(|err| Trap::new(format!("Arg should be utf8 string, err: {}", err)))
+}
+
+/// A request that does not parse is handed back to the contract as an error
+/// of its range call, while an arg out of the memory bounds traps.
+fn read_range_arg(
+    caller: &Caller<InstanceState>,
+    memory: &Memory,
+    ptr: u32,
+    len: u32,
+) -> Result<Result<RangeRequest, String>, Trap> {
+    let arg = read_string_arg(caller, memory, ptr, len)?;
+
+    let range = serde_json::from_str(&arg).map_err(|err| {
+        error!("Error parsing range request, err: {}", err);
+
+        format!("Range request is malformed, err: {}", err)
+    });
+
+    Ok(range)
+}
+
+fn charge_range_gas(caller: &mut Caller<InstanceState>, res: &RangeResult) -> Result<(), Trap> {
+    let item_count = res.as_ref().map(|r| r.items.len()).unwrap_or(0);
+
+    let state = caller.data_mut();
+
+    state.gas_charged += GAS_PER_RANGE_CALL + GAS_PER_RANGE_ITEM * item_count;
+
+    if state.gas_charged > state.gas_limit {
+        return Err(Trap::new(format!(
+            "Out of gas, charged: {}, limit: {}",
+            state.gas_charged, state.gas_limit
+        )));
+    }
+
+    Ok(())
+}
+
+/// Writes the length of `data_bytes` at `ptr_ret_len` and copies the data
+/// into a block allocated by the contract, returning the block offset.
+fn write_return_value(
+    caller: &mut Caller<InstanceState>,
+    memory: &Memory,
+    data_bytes: Vec<u8>,
+    ptr_ret_len: u32,
+) -> Result<i32, Trap> {
+    let data_len = data_bytes.len() as u32;
+
+    memory
+        .write(&mut *caller, ptr_ret_len as usize, &data_len.to_be_bytes())
+        .map_err(|err| {
+            Trap::new(format!(
+                "Return length is out of the memory bounds, ptr: {}, err: {}",
+                ptr_ret_len, err
+            ))
+        })?;
+
+    let alloc: TypedFunc<i32, i32> = caller
+        .get_export(symbols::CTR__ALLOC)
+        .and_then(|f| f.into_func())
+        .ok_or_else(|| Trap::new("Contract should export alloc function"))?
+        .typed(&*caller)
+        .map_err(|err| Trap::new(format!("Alloc function has a wrong type, err: {}", err)))?;
+
+    let ptr_offset = alloc.call(&mut *caller, data_len as i32)?;
+
+    memory
+        .write(&mut *caller, ptr_offset as u32 as usize, &data_bytes)
+        .map_err(|err| {
+            Trap::new(format!(
+                "Allocated block is out of the memory bounds, ptr: {}, err: {}",
+                ptr_offset, err
+            ))
+        })?;
+
+    Ok(ptr_offset)
+}
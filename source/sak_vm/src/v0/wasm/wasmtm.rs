@@ -1,43 +1,57 @@
 use super::linker::make_linker;
 use crate::VMError;
 use sak_contract_std::symbols;
-use sak_logger::{error, info};
-use sak_store_interface::MRSAccessor;
-use sak_vm_interface::wasmtime::{
-    Caller, Config, Engine, Instance, Linker, Module, Store, TypedFunc,
-};
+use sak_logger::info;
+use sak_store_interface::{LedgerAccessor, MRSAccessor};
+use sak_vm_interface::wasmtime::{Config, Engine, ExternType, Instance, Module, Store, ValType};
 use sak_vm_interface::InstanceState;
-use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 pub(crate) struct Wasmtime {}
 
+/// Every import of a contract should come from this module. Anything else,
+/// e.g. WASI, lets a contract observe the environment of the node.
+const HOST_MODULE: &str = "host";
+
+const HOST_FNS: [&str; 6] = [
+    symbols::HOST__LOG,
+    symbols::HOST__GET_MRS_DATA,
+    symbols::HOST__GET_CTR_STATE,
+    symbols::HOST__GET_MRS_DATA_RANGE,
+    symbols::HOST__GET_CTR_STATE_RANGE,
+    symbols::HOST__PUT_MRS_DATA,
+];
+
+/// Most gas a single invocation of a contract may be charged.
+pub(crate) const GAS_LIMIT: usize = 1_000_000;
+
+const CTR_FNS: [&str; 5] = [
+    symbols::CTR__ALLOC,
+    symbols::CTR__DEALLOC,
+    symbols::CTR__INIT,
+    symbols::CTR__EXECUTE,
+    symbols::CTR__UPDATE,
+];
+
 impl Wasmtime {
     pub(crate) fn make_instance(
         wasm: impl AsRef<[u8]>,
+        ctr_addr: &String,
         mrs: &Arc<MRSAccessor>,
+        ledger: &LedgerAccessor,
     ) -> Result<(Instance, Store<InstanceState>), VMError> {
-        let engine = Engine::new(Config::new().wasm_multi_value(true).debug_info(true))?;
+        let engine = Self::make_engine()?;
 
-        let instance_state = InstanceState {};
+        let instance_state = InstanceState {
+            ctr_addr: ctr_addr.to_string(),
+            gas_charged: 0,
+            gas_limit: GAS_LIMIT,
+        };
         let mut store = Store::new(&engine, instance_state);
 
-        let module = match Module::new(&engine, &wasm) {
-            Ok(m) => {
-                {
-                    for i in m.imports() {
-                        info!("imported: {}", i.name());
-                    }
-                }
+        let module = Self::make_module(&engine, &wasm)?;
 
-                m
-            }
-            Err(err) => {
-                return Err(format!("Error creating a module, err: {}", err).into());
-            }
-        };
-
-        let linker = make_linker(engine, mrs)?;
+        let linker = make_linker(engine, mrs, ledger)?;
 
         let instance = match linker.instantiate(&mut store, &module) {
             Ok(i) => i,
@@ -47,62 +61,94 @@ impl Wasmtime {
         return Ok((instance, store));
     }
 
-    pub fn is_valid_wasm(wasm: impl AsRef<[u8]>) -> bool {
-        let engine = Engine::new(Config::new().wasm_multi_value(true).debug_info(true)).unwrap();
+    /// Checks at deploy time that the contract can only be executed
+    /// deterministically, so that validator nodes never diverge.
+    pub(crate) fn validate_wasm(wasm: impl AsRef<[u8]>) -> Result<(), VMError> {
+        let engine = Self::make_engine()?;
 
-        let mut store = Store::new(&engine, 3);
+        let module = Self::make_module(&engine, &wasm)?;
 
-        let module = match Module::new(&engine, &wasm) {
-            Ok(m) => {
-                {
-                    for i in m.imports() {
-                        println!("imported: {}", i.name());
-                    }
+        for ctr_fn in CTR_FNS {
+            match module.get_export(ctr_fn) {
+                Some(ExternType::Func(_)) => {}
+                _ => {
+                    return Err(format!("Contract should export function, {}", ctr_fn).into());
                 }
-
-                m
-            }
-            Err(_err) => {
-                return false;
             }
-        };
+        }
 
-        let linker = Linker::new(&engine);
+        Ok(())
+    }
 
-        let instance = match linker.instantiate(&mut store, &module) {
-            Ok(i) => i,
+    // Threads and SIMD are rejected by the engine when the module gets
+    // compiled. Scalar floats are kept (serde_json in contract_std pulls them
+    // in) but every NaN they produce is canonicalized.
+    fn make_engine() -> Result<Engine, VMError> {
+        let mut config = Config::new();
+
+        config
+            .wasm_multi_value(true)
+            .wasm_simd(false)
+            .wasm_threads(false)
+            .cranelift_nan_canonicalization(true)
+            .debug_info(false);
+
+        let engine = Engine::new(&config)?;
+
+        Ok(engine)
+    }
+
+    // A contract comes on chain as binary wasm, never in the text format
+    fn make_module(engine: &Engine, wasm: impl AsRef<[u8]>) -> Result<Module, VMError> {
+        let module = match Module::from_binary(engine, wasm.as_ref()) {
+            Ok(m) => m,
             Err(err) => {
-                panic!("Error creating an instance, err: {}", err);
+                return Err(format!("Error creating a module, err: {}", err).into());
             }
         };
 
-        let _init: TypedFunc<(), (i32, i32)> = {
-            match instance.get_typed_func(&mut store, symbols::CTR__INIT) {
-                Ok(o) => o,
-                Err(err) => {
-                    return false;
-                }
+        for i in module.imports() {
+            info!("imported: {}::{}", i.module(), i.name());
+
+            if i.module() != HOST_MODULE {
+                return Err(format!(
+                    "Contract may only import from '{}', module: {}, name: {}",
+                    HOST_MODULE,
+                    i.module(),
+                    i.name()
+                )
+                .into());
             }
-        };
 
-        let _execute: TypedFunc<(i32, i32), (i32, i32, i32)> = {
-            match instance.get_typed_func(&mut store, symbols::CTR__EXECUTE) {
-                Ok(o) => o,
-                Err(err) => {
-                    return false;
-                }
+            if !HOST_FNS.contains(&i.name()) {
+                return Err(format!("Unknown host function, name: {}", i.name()).into());
             }
-        };
 
-        let _update: TypedFunc<(i32, i32), (i32, i32, i32, i32)> = {
-            match instance.get_typed_func(&mut store, symbols::CTR__UPDATE) {
-                Ok(o) => o,
-                Err(err) => {
-                    return false;
+            match i.ty() {
+                ExternType::Func(f) => {
+                    if f.params().chain(f.results()).any(|t| is_float(&t)) {
+                        return Err(format!(
+                            "Host function may not take or return floats, name: {}",
+                            i.name()
+                        )
+                        .into());
+                    }
                 }
-            }
-        };
+                _ => {
+                    return Err(
+                        format!("Contract may only import functions, name: {}", i.name()).into(),
+                    );
+                }
+            };
+        }
+
+        Ok(module)
+    }
+}
 
-        true
+fn is_float(t: &ValType) -> bool {
+    match t {
+        ValType::F32 | ValType::F64 => true,
+        _ => false,
     }
 }
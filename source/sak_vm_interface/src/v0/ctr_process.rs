@@ -1,6 +1,7 @@
 use crate::{InstanceState, InvokeReceipt, VMInterfaceError};
 use async_trait::async_trait;
 use sak_contract_std::{ContractFn, CtrRequest, Storage};
+use sak_store_interface::LedgerAccessor;
 
 pub type ContractProcessor = Box<dyn ContractProcess + Send + Sync>;
 
@@ -18,5 +19,9 @@ pub trait ContractProcess {
         ctr_addr: &String,
         contract_wasm: &[u8],
         ctr_fn: ContractFn,
+        ledger: LedgerAccessor,
     ) -> Result<InvokeReceipt, VMInterfaceError>;
+
+    /// Rejects a contract whose execution may not be deterministic.
+    fn validate_wasm(&self, contract_wasm: &[u8]) -> Result<(), VMInterfaceError>;
 }
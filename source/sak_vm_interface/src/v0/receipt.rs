@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use crate::VMInterfaceError;
-use sak_contract_std::{InvokeResult, Storage, ERROR_PLACEHOLDER};
+use sak_contract_std::{parse_result_vec, InvokeResult, Storage};
 
 #[derive(Debug)]
 pub enum FnType {
@@ -16,24 +16,32 @@ pub struct InvokeReceipt {
     pub result: InvokeResult,
     pub updated_ctr_state: Option<HashMap<String, Vec<u8>>>,
     pub updated_mrs: Option<HashMap<String, Vec<u8>>>,
+    pub session_id: Option<String>,
 }
 
 impl InvokeReceipt {
     pub fn from_init(
+        result: InvokeResult,
         updated_ctr_state: Option<HashMap<String, Vec<u8>>>,
     ) -> Result<InvokeReceipt, VMInterfaceError> {
+        let res = try_parse_invoked(result)?;
+
         let receipt = InvokeReceipt {
             gas_charged: 0,
             fn_type: FnType::Init,
-            result: vec![],
+            result: res,
             updated_ctr_state,
             updated_mrs: Some(HashMap::new()),
+            session_id: None,
         };
 
         Ok(receipt)
     }
 
-    pub fn from_execute(result: InvokeResult) -> Result<InvokeReceipt, VMInterfaceError> {
+    pub fn from_execute(
+        result: InvokeResult,
+        session_id: Option<String>,
+    ) -> Result<InvokeReceipt, VMInterfaceError> {
         let res = try_parse_invoked(result)?;
 
         let receipt = InvokeReceipt {
@@ -42,6 +50,7 @@ impl InvokeReceipt {
             result: res,
             updated_ctr_state: Some(HashMap::new()),
             updated_mrs: Some(HashMap::new()),
+            session_id,
         };
 
         Ok(receipt)
@@ -59,20 +68,17 @@ impl InvokeReceipt {
             result: res,
             updated_ctr_state: Some(HashMap::new()),
             updated_mrs: Some(HashMap::new()),
+            session_id: None,
         };
 
         Ok(receipt)
     }
 }
 
+/// Unwraps the result envelope returned by the contract. A contract error
+/// is returned as a `ContractRevert`, which callers can downcast to.
 fn try_parse_invoked(invoked: InvokeResult) -> Result<InvokeResult, VMInterfaceError> {
-    if invoked.len() > 6 {
-        if &invoked[..6] == &ERROR_PLACEHOLDER {
-            let err_msg: &str = std::str::from_utf8(&invoked[6..])?;
-
-            return Err(err_msg.into());
-        }
-    }
+    let res = parse_result_vec(&invoked)?;
 
-    Ok(invoked)
+    Ok(res)
 }
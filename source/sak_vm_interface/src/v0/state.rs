@@ -1,2 +1,7 @@
 #[derive(Debug)]
-pub struct InstanceState {}
+pub struct InstanceState {
+    pub ctr_addr: String,
+    pub gas_charged: usize,
+    // A call charged more than this traps
+    pub gas_limit: usize,
+}
@@ -25,6 +25,15 @@ pub(super) fn create_app<'a>() -> Command<'a> {
                     e.g. 21452",
                 ),
         )
+        .arg(
+            Arg::new("metrics-path") //
+                .long("metrics-path")
+                .takes_value(true)
+                .long_help(
+                    "HTTP path of the RPC server at which the metrics are \n\
+                    served in the Prometheus text format, e.g. /metrics",
+                ),
+        )
         .arg(
             Arg::new("disc-port") //
                 .long("disc-port")
@@ -134,6 +143,15 @@ pub(super) fn create_app<'a>() -> Command<'a> {
                     e.g. 50",
                 ),
         )
+        .arg(
+            Arg::new("p2p-outbound-capacity") //
+                .long("p2p-outbound-capacity")
+                .takes_value(true)
+                .long_help(
+                    "Number of peer table slots for the peers we dial, the \n\
+                    rest goes to inbound and reserved peers e.g. 10",
+                ),
+        )
         .arg(
             Arg::new("p2p-max-conn-count") //
                 .long("p2p-max-conn-count")
@@ -167,6 +185,50 @@ pub(super) fn create_app<'a>() -> Command<'a> {
                     short url: 127.0.0.1:3030",
                 ),
         )
+        .arg(
+            Arg::new("external-addr") //
+                .long("external-addr")
+                .takes_value(true)
+                .long_help(
+                    "External ip and disc port of the node, overriding the \n\
+                    ones detected through the gateway or peers. The p2p port \n\
+                    is assumed to be forwarded as it is unless mapped \n\
+                    e.g. 203.0.113.7:35518",
+                ),
+        )
+        .arg(
+            Arg::new("port-mapping") //
+                .long("port-mapping")
+                .takes_value(false)
+                .long_help("Map the disc and p2p ports at the gateway with UPnP or NAT-PMP"),
+        )
+        .arg(
+            Arg::new("full-block-relay") //
+                .long("full-block-relay")
+                .takes_value(false)
+                .long_help(
+                    "Send blocks to the peers along with all of their txs, \n\
+                    instead of compact blocks",
+                ),
+        )
+        .arg(
+            Arg::new("light") //
+                .long("light")
+                .takes_value(false)
+                .long_help(
+                    "Run as a light node, which keeps the block headers only \n\
+                    and asks the full peers for the proofs",
+                ),
+        )
+        .arg(
+            Arg::new("network-id") //
+                .long("network-id")
+                .takes_value(true)
+                .long_help(
+                    "Id of the network to join, e.g. saksaha_dev. Nodes of \n\
+                    different networks refuse each other",
+                ),
+        )
         .arg(
             Arg::new("miner") //
                 .long("miner")
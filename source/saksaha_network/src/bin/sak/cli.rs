@@ -1,4 +1,5 @@
 use super::app;
+use std::net::SocketAddr;
 
 #[derive(Debug)]
 pub(crate) struct CLIArgs {
@@ -10,10 +11,12 @@ pub(crate) struct CLIArgs {
     pub(crate) p2p_task_interval: Option<u16>,
     pub(crate) p2p_task_queue_capacity: Option<u16>,
     pub(crate) p2p_peer_table_capacity: Option<i16>,
+    pub(crate) p2p_outbound_capacity: Option<i16>,
     pub(crate) p2p_max_conn_count: Option<u16>,
     pub(crate) p2p_dial_interval: Option<u16>,
     pub(crate) public_key: Option<String>,
     pub(crate) rpc_port: Option<u16>,
+    pub(crate) metrics_path: Option<String>,
     pub(crate) p2p_port: Option<u16>,
     pub(crate) addr_expire_duration: Option<u64>,
     pub(crate) addr_monitor_interval: Option<u64>,
@@ -22,9 +25,14 @@ pub(crate) struct CLIArgs {
     pub(crate) mine_interval: Option<u64>,
     pub(crate) node_task_min_interval: Option<u64>,
     pub(crate) peer_register_interval: Option<u64>,
+    pub(crate) full_block_relay: Option<bool>,
+    pub(crate) light: Option<bool>,
+    pub(crate) network_id: Option<String>,
     pub(crate) tx_sync_interval: Option<u64>,
     pub(crate) block_sync_interval: Option<u64>,
     pub(crate) bootstrap_urls: Option<Vec<String>>,
+    pub(crate) external_addr: Option<SocketAddr>,
+    pub(crate) port_mapping: Option<bool>,
 }
 
 pub(crate) fn get_args() -> Result<CLIArgs, String> {
@@ -58,6 +66,17 @@ pub(crate) fn get_args() -> Result<CLIArgs, String> {
         None => None,
     };
 
+    let metrics_path = match matches.value_of("metrics-path") {
+        Some(p) if p.starts_with('/') => Some(String::from(p)),
+        Some(p) => {
+            return Err(format!(
+                "Metrics path has to start with '/', metrics_path: {}",
+                p
+            ));
+        }
+        None => None,
+    };
+
     let cfg_profile = match matches.value_of("cfg-profile") {
         Some(m) => Some(String::from(m)),
         None => None,
@@ -68,6 +87,42 @@ pub(crate) fn get_args() -> Result<CLIArgs, String> {
         None => None,
     };
 
+    let external_addr = match matches.value_of("external-addr") {
+        Some(a) => match a.parse::<SocketAddr>() {
+            Ok(addr) => Some(addr),
+            Err(err) => {
+                return Err(format!(
+                    "Cannot parse the external addr (ip:port), err: {}",
+                    err
+                ));
+            }
+        },
+        None => None,
+    };
+
+    let port_mapping = if matches.is_present("port-mapping") {
+        Some(true)
+    } else {
+        None
+    };
+
+    let full_block_relay = if matches.is_present("full-block-relay") {
+        Some(true)
+    } else {
+        None
+    };
+
+    let light = if matches.is_present("light") {
+        Some(true)
+    } else {
+        None
+    };
+
+    let network_id = match matches.value_of("network-id") {
+        Some(n) => Some(String::from(n)),
+        None => None,
+    };
+
     let disc_dial_interval = match matches.value_of("disc-dial-interval") {
         Some(i) => match i.parse::<u16>() {
             Ok(interval) => Some(interval),
@@ -160,6 +215,19 @@ pub(crate) fn get_args() -> Result<CLIArgs, String> {
         None => None,
     };
 
+    let p2p_outbound_capacity = match matches.value_of("p2p-outbound-capacity") {
+        Some(i) => match i.parse::<i16>() {
+            Ok(capacity) => Some(capacity),
+            Err(err) => {
+                return Err(format!(
+                    "Cannot parse p2p outbound capacity (i16), err: {}",
+                    err,
+                ))
+            }
+        },
+        None => None,
+    };
+
     let p2p_max_conn_count = match matches.value_of("p2p-max-conn-count") {
         Some(i) => match i.parse::<u16>() {
             Ok(interval) => Some(interval),
@@ -291,18 +359,25 @@ pub(crate) fn get_args() -> Result<CLIArgs, String> {
         p2p_task_interval,
         p2p_task_queue_capacity,
         p2p_peer_table_capacity,
+        p2p_outbound_capacity,
         p2p_max_conn_count,
         p2p_dial_interval,
         rpc_port,
+        metrics_path,
         p2p_port,
         addr_expire_duration,
         addr_monitor_interval,
         cfg_profile,
         bootstrap_urls,
+        external_addr,
+        port_mapping,
         miner,
         mine_interval,
         node_task_min_interval,
         peer_register_interval,
+        full_block_relay,
+        light,
+        network_id,
         tx_sync_interval,
         block_sync_interval,
         public_key,
@@ -30,18 +30,25 @@ fn main() {
         p2p_task_interval: cli_args.p2p_task_interval,
         p2p_task_queue_capacity: cli_args.p2p_task_queue_capacity,
         p2p_peer_table_capacity: cli_args.p2p_peer_table_capacity,
+        p2p_outbound_capacity: cli_args.p2p_outbound_capacity,
         p2p_max_conn_count: cli_args.p2p_max_conn_count,
         p2p_dial_interval: cli_args.p2p_dial_interval,
         p2p_port: cli_args.p2p_port,
         rpc_port: cli_args.rpc_port,
+        metrics_path: cli_args.metrics_path,
         addr_expire_duration: cli_args.addr_expire_duration,
         addr_monitor_interval: cli_args.addr_monitor_interval,
         bootstrap_urls: cli_args.bootstrap_urls,
+        external_addr: cli_args.external_addr,
+        port_mapping: cli_args.port_mapping,
         cfg_profile: cli_args.cfg_profile,
         miner: cli_args.miner,
         mine_interval: cli_args.mine_interval,
         node_task_min_interval: cli_args.node_task_min_interval,
         peer_register_interval: cli_args.peer_register_interval,
+        full_block_relay: cli_args.full_block_relay,
+        light: cli_args.light,
+        network_id: cli_args.network_id,
         tx_sync_interval: cli_args.tx_sync_interval,
         block_sync_interval: cli_args.block_sync_interval,
         public_key: cli_args.public_key,
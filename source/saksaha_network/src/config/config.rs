@@ -3,6 +3,7 @@ use crate::{system::SystemRunArgs, PConfig, PersistedP2PConfig, SaksahaError};
 use sak_crypto::SakKey;
 use sak_logger::{info, warn};
 use sak_p2p_addr::UnknownAddr;
+use std::net::SocketAddr;
 
 #[derive(Debug)]
 pub(crate) struct Config {
@@ -25,10 +26,13 @@ pub(crate) struct P2PConfig {
     pub(crate) p2p_dial_interval: Option<u16>,
     pub(crate) p2p_max_conn_count: Option<u16>,
     pub(crate) p2p_peer_table_capacity: Option<i16>,
+    pub(crate) p2p_outbound_capacity: Option<i16>,
     pub(crate) p2p_port: Option<u16>,
     pub(crate) addr_expire_duration: Option<u64>,
     pub(crate) addr_monitor_interval: Option<u64>,
     pub(crate) bootstrap_addrs: Vec<UnknownAddr>,
+    pub(crate) external_addr: Option<SocketAddr>,
+    pub(crate) port_mapping: Option<bool>,
     pub(crate) secret: String,
     pub(crate) public_key_str: String,
 }
@@ -36,6 +40,7 @@ pub(crate) struct P2PConfig {
 #[derive(Debug)]
 pub(crate) struct RPCConfig {
     pub(crate) rpc_port: Option<u16>,
+    pub(crate) metrics_path: Option<String>,
 }
 
 #[derive(Debug)]
@@ -44,6 +49,9 @@ pub(crate) struct NodeConfig {
     pub(crate) mine_interval: Option<u64>,
     pub(crate) node_task_min_interval: Option<u64>,
     pub(crate) peer_register_interval: Option<u64>,
+    pub(crate) full_block_relay: Option<bool>,
+    pub(crate) light: Option<bool>,
+    pub(crate) network_id: Option<String>,
 }
 
 #[derive(Debug)]
@@ -102,9 +110,13 @@ impl Config {
                 mine_interval: sys_run_args.mine_interval,
                 node_task_min_interval: sys_run_args.node_task_min_interval,
                 peer_register_interval: sys_run_args.peer_register_interval,
+                full_block_relay: sys_run_args.full_block_relay,
+                light: sys_run_args.light,
+                network_id: sys_run_args.network_id.clone(),
             },
             rpc: RPCConfig {
                 rpc_port: sys_run_args.rpc_port,
+                metrics_path: sys_run_args.metrics_path.clone(),
             },
             p2p: P2PConfig {
                 disc_port: sys_run_args.disc_port,
@@ -117,10 +129,13 @@ impl Config {
                 p2p_dial_interval: sys_run_args.p2p_dial_interval,
                 p2p_max_conn_count: sys_run_args.p2p_max_conn_count,
                 p2p_peer_table_capacity: sys_run_args.p2p_peer_table_capacity,
+                p2p_outbound_capacity: sys_run_args.p2p_outbound_capacity,
                 p2p_port: sys_run_args.p2p_port,
                 addr_expire_duration: sys_run_args.addr_expire_duration,
                 addr_monitor_interval: sys_run_args.addr_monitor_interval,
                 bootstrap_addrs,
+                external_addr: sys_run_args.external_addr,
+                port_mapping: sys_run_args.port_mapping,
                 secret: pconfig.p2p.secret,
                 public_key_str: pconfig.p2p.public_key,
             },
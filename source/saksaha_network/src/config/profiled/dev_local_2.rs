@@ -23,6 +23,7 @@ pub(crate) fn config(sys_run_args: &SystemRunArgs) -> Config {
             p2p_dial_interval: sys_run_args.p2p_dial_interval,
             p2p_max_conn_count: sys_run_args.p2p_max_conn_count,
             p2p_peer_table_capacity: sys_run_args.p2p_peer_table_capacity,
+            p2p_outbound_capacity: sys_run_args.p2p_outbound_capacity,
             p2p_port: sys_run_args.p2p_port,
             addr_expire_duration: sys_run_args.addr_expire_duration,
             addr_monitor_interval: sys_run_args.addr_monitor_interval,
@@ -30,6 +31,8 @@ pub(crate) fn config(sys_run_args: &SystemRunArgs) -> Config {
             disc_port: None,
             secret: dev_local_2_credential.secret,
             public_key_str: dev_local_2_credential.public_key_str,
+            external_addr: sys_run_args.external_addr,
+            port_mapping: sys_run_args.port_mapping,
             bootstrap_addrs: vec![UnknownAddr {
                 ip: String::from("127.0.0.1"),
                 disc_port: 35518,
@@ -44,9 +47,16 @@ pub(crate) fn config(sys_run_args: &SystemRunArgs) -> Config {
             mine_interval: sys_run_args.mine_interval,
             node_task_min_interval: sys_run_args.node_task_min_interval,
             peer_register_interval: sys_run_args.peer_register_interval,
+            full_block_relay: sys_run_args.full_block_relay,
+            light: sys_run_args.light,
+            network_id: sys_run_args
+                .network_id
+                .clone()
+                .or(Some("saksaha_dev".to_string())),
         },
         rpc: RPCConfig {
             rpc_port: Some(34419),
+            metrics_path: sys_run_args.metrics_path.clone(),
         },
     };
 }
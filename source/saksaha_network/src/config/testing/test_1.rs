@@ -22,6 +22,7 @@ pub(crate) fn mock_config_1() -> Config {
             p2p_dial_interval: None,
             p2p_max_conn_count: None,
             p2p_peer_table_capacity: None,
+            p2p_outbound_capacity: None,
             p2p_port: Some(35519),
             addr_expire_duration: None,
             addr_monitor_interval: None,
@@ -29,6 +30,8 @@ pub(crate) fn mock_config_1() -> Config {
             disc_port: Some(35518),
             secret: credential.secret,
             public_key_str: credential.public_key_str,
+            external_addr: None,
+            port_mapping: None,
             bootstrap_addrs: vec![],
         },
         node: NodeConfig {
@@ -36,9 +39,13 @@ pub(crate) fn mock_config_1() -> Config {
             mine_interval: None,
             node_task_min_interval: None,
             peer_register_interval: None,
+            full_block_relay: None,
+            light: None,
+            network_id: Some("saksaha_test".to_string()),
         },
         rpc: RPCConfig {
             rpc_port: Some(34418),
+            metrics_path: None,
         },
     };
 }
@@ -23,6 +23,7 @@ pub(crate) fn mock_config_2() -> Config {
             p2p_dial_interval: None,
             p2p_max_conn_count: None,
             p2p_peer_table_capacity: None,
+            p2p_outbound_capacity: None,
             p2p_port: Some(35521),
             addr_expire_duration: None,
             addr_monitor_interval: None,
@@ -30,6 +31,8 @@ pub(crate) fn mock_config_2() -> Config {
             disc_port: Some(35520),
             secret: credential_2.secret,
             public_key_str: credential_2.public_key_str,
+            external_addr: None,
+            port_mapping: None,
             bootstrap_addrs: vec![UnknownAddr {
                 ip: String::from("127.0.0.1"),
                 disc_port: 35518,
@@ -44,9 +47,13 @@ pub(crate) fn mock_config_2() -> Config {
             mine_interval: None,
             node_task_min_interval: None,
             peer_register_interval: None,
+            full_block_relay: None,
+            light: None,
+            network_id: Some("saksaha_test".to_string()),
         },
         rpc: RPCConfig {
             rpc_port: Some(34419),
+            metrics_path: None,
         },
     };
 }
@@ -24,6 +24,7 @@ impl Consensus for Pos {
             req_type: "get_validator".to_string(),
             args: vec![],
             ctr_call_type: CtrCallType::Query,
+            session_owner: None,
         };
 
         let validator = match dist_ledger
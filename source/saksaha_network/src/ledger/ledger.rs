@@ -1,13 +1,12 @@
 use super::{consensus::Pos, genesis::GenesisBlock};
-use crate::{
-    fs::{self, SaksahaFS},
-    SaksahaError,
-};
+use crate::SaksahaError;
 use sak_ledger::{Consensus, SakLedger, SakLedgerArgs};
 use sak_machine::{SakMachine, SakMachineArgs};
 use sak_p2p_id::Identity;
 use sak_proof::CoinProof;
+use sak_utils_time::Clock;
 use sak_vm_interface::ContractProcessor;
+use std::path::Path;
 use std::sync::Arc;
 
 pub(crate) struct Ledger {
@@ -16,12 +15,13 @@ pub(crate) struct Ledger {
 
 impl Ledger {
     pub(crate) async fn init(
-        public_key: &String,
+        acc_dir: &Path,
         tx_sync_interval: Option<u64>,
         genesis_block: Option<GenesisBlock>,
         block_sync_interval: Option<u64>,
         identity: Arc<Identity>,
         contract_processor: ContractProcessor,
+        clock: Option<Arc<dyn Clock>>,
     ) -> Result<SakLedger, SaksahaError> {
         let (gen_block_candidate, consensus) = {
             let genesis_block = match genesis_block {
@@ -43,10 +43,7 @@ impl Ledger {
             (genesis_block.block_candidate, consensus)
         };
 
-        let ledger_path = {
-            let acc_dir = SaksahaFS::acc_dir(public_key)?;
-            acc_dir.join("ledger")
-        };
+        let ledger_path = acc_dir.join("ledger");
 
         let dist_ledger_args = SakLedgerArgs {
             tx_sync_interval,
@@ -55,6 +52,7 @@ impl Ledger {
             block_sync_interval,
             ledger_path,
             contract_processor,
+            clock,
         };
 
         let sak_ledger = SakLedger::init(dist_ledger_args).await?;
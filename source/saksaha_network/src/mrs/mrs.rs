@@ -1,16 +1,17 @@
-use crate::{fs::SaksahaFS, SaksahaError};
+use crate::SaksahaError;
 use sak_mrs::{SakMRS, SakMRSArgs};
+use std::path::Path;
 
 pub(crate) struct MRS {}
 
 impl MRS {
-    pub(crate) async fn init(public_key: &String) -> Result<SakMRS, SaksahaError> {
-        let mrs_db_path = {
-            let acc_dir = SaksahaFS::acc_dir(public_key)?;
-            acc_dir.join("mrs")
-        };
+    pub(crate) async fn init(acc_dir: &Path) -> Result<SakMRS, SaksahaError> {
+        let mrs_db_path = acc_dir.join("mrs");
 
-        let mrs_args = SakMRSArgs { mrs_db_path };
+        let mrs_args = SakMRSArgs {
+            mrs_db_path,
+            session_ttl: None,
+        };
 
         let sak_ledger = SakMRS::init(mrs_args).await?;
 
@@ -0,0 +1,11 @@
+/// How a node sends the blocks her peers ask for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum BlockRelay {
+    /// Blocks along with all of their txs.
+    Full,
+
+    /// Block headers and tx hashes. The peer rebuilds the blocks out of her
+    /// tx pool and asks for the txs she misses. Peers not capable of it are
+    /// sent full blocks.
+    Compact,
+}
@@ -1,15 +1,12 @@
-use crate::node::{event_handle, task::NodeTask};
+use crate::node::gossip::Gossip;
 use sak_ledger::DistLedgerEvent;
-use sak_logger::{debug, error, warn};
-use sak_machine::SakMachine;
-use sak_task_queue::TaskQueue;
+use sak_logger::error;
 use std::sync::Arc;
 use tokio::sync::broadcast::Receiver;
 
 pub(in crate::node) struct LedgerEventRoutine {
     pub ledger_event_rx: Receiver<DistLedgerEvent>,
-    pub machine: Arc<SakMachine>,
-    pub node_task_queue: Arc<TaskQueue<NodeTask>>,
+    pub gossip: Arc<Gossip>,
 }
 
 impl LedgerEventRoutine {
@@ -24,26 +21,14 @@ impl LedgerEventRoutine {
                 }
             };
 
-            let event_handle_res = match ev {
+            match ev {
                 DistLedgerEvent::TxPoolStat(new_tx_hashes) => {
-                    self.node_task_queue
-                        .push_back(NodeTask::SendTxHashSyn {
-                            tx_hashes: new_tx_hashes,
-                        })
-                        .await
+                    self.gossip.announce_txs(new_tx_hashes).await;
                 }
                 DistLedgerEvent::NewBlocks(new_blocks) => {
-                    self.node_task_queue
-                        .push_back(NodeTask::SendBlockHashSyn {
-                            new_blocks: new_blocks.clone(),
-                        })
-                        .await
+                    self.gossip.announce_blocks(new_blocks).await;
                 }
             };
-
-            if let Err(err) = event_handle_res {
-                warn!("Error handling ledger event, err: {}", err);
-            }
         }
     }
 }
@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// What happens to the msgs a peer sends to this node. Faults are only set by
+/// the test net for now.
+#[cfg_attr(not(test), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum LinkFault {
+    Drop,
+    Delay(Duration),
+}
+
+/// Faults injected into the links to the peers, keyed by their public keys.
+/// Tests use it to drop or delay msgs and to partition the network. When no
+/// fault is set, it costs a lookup per inbound msg.
+pub(crate) struct FaultInjector {
+    faults: RwLock<HashMap<String, LinkFault>>,
+}
+
+impl FaultInjector {
+    pub fn new() -> FaultInjector {
+        FaultInjector {
+            faults: RwLock::new(HashMap::new()),
+        }
+    }
+
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub async fn set_fault(&self, public_key: &str, fault: LinkFault) {
+        let mut faults = self.faults.write().await;
+
+        faults.insert(public_key.to_string(), fault);
+    }
+
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub async fn clear_all(&self) {
+        let mut faults = self.faults.write().await;
+
+        faults.clear();
+    }
+
+    pub async fn get_fault(&self, public_key: &str) -> Option<LinkFault> {
+        let faults = self.faults.read().await;
+
+        faults.get(public_key).copied()
+    }
+}
@@ -0,0 +1,210 @@
+use super::task::NodeTask;
+use sak_crypto::RandomSource;
+use sak_logger::{debug, warn};
+use sak_p2p_peertable::Peer;
+use sak_task_queue::TaskQueue;
+use sak_types::{BlockHash, BlockHeight, TxHash};
+use sak_utils_time::Clock;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
+use tokio::time::Instant;
+
+// Announcements always reach at least this many peers, if connected
+pub(in crate::node) const MIN_GOSSIP_FANOUT: usize = 4;
+
+// A hash requested from a peer is not requested from another one until then
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct GossipPeer {
+    peer: Arc<Peer>,
+    node_task_queue: Arc<TaskQueue<NodeTask>>,
+}
+
+/// Forwards tx and block announcements to a random subset of the peers not
+/// known to have them, and drops the inbound announcements of hashes that
+/// are already being requested from another peer.
+pub(in crate::node) struct Gossip {
+    // Ordered, so that peers are picked the same way for the same rng
+    peers: RwLock<BTreeMap<String, GossipPeer>>,
+    requested: Mutex<HashMap<String, Instant>>,
+    clock: Arc<dyn Clock>,
+    rng: Arc<dyn RandomSource>,
+}
+
+impl Gossip {
+    pub fn new(clock: Arc<dyn Clock>, rng: Arc<dyn RandomSource>) -> Gossip {
+        Gossip {
+            peers: RwLock::new(BTreeMap::new()),
+            requested: Mutex::new(HashMap::new()),
+            clock,
+            rng,
+        }
+    }
+
+    pub async fn register(&self, peer: Arc<Peer>, node_task_queue: Arc<TaskQueue<NodeTask>>) {
+        let public_key = peer.get_public_key().to_string();
+
+        let mut peers = self.peers.write().await;
+
+        peers.insert(
+            public_key,
+            GossipPeer {
+                peer,
+                node_task_queue,
+            },
+        );
+    }
+
+    /// Removes the peer, unless she has reconnected and been registered
+    /// again in the meantime.
+    pub async fn unregister(&self, peer: &Arc<Peer>) {
+        let mut peers = self.peers.write().await;
+
+        let is_same_peer = match peers.get(peer.get_public_key()) {
+            Some(gossip_peer) => Arc::ptr_eq(&gossip_peer.peer, peer),
+            None => false,
+        };
+
+        if is_same_peer {
+            peers.remove(peer.get_public_key());
+        }
+    }
+
+    pub async fn announce_txs(&self, tx_hashes: Vec<TxHash>) {
+        let targets = self.pick_targets(&tx_hashes).await;
+
+        for (peer, node_task_queue, tx_hashes) in targets {
+            peer.mark_known(&tx_hashes).await;
+
+            if let Err(err) = node_task_queue
+                .push_back(NodeTask::SendTxHashSyn { tx_hashes })
+                .await
+            {
+                warn!("Could not announce txs, err: {}", err);
+            }
+        }
+    }
+
+    pub async fn announce_blocks(&self, new_blocks: Vec<(BlockHeight, BlockHash)>) {
+        let block_hashes: Vec<BlockHash> = new_blocks.iter().map(|(_, h)| h.clone()).collect();
+
+        let targets = self.pick_targets(&block_hashes).await;
+
+        for (peer, node_task_queue, block_hashes) in targets {
+            peer.mark_known(&block_hashes).await;
+
+            let new_blocks = new_blocks
+                .iter()
+                .filter(|(_, h)| block_hashes.contains(h))
+                .cloned()
+                .collect();
+
+            if let Err(err) = node_task_queue
+                .push_back(NodeTask::SendBlockHashSyn { new_blocks })
+                .await
+            {
+                warn!("Could not announce blocks, err: {}", err);
+            }
+        }
+    }
+
+    /// Registered peers having the capability, in a random order.
+    pub async fn get_peers_with_capability(
+        &self,
+        capability: u64,
+    ) -> Vec<(Arc<Peer>, Arc<TaskQueue<NodeTask>>)> {
+        let peers = self.peers.read().await;
+
+        let mut ret = vec![];
+
+        for gossip_peer in peers.values() {
+            if gossip_peer.peer.has_capability(capability).await {
+                ret.push((
+                    gossip_peer.peer.clone(),
+                    gossip_peer.node_task_queue.clone(),
+                ));
+            }
+        }
+
+        let len = ret.len();
+        shuffle_front(self.rng.as_ref(), &mut ret, len);
+
+        ret
+    }
+
+    /// Hashes out of an inbound announcement that are not being requested
+    /// from another peer yet. They are considered requested from now on.
+    pub async fn take_unrequested(&self, hashes: Vec<String>) -> Vec<String> {
+        let now = self.clock.now();
+
+        let mut requested = self.requested.lock().await;
+
+        requested.retain(|_, at| now.duration_since(*at) < REQUEST_TIMEOUT);
+
+        hashes
+            .into_iter()
+            .filter(|h| match requested.contains_key(h) {
+                true => false,
+                false => {
+                    requested.insert(h.clone(), now);
+
+                    true
+                }
+            })
+            .collect()
+    }
+
+    // Peers to announce to, with the hashes each of them is not known to have
+    async fn pick_targets(
+        &self,
+        hashes: &[String],
+    ) -> Vec<(Arc<Peer>, Arc<TaskQueue<NodeTask>>, Vec<String>)> {
+        let peers = self.peers.read().await;
+
+        let mut candidates = vec![];
+
+        for gossip_peer in peers.values() {
+            let unknown = gossip_peer.peer.filter_unknown(hashes).await;
+
+            if !unknown.is_empty() {
+                candidates.push((
+                    gossip_peer.peer.clone(),
+                    gossip_peer.node_task_queue.clone(),
+                    unknown,
+                ));
+            }
+        }
+
+        let fanout = fanout(peers.len()).min(candidates.len());
+
+        shuffle_front(self.rng.as_ref(), &mut candidates, fanout);
+        candidates.truncate(fanout);
+
+        debug!(
+            "Gossiping to {} of {} peers, hash count: {}",
+            candidates.len(),
+            peers.len(),
+            hashes.len(),
+        );
+
+        candidates
+    }
+}
+
+/// Number of peers an announcement is forwarded to, out of `peer_count`.
+pub(in crate::node) fn fanout(peer_count: usize) -> usize {
+    let sqrt = (peer_count as f64).sqrt().ceil() as usize;
+
+    sqrt.max(MIN_GOSSIP_FANOUT).min(peer_count)
+}
+
+// Moves `n` randomly chosen elements to the front (partial Fisher-Yates)
+pub(in crate::node) fn shuffle_front<T>(rng: &dyn RandomSource, v: &mut [T], n: usize) {
+    for i in 0..n.min(v.len()) {
+        let j = i + (rng.next_u64() % (v.len() - i) as u64) as usize;
+
+        v.swap(i, j);
+    }
+}
@@ -0,0 +1,52 @@
+use sak_p2p_transport::PingMsg;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// A peer that has missed this many pongs in a row is dropped.
+pub(in crate::node) const MAX_MISSED_PONGS: usize = 3;
+
+/// Keeps track of the ping sent to a peer and of the pongs it has missed.
+pub(in crate::node) struct Heartbeat {
+    pending_ping: Option<(u128, Instant)>,
+    missed_pongs: usize,
+}
+
+impl Heartbeat {
+    pub fn new() -> Heartbeat {
+        Heartbeat {
+            pending_ping: None,
+            missed_pongs: 0,
+        }
+    }
+
+    /// Makes the next ping. The previous ping, if still unanswered, counts
+    /// as a missed pong.
+    pub fn make_ping(&mut self, now: Instant) -> PingMsg {
+        if self.pending_ping.is_some() {
+            self.missed_pongs += 1;
+        }
+
+        let nonce = sak_crypto::rand() as u128;
+
+        self.pending_ping = Some((nonce, now));
+
+        PingMsg { nonce }
+    }
+
+    /// Returns the round trip time if the pong answers the pending ping.
+    pub fn recv_pong(&mut self, pong: &PingMsg, now: Instant) -> Option<Duration> {
+        match self.pending_ping {
+            Some((nonce, sent_at)) if nonce == pong.nonce => {
+                self.pending_ping = None;
+                self.missed_pongs = 0;
+
+                Some(now.saturating_duration_since(sent_at))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.missed_pongs >= MAX_MISSED_PONGS
+    }
+}
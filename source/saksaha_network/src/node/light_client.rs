@@ -0,0 +1,470 @@
+use super::gossip::Gossip;
+use super::task::NodeTask;
+use super::SaksahaNodeError;
+use sak_logger::{debug, warn};
+use sak_machine::SakMachine;
+use sak_p2p_peertable::{Misbehavior, Peer};
+use sak_p2p_transport::{
+    AuthPath, AuthPathMsg, CmIdxMsg, CtrQueryMsg, CAP_LIGHT_SERVE, MAX_HEADERS_PER_MSG,
+};
+use sak_task_queue::TaskQueue;
+use sak_types::{Block, BlockHeight, Cm, CmIdx};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{oneshot, Mutex};
+
+// Time a full peer is given to answer a request
+const LIGHT_REQ_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Full peers asked before a request is given up
+const MAX_LIGHT_REQ_ATTEMPTS: usize = 3;
+
+// Full peers which must agree on a contract query result. Contract states
+// are not committed in the block headers, so it cannot be proved.
+const CTR_QUERY_QUORUM: usize = 2;
+
+// Full peers which must have sent the same header before it is written. A
+// light node cannot check the signatures of a header, so it is trusted once
+// the peers agree on it.
+const HEADER_QUORUM: usize = 2;
+
+// Time given to the headers asked for while verifying an auth path
+const HEADER_WAIT: Duration = Duration::from_millis(500);
+
+pub(in crate::node) const HEADER_SYNC_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Answer of a full peer to a light node request.
+pub(in crate::node) enum LightRes {
+    AuthPath(AuthPathMsg),
+    CmIdx(CmIdxMsg),
+    CtrQuery(CtrQueryMsg),
+}
+
+impl LightRes {
+    pub fn get_req_id(&self) -> u64 {
+        match self {
+            LightRes::AuthPath(m) => m.req_id,
+            LightRes::CmIdx(m) => m.req_id,
+            LightRes::CtrQuery(m) => m.req_id,
+        }
+    }
+}
+
+// Sender of the answer, along with the peer who has been asked
+type PendingReq = (String, oneshot::Sender<LightRes>);
+
+/// Light node side of the p2p. The node keeps the block headers only, and
+/// asks the full peers for the merkle auth paths and contract query results,
+/// verifying them against the headers.
+pub(crate) struct LightClient {
+    machine: Arc<SakMachine>,
+    gossip: Arc<Gossip>,
+    pending: Mutex<HashMap<u64, PendingReq>>,
+    next_req_id: AtomicU64,
+    header_votes: Mutex<HeaderVotes>,
+}
+
+impl LightClient {
+    pub(in crate::node) fn new(machine: Arc<SakMachine>, gossip: Arc<Gossip>) -> LightClient {
+        LightClient {
+            machine,
+            gossip,
+            pending: Mutex::new(HashMap::new()),
+            next_req_id: AtomicU64::new(0),
+            header_votes: Mutex::new(HeaderVotes::default()),
+        }
+    }
+
+    /// Auth path of the cm, verified against the merkle root of a header.
+    pub(crate) async fn get_auth_path(
+        &self,
+        cm_idx: CmIdx,
+    ) -> Result<Vec<(Cm, bool)>, SaksahaNodeError> {
+        let auth_path = self
+            .get_verified_auth_path(cm_idx)
+            .await?
+            .ok_or(format!("Cm idx is not in the tree, cm_idx: {}", cm_idx))?;
+
+        Ok(auth_path.path)
+    }
+
+    /// Idx of the cm. The idx a full peer gives is checked with the auth
+    /// path of it. A cm not found cannot be proved so, and is `None`.
+    pub(crate) async fn get_cm_idx(&self, cm: Cm) -> Result<Option<CmIdx>, SaksahaNodeError> {
+        for (peer, node_task_queue) in self.get_full_peers().await? {
+            let res = self
+                .request(&peer, &node_task_queue, |req_id| NodeTask::SendCmIdxReq {
+                    req_id,
+                    cm,
+                })
+                .await;
+
+            let cm_idx = match res {
+                Ok(LightRes::CmIdx(m)) => m.cm_idx,
+                Ok(_) => {
+                    peer.penalize(Misbehavior::UnexpectedMsg).await;
+                    continue;
+                }
+                Err(err) => {
+                    warn!("Could not get the cm idx, err: {}", err);
+                    continue;
+                }
+            };
+
+            let cm_idx = match cm_idx {
+                Some(i) => i,
+                None => return Ok(None),
+            };
+
+            match self.get_verified_auth_path(cm_idx).await? {
+                Some(auth_path) if auth_path.cm == cm => return Ok(Some(cm_idx)),
+                _ => {
+                    warn!(
+                        "Full peer has given a wrong cm idx, her_public_key: {}",
+                        peer.get_public_key_short(),
+                    );
+
+                    peer.penalize(Misbehavior::InvalidProof).await;
+                }
+            };
+        }
+
+        Err("Could not get a verified cm idx from the full peers".into())
+    }
+
+    /// Result of a contract query, which the full peers asked agree on.
+    pub(crate) async fn query_ctr(
+        &self,
+        ctr_addr: String,
+        req_type: String,
+        args: Vec<u8>,
+    ) -> Result<Vec<u8>, SaksahaNodeError> {
+        let mut answers: Vec<CtrQueryMsg> = vec![];
+
+        for (peer, node_task_queue) in self.get_full_peers().await? {
+            if answers.len() >= CTR_QUERY_QUORUM {
+                break;
+            }
+
+            let res = self
+                .request(&peer, &node_task_queue, |req_id| {
+                    NodeTask::SendCtrQueryReq {
+                        req_id,
+                        ctr_addr: ctr_addr.clone(),
+                        req_type: req_type.clone(),
+                        args: args.clone(),
+                    }
+                })
+                .await;
+
+            match res {
+                Ok(LightRes::CtrQuery(m)) => answers.push(m),
+                Ok(_) => {
+                    peer.penalize(Misbehavior::UnexpectedMsg).await;
+                }
+                Err(err) => warn!("Could not query the contract, err: {}", err),
+            };
+        }
+
+        if answers.len() < CTR_QUERY_QUORUM {
+            return Err(format!(
+                "Not enough full peers have answered the query, answered: {}, \
+                quorum: {}",
+                answers.len(),
+                CTR_QUERY_QUORUM,
+            )
+            .into());
+        }
+
+        let first = &answers[0];
+
+        if answers
+            .iter()
+            .any(|a| a.result != first.result || a.error != first.error)
+        {
+            return Err("Full peers disagree on the contract query result".into());
+        }
+
+        match &first.error {
+            Some(err) => Err(err.clone().into()),
+            None => Ok(first.result.clone()),
+        }
+    }
+
+    /// Hands the answer of a full peer to the request waiting for it.
+    /// Returns false if no request of the peer is waiting for it.
+    pub(in crate::node) async fn resolve(&self, peer: &Arc<Peer>, res: LightRes) -> bool {
+        let mut pending = self.pending.lock().await;
+
+        let req_id = res.get_req_id();
+
+        let is_asked_peer = match pending.get(&req_id) {
+            Some((public_key, _)) => public_key == peer.get_public_key(),
+            None => false,
+        };
+
+        if !is_asked_peer {
+            return false;
+        }
+
+        if let Some((_, res_tx)) = pending.remove(&req_id) {
+            let _ = res_tx.send(res);
+        }
+
+        true
+    }
+
+    /// Headers sent by the peer which the full peers agree on, in the height
+    /// order. The others are held until enough peers have sent them.
+    pub(in crate::node) async fn agree_on_headers(
+        &self,
+        peer: &Arc<Peer>,
+        blocks: Vec<Block>,
+    ) -> Result<Vec<Block>, SaksahaNodeError> {
+        let latest_block_height = self.machine.ledger.get_latest_block_height()?.unwrap_or(0);
+
+        let agreed =
+            self.header_votes
+                .lock()
+                .await
+                .add(peer.get_public_key(), blocks, latest_block_height);
+
+        Ok(agreed)
+    }
+
+    /// Asks the full peers ahead of the node for the headers it has not got
+    /// yet. More than one is asked, as a header is written only once they
+    /// agree on it.
+    pub(in crate::node) async fn sync_headers(&self) -> Result<(), SaksahaNodeError> {
+        let latest_block_height = self.machine.ledger.get_latest_block_height()?.unwrap_or(0);
+
+        let mut peers_ahead = vec![];
+
+        for (peer, node_task_queue) in self.get_full_peers().await? {
+            if let Some(s) = peer.get_chain_status().await {
+                if s.best_height > latest_block_height {
+                    peers_ahead.push(node_task_queue);
+                }
+            }
+        }
+
+        if !peers_ahead.is_empty() {
+            debug!(
+                "Syncing headers, latest_block_height: {}, peer count: {}",
+                latest_block_height,
+                peers_ahead.len(),
+            );
+        }
+
+        for node_task_queue in peers_ahead {
+            node_task_queue
+                .push_back(NodeTask::SendHeaderReq {
+                    from_height: latest_block_height + 1,
+                })
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn get_verified_auth_path(
+        &self,
+        cm_idx: CmIdx,
+    ) -> Result<Option<AuthPath>, SaksahaNodeError> {
+        for (peer, node_task_queue) in self.get_full_peers().await? {
+            let res = self
+                .request(&peer, &node_task_queue, |req_id| {
+                    NodeTask::SendAuthPathReq { req_id, cm_idx }
+                })
+                .await;
+
+            let auth_path = match res {
+                Ok(LightRes::AuthPath(m)) => m.auth_path,
+                Ok(_) => {
+                    peer.penalize(Misbehavior::UnexpectedMsg).await;
+                    continue;
+                }
+                Err(err) => {
+                    warn!("Could not get the auth path, err: {}", err);
+                    continue;
+                }
+            };
+
+            let auth_path = match auth_path {
+                Some(a) => a,
+                None => return Ok(None),
+            };
+
+            if self.verify_auth_path(&auth_path, &peer).await? {
+                return Ok(Some(auth_path));
+            }
+        }
+
+        Err("Could not get a verified auth path from the full peers".into())
+    }
+
+    // The peer may be ahead of the node, in which case the headers up to the
+    // height of the auth path are asked for first
+    async fn verify_auth_path(
+        &self,
+        auth_path: &AuthPath,
+        peer: &Arc<Peer>,
+    ) -> Result<bool, SaksahaNodeError> {
+        let ledger = &self.machine.ledger;
+
+        let mut header = ledger.get_block_by_height(&auth_path.block_height).await?;
+
+        if header.is_none() {
+            self.sync_headers().await?;
+
+            ledger.clock.sleep(HEADER_WAIT).await;
+
+            header = ledger.get_block_by_height(&auth_path.block_height).await?;
+        }
+
+        let header = match header {
+            Some(h) => h,
+            None => {
+                debug!(
+                    "Header of the auth path is not synced yet, block_height: {}",
+                    auth_path.block_height,
+                );
+
+                return Ok(false);
+            }
+        };
+
+        let is_valid =
+            ledger.verify_auth_path(&auth_path.cm, &auth_path.path, &header.merkle_rt)?;
+
+        if !is_valid {
+            warn!(
+                "Full peer has given an invalid auth path, her_public_key: {}",
+                peer.get_public_key_short(),
+            );
+
+            peer.penalize(Misbehavior::InvalidProof).await;
+        }
+
+        Ok(is_valid)
+    }
+
+    async fn get_full_peers(
+        &self,
+    ) -> Result<Vec<(Arc<Peer>, Arc<TaskQueue<NodeTask>>)>, SaksahaNodeError> {
+        // Peers are asked in a random order, drawn from the rng of the
+        // gossip, so that the same few are not the only ones trusted
+        let mut full_peers = self.gossip.get_peers_with_capability(CAP_LIGHT_SERVE).await;
+
+        if full_peers.is_empty() {
+            return Err("No full peer is connected".into());
+        }
+
+        full_peers.truncate(MAX_LIGHT_REQ_ATTEMPTS);
+
+        Ok(full_peers)
+    }
+
+    async fn request<F>(
+        &self,
+        peer: &Arc<Peer>,
+        node_task_queue: &Arc<TaskQueue<NodeTask>>,
+        make_task: F,
+    ) -> Result<LightRes, SaksahaNodeError>
+    where
+        F: FnOnce(u64) -> NodeTask,
+    {
+        let req_id = self.next_req_id.fetch_add(1, Ordering::Relaxed);
+
+        let (res_tx, res_rx) = oneshot::channel();
+
+        {
+            let mut pending = self.pending.lock().await;
+
+            pending.insert(req_id, (peer.get_public_key().to_string(), res_tx));
+        }
+
+        if let Err(err) = node_task_queue.push_back(make_task(req_id)).await {
+            self.pending.lock().await.remove(&req_id);
+
+            return Err(err.into());
+        }
+
+        let res = tokio::select! {
+            res = res_rx => res.ok(),
+            _ = self.machine.ledger.clock.sleep(LIGHT_REQ_TIMEOUT) => None,
+        };
+
+        match res {
+            Some(res) => Ok(res),
+            None => {
+                self.pending.lock().await.remove(&req_id);
+
+                Err(format!(
+                    "Full peer has not answered, her_public_key: {}",
+                    peer.get_public_key_short(),
+                )
+                .into())
+            }
+        }
+    }
+}
+
+/// Headers sent by the full peers, held by the height until `HEADER_QUORUM`
+/// of them have sent the same one.
+#[derive(Default)]
+pub(in crate::node) struct HeaderVotes {
+    // Header each peer has sent, by her public key
+    votes: HashMap<BlockHeight, HashMap<String, Block>>,
+}
+
+impl HeaderVotes {
+    /// Adds the headers the peer has sent, and returns the ones agreed on.
+    /// Headers not above the latest block height, or too far ahead of it,
+    /// are dropped.
+    pub(in crate::node) fn add(
+        &mut self,
+        public_key: &str,
+        blocks: Vec<Block>,
+        latest_block_height: BlockHeight,
+    ) -> Vec<Block> {
+        self.votes.retain(|height, _| *height > latest_block_height);
+
+        let max_height = latest_block_height + MAX_HEADERS_PER_MSG as BlockHeight;
+
+        let mut agreed = vec![];
+
+        for block in blocks {
+            let height = block.block_height;
+
+            if height <= latest_block_height || height > max_height {
+                continue;
+            }
+
+            let block_hash = block.get_block_hash().clone();
+
+            let votes = self.votes.entry(height).or_insert_with(HashMap::new);
+
+            votes.insert(public_key.to_string(), block);
+
+            let vote_count = votes
+                .values()
+                .filter(|b| b.get_block_hash() == &block_hash)
+                .count();
+
+            if vote_count >= HEADER_QUORUM {
+                if let Some(mut votes) = self.votes.remove(&height) {
+                    if let Some(b) = votes.remove(public_key) {
+                        agreed.push(b);
+                    }
+                }
+            }
+        }
+
+        agreed.sort_by(|a, b| a.block_height.cmp(&b.block_height));
+
+        agreed
+    }
+}
@@ -1,14 +1,27 @@
-use super::{miner::Miner, peer_node::PeerNode};
+use super::event_handle::LedgerEventRoutine;
+use super::fault::FaultInjector;
+use super::gossip::Gossip;
+use super::light_client::{LightClient, HEADER_SYNC_INTERVAL};
+use super::{miner::Miner, peer_node::PeerNode, BlockRelay};
+use sak_crypto::RandomSource;
 use sak_logger::{debug, info, warn};
 use sak_machine::SakMachine;
 use sak_p2p_discovery::Discovery;
 use sak_p2p_peertable::PeerTable;
+use sak_utils_time::Clock;
 use std::{sync::Arc, time::Duration};
-use tokio::time::Instant;
+use tokio::sync::Mutex;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
 
 const PEER_REGISTER_MIN_INTERVAL: u64 = 1000;
 const NODE_TASK_INTERVAL: u64 = 1000;
 const MINER: bool = false;
+const FULL_BLOCK_RELAY: bool = false;
+const LIGHT: bool = false;
+
+// Nodes of different networks refuse each other at hello
+const NETWORK_ID: &str = "saksaha_dev";
 
 pub(crate) struct LocalNode {
     pub peer_table: Arc<PeerTable>,
@@ -17,7 +30,14 @@ pub(crate) struct LocalNode {
     pub mine_interval: Option<u64>,
     pub node_task_interval: Duration,
     pub peer_register_interval: Duration,
+    pub block_relay: BlockRelay,
     pub discovery: Arc<Discovery>,
+    pub gossip: Arc<Gossip>,
+    pub light_client: Option<Arc<LightClient>>,
+    pub network_id: String,
+    pub fault_injector: Arc<FaultInjector>,
+    pub shutdown_token: CancellationToken,
+    peer_nodes: Mutex<JoinSet<()>>,
 }
 
 impl LocalNode {
@@ -28,7 +48,12 @@ impl LocalNode {
         mine_interval: Option<u64>,
         node_task_interval: Option<u64>,
         peer_register_interval: Option<u64>,
+        full_block_relay: Option<bool>,
+        light: Option<bool>,
+        network_id: Option<String>,
+        rng: Option<Arc<dyn RandomSource>>,
         discovery: Arc<Discovery>,
+        shutdown_token: CancellationToken,
     ) -> LocalNode {
         let node_task_interval = match node_task_interval {
             Some(i) => Duration::from_millis(i),
@@ -40,10 +65,36 @@ impl LocalNode {
             None => Duration::from_millis(PEER_REGISTER_MIN_INTERVAL),
         };
 
+        let block_relay = match full_block_relay.unwrap_or(FULL_BLOCK_RELAY) {
+            true => BlockRelay::Full,
+            false => BlockRelay::Compact,
+        };
+
+        let network_id = network_id.unwrap_or_else(|| NETWORK_ID.to_string());
+
+        let rng = rng.unwrap_or_else(sak_crypto::thread_random_source);
+
+        let gossip = Arc::new(Gossip::new(machine.ledger.clock.clone(), rng));
+
+        // A light node has no block txs, hence does not mine
+        let (light_client, miner) = match light.unwrap_or(LIGHT) {
+            true => {
+                let c = LightClient::new(machine.clone(), gossip.clone());
+
+                (Some(Arc::new(c)), Some(false))
+            }
+            false => (None, miner),
+        };
+
         debug!(
             "local node is initialized, node_task_interval: {:?},\
-            peer_register_interval: {:?}",
-            node_task_interval, peer_register_interval,
+            peer_register_interval: {:?}, block_relay: {:?}, light: {}, \
+            network_id: {}",
+            node_task_interval,
+            peer_register_interval,
+            block_relay,
+            light_client.is_some(),
+            network_id,
         );
 
         LocalNode {
@@ -53,7 +104,14 @@ impl LocalNode {
             mine_interval,
             node_task_interval,
             peer_register_interval,
+            block_relay,
             discovery,
+            gossip,
+            light_client,
+            network_id,
+            fault_injector: Arc::new(FaultInjector::new()),
+            shutdown_token,
+            peer_nodes: Mutex::new(JoinSet::new()),
         }
     }
 
@@ -64,45 +122,135 @@ impl LocalNode {
 
         if miner {
             let mine_interval = self.mine_interval;
+            let shutdown_token = self.shutdown_token.clone();
+
             tokio::spawn(async move {
                 let mut miner = Miner::init(machine, mine_interval);
 
-                miner.run().await;
+                tokio::select! {
+                    _ = miner.run() => {},
+                    _ = shutdown_token.cancelled() => {
+                        info!("Miner has stopped");
+                    },
+                };
             });
         }
 
         {
-            let peer_queue_iter = self.peer_table.peer_queue_iter();
-            let mut peer_queue_iter_lock = peer_queue_iter.write().await;
-
-            loop {
-                let now = Instant::now();
+            let mut ledger_event_routine = LedgerEventRoutine {
+                ledger_event_rx: self.machine.ledger.ledger_event_tx.subscribe(),
+                gossip: self.gossip.clone(),
+            };
 
-                let machine = self.machine.clone();
+            let shutdown_token = self.shutdown_token.clone();
 
-                let peer = match peer_queue_iter_lock.next().await {
-                    Ok(p) => p.clone(),
-                    Err(_) => continue,
+            tokio::spawn(async move {
+                tokio::select! {
+                    _ = ledger_event_routine.run() => {},
+                    _ = shutdown_token.cancelled() => {},
                 };
+            });
+        }
 
-                let peer_node = PeerNode {
-                    peer_table: self.peer_table.clone(),
-                    peer: peer.clone(),
-                    discovery: self.discovery.clone(),
-                    machine,
-                    node_task_min_interval: self.node_task_interval.clone(),
+        if let Some(light_client) = self.light_client.clone() {
+            let clock = self.machine.ledger.clock.clone();
+            let shutdown_token = self.shutdown_token.clone();
+
+            tokio::spawn(async move {
+                tokio::select! {
+                    _ = sync_headers(light_client, clock) => {},
+                    _ = shutdown_token.cancelled() => {
+                        info!("Header sync has stopped");
+                    },
                 };
+            });
+        }
+
+        tokio::select! {
+            _ = self.register_peers() => {},
+            _ = self.shutdown_token.cancelled() => {
+                info!("Local node has stopped registering peers");
+            },
+        };
+    }
+
+    /// Waits for the peer nodes to stop, which they do on the shutdown token
+    /// once they have drained their task queues.
+    pub(crate) async fn join_peer_nodes(&self) {
+        let mut peer_nodes = self.peer_nodes.lock().await;
+
+        while let Some(res) = peer_nodes.join_next().await {
+            if let Err(err) = res {
+                warn!("Peer node has not ended well, err: {}", err);
+            }
+        }
+    }
+
+    /// Aborts the peer nodes still running and waits for them to end.
+    pub(crate) async fn abort_peer_nodes(&self) {
+        self.peer_nodes.lock().await.shutdown().await;
+    }
+
+    async fn register_peers(&self) {
+        let peer_queue_iter = self.peer_table.peer_queue_iter();
+        let mut peer_queue_iter_lock = peer_queue_iter.write().await;
+
+        let clock = self.machine.ledger.clock.clone();
+
+        loop {
+            let now = clock.now();
+
+            let machine = self.machine.clone();
 
-                tokio::spawn(async move {
+            let peer = match peer_queue_iter_lock.next().await {
+                Ok(p) => p.clone(),
+                Err(_) => continue,
+            };
+
+            let peer_node = PeerNode {
+                peer_table: self.peer_table.clone(),
+                peer: peer.clone(),
+                discovery: self.discovery.clone(),
+                machine,
+                node_task_min_interval: self.node_task_interval.clone(),
+                block_relay: self.block_relay,
+                gossip: self.gossip.clone(),
+                light_client: self.light_client.clone(),
+                network_id: self.network_id.clone(),
+                fault_injector: self.fault_injector.clone(),
+                shutdown_token: self.shutdown_token.clone(),
+            };
+
+            {
+                let mut peer_nodes = self.peer_nodes.lock().await;
+
+                // Reaps the peer nodes that have ended
+                while peer_nodes.try_join_next().is_some() {}
+
+                peer_nodes.spawn(async move {
                     let res = peer_node.run().await;
 
                     if let Err(err) = res {
                         warn!("Peer routine is terminated, err: {}", err);
                     }
                 });
-
-                tokio::time::sleep_until(now + self.peer_register_interval).await;
             }
+
+            let next_at = now + self.peer_register_interval;
+
+            clock
+                .sleep(next_at.saturating_duration_since(clock.now()))
+                .await;
+        }
+    }
+}
+
+async fn sync_headers(light_client: Arc<LightClient>, clock: Arc<dyn Clock>) {
+    loop {
+        if let Err(err) = light_client.sync_headers().await {
+            warn!("Could not sync the headers, err: {}", err);
         }
+
+        clock.sleep(HEADER_SYNC_INTERVAL).await;
     }
 }
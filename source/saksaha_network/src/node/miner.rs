@@ -1,10 +1,7 @@
 use colored::Colorize;
 use sak_logger::{error, info, warn};
 use sak_machine::SakMachine;
-use std::{
-    sync::Arc,
-    time::{Duration, SystemTime},
-};
+use std::{sync::Arc, time::Duration};
 
 const MINE_INTERVAL: u64 = 5000;
 
@@ -33,10 +30,10 @@ impl Miner {
             mine_interval
         );
 
-        loop {
-            // println!("1111");
+        let clock = self.machine.ledger.clock.clone();
 
-            let time_since = SystemTime::now();
+        loop {
+            let time_since = clock.now();
 
             match self.machine.ledger.write_block(None).await {
                 Ok(_) => (),
@@ -45,7 +42,9 @@ impl Miner {
                 }
             };
 
-            sak_utils_time::wait_until_min_interval(time_since, mine_interval).await;
+            let elapsed = clock.now().saturating_duration_since(time_since);
+
+            clock.sleep(mine_interval.saturating_sub(elapsed)).await;
         }
     }
 }
@@ -1,4 +1,9 @@
+mod block_relay;
 mod event_handle;
+mod fault;
+mod gossip;
+mod heartbeat;
+mod light_client;
 mod local_node;
 mod miner;
 mod msg_handle;
@@ -8,6 +13,9 @@ mod task;
 #[cfg(test)]
 mod tests;
 
+pub(crate) use block_relay::*;
+pub(crate) use fault::*;
+pub(crate) use light_client::*;
 pub(crate) use local_node::*;
 
 pub(crate) type SaksahaNodeError = Box<dyn std::error::Error + Send + Sync>;
@@ -1,5 +1,7 @@
 use crate::node::SaksahaNodeError;
+use sak_logger::warn;
 use sak_machine::SakMachine;
+use sak_p2p_peertable::{Misbehavior, Peer};
 use sak_p2p_transport::{BlockAckMsg, BlockSynMsg, ErrorMsg, Msg, UpgradedConn};
 use sak_types::{BlockHash, BlockHeight};
 use std::sync::Arc;
@@ -52,15 +54,29 @@ pub(in crate::node) async fn recv_block_ack(
 pub(in crate::node) async fn recv_block_syn(
     block_syn_msg: BlockSynMsg,
     machine: &Arc<SakMachine>,
+    peer: &Arc<Peer>,
     mut conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
 ) -> Result<(), SaksahaNodeError> {
     let blocks = block_syn_msg.blocks;
 
-    let _ = machine
+    peer.mark_known(blocks.iter().map(|(block, _)| block.get_block_hash()))
+        .await;
+
+    // Blocks out of the height order are skipped, not penalized, since they
+    // may have been written from another peer already
+    match machine
         .ledger
         // .dist_ledger
         .write_blocks(blocks)
-        .await;
+        .await
+    {
+        Ok(_) => peer.reward().await,
+        Err(err) => {
+            warn!("Received an invalid block, err: {}", err);
+
+            peer.penalize(Misbehavior::InvalidBlock).await;
+        }
+    };
 
     let block_ack_msg = Msg::BlockAck(BlockAckMsg {});
 
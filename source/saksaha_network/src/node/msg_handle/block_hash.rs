@@ -1,7 +1,8 @@
-use crate::node::{task::NodeTask, SaksahaNodeError};
+use crate::node::{gossip::Gossip, task::NodeTask, BlockRelay, SaksahaNodeError};
 use sak_logger::{debug, info, warn};
 use sak_machine::SakMachine;
-use sak_p2p_transport::{BlockHashSyncMsg, ErrorMsg, Msg, UpgradedConn};
+use sak_p2p_peertable::{Misbehavior, Peer};
+use sak_p2p_transport::{BlockHashSyncMsg, ErrorMsg, Msg, UpgradedConn, CAP_COMPACT_BLOCK_RELAY};
 use sak_task_queue::TaskQueue;
 use sak_types::{BlockHash, BlockHeight};
 use std::sync::Arc;
@@ -22,9 +23,35 @@ pub(in crate::node) async fn send_block_hash_syn(
 
 pub(in crate::node) async fn recv_block_hash_ack(
     block_hash_ack_msg: BlockHashSyncMsg,
+    machine: &Arc<SakMachine>,
+    peer: &Arc<Peer>,
     task_queue: &Arc<TaskQueue<NodeTask>>,
+    block_relay: BlockRelay,
 ) -> Result<(), SaksahaNodeError> {
-    let new_blocks = block_hash_ack_msg.new_blocks;
+    let new_blocks = filter_known_blocks(block_hash_ack_msg, machine, peer).await?;
+
+    let task = if block_relay == BlockRelay::Compact
+        && peer.has_capability(CAP_COMPACT_BLOCK_RELAY).await
+    {
+        NodeTask::SendCompactBlockSyn { new_blocks }
+    } else {
+        NodeTask::SendBlockSyn { new_blocks }
+    };
+
+    task_queue.push_back(task).await?;
+
+    Ok(())
+}
+
+/// The peer could not rebuild a compact block, so the block is sent with
+/// all of its txs.
+pub(in crate::node) async fn recv_full_block_req(
+    full_block_req_msg: BlockHashSyncMsg,
+    machine: &Arc<SakMachine>,
+    peer: &Arc<Peer>,
+    task_queue: &Arc<TaskQueue<NodeTask>>,
+) -> Result<(), SaksahaNodeError> {
+    let new_blocks = filter_known_blocks(full_block_req_msg, machine, peer).await?;
 
     task_queue
         .push_back(NodeTask::SendBlockSyn { new_blocks })
@@ -33,13 +60,42 @@ pub(in crate::node) async fn recv_block_hash_ack(
     Ok(())
 }
 
+async fn filter_known_blocks(
+    block_hash_sync_msg: BlockHashSyncMsg,
+    machine: &Arc<SakMachine>,
+    peer: &Arc<Peer>,
+) -> Result<Vec<(BlockHeight, BlockHash)>, SaksahaNodeError> {
+    let mut new_blocks = vec![];
+
+    for (height, block_hash) in block_hash_sync_msg.new_blocks {
+        if machine.ledger.get_block(&block_hash)?.is_none() {
+            peer.penalize(Misbehavior::UnknownBlock).await;
+
+            continue;
+        }
+
+        new_blocks.push((height, block_hash));
+    }
+
+    Ok(new_blocks)
+}
+
 pub(in crate::node) async fn recv_block_hash_syn(
     block_hash_syn_msg: BlockHashSyncMsg,
     machine: &Arc<SakMachine>,
+    peer: &Arc<Peer>,
+    gossip: &Arc<Gossip>,
     mut conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
 ) -> Result<(), SaksahaNodeError> {
     let new_blocks = block_hash_syn_msg.new_blocks;
 
+    peer.mark_known(new_blocks.iter().map(|(_, block_hash)| block_hash))
+        .await;
+
+    if let Some(height) = new_blocks.iter().map(|(height, _)| *height).max() {
+        peer.update_best_height(height).await;
+    }
+
     let (_, latest_block_hash) = machine
         .ledger
         // .dist_ledger
@@ -53,7 +109,7 @@ pub(in crate::node) async fn recv_block_hash_syn(
         latest_block_hash, new_blocks,
     );
 
-    let mut blocks_to_req = vec![];
+    let mut blocks_not_written = vec![];
     for (height, block_hash) in new_blocks {
         if machine
             .ledger
@@ -61,10 +117,26 @@ pub(in crate::node) async fn recv_block_hash_syn(
             .get_block(&block_hash)?
             .is_none()
         {
-            blocks_to_req.push((height, block_hash));
+            blocks_not_written.push((height, block_hash));
         }
     }
 
+    // Blocks announced by several peers at once are requested from one of
+    // them
+    let unrequested = gossip
+        .take_unrequested(
+            blocks_not_written
+                .iter()
+                .map(|(_, block_hash)| block_hash.clone())
+                .collect(),
+        )
+        .await;
+
+    let blocks_to_req = blocks_not_written
+        .into_iter()
+        .filter(|(_, block_hash)| unrequested.contains(block_hash))
+        .collect();
+
     conn_lock
         .send(Msg::BlockHashAck(BlockHashSyncMsg {
             new_blocks: blocks_to_req,
@@ -0,0 +1,257 @@
+use crate::node::SaksahaNodeError;
+use sak_logger::{debug, warn};
+use sak_machine::SakMachine;
+use sak_p2p_peertable::{Misbehavior, Peer};
+use sak_p2p_transport::{
+    BlockHashSyncMsg, BlockTxsMsg, BlockTxsReqMsg, CompactBlockSynMsg, ErrorMsg, Msg, UpgradedConn,
+};
+use sak_types::{Block, BlockHash, BlockHeight, TxCandidate, TxHash};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLockWriteGuard;
+
+pub(in crate::node) async fn send_compact_block_syn(
+    mut conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
+    new_blocks: Vec<(BlockHeight, BlockHash)>,
+    machine: &Arc<SakMachine>,
+) -> Result<(), SaksahaNodeError> {
+    let block_hashes: Vec<&BlockHash> = new_blocks
+        .iter()
+        .map(|(_, block_hash)| block_hash)
+        .collect();
+
+    let blocks = machine.ledger.get_blocks(block_hashes).await?;
+
+    conn_lock
+        .send(Msg::CompactBlockSyn(CompactBlockSynMsg { blocks }))
+        .await;
+
+    Ok(())
+}
+
+pub(in crate::node) async fn recv_compact_block_syn(
+    compact_block_syn_msg: CompactBlockSynMsg,
+    machine: &Arc<SakMachine>,
+    peer: &Arc<Peer>,
+    mut conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
+) -> Result<(), SaksahaNodeError> {
+    let mut blocks = compact_block_syn_msg.blocks;
+
+    peer.mark_known(blocks.iter().map(|block| block.get_block_hash()))
+        .await;
+
+    blocks.sort_by(|a, b| a.block_height.cmp(&b.block_height));
+
+    let mut rebuilt_blocks = vec![];
+    let mut blocks = blocks.into_iter();
+
+    for block in blocks.by_ref() {
+        if machine.ledger.get_block(block.get_block_hash())?.is_some() {
+            continue;
+        }
+
+        match machine
+            .ledger
+            .get_block_txs_from_pool(&block.tx_hashes)
+            .await
+        {
+            Ok(tx_candidates) => rebuilt_blocks.push((block, tx_candidates)),
+            Err(missing_tx_hashes) => {
+                debug!(
+                    "Compact block misses txs in the pool, block_hash: {}, \
+                    missing tx count: {}",
+                    block.get_block_hash(),
+                    missing_tx_hashes.len(),
+                );
+
+                send_block_txs_req(&mut conn_lock, &block, missing_tx_hashes).await;
+
+                break;
+            }
+        };
+    }
+
+    write_rebuilt_blocks(rebuilt_blocks, machine, peer).await;
+
+    // Blocks are written in the height order, so the ones after a block
+    // missing txs are completed by the same peer, in the same order
+    for block in blocks {
+        let missing_tx_hashes = match machine
+            .ledger
+            .get_block_txs_from_pool(&block.tx_hashes)
+            .await
+        {
+            Ok(_) => vec![],
+            Err(h) => h,
+        };
+
+        send_block_txs_req(&mut conn_lock, &block, missing_tx_hashes).await;
+    }
+
+    Ok(())
+}
+
+pub(in crate::node) async fn recv_block_txs_req(
+    block_txs_req_msg: BlockTxsReqMsg,
+    machine: &Arc<SakMachine>,
+    peer: &Arc<Peer>,
+    mut conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
+) -> Result<(), SaksahaNodeError> {
+    let block = match machine.ledger.get_block(&block_txs_req_msg.block_hash)? {
+        Some(b) => b,
+        None => {
+            peer.penalize(Misbehavior::UnknownBlock).await;
+
+            conn_lock
+                .send(Msg::Error(ErrorMsg {
+                    error: format!(
+                        "Block does not exist, block_hash: {}",
+                        block_txs_req_msg.block_hash,
+                    ),
+                }))
+                .await;
+
+            return Ok(());
+        }
+    };
+
+    let tx_hashes = block_txs_req_msg.tx_hashes;
+
+    if tx_hashes.iter().any(|h| !block.tx_hashes.contains(h)) {
+        peer.penalize(Misbehavior::UnexpectedMsg).await;
+
+        return Err(format!(
+            "Requested txs are not in the block, block_hash: {}",
+            block.get_block_hash(),
+        )
+        .into());
+    }
+
+    let tx_candidates = machine
+        .ledger
+        .get_txs(&tx_hashes)
+        .await?
+        .into_iter()
+        .map(|tx| tx.downgrade())
+        .collect();
+
+    conn_lock
+        .send(Msg::BlockTxs(BlockTxsMsg {
+            block,
+            tx_candidates,
+        }))
+        .await;
+
+    Ok(())
+}
+
+pub(in crate::node) async fn recv_block_txs(
+    block_txs_msg: BlockTxsMsg,
+    machine: &Arc<SakMachine>,
+    peer: &Arc<Peer>,
+    mut conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
+) -> Result<(), SaksahaNodeError> {
+    let BlockTxsMsg {
+        block,
+        tx_candidates,
+    } = block_txs_msg;
+
+    if machine.ledger.get_block(block.get_block_hash())?.is_some() {
+        return Ok(());
+    }
+
+    let latest_block_height = machine.ledger.get_latest_block_height()?.unwrap_or(0);
+
+    let rebuilt_txs = if block.block_height == latest_block_height + 1 {
+        rebuild_block_txs(&block, tx_candidates, machine).await
+    } else {
+        None
+    };
+
+    match rebuilt_txs {
+        Some(tx_candidates) => {
+            write_rebuilt_blocks(vec![(block, tx_candidates)], machine, peer).await;
+        }
+        None => {
+            debug!(
+                "Could not rebuild the compact block, requesting the full \
+                block, block_hash: {}",
+                block.get_block_hash(),
+            );
+
+            conn_lock
+                .send(Msg::FullBlockReq(BlockHashSyncMsg {
+                    new_blocks: vec![(block.block_height, block.get_block_hash().to_string())],
+                }))
+                .await;
+        }
+    };
+
+    Ok(())
+}
+
+// Txs of the block out of the ones received and the pool, in the block order
+async fn rebuild_block_txs(
+    block: &Block,
+    tx_candidates: Vec<TxCandidate>,
+    machine: &Arc<SakMachine>,
+) -> Option<Vec<TxCandidate>> {
+    let mut received: HashMap<TxHash, TxCandidate> = tx_candidates
+        .into_iter()
+        .map(|tc| (tc.get_tx_hash().to_string(), tc))
+        .collect();
+
+    let not_received: Vec<TxHash> = block
+        .tx_hashes
+        .iter()
+        .filter(|h| !received.contains_key(*h))
+        .cloned()
+        .collect();
+
+    let mut pooled: HashMap<TxHash, TxCandidate> =
+        match machine.ledger.get_block_txs_from_pool(&not_received).await {
+            Ok(tcs) => tcs
+                .into_iter()
+                .map(|tc| (tc.get_tx_hash().to_string(), tc))
+                .collect(),
+            Err(_) => return None,
+        };
+
+    block
+        .tx_hashes
+        .iter()
+        .map(|h| received.remove(h).or_else(|| pooled.remove(h)))
+        .collect()
+}
+
+async fn send_block_txs_req(
+    conn_lock: &mut RwLockWriteGuard<'_, UpgradedConn>,
+    block: &Block,
+    tx_hashes: Vec<String>,
+) {
+    conn_lock
+        .send(Msg::BlockTxsReq(BlockTxsReqMsg {
+            block_hash: block.get_block_hash().to_string(),
+            tx_hashes,
+        }))
+        .await;
+}
+
+async fn write_rebuilt_blocks(
+    blocks: Vec<(Block, Vec<TxCandidate>)>,
+    machine: &Arc<SakMachine>,
+    peer: &Arc<Peer>,
+) {
+    if blocks.is_empty() {
+        return;
+    }
+
+    match machine.ledger.write_blocks_from_candidates(blocks).await {
+        Ok(_) => peer.reward().await,
+        Err(err) => {
+            warn!("Received an invalid block, err: {}", err);
+
+            peer.penalize(Misbehavior::InvalidBlock).await;
+        }
+    };
+}
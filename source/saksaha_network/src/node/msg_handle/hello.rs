@@ -1,21 +1,68 @@
-use crate::node::{task::NodeTask, SaksahaNodeError};
-use sak_logger::{debug, info, warn};
+use crate::node::{task::NodeTask, LightClient, SaksahaNodeError};
+use sak_logger::{debug, warn};
+use sak_machine::SakMachine;
 use sak_p2p_addr::UnknownAddr;
 use sak_p2p_discovery::Discovery;
-use sak_p2p_peertable::{Peer, PeerTable};
-use sak_p2p_transport::{HelloMsg, Msg, TxHashSyncMsg, UpgradedConn};
+use sak_p2p_peertable::{Peer, PeerChainStatus, PeerStatus, PeerTable};
+use sak_p2p_transport::{
+    ErrorMsg, HelloMsg, Msg, UpgradedConn, CAP_BLOCK_RELAY, CAP_COMPACT_BLOCK_RELAY,
+    CAP_LIGHT_SERVE, CAP_TX_RELAY, MAX_HEADERS_PER_MSG,
+};
 use sak_task_queue::TaskQueue;
-use sak_types::TxHash;
+use sak_types::BlockHeight;
 use std::sync::Arc;
 use tokio::sync::RwLockWriteGuard;
 
+// Compact blocks are always accepted, whichever relay the node sends with
+const CAPABILITIES: u64 =
+    CAP_TX_RELAY | CAP_BLOCK_RELAY | CAP_COMPACT_BLOCK_RELAY | CAP_LIGHT_SERVE;
+
+// A light node keeps no block txs, so she only relays txs
+const LIGHT_CAPABILITIES: u64 = CAP_TX_RELAY;
+
+pub(in crate::node) async fn make_hello_msg(
+    machine: &Arc<SakMachine>,
+    light_client: &Option<Arc<LightClient>>,
+    network_id: &str,
+    unknown_addrs: Vec<UnknownAddr>,
+) -> Result<HelloMsg, SaksahaNodeError> {
+    // Without a genesis, the node cannot tell which chain she is on
+    let genesis_hash = match machine.ledger.get_block_by_height(&0).await? {
+        Some(b) => b.get_block_hash().to_string(),
+        None => return Err("Genesis block does not exist, cannot say hello".into()),
+    };
+
+    let best_height = machine.ledger.get_latest_block_height()?.unwrap_or(0);
+
+    let capabilities = match light_client {
+        Some(_) => LIGHT_CAPABILITIES,
+        None => CAPABILITIES,
+    };
+
+    let hello_msg = HelloMsg::new(
+        network_id.to_string(),
+        genesis_hash,
+        best_height,
+        capabilities,
+        unknown_addrs,
+    );
+
+    Ok(hello_msg)
+}
+
 pub(in crate::node) async fn send_hello_syn(
     mut conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
-    discovery: &Arc<Discovery>,
+    machine: &Arc<SakMachine>,
+    peer: &Arc<Peer>,
+    light_client: &Option<Arc<LightClient>>,
+    network_id: &str,
     unknown_addrs: Vec<UnknownAddr>,
-    task_queue: &Arc<TaskQueue<NodeTask>>,
 ) -> Result<(), SaksahaNodeError> {
-    let hello_syn_msg = HelloMsg::new(unknown_addrs)?;
+    let hello_syn_msg = match make_hello_msg(machine, light_client, network_id, unknown_addrs).await
+    {
+        Ok(m) => m,
+        Err(err) => return disconnect(peer, conn_lock, err.to_string()).await,
+    };
 
     let _receipt = conn_lock.send(Msg::HelloSyn(hello_syn_msg)).await;
 
@@ -24,13 +71,26 @@ pub(in crate::node) async fn send_hello_syn(
 
 pub(in crate::node) async fn recv_hello_ack(
     hello_ack: HelloMsg,
-    peer_table: &Arc<PeerTable>,
+    machine: &Arc<SakMachine>,
+    peer: &Arc<Peer>,
     discovery: &Arc<Discovery>,
-    mut conn: RwLockWriteGuard<'_, UpgradedConn>,
+    task_queue: &Arc<TaskQueue<NodeTask>>,
+    light_client: &Option<Arc<LightClient>>,
+    network_id: &str,
+    conn: RwLockWriteGuard<'_, UpgradedConn>,
 ) -> Result<(), SaksahaNodeError> {
-    let HelloMsg { unknown_addrs } = hello_ack;
+    let my_hello = match make_hello_msg(machine, light_client, network_id, vec![]).await {
+        Ok(m) => m,
+        Err(err) => return disconnect(peer, conn, err.to_string()).await,
+    };
+
+    if let Err(reason) = my_hello.check_compatible(&hello_ack) {
+        return disconnect(peer, conn, reason).await;
+    }
+
+    handle_chain_status(&hello_ack, &my_hello, peer, machine, task_queue).await?;
 
-    for unknown_addr in unknown_addrs {
+    for unknown_addr in hello_ack.unknown_addrs {
         discovery.enqueue_who_are_you(&unknown_addr).await;
     }
 
@@ -39,21 +99,110 @@ pub(in crate::node) async fn recv_hello_ack(
 
 pub(in crate::node) async fn recv_hello_syn(
     hello_msg: HelloMsg,
+    machine: &Arc<SakMachine>,
+    peer: &Arc<Peer>,
     peer_table: &Arc<PeerTable>,
     discovery: &Arc<Discovery>,
+    task_queue: &Arc<TaskQueue<NodeTask>>,
+    light_client: &Option<Arc<LightClient>>,
+    network_id: &str,
     mut conn: RwLockWriteGuard<'_, UpgradedConn>,
 ) -> Result<(), SaksahaNodeError> {
-    let HelloMsg { unknown_addrs } = hello_msg;
+    let unknown_addrs = peer_table.get_peer_addrs().await;
 
-    for unknown_addr in unknown_addrs {
-        discovery.enqueue_who_are_you(&unknown_addr).await;
+    let hello_ack_msg = match make_hello_msg(machine, light_client, network_id, unknown_addrs).await
+    {
+        Ok(m) => m,
+        Err(err) => return disconnect(peer, conn, err.to_string()).await,
+    };
+
+    if let Err(reason) = hello_ack_msg.check_compatible(&hello_msg) {
+        return disconnect(peer, conn, reason).await;
     }
 
-    let unknown_addrs = peer_table.get_peer_addrs().await;
+    handle_chain_status(&hello_msg, &hello_ack_msg, peer, machine, task_queue).await?;
 
-    let hello_ack_msg = HelloMsg::new(unknown_addrs).unwrap();
+    for unknown_addr in hello_msg.unknown_addrs {
+        discovery.enqueue_who_are_you(&unknown_addr).await;
+    }
 
     conn.send(Msg::HelloAck(hello_ack_msg)).await;
 
     Ok(())
 }
+
+// Keeps the chain status of the peer, and sends her the blocks she is
+// behind of. A light node has no block txs to send.
+async fn handle_chain_status(
+    her_hello: &HelloMsg,
+    my_hello: &HelloMsg,
+    peer: &Arc<Peer>,
+    machine: &Arc<SakMachine>,
+    task_queue: &Arc<TaskQueue<NodeTask>>,
+) -> Result<(), SaksahaNodeError> {
+    peer.set_chain_status(PeerChainStatus {
+        best_height: her_hello.best_height,
+        capabilities: her_hello.capabilities,
+    })
+    .await;
+
+    if her_hello.best_height >= my_hello.best_height
+        || !her_hello.has_capability(CAP_BLOCK_RELAY)
+        || !my_hello.has_capability(CAP_BLOCK_RELAY)
+    {
+        return Ok(());
+    }
+
+    // Only the heights above her claim are read, and no more than fit in a
+    // msg, so that a peer claiming a low height cannot have the whole chain
+    // loaded on every hello
+    let to_height = my_hello
+        .best_height
+        .min(her_hello.best_height + MAX_HEADERS_PER_MSG as BlockHeight);
+
+    let mut new_blocks = vec![];
+
+    for height in her_hello.best_height + 1..=to_height {
+        match machine.ledger.get_block_by_height(&height).await? {
+            Some(b) => new_blocks.push((height, b.get_block_hash().to_string())),
+            None => break,
+        };
+    }
+
+    // Latest first, as the ledger lists the blocks
+    new_blocks.reverse();
+
+    debug!(
+        "Peer is behind, her_best_height: {}, my_best_height: {}",
+        her_hello.best_height, my_hello.best_height,
+    );
+
+    task_queue
+        .push_back(NodeTask::SendBlockHashSyn { new_blocks })
+        .await?;
+
+    Ok(())
+}
+
+async fn disconnect(
+    peer: &Arc<Peer>,
+    mut conn: RwLockWriteGuard<'_, UpgradedConn>,
+    reason: String,
+) -> Result<(), SaksahaNodeError> {
+    warn!(
+        "Peer is not compatible, disconnecting, her_public_key: {}, \
+        reason: {}",
+        peer.get_public_key_short(),
+        reason,
+    );
+
+    let _ = conn
+        .send(Msg::Error(ErrorMsg {
+            error: reason.clone(),
+        }))
+        .await;
+
+    peer.set_peer_status(PeerStatus::Disconnected).await;
+
+    Err(reason.into())
+}
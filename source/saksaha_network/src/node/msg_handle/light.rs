@@ -0,0 +1,315 @@
+use crate::node::light_client::LightRes;
+use crate::node::{LightClient, SaksahaNodeError};
+use sak_contract_std::{CtrCallType, CtrRequest};
+use sak_logger::{debug, warn};
+use sak_machine::SakMachine;
+use sak_p2p_peertable::{Misbehavior, Peer};
+use sak_p2p_transport::{
+    AuthPath, AuthPathMsg, AuthPathReqMsg, BlockHashSyncMsg, CmIdxMsg, CmIdxReqMsg, CtrQueryMsg,
+    CtrQueryReqMsg, HeaderReqMsg, HeadersMsg, Msg, TxHashSyncMsg, UpgradedConn,
+    MAX_HEADERS_PER_MSG,
+};
+use sak_types::{BlockHeight, Cm, CmIdx};
+use std::sync::Arc;
+use tokio::sync::RwLockWriteGuard;
+
+// Times an auth path is made again if a block is written meanwhile
+const AUTH_PATH_RETRY: usize = 3;
+
+pub(in crate::node) async fn send_header_req(
+    mut conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
+    from_height: BlockHeight,
+) -> Result<(), SaksahaNodeError> {
+    conn_lock
+        .send(Msg::HeaderReq(HeaderReqMsg { from_height }))
+        .await;
+
+    Ok(())
+}
+
+pub(in crate::node) async fn send_auth_path_req(
+    mut conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
+    req_id: u64,
+    cm_idx: CmIdx,
+) -> Result<(), SaksahaNodeError> {
+    conn_lock
+        .send(Msg::AuthPathReq(AuthPathReqMsg { req_id, cm_idx }))
+        .await;
+
+    Ok(())
+}
+
+pub(in crate::node) async fn send_cm_idx_req(
+    mut conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
+    req_id: u64,
+    cm: Cm,
+) -> Result<(), SaksahaNodeError> {
+    conn_lock
+        .send(Msg::CmIdxReq(CmIdxReqMsg { req_id, cm }))
+        .await;
+
+    Ok(())
+}
+
+pub(in crate::node) async fn send_ctr_query_req(
+    mut conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
+    req_id: u64,
+    ctr_addr: String,
+    req_type: String,
+    args: Vec<u8>,
+) -> Result<(), SaksahaNodeError> {
+    conn_lock
+        .send(Msg::CtrQueryReq(CtrQueryReqMsg {
+            req_id,
+            ctr_addr,
+            req_type,
+            args,
+        }))
+        .await;
+
+    Ok(())
+}
+
+pub(in crate::node) async fn recv_header_req(
+    header_req_msg: HeaderReqMsg,
+    machine: &Arc<SakMachine>,
+    mut conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
+) -> Result<(), SaksahaNodeError> {
+    let mut blocks = vec![];
+
+    let mut height = header_req_msg.from_height;
+
+    while blocks.len() < MAX_HEADERS_PER_MSG {
+        match machine.ledger.get_block_by_height(&height).await? {
+            Some(b) => blocks.push(b),
+            None => break,
+        };
+
+        height += 1;
+    }
+
+    conn_lock.send(Msg::Headers(HeadersMsg { blocks })).await;
+
+    Ok(())
+}
+
+pub(in crate::node) async fn recv_headers(
+    headers_msg: HeadersMsg,
+    machine: &Arc<SakMachine>,
+    peer: &Arc<Peer>,
+    light_client: &Option<Arc<LightClient>>,
+    conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
+) -> Result<(), SaksahaNodeError> {
+    let light_client = light_client
+        .as_ref()
+        .ok_or("Headers are received by a light node only")?;
+
+    let header_count = headers_msg.blocks.len();
+
+    let headers = light_client
+        .agree_on_headers(peer, headers_msg.blocks)
+        .await?;
+
+    let written = match machine.ledger.write_block_headers(headers).await {
+        Ok(w) => w,
+        Err(err) => {
+            warn!("Received an invalid block header, err: {}", err);
+
+            peer.penalize(Misbehavior::InvalidBlock).await;
+
+            return Ok(());
+        }
+    };
+
+    if written.is_empty() {
+        return Ok(());
+    }
+
+    peer.reward().await;
+
+    // The peer has got more headers than a msg holds
+    if header_count == MAX_HEADERS_PER_MSG {
+        let latest_block_height = machine.ledger.get_latest_block_height()?.unwrap_or(0);
+
+        send_header_req(conn_lock, latest_block_height + 1).await?;
+    }
+
+    Ok(())
+}
+
+/// A light node asks for the headers of the announced blocks instead of the
+/// blocks.
+pub(in crate::node) async fn recv_block_hash_syn_light(
+    block_hash_syn_msg: BlockHashSyncMsg,
+    machine: &Arc<SakMachine>,
+    peer: &Arc<Peer>,
+    conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
+) -> Result<(), SaksahaNodeError> {
+    let new_blocks = block_hash_syn_msg.new_blocks;
+
+    peer.mark_known(new_blocks.iter().map(|(_, block_hash)| block_hash))
+        .await;
+
+    let best_height = match new_blocks.iter().map(|(height, _)| *height).max() {
+        Some(h) => h,
+        None => return Ok(()),
+    };
+
+    peer.update_best_height(best_height).await;
+
+    let latest_block_height = machine.ledger.get_latest_block_height()?.unwrap_or(0);
+
+    if best_height > latest_block_height {
+        send_header_req(conn_lock, latest_block_height + 1).await?;
+    }
+
+    Ok(())
+}
+
+/// A light node does not keep the txs of the others. Only the ones sent
+/// through her rpc are in her pool.
+pub(in crate::node) async fn recv_tx_hash_syn_light(
+    tx_hash_syn_msg: TxHashSyncMsg,
+    peer: &Arc<Peer>,
+    mut conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
+) -> Result<(), SaksahaNodeError> {
+    peer.mark_known(&tx_hash_syn_msg.tx_hashes).await;
+
+    conn_lock
+        .send(Msg::TxHashAck(TxHashSyncMsg { tx_hashes: vec![] }))
+        .await;
+
+    Ok(())
+}
+
+pub(in crate::node) async fn recv_auth_path_req(
+    auth_path_req_msg: AuthPathReqMsg,
+    machine: &Arc<SakMachine>,
+    mut conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
+) -> Result<(), SaksahaNodeError> {
+    let auth_path = make_auth_path(auth_path_req_msg.cm_idx, machine).await?;
+
+    conn_lock
+        .send(Msg::AuthPath(AuthPathMsg {
+            req_id: auth_path_req_msg.req_id,
+            auth_path,
+        }))
+        .await;
+
+    Ok(())
+}
+
+pub(in crate::node) async fn recv_cm_idx_req(
+    cm_idx_req_msg: CmIdxReqMsg,
+    machine: &Arc<SakMachine>,
+    mut conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
+) -> Result<(), SaksahaNodeError> {
+    let cm_idx = machine.ledger.get_cm_idx_by_cm(&cm_idx_req_msg.cm).await?;
+
+    conn_lock
+        .send(Msg::CmIdx(CmIdxMsg {
+            req_id: cm_idx_req_msg.req_id,
+            cm_idx,
+        }))
+        .await;
+
+    Ok(())
+}
+
+pub(in crate::node) async fn recv_ctr_query_req(
+    ctr_query_req_msg: CtrQueryReqMsg,
+    machine: &Arc<SakMachine>,
+    mut conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
+) -> Result<(), SaksahaNodeError> {
+    let CtrQueryReqMsg {
+        req_id,
+        ctr_addr,
+        req_type,
+        args,
+    } = ctr_query_req_msg;
+
+    let req = CtrRequest {
+        ctr_addr,
+        req_type,
+        args,
+        ctr_call_type: CtrCallType::Query,
+        session_owner: None,
+    };
+
+    let ctr_query_msg = match machine.ledger.execute_ctr(req).await {
+        Ok(result) => CtrQueryMsg {
+            req_id,
+            result,
+            error: None,
+        },
+        Err(err) => CtrQueryMsg {
+            req_id,
+            result: vec![],
+            error: Some(err.to_string()),
+        },
+    };
+
+    conn_lock.send(Msg::CtrQuery(ctr_query_msg)).await;
+
+    Ok(())
+}
+
+/// Answer of a full peer to a request of this light node.
+pub(in crate::node) async fn recv_light_res(
+    light_res: LightRes,
+    peer: &Arc<Peer>,
+    light_client: &Option<Arc<LightClient>>,
+) -> Result<(), SaksahaNodeError> {
+    let is_resolved = match light_client {
+        Some(c) => c.resolve(peer, light_res).await,
+        None => false,
+    };
+
+    if !is_resolved {
+        peer.penalize(Misbehavior::UnexpectedMsg).await;
+
+        return Err("Received an answer to no request".into());
+    }
+
+    Ok(())
+}
+
+// Auth path at the latest block. It is made again if a block gets written
+// meanwhile, as the path would not lead to the merkle root of the block.
+async fn make_auth_path(
+    cm_idx: CmIdx,
+    machine: &Arc<SakMachine>,
+) -> Result<Option<AuthPath>, SaksahaNodeError> {
+    let ledger = &machine.ledger;
+
+    for _ in 0..AUTH_PATH_RETRY {
+        let latest_cm_idx = match ledger.ledger_db.get_latest_cm_idx()? {
+            Some(i) => i,
+            None => return Ok(None),
+        };
+
+        if cm_idx > latest_cm_idx {
+            return Ok(None);
+        }
+
+        let block_height = ledger
+            .get_latest_block_height()?
+            .ok_or("Latest block height does not exist")?;
+
+        let path = ledger.get_auth_path(&cm_idx).await?;
+
+        let cm = ledger.get_merkle_node(&format!("0_{}", cm_idx)).await?;
+
+        if ledger.get_latest_block_height()? == Some(block_height) {
+            return Ok(Some(AuthPath {
+                block_height,
+                cm,
+                path,
+            }));
+        }
+
+        debug!("A block has been written while making an auth path, retrying");
+    }
+
+    Err(format!("Could not make an auth path, cm_idx: {}", cm_idx).into())
+}
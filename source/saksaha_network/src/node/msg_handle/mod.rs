@@ -1,18 +1,27 @@
 mod block;
 mod block_hash;
+mod compact_block;
 mod hello;
+mod light;
+mod ping;
 mod tx;
 mod tx_hash;
 
+use super::gossip::Gossip;
+use super::light_client::LightRes;
 use super::task::NodeTask;
+use super::BlockRelay;
+use super::LightClient;
 use crate::SaksahaError;
 pub(in crate::node) use block::*;
 pub(in crate::node) use block_hash::*;
+pub(in crate::node) use compact_block::*;
 pub(in crate::node) use hello::*;
+pub(in crate::node) use light::*;
 use sak_logger::{debug, info, warn};
 use sak_machine::SakMachine;
 use sak_p2p_discovery::Discovery;
-use sak_p2p_peertable::{Peer, PeerTable};
+use sak_p2p_peertable::{Misbehavior, Peer, PeerTable};
 use sak_p2p_transport::{Msg, TxHashSyncMsg, TxSynMsg, UpgradedConn, UpgradedP2PCodec};
 use sak_task_queue::TaskQueue;
 use std::sync::Arc;
@@ -25,41 +34,126 @@ pub(in crate::node) async fn handle_msg<'a>(
     machine: &Arc<SakMachine>,
     conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
     task_queue: &Arc<TaskQueue<NodeTask>>,
+    peer: &Arc<Peer>,
     peer_table: &Arc<PeerTable>,
     discovery: &Arc<Discovery>,
+    gossip: &Arc<Gossip>,
+    block_relay: BlockRelay,
+    light_client: &Option<Arc<LightClient>>,
+    network_id: &str,
 ) -> Result<(), SaksahaError> {
+    let is_light = light_client.is_some();
+
     match msg {
+        Msg::Ping(ping) => {
+            ping::recv_ping(ping, conn_lock).await?;
+        }
         Msg::HelloSyn(hello_msg) => {
-            hello::recv_hello_syn(hello_msg, peer_table, discovery, conn_lock).await?;
+            hello::recv_hello_syn(
+                hello_msg,
+                machine,
+                peer,
+                peer_table,
+                discovery,
+                task_queue,
+                light_client,
+                network_id,
+                conn_lock,
+            )
+            .await?;
         }
         Msg::HelloAck(hello_msg) => {
-            hello::recv_hello_ack(hello_msg, peer_table, discovery, conn_lock).await?;
+            hello::recv_hello_ack(
+                hello_msg,
+                machine,
+                peer,
+                discovery,
+                task_queue,
+                light_client,
+                network_id,
+                conn_lock,
+            )
+            .await?;
+        }
+        Msg::TxHashSyn(tx_hash_sync) if is_light => {
+            light::recv_tx_hash_syn_light(tx_hash_sync, peer, conn_lock).await?;
         }
         Msg::TxHashSyn(tx_hash_sync) => {
-            tx_hash::recv_tx_hash_syn(tx_hash_sync, machine, conn_lock).await?;
+            tx_hash::recv_tx_hash_syn(tx_hash_sync, machine, peer, gossip, conn_lock).await?;
         }
         Msg::TxHashAck(tx_hash_sync) => {
             tx_hash::recv_tx_hash_ack(tx_hash_sync, task_queue).await?;
         }
         Msg::TxSyn(tx_syn) => {
-            tx::recv_tx_syn(tx_syn, machine, conn_lock).await?;
+            tx::recv_tx_syn(tx_syn, machine, peer, conn_lock).await?;
         }
         Msg::TxAck(tx_ack) => {
             tx::recv_tx_ack(tx_ack, machine, conn_lock).await?;
         }
+        Msg::BlockHashSyn(block_hash_syn) if is_light => {
+            light::recv_block_hash_syn_light(block_hash_syn, machine, peer, conn_lock).await?;
+        }
         Msg::BlockHashSyn(block_hash_syn) => {
-            block_hash::recv_block_hash_syn(block_hash_syn, machine, conn_lock).await?;
+            block_hash::recv_block_hash_syn(block_hash_syn, machine, peer, gossip, conn_lock)
+                .await?;
         }
         Msg::BlockHashAck(block_hash_ack) => {
-            block_hash::recv_block_hash_ack(block_hash_ack, task_queue).await?;
+            block_hash::recv_block_hash_ack(block_hash_ack, machine, peer, task_queue, block_relay)
+                .await?;
         }
         Msg::BlockSyn(block_syn_msg) => {
-            block::recv_block_syn(block_syn_msg, machine, conn_lock).await?;
+            block::recv_block_syn(block_syn_msg, machine, peer, conn_lock).await?;
         }
         Msg::BlockAck(block_ack_msg) => {
             block::recv_block_ack(block_ack_msg, machine).await?;
         }
+        Msg::CompactBlockSyn(compact_block_syn_msg) => {
+            compact_block::recv_compact_block_syn(compact_block_syn_msg, machine, peer, conn_lock)
+                .await?;
+        }
+        Msg::BlockTxsReq(block_txs_req_msg) => {
+            compact_block::recv_block_txs_req(block_txs_req_msg, machine, peer, conn_lock).await?;
+        }
+        Msg::BlockTxs(block_txs_msg) => {
+            compact_block::recv_block_txs(block_txs_msg, machine, peer, conn_lock).await?;
+        }
+        Msg::FullBlockReq(full_block_req) => {
+            block_hash::recv_full_block_req(full_block_req, machine, peer, task_queue).await?;
+        }
+        Msg::HeaderReq(header_req) if !is_light => {
+            light::recv_header_req(header_req, machine, conn_lock).await?;
+        }
+        Msg::Headers(headers) if is_light => {
+            light::recv_headers(headers, machine, peer, light_client, conn_lock).await?;
+        }
+        Msg::AuthPathReq(auth_path_req) if !is_light => {
+            light::recv_auth_path_req(auth_path_req, machine, conn_lock).await?;
+        }
+        Msg::CmIdxReq(cm_idx_req) if !is_light => {
+            light::recv_cm_idx_req(cm_idx_req, machine, conn_lock).await?;
+        }
+        Msg::CtrQueryReq(ctr_query_req) if !is_light => {
+            light::recv_ctr_query_req(ctr_query_req, machine, conn_lock).await?;
+        }
+        Msg::AuthPath(auth_path) => {
+            light::recv_light_res(LightRes::AuthPath(auth_path), peer, light_client).await?;
+        }
+        Msg::CmIdx(cm_idx) => {
+            light::recv_light_res(LightRes::CmIdx(cm_idx), peer, light_client).await?;
+        }
+        Msg::CtrQuery(ctr_query) => {
+            light::recv_light_res(LightRes::CtrQuery(ctr_query), peer, light_client).await?;
+        }
+        Msg::Error(error_msg) => {
+            warn!(
+                "Peer has sent an error, her_public_key: {}, err: {}",
+                peer.get_public_key_short(),
+                error_msg.error,
+            );
+        }
         _ => {
+            peer.penalize(Misbehavior::UnexpectedMsg).await;
+
             return Err(format!("Msg not valid at this stage, discarding, msg: {:?}", msg).into());
         }
     };
@@ -0,0 +1,14 @@
+use crate::node::SaksahaNodeError;
+use sak_p2p_transport::{Msg, PingMsg, UpgradedConn};
+use tokio::sync::RwLockWriteGuard;
+
+pub(in crate::node) async fn recv_ping(
+    ping: PingMsg,
+    mut conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
+) -> Result<(), SaksahaNodeError> {
+    conn_lock
+        .send(Msg::Pong(PingMsg { nonce: ping.nonce }))
+        .await;
+
+    Ok(())
+}
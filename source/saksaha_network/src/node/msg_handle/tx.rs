@@ -1,6 +1,7 @@
 use crate::node::{task::NodeTask, SaksahaNodeError};
 use sak_logger::{debug, info, warn};
 use sak_machine::SakMachine;
+use sak_p2p_peertable::{Misbehavior, Peer};
 use sak_p2p_transport::{ErrorMsg, Msg, TxAckMsg, TxSynMsg, UpgradedConn};
 use sak_task_queue::TaskQueue;
 use sak_types::TxHash;
@@ -36,14 +37,29 @@ pub(in crate::node) async fn recv_tx_ack(
 pub(in crate::node) async fn recv_tx_syn(
     tx_syn: TxSynMsg,
     machine: &SakMachine,
+    peer: &Arc<Peer>,
     mut conn_lock: RwLockWriteGuard<'_, UpgradedConn>,
 ) -> Result<(), SaksahaNodeError> {
-    machine
+    let tx_hashes: Vec<TxHash> = tx_syn
+        .tx_candidates
+        .iter()
+        .map(|tc| tc.get_tx_hash().to_string())
+        .collect();
+
+    peer.mark_known(&tx_hashes).await;
+
+    let invalid_count = machine
         .ledger
         // .dist_ledger
         .insert_into_pool(tx_syn.tx_candidates)
         .await;
 
+    if invalid_count > 0 {
+        peer.penalize(Misbehavior::InvalidTx).await;
+    } else {
+        peer.reward().await;
+    }
+
     let tx_ack_msg = Msg::TxAck(TxAckMsg {});
 
     conn_lock.send(tx_ack_msg).await;
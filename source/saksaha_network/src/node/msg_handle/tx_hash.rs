@@ -1,4 +1,4 @@
-use crate::node::{task::NodeTask, SaksahaNodeError};
+use crate::node::{gossip::Gossip, task::NodeTask, SaksahaNodeError};
 use sak_logger::{debug, info, warn};
 use sak_machine::SakMachine;
 use sak_p2p_peertable::Peer;
@@ -35,14 +35,21 @@ pub(in crate::node) async fn recv_tx_hash_ack(
 pub(in crate::node) async fn recv_tx_hash_syn(
     tx_hash_syn_msg: TxHashSyncMsg,
     machine: &Arc<SakMachine>,
+    peer: &Arc<Peer>,
+    gossip: &Arc<Gossip>,
     mut conn: RwLockWriteGuard<'_, UpgradedConn>,
 ) -> Result<(), SaksahaNodeError> {
-    let txs_to_request = machine
+    peer.mark_known(&tx_hash_syn_msg.tx_hashes).await;
+
+    let txs_not_in_pool = machine
         .ledger
         // .dist_ledger
         .get_tx_pool_diff(tx_hash_syn_msg.tx_hashes)
         .await;
 
+    // Txs announced by several peers at once are requested from one of them
+    let txs_to_request = gossip.take_unrequested(txs_not_in_pool).await;
+
     conn.send(Msg::TxHashAck(TxHashSyncMsg {
         tx_hashes: txs_to_request,
     }))
@@ -1,14 +1,28 @@
 use super::task;
 use super::{msg_handle, SaksahaNodeError};
-use crate::node::event_handle::{self, LedgerEventRoutine};
+use crate::node::gossip::Gossip;
+use crate::node::heartbeat::{Heartbeat, MAX_MISSED_PONGS};
 use crate::node::task::NodeTask;
+use crate::node::BlockRelay;
+use crate::node::{FaultInjector, LightClient, LinkFault};
 use sak_logger::{debug, error, warn};
 use sak_machine::SakMachine;
 use sak_p2p_discovery::Discovery;
-use sak_p2p_peertable::{Peer, PeerStatus, PeerTable};
+use sak_p2p_peertable::{Misbehavior, Peer, PeerStatus, PeerTable};
+use sak_p2p_transport::{Msg, PingMsg};
 use sak_task_queue::TaskQueue;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+// A banned peer is not connected for a day
+const PEER_BAN_DURATION_SEC: i64 = 24 * 3600;
+
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+
+// Time a peer node is given on shutdown to send the tasks queued so far,
+// within the shutdown timeout of the node
+const TASK_DRAIN_TIMEOUT: Duration = Duration::from_secs(3);
 
 pub(in crate::node) struct PeerNode {
     pub peer_table: Arc<PeerTable>,
@@ -16,6 +30,12 @@ pub(in crate::node) struct PeerNode {
     pub machine: Arc<SakMachine>,
     pub discovery: Arc<Discovery>,
     pub node_task_min_interval: Duration,
+    pub block_relay: BlockRelay,
+    pub gossip: Arc<Gossip>,
+    pub light_client: Option<Arc<LightClient>>,
+    pub network_id: String,
+    pub fault_injector: Arc<FaultInjector>,
+    pub shutdown_token: CancellationToken,
 }
 
 impl PeerNode {
@@ -28,24 +48,21 @@ impl PeerNode {
 
         let node_task_queue = Arc::new(TaskQueue::new(100));
 
-        {
-            let ledger_event_rx = {
-                let rx = self.machine.ledger.ledger_event_tx.clone().subscribe();
+        self.gossip
+            .register(self.peer.clone(), node_task_queue.clone())
+            .await;
 
-                rx
-            };
+        let res = self.run_routine(&node_task_queue).await;
 
-            let mut ledger_event_routine = LedgerEventRoutine {
-                ledger_event_rx,
-                machine: self.machine.clone(),
-                node_task_queue: node_task_queue.clone(),
-            };
+        self.gossip.unregister(&self.peer).await;
 
-            tokio::spawn(async move {
-                ledger_event_routine.run().await;
-            });
-        }
+        res
+    }
 
+    async fn run_routine(
+        &self,
+        node_task_queue: &Arc<TaskQueue<NodeTask>>,
+    ) -> Result<(), SaksahaNodeError> {
         {
             // say hello
             let unknown_addrs = self.peer_table.get_peer_addrs().await;
@@ -57,37 +74,70 @@ impl PeerNode {
             }
         }
 
-        {
-            // Late sync routine
-            if let Ok(new_blocks) = self
-                .machine
-                .ledger
-                // .dist_ledger
-                .get_all_blocks()
-                .await
-            {
-                if new_blocks.len() > 1 {
-                    node_task_queue
-                        .push_back(NodeTask::SendBlockHashSyn { new_blocks })
-                        .await?
-                }
-            }
-        }
+        let clock = self.machine.ledger.clock.clone();
+
+        let mut heartbeat = Heartbeat::new();
+
+        // The first ping goes out right away
+        let mut next_ping_at = clock.now();
 
         loop {
             let mut conn_lock = self.peer.get_transport().conn.write().await;
 
+            let ping_wait = clock.sleep(next_ping_at.saturating_duration_since(clock.now()));
+
             tokio::select! {
+                _ = self.shutdown_token.cancelled() => {
+                    drop(conn_lock);
+
+                    let tasks = node_task_queue.close().await;
+                    let task_count = tasks.len();
+
+                    tokio::select! {
+                        _ = self.drain_tasks(tasks) => {},
+                        _ = clock.sleep(TASK_DRAIN_TIMEOUT) => {
+                            warn!(
+                                "Peer node could not drain the tasks in {:?}, \
+                                her_public_key: {}",
+                                TASK_DRAIN_TIMEOUT,
+                                self.peer.get_public_key_short(),
+                            );
+                        },
+                    };
+
+                    debug!(
+                        "Peer node has stopped, her_public_key: {}, \
+                        queued tasks: {}",
+                        self.peer.get_public_key_short(),
+                        task_count,
+                    );
+
+                    return Ok(());
+                },
+                _ = ping_wait => {
+                    next_ping_at = clock.now() + PING_INTERVAL;
+
+                    let ping = heartbeat.make_ping(clock.now());
+
+                    if heartbeat.is_dead() {
+                        return Err(self.drop_unresponsive_peer().await);
+                    }
+
+                    if let Err(err) = conn_lock.send(Msg::Ping(ping)).await {
+                        warn!("Failed to send ping, err: {}", err);
+                    }
+                },
                 task = node_task_queue.pop_front() => {
 
                     let task = task?;
 
                     match task::handle_task(
                         task,
-                        &node_task_queue,
                         conn_lock,
                         &self.machine,
-                        &self.discovery
+                        &self.peer,
+                        &self.light_client,
+                        &self.network_id,
                     ).await {
                         Ok(r) => r,
                         Err(err) => {
@@ -97,24 +147,65 @@ impl PeerNode {
                             );
                         }
                     };
+
+                    if self.peer.is_disconnected().await {
+                        return Err(format!(
+                            "Peer has been disconnected, her_public_key: {}",
+                            self.peer.get_public_key_short(),
+                        )
+                        .into());
+                    }
                 },
                 maybe_msg = conn_lock.next_msg() => {
                     match maybe_msg {
                         Some(msg) => match msg {
+                            Ok(Msg::Pong(pong)) => {
+                                self.recv_pong(&mut heartbeat, &pong).await;
+                            }
                             Ok(m) => {
-                                let _ = msg_handle::handle_msg(
-                                    m,
-                                    &self.machine,
-                                    conn_lock,
-                                    &node_task_queue,
-                                    // &self.peer,
-                                    &self.peer_table,
-                                    &self.discovery,
-                                )
-                                .await;
+                                if !self.pass_link_fault(&m).await {
+                                    debug!(
+                                        "Dropped a msg by an injected fault, \
+                                        msg: {}",
+                                        m.get_type(),
+                                    );
+                                } else if self
+                                    .peer
+                                    .allow_msg(m.get_type(), clock.now().into_std())
+                                    .await
+                                {
+                                    let _ = msg_handle::handle_msg(
+                                        m,
+                                        &self.machine,
+                                        conn_lock,
+                                        node_task_queue,
+                                        &self.peer,
+                                        &self.peer_table,
+                                        &self.discovery,
+                                        &self.gossip,
+                                        self.block_relay,
+                                        &self.light_client,
+                                        &self.network_id,
+                                    )
+                                    .await;
+                                } else {
+                                    debug!(
+                                        "Peer exceeded the rate limit, \
+                                        discarding msg: {}",
+                                        m.get_type(),
+                                    );
+
+                                    self.peer.penalize(
+                                        Misbehavior::RateLimited,
+                                    ).await;
+                                }
                             }
                             Err(err) => {
                                 error!("Failed to parse the msg, err: {}", err);
+
+                                self.peer.penalize(
+                                    Misbehavior::ParseError,
+                                ).await;
                             }
                         },
                         None => {
@@ -132,8 +223,127 @@ impl PeerNode {
                         }
                     };
 
+                    if self.peer.get_score().await.should_ban() {
+                        return Err(self.ban_peer().await);
+                    }
+
+                    if self.peer.is_disconnected().await {
+                        return Err(format!(
+                            "Peer has been disconnected, her_public_key: {}",
+                            self.peer.get_public_key_short(),
+                        )
+                        .into());
+                    }
                 }
             }
         }
     }
+
+    // Tasks left in the queue on shutdown, such as the relays of the latest
+    // txs and blocks, still go out to the peer
+    async fn drain_tasks(&self, tasks: Vec<NodeTask>) {
+        for task in tasks {
+            let conn_lock = self.peer.get_transport().conn.write().await;
+
+            if let Err(err) = task::handle_task(
+                task,
+                conn_lock,
+                &self.machine,
+                &self.peer,
+                &self.light_client,
+                &self.network_id,
+            )
+            .await
+            {
+                warn!("Could not handle a task on shutdown, err: {}", err);
+            }
+        }
+    }
+
+    // Keepalives always go through, so a link survives a partition
+    async fn pass_link_fault(&self, msg: &Msg) -> bool {
+        if let Msg::Ping(_) = msg {
+            return true;
+        }
+
+        match self
+            .fault_injector
+            .get_fault(self.peer.get_public_key())
+            .await
+        {
+            None => true,
+            Some(LinkFault::Drop) => false,
+            Some(LinkFault::Delay(delay)) => {
+                self.machine.ledger.clock.sleep(delay).await;
+
+                true
+            }
+        }
+    }
+
+    async fn recv_pong(&self, heartbeat: &mut Heartbeat, pong: &PingMsg) {
+        match heartbeat.recv_pong(pong, self.machine.ledger.clock.now()) {
+            Some(rtt) => {
+                debug!(
+                    "Received pong, her_public_key: {}, rtt: {:?}",
+                    self.peer.get_public_key_short(),
+                    rtt,
+                );
+
+                self.peer.set_rtt(rtt).await;
+            }
+            None => {
+                debug!(
+                    "Received a pong not answering the latest ping, \
+                    her_public_key: {}",
+                    self.peer.get_public_key_short(),
+                );
+            }
+        }
+    }
+
+    // The addr of a disconnected peer is removed from the disc addr table
+    // by the discovery runtime, and the slot is put back once the peer is
+    // dropped.
+    async fn drop_unresponsive_peer(&self) -> SaksahaNodeError {
+        self.peer.set_peer_status(PeerStatus::Disconnected).await;
+
+        self.peer_table
+            .remove_mapping(&self.peer.get_public_key().to_string())
+            .await;
+
+        format!(
+            "Peer has missed {} pongs in a row, her_public_key: {}",
+            MAX_MISSED_PONGS,
+            self.peer.get_public_key_short(),
+        )
+        .into()
+    }
+
+    async fn ban_peer(&self) -> SaksahaNodeError {
+        let score = self.peer.get_score().await;
+
+        warn!(
+            "Banning a misbehaving peer, her_public_key: {}, score: {}",
+            self.peer.get_public_key_short(),
+            score.score,
+        );
+
+        if let Err(err) = self
+            .discovery
+            .addr_table
+            .ban(&self.peer.get_addr().known_addr, PEER_BAN_DURATION_SEC)
+            .await
+        {
+            warn!("Could not ban the peer, err: {}", err);
+        }
+
+        self.peer.set_peer_status(PeerStatus::Disconnected).await;
+
+        format!(
+            "Peer has been banned, her_public_key: {}",
+            self.peer.get_public_key_short(),
+        )
+        .into()
+    }
 }
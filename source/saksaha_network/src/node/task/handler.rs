@@ -1,23 +1,31 @@
 use super::NodeTask;
-use crate::node::{msg_handle, SaksahaNodeError};
+use crate::node::{msg_handle, LightClient, SaksahaNodeError};
 use sak_logger::{debug, error, warn};
 use sak_machine::SakMachine;
-use sak_p2p_discovery::Discovery;
+use sak_p2p_peertable::Peer;
 use sak_p2p_transport::UpgradedConn;
-use sak_task_queue::TaskQueue;
 use std::sync::Arc;
 use tokio::sync::RwLockWriteGuard;
 
 pub(in crate::node) async fn handle_task<'a>(
     task: NodeTask,
-    task_queue: &Arc<TaskQueue<NodeTask>>,
     conn_lock: RwLockWriteGuard<'a, UpgradedConn>,
     machine: &Arc<SakMachine>,
-    discovery: &Arc<Discovery>,
+    peer: &Arc<Peer>,
+    light_client: &Option<Arc<LightClient>>,
+    network_id: &str,
 ) -> Result<(), SaksahaNodeError> {
     match task {
         NodeTask::SendHelloSyn { unknown_addrs } => {
-            msg_handle::send_hello_syn(conn_lock, discovery, unknown_addrs, task_queue).await?;
+            msg_handle::send_hello_syn(
+                conn_lock,
+                machine,
+                peer,
+                light_client,
+                network_id,
+                unknown_addrs,
+            )
+            .await?;
         }
         NodeTask::SendTxHashSyn { tx_hashes } => {
             msg_handle::send_tx_hash_syn(conn_lock, tx_hashes).await?;
@@ -31,6 +39,26 @@ pub(in crate::node) async fn handle_task<'a>(
         NodeTask::SendBlockSyn { new_blocks } => {
             msg_handle::send_block_syn(conn_lock, new_blocks, &machine).await?;
         }
+        NodeTask::SendCompactBlockSyn { new_blocks } => {
+            msg_handle::send_compact_block_syn(conn_lock, new_blocks, &machine).await?;
+        }
+        NodeTask::SendHeaderReq { from_height } => {
+            msg_handle::send_header_req(conn_lock, from_height).await?;
+        }
+        NodeTask::SendAuthPathReq { req_id, cm_idx } => {
+            msg_handle::send_auth_path_req(conn_lock, req_id, cm_idx).await?;
+        }
+        NodeTask::SendCmIdxReq { req_id, cm } => {
+            msg_handle::send_cm_idx_req(conn_lock, req_id, cm).await?;
+        }
+        NodeTask::SendCtrQueryReq {
+            req_id,
+            ctr_addr,
+            req_type,
+            args,
+        } => {
+            msg_handle::send_ctr_query_req(conn_lock, req_id, ctr_addr, req_type, args).await?;
+        }
     };
 
     Ok(())
@@ -1,5 +1,5 @@
 use sak_p2p_addr::UnknownAddr;
-use sak_types::{BlockHash, BlockHeight, TxHash};
+use sak_types::{BlockHash, BlockHeight, Cm, CmIdx, TxHash};
 
 #[derive(Debug)]
 pub(in crate::node) enum NodeTask {
@@ -18,6 +18,26 @@ pub(in crate::node) enum NodeTask {
     SendBlockSyn {
         new_blocks: Vec<(BlockHeight, BlockHash)>,
     },
+    SendCompactBlockSyn {
+        new_blocks: Vec<(BlockHeight, BlockHash)>,
+    },
+    SendHeaderReq {
+        from_height: BlockHeight,
+    },
+    SendAuthPathReq {
+        req_id: u64,
+        cm_idx: CmIdx,
+    },
+    SendCmIdxReq {
+        req_id: u64,
+        cm: Cm,
+    },
+    SendCtrQueryReq {
+        req_id: u64,
+        ctr_addr: String,
+        req_type: String,
+        args: Vec<u8>,
+    },
 }
 
 impl std::fmt::Display for NodeTask {
@@ -38,6 +58,21 @@ impl std::fmt::Display for NodeTask {
             Self::SendBlockSyn { .. } => {
                 write!(f, "SendBlockSyn",)
             }
+            Self::SendCompactBlockSyn { .. } => {
+                write!(f, "SendCompactBlockSyn",)
+            }
+            Self::SendHeaderReq { .. } => {
+                write!(f, "SendHeaderReq",)
+            }
+            Self::SendAuthPathReq { .. } => {
+                write!(f, "SendAuthPathReq",)
+            }
+            Self::SendCmIdxReq { .. } => {
+                write!(f, "SendCmIdxReq",)
+            }
+            Self::SendCtrQueryReq { .. } => {
+                write!(f, "SendCtrQueryReq",)
+            }
         }
     }
 }
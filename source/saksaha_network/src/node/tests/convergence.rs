@@ -0,0 +1,69 @@
+use crate::tests::TestNet;
+use std::time::Duration;
+
+const CONVERGE_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_nodes_converge_on_the_mined_block() {
+    let test_net = TestNet::builder()
+        .nodes(3)
+        .miners(&[0])
+        .mine_interval(1000)
+        .build()
+        .await;
+
+    // A slow link does not keep the nodes from converging
+    test_net.delay_msgs(0, 1, Duration::from_millis(200)).await;
+
+    // Sent to a node that does not mine, so that the tx has to travel
+    test_net
+        .send_tx(2, sak_types::mock_pour_tc_random())
+        .await
+        .expect("Node should be able to send a transaction");
+
+    let (height, _) = test_net
+        .wait_until_same_tip(1, CONVERGE_TIMEOUT)
+        .await
+        .expect("Nodes should converge");
+
+    assert!(height >= 1);
+
+    test_net.shutdown().await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_partitioned_node_catches_up_after_heal_and_restart() {
+    let mut test_net = TestNet::builder()
+        .nodes(3)
+        .miners(&[0])
+        .mine_interval(1000)
+        .build()
+        .await;
+
+    test_net.partition(&[&[0, 1], &[2]]).await;
+
+    test_net
+        .send_tx(0, sak_types::mock_pour_tc_random())
+        .await
+        .expect("Node should be able to send a transaction");
+
+    tokio::time::sleep(Duration::from_secs(5)).await;
+
+    let (height_0, _) = test_net.node(0).get_tip().await.unwrap();
+    let (height_2, _) = test_net.node(2).get_tip().await.unwrap();
+
+    assert!(height_0 >= 1);
+    assert_eq!(height_2, 0, "Partitioned node should not get the block");
+
+    test_net.heal().await;
+
+    // The node hears of the blocks she has missed at hello
+    test_net.restart(2).await.expect("Node should be restarted");
+
+    test_net
+        .wait_until_same_tip(height_0, CONVERGE_TIMEOUT)
+        .await
+        .expect("Nodes should converge after the partition heals");
+
+    test_net.shutdown().await;
+}
@@ -0,0 +1,62 @@
+use crate::node::gossip::{fanout, shuffle_front, Gossip, MIN_GOSSIP_FANOUT};
+use sak_crypto::SeededRandomSource;
+use sak_utils_time::SimClock;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[test]
+fn test_gossip_fanout_grows_with_sqrt_of_peers() {
+    assert_eq!(fanout(0), 0);
+    assert_eq!(fanout(2), 2);
+    assert_eq!(fanout(MIN_GOSSIP_FANOUT), MIN_GOSSIP_FANOUT);
+    assert_eq!(fanout(10), MIN_GOSSIP_FANOUT);
+    assert_eq!(fanout(100), 10);
+    assert_eq!(fanout(101), 11);
+}
+
+#[test]
+fn test_gossip_shuffle_is_reproducible_from_the_seed() {
+    let shuffled = |seed: u64| {
+        let rng = SeededRandomSource::new(seed);
+        let mut v: Vec<usize> = (0..20).collect();
+
+        shuffle_front(&rng, &mut v, 5);
+
+        v
+    };
+
+    assert_eq!(shuffled(7), shuffled(7));
+    assert_ne!(shuffled(7), shuffled(8));
+
+    // Only the front is picked, the rest keeps every other element
+    let mut v = shuffled(7);
+    v.sort();
+    assert_eq!(v, (0..20).collect::<Vec<_>>());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_gossip_requests_a_hash_announced_twice_once() {
+    let clock = Arc::new(SimClock::new());
+
+    let gossip = Gossip::new(clock.clone(), Arc::new(SeededRandomSource::new(0)));
+
+    let first = gossip
+        .take_unrequested(vec!["a".to_string(), "b".to_string()])
+        .await;
+
+    assert_eq!(first, vec!["a".to_string(), "b".to_string()]);
+
+    // The same hashes announced by another peer
+    let second = gossip
+        .take_unrequested(vec!["b".to_string(), "c".to_string()])
+        .await;
+
+    assert_eq!(second, vec!["c".to_string()]);
+
+    // Requests that were never answered expire
+    clock.advance_by(Duration::from_secs(60));
+
+    let third = gossip.take_unrequested(vec!["b".to_string()]).await;
+
+    assert_eq!(third, vec!["b".to_string()]);
+}
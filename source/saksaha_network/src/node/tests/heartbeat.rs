@@ -0,0 +1,60 @@
+use crate::node::heartbeat::{Heartbeat, MAX_MISSED_PONGS};
+use sak_p2p_transport::PingMsg;
+use std::time::Duration;
+use tokio::time::Instant;
+
+#[test]
+fn test_heartbeat_measures_rtt_of_matching_pong() {
+    let mut heartbeat = Heartbeat::new();
+
+    let sent_at = Instant::now();
+    let recv_at = sent_at + Duration::from_millis(120);
+
+    let ping = heartbeat.make_ping(sent_at);
+
+    assert!(heartbeat
+        .recv_pong(
+            &PingMsg {
+                nonce: ping.nonce.wrapping_add(1)
+            },
+            recv_at,
+        )
+        .is_none());
+
+    assert_eq!(
+        heartbeat.recv_pong(&PingMsg { nonce: ping.nonce }, recv_at),
+        Some(Duration::from_millis(120)),
+    );
+
+    // The same pong does not answer twice
+    assert!(heartbeat
+        .recv_pong(&PingMsg { nonce: ping.nonce }, recv_at)
+        .is_none());
+}
+
+#[test]
+fn test_heartbeat_is_dead_after_missed_pongs() {
+    let mut heartbeat = Heartbeat::new();
+
+    let now = Instant::now();
+
+    let mut ping = heartbeat.make_ping(now);
+
+    for _ in 0..MAX_MISSED_PONGS - 1 {
+        ping = heartbeat.make_ping(now);
+
+        assert!(!heartbeat.is_dead());
+    }
+
+    heartbeat.recv_pong(&ping, now).unwrap();
+
+    heartbeat.make_ping(now);
+
+    for _ in 0..MAX_MISSED_PONGS {
+        assert!(!heartbeat.is_dead());
+
+        heartbeat.make_ping(now);
+    }
+
+    assert!(heartbeat.is_dead());
+}
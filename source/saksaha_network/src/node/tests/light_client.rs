@@ -0,0 +1,213 @@
+use super::utils::{make_test_context, TestContext};
+use crate::node::HeaderVotes;
+use crate::tests::SaksahaTestUtils;
+use sak_credential::CredentialProfile;
+use sak_types::Block;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_light_ledger_verifies_auth_path_against_synced_headers() {
+    let test_credential_1 = CredentialProfile::test_1();
+    let test_credential_2 = CredentialProfile::test_2();
+
+    SaksahaTestUtils::init_test(&[
+        &test_credential_1.public_key_str,
+        &test_credential_2.public_key_str,
+    ]);
+
+    let TestContext {
+        machine: full_machine,
+        ..
+    } = make_test_context(
+        None,
+        None,
+        test_credential_1.secret,
+        test_credential_1.public_key_str,
+        Some(false),
+    )
+    .await;
+
+    let TestContext {
+        machine: light_machine,
+        ..
+    } = make_test_context(
+        None,
+        None,
+        test_credential_2.secret,
+        test_credential_2.public_key_str,
+        Some(false),
+    )
+    .await;
+
+    full_machine
+        .ledger
+        .send_tx(sak_types::mock_pour_tc_random())
+        .await
+        .expect("Node should be able to send a transaction");
+
+    full_machine
+        .ledger
+        .write_block(None)
+        .await
+        .expect("Block should be written")
+        .expect("Block hash should be returned");
+
+    let latest_height = full_machine
+        .ledger
+        .get_latest_block_height()
+        .unwrap()
+        .expect("Latest block height should exist");
+
+    let mut headers = vec![];
+
+    for height in 0..=latest_height {
+        let block = full_machine
+            .ledger
+            .get_block_by_height(&height)
+            .await
+            .unwrap()
+            .expect("Block should exist");
+
+        headers.push(block);
+    }
+
+    // Genesis is already known to the light ledger
+    let written = light_machine
+        .ledger
+        .write_block_headers(headers.clone())
+        .await
+        .expect("Headers should be written");
+
+    assert_eq!(written.len() as u128, latest_height);
+
+    let written = light_machine
+        .ledger
+        .write_block_headers(headers)
+        .await
+        .expect("Known headers should be skipped");
+
+    assert!(written.is_empty());
+
+    let header = light_machine
+        .ledger
+        .get_block_by_height(&latest_height)
+        .await
+        .unwrap()
+        .expect("Header should be written");
+
+    let cm = full_machine
+        .ledger
+        .get_merkle_node(&"0_0".to_string())
+        .await
+        .unwrap();
+
+    let mut auth_path = full_machine.ledger.get_auth_path(&0).await.unwrap();
+
+    assert!(light_machine
+        .ledger
+        .verify_auth_path(&cm, &auth_path, &header.merkle_rt)
+        .unwrap());
+
+    auth_path[0].1 = !auth_path[0].1;
+
+    assert!(!light_machine
+        .ledger
+        .verify_auth_path(&cm, &auth_path, &header.merkle_rt)
+        .unwrap());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_light_client_writes_only_the_headers_full_peers_agree_on() {
+    let test_credential_1 = CredentialProfile::test_1();
+    let test_credential_2 = CredentialProfile::test_2();
+
+    SaksahaTestUtils::init_test(&[
+        &test_credential_1.public_key_str,
+        &test_credential_2.public_key_str,
+    ]);
+
+    let TestContext {
+        machine: full_machine,
+        ..
+    } = make_test_context(
+        None,
+        None,
+        test_credential_1.secret,
+        test_credential_1.public_key_str,
+        Some(false),
+    )
+    .await;
+
+    let TestContext {
+        machine: light_machine,
+        ..
+    } = make_test_context(
+        None,
+        None,
+        test_credential_2.secret,
+        test_credential_2.public_key_str,
+        Some(false),
+    )
+    .await;
+
+    full_machine
+        .ledger
+        .send_tx(sak_types::mock_pour_tc_random())
+        .await
+        .expect("Node should be able to send a transaction");
+
+    full_machine
+        .ledger
+        .write_block(None)
+        .await
+        .expect("Block should be written")
+        .expect("Block hash should be returned");
+
+    let header = full_machine
+        .ledger
+        .get_block_by_height(&1)
+        .await
+        .unwrap()
+        .expect("Block should exist");
+
+    let tampered_header = Block::new(
+        header.validator_sig.clone(),
+        header.tx_hashes.clone(),
+        header.witness_sigs.clone(),
+        header.created_at.clone(),
+        header.block_height,
+        [1; 32],
+    );
+
+    let mut header_votes = HeaderVotes::default();
+
+    let agreed = header_votes.add("peer_1", vec![tampered_header.clone()], 0);
+    assert!(agreed.is_empty());
+
+    let agreed = header_votes.add("peer_2", vec![header.clone()], 0);
+    assert!(agreed.is_empty(), "Peers disagree on the header");
+
+    // Same peer sending it again does not make a quorum
+    let agreed = header_votes.add("peer_2", vec![header.clone()], 0);
+    assert!(agreed.is_empty());
+
+    let agreed = header_votes.add("peer_3", vec![header.clone()], 0);
+    assert_eq!(agreed, vec![header.clone()]);
+
+    light_machine
+        .ledger
+        .write_block_headers(agreed)
+        .await
+        .expect("Header should be written");
+
+    let written_header = light_machine
+        .ledger
+        .get_block_by_height(&1)
+        .await
+        .unwrap()
+        .expect("Header should be written");
+
+    assert_eq!(written_header.get_block_hash(), header.get_block_hash());
+
+    let agreed = header_votes.add("peer_1", vec![tampered_header], 1);
+    assert!(agreed.is_empty(), "Header below the latest one is dropped");
+}
@@ -1,6 +1,13 @@
 mod concurrent_sync;
+mod convergence;
+mod gossip;
+mod heartbeat;
+mod light_client;
 mod p2p_block_sync;
+mod p2p_compact_block;
 mod p2p_marshal_tx_pool;
 mod p2p_stream_cipher;
 mod p2p_tx_sync;
+mod shutdown;
+mod sim;
 mod utils;
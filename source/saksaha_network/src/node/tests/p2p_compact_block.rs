@@ -0,0 +1,117 @@
+use super::utils::{make_test_context, TestContext};
+use crate::tests::SaksahaTestUtils;
+use sak_credential::CredentialProfile;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_compact_block_is_rebuilt_from_the_pool_and_missing_txs() {
+    let test_credential_1 = CredentialProfile::test_1();
+    let test_credential_2 = CredentialProfile::test_2();
+
+    SaksahaTestUtils::init_test(&[
+        &test_credential_1.public_key_str,
+        &test_credential_2.public_key_str,
+    ]);
+
+    let TestContext {
+        p2p_host: p2p_host_1,
+        local_node: local_node_1,
+        machine: machine_1,
+        ..
+    } = make_test_context(
+        Some(35519),
+        Some(35518),
+        test_credential_1.secret,
+        test_credential_1.public_key_str,
+        Some(false),
+    )
+    .await;
+
+    let TestContext {
+        p2p_host: p2p_host_2,
+        local_node: local_node_2,
+        machine: machine_2,
+        ..
+    } = make_test_context(
+        Some(35521),
+        Some(35520),
+        test_credential_2.secret,
+        test_credential_2.public_key_str,
+        Some(false),
+    )
+    .await;
+
+    {
+        let machine_1 = machine_1.clone();
+        tokio::spawn(async move {
+            tokio::join!(p2p_host_1.run(), local_node_1.run(), machine_1.run());
+        });
+
+        let machine_2 = machine_2.clone();
+        tokio::spawn(async move {
+            tokio::join!(p2p_host_2.run(), local_node_2.run(), machine_2.run());
+        });
+    }
+
+    // The tx reaches node_2 before the block does, so the block is rebuilt
+    // out of her pool
+    let tx_1 = sak_types::mock_pour_tc_random();
+
+    machine_1
+        .ledger
+        .send_tx(tx_1.clone())
+        .await
+        .expect("Node should be able to send a transaction");
+
+    tokio::time::sleep(Duration::from_secs(4)).await;
+
+    assert!(
+        machine_2.ledger.tx_pool_contains(tx_1.get_tx_hash()).await,
+        "tx pool 2 should contain tx 1"
+    );
+
+    machine_1
+        .ledger
+        .write_block(None)
+        .await
+        .expect("Block should be written");
+
+    tokio::time::sleep(Duration::from_secs(5)).await;
+
+    let height_1 = machine_1.ledger.get_latest_block_height().unwrap();
+    let height_2 = machine_2.ledger.get_latest_block_height().unwrap();
+
+    assert_eq!(height_1, height_2, "block 1 should be rebuilt on node 2");
+
+    assert!(
+        !machine_2.ledger.tx_pool_contains(tx_1.get_tx_hash()).await,
+        "tx 1 should leave the pool once its block is written"
+    );
+
+    // The block is written before its tx is synced, so node_2 asks for it
+    let tx_2 = sak_types::mock_pour_tc_random();
+
+    machine_1
+        .ledger
+        .send_tx(tx_2.clone())
+        .await
+        .expect("Node should be able to send a transaction");
+
+    machine_1
+        .ledger
+        .write_block(None)
+        .await
+        .expect("Block should be written");
+
+    assert!(!machine_2.ledger.tx_pool_contains(tx_2.get_tx_hash()).await);
+
+    tokio::time::sleep(Duration::from_secs(5)).await;
+
+    let height_1 = machine_1.ledger.get_latest_block_height().unwrap();
+    let height_2 = machine_2.ledger.get_latest_block_height().unwrap();
+
+    assert_eq!(
+        height_1, height_2,
+        "block 2 should be completed with the txs node 2 missed"
+    );
+}
@@ -39,6 +39,7 @@ async fn test_two_nodes_talk_on_stream_cipher() {
         machine: machine_1,
         peer_table: peer_table_1,
         identity: identity_1,
+        ..
     } = test_context_1;
 
     let test_context_2 = utils::make_test_context(
@@ -0,0 +1,128 @@
+use super::utils::{make_test_context, TestContext};
+use crate::fs::SaksahaFS;
+use crate::ledger::Ledger;
+use crate::mrs::MRS;
+use crate::system::ShutdownMng;
+use crate::tests::SaksahaTestUtils;
+use sak_credential::CredentialProfile;
+use sak_machine::SakMachine;
+use sak_store_interface::MRSAccessor;
+use sak_vm::SakVM;
+use sak_vm_interface::ContractProcessor;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_restart_after_shutdown_keeps_ledger() {
+    let test_credential = CredentialProfile::test_1();
+
+    SaksahaTestUtils::init_test(&[&test_credential.public_key_str]);
+
+    let TestContext {
+        p2p_host,
+        local_node,
+        machine,
+        identity,
+        shutdown_token,
+        ..
+    } = make_test_context(
+        None,
+        None,
+        test_credential.secret.clone(),
+        test_credential.public_key_str.clone(),
+        Some(false),
+    )
+    .await;
+
+    let shutdown_manager = ShutdownMng::new(shutdown_token.clone());
+
+    let discovery = p2p_host.get_discovery();
+
+    let system_thread = {
+        let machine = machine.clone();
+        let local_node = local_node.clone();
+
+        tokio::spawn(async move {
+            tokio::join!(p2p_host.run(), local_node.run(), async {
+                tokio::select! {
+                    _ = machine.run() => {},
+                    _ = shutdown_token.cancelled() => {},
+                }
+            });
+        })
+    };
+
+    machine
+        .ledger
+        .send_tx(sak_types::mock_pour_tc_random())
+        .await
+        .expect("Node should be able to send a transaction");
+
+    let block_hash = machine
+        .ledger
+        .write_block(None)
+        .await
+        .expect("Block should be written")
+        .expect("Block hash should be returned");
+
+    let latest_height = machine
+        .ledger
+        .get_latest_block_height()
+        .unwrap()
+        .expect("Latest block height should exist");
+
+    tokio::time::timeout(
+        Duration::from_secs(15),
+        shutdown_manager.shutdown(Some(system_thread), local_node.clone(), machine, discovery),
+    )
+    .await
+    .expect("Shutdown should finish in time")
+    .expect("Shutdown should succeed");
+
+    drop(local_node);
+
+    // The node comes back on the same data dir
+    let mrs = {
+        let m = MRS::init(&SaksahaFS::acc_dir(&test_credential.public_key_str).unwrap())
+            .await
+            .expect("MRS should be reopened");
+
+        Arc::new(Box::new(m) as MRSAccessor)
+    };
+
+    let vm: ContractProcessor = {
+        let v = SakVM::init(mrs.clone()).unwrap();
+        Box::new(v)
+    };
+
+    let ledger = Ledger::init(
+        &SaksahaFS::acc_dir(&test_credential.public_key_str).unwrap(),
+        None,
+        None,
+        None,
+        identity,
+        vm,
+        None,
+    )
+    .await
+    .expect("Ledger should be reopened");
+
+    let machine = SakMachine { ledger, mrs };
+
+    let restarted_height = machine
+        .ledger
+        .get_latest_block_height()
+        .unwrap()
+        .expect("Latest block height should survive the restart");
+
+    assert_eq!(latest_height, restarted_height);
+
+    let block = machine
+        .ledger
+        .get_block_by_height(&latest_height)
+        .await
+        .unwrap()
+        .expect("Block should survive the restart");
+
+    assert_eq!(&block_hash, block.get_block_hash());
+}
@@ -0,0 +1,76 @@
+use crate::tests::SimNet;
+use sak_types::BlockHeight;
+use std::time::Duration;
+
+// Virtual time, the run itself takes far less
+const CONVERGE_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[tokio::test(start_paused = true)]
+async fn test_sim_nodes_converge_on_the_mined_blocks() {
+    let sim_net = SimNet::builder()
+        .seed(7)
+        .nodes(4)
+        .miners(&[0])
+        .mine_interval(1000)
+        .latency(Duration::from_millis(20), Duration::from_millis(500))
+        .build()
+        .await;
+
+    // Sent to a node that does not mine, so that the tx has to travel
+    let tx_hash = sim_net
+        .send_tx(3, sak_types::mock_pour_tc_random())
+        .await
+        .expect("Node should be able to send a transaction");
+
+    let (height, _) = sim_net
+        .run_until_same_tip(2, CONVERGE_TIMEOUT)
+        .await
+        .expect("Sim nodes should converge");
+
+    assert!(height >= 2);
+    assert!(sim_net.elapsed() >= Duration::from_secs(1));
+
+    for idx in 0..4 {
+        let tx = sim_net
+            .node(idx)
+            .machine
+            .ledger
+            .get_tx(&tx_hash)
+            .await
+            .unwrap();
+
+        assert!(tx.is_some(), "Tx should be in the block of node {}", idx);
+    }
+
+    sim_net.shutdown().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_sim_runs_the_same_from_the_same_seed() {
+    let first = run_until_converged(11).await;
+    let second = run_until_converged(11).await;
+
+    assert_eq!(first, second);
+}
+
+// Height the nodes converge on, and the virtual time it takes them
+async fn run_until_converged(seed: u64) -> (BlockHeight, Duration) {
+    let sim_net = SimNet::builder()
+        .seed(seed)
+        .nodes(3)
+        .miners(&[0])
+        .mine_interval(1000)
+        .build()
+        .await;
+
+    let (height, _) = sim_net
+        .run_until_same_tip(2, CONVERGE_TIMEOUT)
+        .await
+        .expect("Sim nodes should converge");
+
+    let elapsed = sim_net.elapsed();
+
+    sim_net.shutdown().await;
+
+    (height, elapsed)
+}
@@ -1,3 +1,4 @@
+use crate::fs::SaksahaFS;
 use crate::ledger::Ledger;
 use crate::mrs::MRS;
 use crate::node::LocalNode;
@@ -16,6 +17,7 @@ use sak_store_interface::MRSAccessor;
 use sak_vm::SakVM;
 use sak_vm_interface::ContractProcessor;
 use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 
 pub(crate) struct TestContext {
     pub p2p_host: P2PHost,
@@ -23,6 +25,7 @@ pub(crate) struct TestContext {
     pub machine: Arc<SakMachine>,
     pub peer_table: Arc<PeerTable>,
     pub identity: Arc<Identity>,
+    pub shutdown_token: CancellationToken,
 }
 
 pub(crate) struct DualNodeTestContext {
@@ -71,7 +74,7 @@ pub(crate) async fn make_test_context(
     };
 
     let p2p_peer_table = {
-        let ps = PeerTable::init(None)
+        let ps = PeerTable::init(None, None, vec![])
             .await
             .expect("Peer table should be initialized");
 
@@ -101,6 +104,8 @@ pub(crate) async fn make_test_context(
         status: AddrStatus::Initialized,
     }];
 
+    let shutdown_token = CancellationToken::new();
+
     let p2p_host_args = P2PHostArgs {
         addr_expire_duration: None,
         addr_monitor_interval: None,
@@ -118,14 +123,20 @@ pub(crate) async fn make_test_context(
         bootstrap_addrs,
         identity: identity.clone(),
         peer_table: p2p_peer_table.clone(),
+        addr_book_path: None,
+        external_addr: None,
+        port_mapping: false,
+        shutdown_token: shutdown_token.clone(),
     };
 
     let p2p_host = P2PHost::init(p2p_host_args)
         .await
         .expect("P2P Host should be initialized");
 
+    let acc_dir = SaksahaFS::acc_dir(&public_key_str).unwrap();
+
     let mrs = {
-        let m = MRS::init(&public_key_str).await.unwrap();
+        let m = MRS::init(&acc_dir).await.unwrap();
         let m = Box::new(m) as MRSAccessor;
         Arc::new(m)
     };
@@ -136,7 +147,7 @@ pub(crate) async fn make_test_context(
     };
 
     let ledger = {
-        Ledger::init(&public_key_str, None, None, None, identity.clone(), vm)
+        Ledger::init(&acc_dir, None, None, None, identity.clone(), vm, None)
             .await
             .unwrap()
     };
@@ -155,7 +166,12 @@ pub(crate) async fn make_test_context(
             None,
             None,
             None,
+            None,
+            None,
+            None,
+            None,
             p2p_host.get_discovery().clone(),
+            shutdown_token.clone(),
         );
 
         Arc::new(ln)
@@ -167,6 +183,7 @@ pub(crate) async fn make_test_context(
         machine,
         peer_table: p2p_peer_table,
         identity,
+        shutdown_token,
     }
 }
 
@@ -3,13 +3,17 @@ use super::task::runtime::P2PTaskRuntime;
 use super::task::P2PTask;
 use super::{dial_scheduler::P2PDialScheduler, server::Server};
 use super::{P2PHostError, P2PMonitor};
+use sak_logger::info;
 use sak_p2p_addr::UnknownAddr;
 use sak_p2p_discovery::{Discovery, DiscoveryArgs};
 use sak_p2p_id::Identity;
 use sak_p2p_peertable::PeerTable;
 use sak_task_queue::TaskQueue;
+use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::net::{TcpListener, UdpSocket};
+use tokio_util::sync::CancellationToken;
 
 const P2P_TASK_QUEUE_CAPACITY: usize = 10;
 
@@ -21,6 +25,7 @@ pub(crate) struct P2PHost {
     p2p_task_runtime: P2PTaskRuntime,
     peer_table: Arc<PeerTable>,
     identity: Arc<Identity>,
+    shutdown_token: CancellationToken,
 }
 
 pub(crate) struct P2PHostArgs {
@@ -40,6 +45,10 @@ pub(crate) struct P2PHostArgs {
     pub(crate) identity: Arc<Identity>,
     pub(crate) disc_socket: UdpSocket,
     pub(crate) peer_table: Arc<PeerTable>,
+    pub(crate) addr_book_path: Option<PathBuf>,
+    pub(crate) external_addr: Option<SocketAddr>,
+    pub(crate) port_mapping: bool,
+    pub(crate) shutdown_token: CancellationToken,
 }
 
 impl P2PHost {
@@ -76,6 +85,9 @@ impl P2PHost {
                 udp_socket: p2p_host_args.disc_socket,
                 p2p_port: p2p_host_args.p2p_port,
                 bootstrap_addrs: p2p_host_args.bootstrap_addrs,
+                addr_book_path: p2p_host_args.addr_book_path,
+                external_addr: p2p_host_args.external_addr,
+                port_mapping: p2p_host_args.port_mapping,
             };
 
             let (disc, disc_port) = Discovery::init(disc_args).await?;
@@ -119,17 +131,31 @@ impl P2PHost {
             p2p_server,
             peer_table: p2p_host_args.peer_table.clone(),
             identity: p2p_host_args.identity.clone(),
+            shutdown_token: p2p_host_args.shutdown_token,
         };
 
         Ok(host)
     }
 
     pub(crate) async fn run(&self) {
-        tokio::join!(
-            self.p2p_task_runtime.run(),
-            self.p2p_discovery.run(),
-            self.p2p_server.run(),
-            self.p2p_dial_scheduler.run(),
+        tokio::select! {
+            _ = async {
+                tokio::join!(
+                    self.p2p_task_runtime.run(),
+                    self.p2p_discovery.run(),
+                    self.p2p_server.run(),
+                    self.p2p_dial_scheduler.run(),
+                )
+            } => {},
+            _ = self.shutdown_token.cancelled() => {},
+        };
+
+        let p2p_task_count = self.p2p_task_queue.close().await.len();
+        let disc_task_count = self.p2p_discovery.close_task_queue().await;
+
+        info!(
+            "P2P host has stopped, dropped p2p tasks: {}, dropped disc tasks: {}",
+            p2p_task_count, disc_task_count,
         );
     }
 
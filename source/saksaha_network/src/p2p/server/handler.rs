@@ -1,6 +1,6 @@
 use chrono::Utc;
 use futures::StreamExt;
-use sak_logger::{error, warn};
+use sak_logger::{debug, warn};
 use sak_p2p_discovery::AddrTable;
 use sak_p2p_id::Identity;
 use sak_p2p_peertable::{Peer, PeerStatus, PeerTable};
@@ -8,11 +8,21 @@ use sak_p2p_transport::{
     handshake::{self, HandshakeRecvArgs},
     Conn, Msg,
 };
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+};
 use tokio::sync::{RwLock, Semaphore};
 
+// Handshakes a single ip may have in progress. Loopback is not limited.
+const MAX_PENDING_HANDSHAKES_PER_IP: usize = 2;
+
+pub(super) type PendingHandshakes = Arc<Mutex<HashMap<IpAddr, usize>>>;
+
 pub(super) struct Handler {
     pub(crate) conn_semaphore: Arc<Semaphore>,
+    pub(crate) pending_handshakes: PendingHandshakes,
 }
 
 impl Handler {
@@ -23,18 +33,35 @@ impl Handler {
         peer_table: Arc<PeerTable>,
         addr_table: Arc<AddrTable>,
     ) {
-        let handshake_recv_args = HandshakeRecvArgs { identity };
+        let her_ip = conn.socket_addr.ip().to_string();
 
-        let peer_slot_guard = match peer_table.get_empty_slot().await {
-            Ok(s) => s,
-            Err(err) => {
-                error!(
-                    "Empty slot is not available in the peer table, err: {}",
-                    err
-                );
-                return;
-            }
-        };
+        // Handshake costs a key exchange, so the checks not depending on her
+        // key are done before it
+        if !peer_table.has_inbound_capacity().await {
+            debug!(
+                "Inbound slots are all taken, refusing the connection, \
+                her_ip: {}",
+                her_ip,
+            );
+
+            return;
+        }
+
+        let handshake_guard =
+            match HandshakeGuard::new(conn.socket_addr.ip(), &self.pending_handshakes) {
+                Some(g) => g,
+                None => {
+                    debug!(
+                        "Too many handshakes in progress from the ip, refusing \
+                        the connection, her_ip: {}",
+                        her_ip,
+                    );
+
+                    return;
+                }
+            };
+
+        let handshake_recv_args = HandshakeRecvArgs { identity };
 
         let (transport, her_public_key_str) =
             match handshake::receive_handshake(handshake_recv_args, conn).await {
@@ -45,6 +72,35 @@ impl Handler {
                 }
             };
 
+        drop(handshake_guard);
+
+        if addr_table.is_banned(&her_public_key_str) {
+            warn!(
+                "Peer is banned, refusing the handshake, her_public_key: {}",
+                her_public_key_str,
+            );
+
+            return;
+        }
+
+        // Slot is given once she is known, so that reserved peers and
+        // subnet limits can be told apart
+        let peer_slot_guard = match peer_table
+            .get_inbound_slot(&her_public_key_str, &her_ip)
+            .await
+        {
+            Ok(s) => s,
+            Err(err) => {
+                debug!(
+                    "Inbound slot is not available, refusing the peer, \
+                    her_public_key: {}, err: {}",
+                    her_public_key_str, err,
+                );
+
+                return;
+            }
+        };
+
         let addr = match addr_table.get_mapped_addr(&her_public_key_str).await {
             Some(a) => a,
             None => {
@@ -76,6 +132,64 @@ impl Handler {
     }
 }
 
+// Counts a handshake against the ip of the peer while it is in progress
+struct HandshakeGuard {
+    // None if the ip is not limited
+    ip: Option<IpAddr>,
+    pending_handshakes: PendingHandshakes,
+}
+
+impl HandshakeGuard {
+    fn new(ip: IpAddr, pending_handshakes: &PendingHandshakes) -> Option<HandshakeGuard> {
+        if ip.is_loopback() {
+            return Some(HandshakeGuard {
+                ip: None,
+                pending_handshakes: pending_handshakes.clone(),
+            });
+        }
+
+        let mut pending = match pending_handshakes.lock() {
+            Ok(p) => p,
+            Err(err) => err.into_inner(),
+        };
+
+        let count = pending.entry(ip).or_insert(0);
+
+        if *count >= MAX_PENDING_HANDSHAKES_PER_IP {
+            return None;
+        }
+
+        *count += 1;
+
+        Some(HandshakeGuard {
+            ip: Some(ip),
+            pending_handshakes: pending_handshakes.clone(),
+        })
+    }
+}
+
+impl Drop for HandshakeGuard {
+    fn drop(&mut self) {
+        let ip = match self.ip {
+            Some(i) => i,
+            None => return,
+        };
+
+        let mut pending = match self.pending_handshakes.lock() {
+            Ok(p) => p,
+            Err(err) => err.into_inner(),
+        };
+
+        if let Some(count) = pending.get_mut(&ip) {
+            *count -= 1;
+
+            if *count == 0 {
+                pending.remove(&ip);
+            }
+        }
+    }
+}
+
 impl Drop for Handler {
     fn drop(&mut self) {
         self.conn_semaphore.add_permits(1);
@@ -1,4 +1,4 @@
-use super::handler::Handler;
+use super::handler::{Handler, PendingHandshakes};
 use crate::p2p::P2PHostError;
 use colored::Colorize;
 use sak_logger::{debug, info, warn};
@@ -6,7 +6,11 @@ use sak_p2p_discovery::AddrTable;
 use sak_p2p_id::Identity;
 use sak_p2p_peertable::PeerTable;
 use sak_p2p_transport::Conn;
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 use tokio::{
     net::{TcpListener, TcpStream},
     sync::Semaphore,
@@ -16,6 +20,7 @@ const MAX_CONN_COUNT: usize = 50;
 
 pub(crate) struct Server {
     conn_semaphore: Arc<Semaphore>,
+    pending_handshakes: PendingHandshakes,
     p2p_socket: TcpListener,
     identity: Arc<Identity>,
     peer_table: Arc<PeerTable>,
@@ -46,6 +51,7 @@ impl Server {
 
         let s = Server {
             conn_semaphore,
+            pending_handshakes: Arc::new(Mutex::new(HashMap::new())),
             p2p_socket,
             identity,
             peer_table,
@@ -112,6 +118,7 @@ impl Server {
 
             let mut handler = Handler {
                 conn_semaphore: conn_semaphore.clone(),
+                pending_handshakes: self.pending_handshakes.clone(),
             };
 
             let identity = self.identity.clone();
@@ -1,5 +1,5 @@
 use crate::p2p::task::P2PTask;
-use sak_logger::{debug, error, warn};
+use sak_logger::{debug, warn};
 use sak_p2p_id::Identity;
 use sak_p2p_peertable::{Peer, PeerStatus};
 use sak_p2p_transport::{
@@ -27,12 +27,15 @@ pub(crate) async fn run(task: P2PTask, my_identity: Arc<Identity>) {
                 return;
             }
 
-            let peer_slot_guard = match peer_table.get_empty_slot().await {
+            let peer_slot_guard = match peer_table
+                .get_outbound_slot(&known_addr.public_key_str)
+                .await
+            {
                 Ok(p) => p,
                 Err(err) => {
-                    error!(
-                        "Fatal error. Empty slot is not available in the \
-                        peer table, err: {}",
+                    debug!(
+                        "Outbound slot is not available, abandoning \
+                        handshake init task, err: {}",
                         err
                     );
 
@@ -8,6 +8,7 @@ use sak_p2p_discovery::{Discovery, DiscoveryArgs};
 use sak_p2p_id::Identity;
 use sak_p2p_peertable::PeerTable;
 use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 
 // #[derive(Clone)]
 // pub(crate) struct MockClient {
@@ -33,7 +34,7 @@ pub(crate) async fn mock_p2p_host(
     };
 
     let peer_table = {
-        let ps = PeerTable::init(None)
+        let ps = PeerTable::init(None, None, vec![])
             .await
             .expect("Peer table should be initialized");
 
@@ -64,6 +65,10 @@ pub(crate) async fn mock_p2p_host(
         identity: identity.clone(),
         disc_socket: udp_socket,
         peer_table: peer_table.clone(),
+        addr_book_path: None,
+        external_addr: None,
+        port_mapping: false,
+        shutdown_token: CancellationToken::new(),
     };
 
     let p2p_host = {
@@ -103,7 +108,7 @@ pub(crate) async fn mock_p2p_host(
 //     };
 
 //     let peer_table = {
-//         let ps = PeerTable::init(None)
+//         let ps = PeerTable::init(None, None, vec![])
 //             .await
 //             .expect("Peer table should be initialized");
 
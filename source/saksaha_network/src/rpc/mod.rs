@@ -1,5 +1,6 @@
 mod routes;
 mod rpc;
+mod ws;
 
 #[cfg(test)]
 mod tests;
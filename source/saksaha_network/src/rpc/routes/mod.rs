@@ -60,6 +60,18 @@ pub(in crate::rpc) fn get_routes() -> HashMap<&'static str, Handler<Arc<SystemHa
                 Box::pin(v0::get_cm_idx(route_state, params, sys_handle))
             }),
         },
+        Path {
+            method: "get_session",
+            handler: Box::new(|route_state, params, sys_handle| {
+                Box::pin(v0::get_session(route_state, params, sys_handle))
+            }),
+        },
+        Path {
+            method: "confirm_session",
+            handler: Box::new(|route_state, params, sys_handle| {
+                Box::pin(v0::confirm_session(route_state, params, sys_handle))
+            }),
+        },
     ];
 
     let mut map = HashMap::new();
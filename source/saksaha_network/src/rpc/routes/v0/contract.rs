@@ -2,10 +2,10 @@ use crate::system::SystemHandle;
 use async_trait::async_trait;
 use hyper::{Body, Response};
 use hyper_rpc_router::{
-    make_error_response, make_success_response, require_params_parsed, require_some_params, Params,
-    RouteState,
+    make_error_response, make_error_response_with_data, make_success_response,
+    require_params_parsed, require_some_params, Params, RouteState,
 };
-use sak_contract_std::{CtrRequest, CtrRequestData};
+use sak_contract_std::{ContractRevert, CtrCallType, CtrRequest, CtrRequestData};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
@@ -13,11 +13,15 @@ use std::sync::Arc;
 pub(crate) struct QueryCtrRequest {
     pub ctr_addr: String,
     pub req: CtrRequestData,
+    // Key that may confirm the session, if the execution opens one
+    #[serde(default)]
+    pub public_key: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub(crate) struct QueryCtrResponse {
     pub result: Vec<u8>,
+    pub session_id: Option<String>,
 }
 
 pub(in crate::rpc) async fn query_ctr(
@@ -29,17 +33,60 @@ pub(in crate::rpc) async fn query_ctr(
 
     let rb: QueryCtrRequest = require_params_parsed!(route_state, &params);
 
+    // A light node has no contract states, and asks the full peers
+    if let Some(light_client) = &sys_handle.light_client {
+        if !matches!(rb.req.ctr_call_type, CtrCallType::Query) {
+            return make_error_response(
+                route_state.resp,
+                Some(route_state.id),
+                "A light node can only query contracts".into(),
+            );
+        }
+
+        return match light_client
+            .query_ctr(rb.ctr_addr, rb.req.req_type, rb.req.args)
+            .await
+        {
+            Ok(result) => make_success_response(
+                route_state,
+                QueryCtrResponse {
+                    result,
+                    session_id: None,
+                },
+            ),
+            Err(err) => make_error_response(route_state.resp, Some(route_state.id), err),
+        };
+    }
+
     let ctr_request = CtrRequest {
         ctr_addr: rb.ctr_addr.clone(),
         req_type: rb.req.req_type,
         args: rb.req.args,
         ctr_call_type: rb.req.ctr_call_type,
+        session_owner: rb.public_key,
     };
 
-    let res = sys_handle.machine.ledger.execute_ctr(ctr_request).await;
+    let res = sys_handle
+        .machine
+        .ledger
+        .execute_ctr_with_receipt(ctr_request)
+        .await;
 
     match res {
-        Ok(result) => make_success_response(route_state, QueryCtrResponse { result }),
-        Err(err) => make_error_response(route_state.resp, Some(route_state.id), err),
+        Ok(receipt) => make_success_response(
+            route_state,
+            QueryCtrResponse {
+                result: receipt.result,
+                session_id: receipt.session_id,
+            },
+        ),
+        Err(err) => match err.downcast_ref::<ContractRevert>() {
+            Some(revert) => {
+                let data = serde_json::to_value(revert).ok();
+
+                make_error_response_with_data(route_state.resp, Some(route_state.id), err, data)
+            }
+            None => make_error_response(route_state.resp, Some(route_state.id), err),
+        },
     }
 }
@@ -1,11 +1,13 @@
 mod block;
 mod contract;
 mod proof;
+mod session;
 mod status;
 mod tx;
 
 pub(in crate::rpc) use block::*;
 pub(in crate::rpc) use contract::*;
 pub(in crate::rpc) use proof::*;
+pub(in crate::rpc) use session::*;
 pub(in crate::rpc) use status::*;
 pub(in crate::rpc) use tx::*;
@@ -27,13 +27,20 @@ pub(in crate::rpc) async fn get_auth_path(
 
     let rb: GetAuthPathRequest = require_params_parsed!(route_state, &params);
 
-    match sys_handle
-        .machine
-        .ledger
-        // .dist_ledger
-        .get_auth_path(&rb.cm_idx)
-        .await
-    {
+    // A light node has no merkle tree, and asks the full peers
+    let res = match &sys_handle.light_client {
+        Some(c) => c.get_auth_path(rb.cm_idx).await,
+        None => {
+            sys_handle
+                .machine
+                .ledger
+                // .dist_ledger
+                .get_auth_path(&rb.cm_idx)
+                .await
+        }
+    };
+
+    match res {
         Ok(auth_path) => {
             let get_auth_path_resp = GetAuthPathResponse { auth_path };
 
@@ -64,13 +71,19 @@ pub(in crate::rpc) async fn get_cm_idx(
 
     let rb: GetCmIdxRequest = require_params_parsed!(route_state, &params);
 
-    match sys_handle
-        .machine
-        .ledger
-        // .dist_ledger
-        .get_cm_idx_by_cm(&rb.cm)
-        .await
-    {
+    let res = match &sys_handle.light_client {
+        Some(c) => c.get_cm_idx(rb.cm).await,
+        None => {
+            sys_handle
+                .machine
+                .ledger
+                // .dist_ledger
+                .get_cm_idx_by_cm(&rb.cm)
+                .await
+        }
+    };
+
+    match res {
         Ok(cm_idx) => {
             let get_auth_path_resp = GetCmIdxResponse { cm_idx };
 
@@ -0,0 +1,69 @@
+use crate::system::SystemHandle;
+use hyper::{Body, Response};
+use hyper_rpc_router::{
+    make_error_response, make_success_response, require_params_parsed, require_some_params, Params,
+    RouteState,
+};
+use sak_store_interface::{ConfirmSessionArgs, MRSInterface, Session};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(in crate::rpc) struct GetSessionRequest {
+    pub session_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(in crate::rpc) struct GetSessionResponse {
+    pub session: Option<Session>,
+}
+
+pub(in crate::rpc) async fn get_session(
+    route_state: RouteState,
+    params: Params,
+    sys_handle: Arc<SystemHandle>,
+) -> Response<Body> {
+    let params = require_some_params!(route_state, params, "get_session should contain params",);
+
+    let rb: GetSessionRequest = require_params_parsed!(route_state, &params);
+
+    let session = sys_handle.machine.mrs.get_session(&rb.session_id).await;
+
+    make_success_response(route_state, GetSessionResponse { session })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(in crate::rpc) struct ConfirmSessionRequest {
+    pub session_id: String,
+    pub public_key: String,
+    pub sig: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub(in crate::rpc) struct ConfirmSessionResponse {
+    pub session_id: String,
+}
+
+pub(in crate::rpc) async fn confirm_session(
+    route_state: RouteState,
+    params: Params,
+    sys_handle: Arc<SystemHandle>,
+) -> Response<Body> {
+    let params =
+        require_some_params!(route_state, params, "confirm_session should contain params",);
+
+    let rb: ConfirmSessionRequest = require_params_parsed!(route_state, &params);
+
+    let session_id = rb.session_id.to_string();
+
+    let confirm_args = ConfirmSessionArgs {
+        session_id: rb.session_id,
+        public_key: rb.public_key,
+        sig: rb.sig,
+    };
+
+    match sys_handle.machine.mrs.confirm_session(confirm_args).await {
+        Ok(_) => make_success_response(route_state, ConfirmSessionResponse { session_id }),
+        Err(err) => make_error_response(route_state.resp, Some(route_state.id), err),
+    }
+}
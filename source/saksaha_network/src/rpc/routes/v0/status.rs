@@ -1,13 +1,89 @@
 use crate::system::SystemHandle;
+use crate::SaksahaError;
 use hyper::{Body, Response};
-use hyper_rpc_router::{make_success_response, Params, RouteState};
+use hyper_rpc_router::{make_error_response, make_success_response, Params, RouteState};
+use sak_p2p_peertable::{PeerInfo, SlotKind};
+use sak_p2p_transport::P2P_PROTOCOL_VERSION;
+use sak_types::{BlockHash, BlockHeight};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GetNodeStatusResponse {
-    addr_vec: Vec<String>,
-    peer_vec: Vec<String>,
+    pub version: String,
+    pub protocol_version: u32,
+    pub public_key: String,
+    pub light: bool,
+    pub chain: ChainStatus,
+    pub sync: SyncStatus,
+    pub mempool_size: usize,
+    pub peers: Vec<PeerStatus>,
+    pub known_addr_count: usize,
+    pub external_endpoint: ExternalEndpointStatus,
+    pub mrs: MrsStatus,
+    pub ledger_db: DbStatus,
+    pub uptime_sec: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChainStatus {
+    pub latest_height: Option<BlockHeight>,
+    pub latest_block_hash: Option<BlockHash>,
+    // Blocks are never reverted once written, so far the latest one is
+    // final as well
+    pub finalized_height: Option<BlockHeight>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncState {
+    Syncing,
+    Synced,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SyncStatus {
+    pub state: SyncState,
+    // Best height the peers have told us of
+    pub target_height: Option<BlockHeight>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerDirection {
+    Inbound,
+    Outbound,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PeerStatus {
+    pub public_key: String,
+    pub p2p_endpoint: String,
+    pub direction: PeerDirection,
+    pub reserved: bool,
+    pub rtt_ms: Option<u64>,
+    pub score: i64,
+    pub best_height: Option<BlockHeight>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExternalEndpointStatus {
+    // Not known unless given, reported by the gateway or observed by peers
+    pub ip: Option<String>,
+    pub disc_port: u16,
+    pub p2p_port: u16,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MrsStatus {
+    pub slot_count: u64,
+    pub size_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DbStatus {
+    pub size_bytes: u64,
+    pub key_count: u64,
 }
 
 pub(in crate::rpc) async fn get_status(
@@ -15,14 +91,112 @@ pub(in crate::rpc) async fn get_status(
     _params: Params,
     sys_handle: Arc<SystemHandle>,
 ) -> Response<Body> {
-    let addr_vec = sys_handle
-        .p2p_monitor
+    match make_node_status(&sys_handle).await {
+        Ok(status) => make_success_response(route_state, status),
+        Err(err) => make_error_response(route_state.resp, Some(route_state.id), err.into()),
+    }
+}
+
+async fn make_node_status(
+    sys_handle: &SystemHandle,
+) -> Result<GetNodeStatusResponse, SaksahaError> {
+    let ledger = &sys_handle.machine.ledger;
+    let p2p_monitor = &sys_handle.p2p_monitor;
+
+    let (latest_height, latest_block_hash) = match ledger.get_latest_block_hash().await? {
+        Some((height, hash)) => (Some(height), Some(hash)),
+        None => (None, None),
+    };
+
+    let peer_infos = p2p_monitor.peer_table.get_peer_infos().await;
+
+    let sync = {
+        let target_height = peer_infos
+            .iter()
+            .filter_map(|p| p.chain_status.as_ref().map(|c| c.best_height))
+            .max();
+
+        let state = match (target_height, latest_height) {
+            (Some(target), Some(latest)) if target > latest => SyncState::Syncing,
+            (Some(_), None) => SyncState::Syncing,
+            _ => SyncState::Synced,
+        };
+
+        SyncStatus {
+            state,
+            target_height,
+        }
+    };
+
+    let mrs = {
+        let stats = sys_handle.machine.mrs.get_stats()?;
+
+        MrsStatus {
+            slot_count: sys_handle.machine.mrs.get_slot_count()?,
+            size_bytes: stats.size_bytes,
+        }
+    };
+
+    let ledger_db = {
+        let stats = ledger.get_db_stats()?;
+
+        DbStatus {
+            size_bytes: stats.size_bytes,
+            key_count: stats.key_count,
+        }
+    };
+
+    let known_addr_count = p2p_monitor
         .p2p_discovery
         .addr_table
         .get_status()
-        .await;
+        .await
+        .len();
+
+    let external_endpoint = {
+        let (ip, ports) = p2p_monitor.p2p_discovery.get_external_endpoint().await;
+
+        ExternalEndpointStatus {
+            ip: ip.map(|i| i.to_string()),
+            disc_port: ports.disc_port,
+            p2p_port: ports.p2p_port,
+        }
+    };
+
+    Ok(GetNodeStatusResponse {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        protocol_version: P2P_PROTOCOL_VERSION,
+        public_key: sys_handle.public_key.clone(),
+        light: sys_handle.light_client.is_some(),
+        chain: ChainStatus {
+            latest_height,
+            latest_block_hash,
+            finalized_height: latest_height,
+        },
+        sync,
+        mempool_size: ledger.get_tx_pool_size().await,
+        peers: peer_infos.into_iter().map(make_peer_status).collect(),
+        known_addr_count,
+        external_endpoint,
+        mrs,
+        ledger_db,
+        uptime_sec: sys_handle.started_at.elapsed().as_secs(),
+    })
+}
 
-    let peer_vec = sys_handle.p2p_monitor.peer_table.get_status().await;
+fn make_peer_status(peer_info: PeerInfo) -> PeerStatus {
+    let direction = match peer_info.is_inbound {
+        true => PeerDirection::Inbound,
+        false => PeerDirection::Outbound,
+    };
 
-    return make_success_response(route_state, GetNodeStatusResponse { addr_vec, peer_vec });
+    PeerStatus {
+        public_key: peer_info.public_key_str,
+        p2p_endpoint: peer_info.p2p_endpoint,
+        direction,
+        reserved: peer_info.slot_kind == SlotKind::Reserved,
+        rtt_ms: peer_info.rtt_ms,
+        score: peer_info.score.score,
+        best_height: peer_info.chain_status.map(|c| c.best_height),
+    }
 }
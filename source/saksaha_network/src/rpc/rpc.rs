@@ -1,19 +1,29 @@
-use super::{routes, RPCError};
+use super::{routes, ws, RPCError};
 use crate::SystemHandle;
 use hyper_rpc_router::Router;
-use hyper_server::{cors, HttpServer, Middleware};
+use hyper_server::{cors, metrics, websocket, HttpServer, Middleware};
+use sak_logger::warn;
 use std::sync::Arc;
 use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+
+const METRICS_PATH: &str = "/metrics";
+
+const WS_PATH: &str = "/ws";
 
 pub(crate) struct RPCArgs {
     pub sys_handle: Arc<SystemHandle>,
     pub rpc_socket: TcpListener,
+    pub shutdown_token: CancellationToken,
+    pub metrics_path: Option<String>,
 }
 
 pub(crate) struct RPC {
     sys_handle: Arc<SystemHandle>,
     rpc_socket: TcpListener,
     server: HttpServer,
+    shutdown_token: CancellationToken,
+    metrics_path: String,
 }
 
 impl RPC {
@@ -24,6 +34,10 @@ impl RPC {
             sys_handle: rpc_args.sys_handle,
             rpc_socket: rpc_args.rpc_socket,
             server,
+            shutdown_token: rpc_args.shutdown_token,
+            metrics_path: rpc_args
+                .metrics_path
+                .unwrap_or_else(|| METRICS_PATH.to_string()),
         };
 
         Ok(rpc)
@@ -39,16 +53,53 @@ impl RPC {
 
         let cors = Middleware::new(Box::new(cors));
 
+        let metrics = metrics(self.metrics_path, gather_metrics);
+
+        let ws = {
+            let shutdown_token = self.shutdown_token.clone();
+
+            websocket(WS_PATH.to_string(), move |ws_stream, sys_handle| {
+                ws::serve_ws_conn(ws_stream, sys_handle, shutdown_token.clone())
+            })
+        };
+
         let route = {
             let m = Middleware::new(Box::new(move |req, res, ctx| router.route(req, res, ctx)));
 
             m
         };
 
-        let middlewares = vec![cors, route];
+        let middlewares = vec![cors, metrics, ws, route];
+
+        let shutdown_token = self.shutdown_token;
 
         self.server
-            .run(self.rpc_socket, self.sys_handle, middlewares)
+            .run_until(self.rpc_socket, self.sys_handle, middlewares, async move {
+                shutdown_token.cancelled().await
+            })
             .await
     }
 }
+
+fn gather_metrics(sys_handle: &Arc<SystemHandle>) -> String {
+    let metrics = &sak_metrics::metrics().db;
+
+    // Store sizes are not tracked as they change, so they are read here
+    match sys_handle.machine.ledger.get_db_stats() {
+        Ok(s) => {
+            metrics.ledger_db_size_bytes.set(s.size_bytes as f64);
+            metrics.ledger_db_keys.set(s.key_count as f64);
+        }
+        Err(err) => warn!("Cannot read the ledger db stats, err: {}", err),
+    };
+
+    match sys_handle.machine.mrs.get_stats() {
+        Ok(s) => {
+            metrics.mrs_db_size_bytes.set(s.size_bytes as f64);
+            metrics.mrs_records.set(s.record_count as f64);
+        }
+        Err(err) => warn!("Cannot read the mrs stats, err: {}", err),
+    };
+
+    sak_metrics::metrics().gather()
+}
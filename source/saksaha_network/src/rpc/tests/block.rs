@@ -47,15 +47,13 @@ async fn test_call_get_block_with_good_params() {
     };
 
     let body = {
-        let params = format!("{{\"block_hash\":\"{}\"}}", original_block_hash)
-            .as_bytes()
-            .to_vec();
+        let params = serde_json::json!({ "block_hash": original_block_hash });
 
         let json_request = JsonRequest {
             jsonrpc: "2.0".to_string(),
             method: "get_block".to_string(),
             params: Some(params),
-            id: "test_1".to_string(),
+            id: Some("test_1".into()),
         };
 
         let str = serde_json::to_string(&json_request).unwrap();
@@ -125,17 +123,15 @@ async fn test_call_get_block_with_wrong_params() {
     };
 
     let body = {
-        let params = r#"
-973f486c42f67e8520367a46f1a13caf969224d99d1b2f02943c6d926b7bc04b
-"#
-        .as_bytes()
-        .to_vec();
+        let params = serde_json::Value::String(
+            "973f486c42f67e8520367a46f1a13caf969224d99d1b2f02943c6d926b7bc04b".to_string(),
+        );
 
         let json_request = JsonRequest {
             jsonrpc: "2.0".to_string(),
             method: "get_block".to_string(),
             params: Some(params),
-            id: "test_1".to_string(),
+            id: Some("test_1".into()),
         };
 
         let str = serde_json::to_string(&json_request).unwrap();
@@ -221,20 +217,16 @@ async fn test_call_get_block_list() {
     };
 
     let body = {
-        let params = r#"
-{
-    "offset": 5,
-    "limit": 1000
-}
-"#
-        .as_bytes()
-        .to_vec();
+        let params = serde_json::json!({
+            "offset": 5,
+            "limit": 1000
+        });
 
         let json_request = JsonRequest {
             jsonrpc: "2.0".to_string(),
             method: "get_block_list".to_string(),
             params: Some(params),
-            id: "test_1".to_string(),
+            id: Some("test_1".into()),
         };
 
         let str = serde_json::to_string(&json_request).unwrap();
@@ -48,18 +48,19 @@ async fn test_call_contract() {
             ctr_call_type: CtrCallType::Query,
         };
 
-        let call_ctr_req = QueryCtrRequest { ctr_addr, req };
+        let call_ctr_req = QueryCtrRequest {
+            ctr_addr,
+            req,
+            public_key: None,
+        };
 
-        let params = serde_json::to_string(&call_ctr_req)
-            .unwrap()
-            .as_bytes()
-            .to_vec();
+        let params = serde_json::to_value(&call_ctr_req).unwrap();
 
         let json_request = JsonRequest {
             jsonrpc: "2.0".to_string(),
             method: "query_ctr".to_string(),
             params: Some(params),
-            id: "test_1".to_string(),
+            id: Some("test_1".into()),
         };
 
         let str = serde_json::to_string(&json_request).unwrap();
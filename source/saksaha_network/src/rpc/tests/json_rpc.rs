@@ -0,0 +1,145 @@
+use super::utils::{self, TestContext};
+use crate::tests::SaksahaTestUtils;
+use hyper::{Body, Client, Method, Request, StatusCode, Uri};
+use hyper_rpc_router::MAX_BATCH_LEN;
+use sak_credential::CredentialProfile;
+use sak_rpc_interface::{INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND, PARSE_ERROR};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+
+async fn post(rpc_socket_addr: &SocketAddr, body: String) -> (StatusCode, Option<Value>) {
+    let uri: Uri = format!("http://localhost:{}", rpc_socket_addr.port())
+        .parse()
+        .expect("URI should be made");
+
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .body(Body::from(body))
+        .expect("request builder should be made");
+
+    let resp = Client::new().request(req).await.unwrap();
+    let status = resp.status();
+
+    let b = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+
+    if b.is_empty() {
+        (status, None)
+    } else {
+        (status, Some(serde_json::from_slice(&b).unwrap()))
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_json_rpc_errors_have_standard_codes() {
+    let test_credential_1 = CredentialProfile::test_1();
+
+    SaksahaTestUtils::init_test(&[&test_credential_1.public_key_str]);
+
+    let TestContext {
+        rpc,
+        rpc_socket_addr,
+        ..
+    } = utils::make_test_context(test_credential_1.secret, test_credential_1.public_key_str).await;
+
+    tokio::spawn(async move { rpc.run().await });
+
+    let (_, resp) = post(&rpc_socket_addr, "{ not json".into()).await;
+    let resp = resp.unwrap();
+
+    assert_eq!(resp["error"]["code"], PARSE_ERROR);
+    assert_eq!(resp["id"], Value::Null);
+
+    let body = json!({ "jsonrpc": "1.0", "method": "get_status", "id": 1 });
+    let (_, resp) = post(&rpc_socket_addr, body.to_string()).await;
+    let resp = resp.unwrap();
+
+    assert_eq!(resp["error"]["code"], INVALID_REQUEST);
+    assert_eq!(resp["id"], 1);
+
+    let body = json!({ "jsonrpc": "2.0", "method": "no_such_method", "id": "a" });
+    let (_, resp) = post(&rpc_socket_addr, body.to_string()).await;
+    let resp = resp.unwrap();
+
+    assert_eq!(resp["error"]["code"], METHOD_NOT_FOUND);
+    assert_eq!(resp["id"], "a");
+
+    let body = json!({ "jsonrpc": "2.0", "method": "get_block", "params": {}, "id": null });
+    let (status, resp) = post(&rpc_socket_addr, body.to_string()).await;
+    let resp = resp.unwrap();
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(resp["error"]["code"], INVALID_PARAMS);
+    assert_eq!(resp["id"], Value::Null);
+    assert!(resp.get("result").is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_json_rpc_batch_and_notifications() {
+    let test_credential_1 = CredentialProfile::test_1();
+
+    SaksahaTestUtils::init_test(&[&test_credential_1.public_key_str]);
+
+    let TestContext {
+        rpc,
+        rpc_socket_addr,
+        machine,
+    } = utils::make_test_context(test_credential_1.secret, test_credential_1.public_key_str).await;
+
+    tokio::spawn(async move { rpc.run().await });
+
+    let (_, block_hash) = machine
+        .ledger
+        .get_latest_block_hash()
+        .await
+        .unwrap()
+        .expect("Genesis block should be written");
+
+    // Params as the bytes of the serialized JSON, as the clients not yet
+    // migrated send them
+    let legacy_params = serde_json::to_vec(&json!({ "block_hash": block_hash })).unwrap();
+
+    let body = json!([
+        { "jsonrpc": "2.0", "method": "get_status", "id": 1 },
+        { "jsonrpc": "2.0", "method": "get_status" },
+        { "jsonrpc": "2.0", "method": "get_block", "params": legacy_params, "id": "b" },
+        { "jsonrpc": "2.0", "method": "no_such_method", "id": 3 },
+        1,
+    ]);
+
+    let (status, resp) = post(&rpc_socket_addr, body.to_string()).await;
+
+    assert_eq!(status, StatusCode::OK);
+
+    let resps = resp.unwrap();
+    let resps = resps.as_array().unwrap();
+
+    // The notification is not replied to
+    assert_eq!(resps.len(), 4);
+
+    let find = |id: Value| resps.iter().find(|r| r["id"] == id).unwrap();
+
+    assert!(find(json!(1))["result"].is_object());
+    assert_eq!(
+        find(json!("b"))["result"]["block"]["block_hash"],
+        block_hash.as_str()
+    );
+    assert_eq!(find(json!(3))["error"]["code"], METHOD_NOT_FOUND);
+    assert_eq!(find(Value::Null)["error"]["code"], INVALID_REQUEST);
+
+    let body = json!({ "jsonrpc": "2.0", "method": "get_status" });
+    let (status, resp) = post(&rpc_socket_addr, body.to_string()).await;
+
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert!(resp.is_none());
+
+    let body: Vec<Value> = (0..MAX_BATCH_LEN + 1)
+        .map(|id| json!({ "jsonrpc": "2.0", "method": "get_status", "id": id }))
+        .collect();
+
+    let (_, resp) = post(&rpc_socket_addr, Value::Array(body).to_string()).await;
+    let resp = resp.unwrap();
+
+    assert_eq!(resp["error"]["code"], INVALID_REQUEST);
+    assert_eq!(resp["id"], Value::Null);
+}
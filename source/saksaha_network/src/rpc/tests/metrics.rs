@@ -0,0 +1,52 @@
+use super::utils::{self, TestContext};
+use crate::tests::SaksahaTestUtils;
+use hyper::{header, Body, Client, Method, Request, StatusCode, Uri};
+use sak_credential::CredentialProfile;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rpc_serves_metrics_in_prometheus_format() {
+    let test_credential_1 = CredentialProfile::test_1();
+
+    SaksahaTestUtils::init_test(&[&test_credential_1.public_key_str]);
+
+    let TestContext {
+        rpc,
+        rpc_socket_addr,
+        ..
+    } = utils::make_test_context(test_credential_1.secret, test_credential_1.public_key_str).await;
+
+    let client = Client::new();
+
+    tokio::spawn(async move { rpc.run().await });
+
+    let uri: Uri = {
+        let u = format!("http://localhost:{}/metrics", rpc_socket_addr.port());
+
+        u.parse().expect("URI should be made")
+    };
+
+    let req = Request::builder()
+        .method(Method::GET)
+        .uri(uri)
+        .body(Body::empty())
+        .expect("request builder should be made");
+
+    let resp = client.request(req).await.unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let content_type = resp.headers()[header::CONTENT_TYPE].to_str().unwrap();
+
+    assert!(content_type.starts_with("text/plain; version=0.0.4"));
+
+    let b = hyper::body::to_bytes(resp.into_body()).await.unwrap();
+    let metrics = String::from_utf8(b.to_vec()).unwrap();
+
+    assert!(metrics.contains("# TYPE saksaha_ledger_blocks_written_total counter\n"));
+    assert!(metrics.contains("# TYPE saksaha_ledger_proof_verify_seconds histogram\n"));
+    assert!(metrics.contains("# TYPE saksaha_sync_pool_txs gauge\n"));
+
+    // Store sizes are read at scrape
+    assert!(metrics.contains("\nsaksaha_ledger_db_keys "));
+    assert!(metrics.contains("\nsaksaha_mrs_records "));
+}
@@ -1,6 +1,9 @@
 mod block;
 mod contract;
+mod json_rpc;
+mod metrics;
 mod proof;
 mod status;
 mod tx;
 mod utils;
+mod ws;
@@ -1,5 +1,6 @@
 use super::utils::{self, TestContext};
-use crate::{rpc::routes::v0::GetNodeStatusResponse, tests::SaksahaTestUtils};
+use crate::rpc::routes::v0::{GetNodeStatusResponse, SyncState};
+use crate::tests::SaksahaTestUtils;
 use hyper::{Body, Client, Method, Request, Uri};
 use sak_credential::CredentialProfile;
 use sak_rpc_interface::{JsonRequest, JsonResponse};
@@ -10,6 +11,8 @@ async fn test_rpc_client_request_correct_status() {
 
     SaksahaTestUtils::init_test(&[&test_credential_1.public_key_str]);
 
+    let public_key = test_credential_1.public_key_str.clone();
+
     let TestContext {
         rpc,
         rpc_socket_addr,
@@ -34,7 +37,7 @@ async fn test_rpc_client_request_correct_status() {
             jsonrpc: "2.0".to_string(),
             method: "get_status".to_string(),
             params: None,
-            id: "test_1".to_string(),
+            id: Some("test_1".into()),
         };
 
         let str = serde_json::to_string(&json_request).unwrap();
@@ -52,13 +55,29 @@ async fn test_rpc_client_request_correct_status() {
 
     let b = hyper::body::to_bytes(resp.into_body()).await.unwrap();
 
-    match serde_json::from_slice::<JsonResponse<GetNodeStatusResponse>>(&b) {
+    let status = match serde_json::from_slice::<JsonResponse<GetNodeStatusResponse>>(&b) {
         Ok(b) => match b.result {
-            Some(v) => {
-                println!("Successfully get status : {:?}", v);
-            }
+            Some(v) => v,
             _ => panic!("Failed to get status"),
         },
         Err(err) => panic!("Failed to get response : {:?}", err),
     };
+
+    assert_eq!(status.public_key, public_key);
+    assert!(!status.light);
+
+    // Genesis block is written at init
+    assert!(status.chain.latest_height.is_some());
+    assert!(status.chain.latest_block_hash.is_some());
+    assert_eq!(status.chain.finalized_height, status.chain.latest_height);
+
+    // No peer has told of a better height
+    assert_eq!(status.sync.state, SyncState::Synced);
+    assert_eq!(status.sync.target_height, None);
+
+    assert_eq!(status.mempool_size, 0);
+    assert!(status.peers.is_empty());
+
+    // Neither given nor observed by any peer
+    assert_eq!(status.external_endpoint.ip, None);
 }
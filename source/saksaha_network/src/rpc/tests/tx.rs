@@ -313,16 +313,13 @@ async fn test_rpc_reqeust_wrong_send_pour_tx() {
     let body = {
         let send_req = String::from("False request");
 
-        let params = serde_json::to_string(&send_req)
-            .unwrap()
-            .as_bytes()
-            .to_vec();
+        let params = serde_json::to_value(&send_req).unwrap();
 
         let json_request = JsonRequest {
             jsonrpc: "2.0".to_string(),
             method: "send_pour_tx".to_string(),
             params: Some(params),
-            id: "test_1".to_string(),
+            id: Some("test_1".into()),
         };
 
         let str = serde_json::to_string(&json_request).unwrap();
@@ -387,16 +384,13 @@ async fn test_rpc_reqeust_correct_send_mint_tx() {
             tc_dummy.s,
         );
 
-        let params = serde_json::to_string(&send_req)
-            .unwrap()
-            .as_bytes()
-            .to_vec();
+        let params = serde_json::to_value(&send_req).unwrap();
 
         let json_request = JsonRequest {
             jsonrpc: "2.0".to_string(),
             method: "send_mint_tx".to_string(),
             params: Some(params),
-            id: "test_1".to_string(),
+            id: Some("test_1".into()),
         };
 
         let str = serde_json::to_string(&json_request).unwrap();
@@ -456,16 +450,13 @@ async fn test_rpc_reqeust_wrong_send_mint_tx() {
     let body = {
         let send_req = String::from("False request");
 
-        let params = serde_json::to_string(&send_req)
-            .unwrap()
-            .as_bytes()
-            .to_vec();
+        let params = serde_json::to_value(&send_req).unwrap();
 
         let json_request = JsonRequest {
             jsonrpc: "2.0".to_string(),
             method: "send_mint_tx".to_string(),
             params: Some(params),
-            id: "test_1".to_string(),
+            id: Some("test_1".into()),
         };
 
         let str = serde_json::to_string(&json_request).unwrap();
@@ -1,3 +1,4 @@
+use crate::fs::SaksahaFS;
 use crate::ledger::Ledger;
 use crate::mrs::MRS;
 use crate::p2p::{P2PHost, P2PHostArgs};
@@ -15,6 +16,8 @@ use sak_vm::SakVM;
 use sak_vm_interface::ContractProcessor;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
 
 pub(in crate::rpc) struct TestContext {
     pub rpc: RPC,
@@ -49,9 +52,9 @@ pub(in crate::rpc) async fn make_test_context(
     };
 
     let mrs = {
-        let pk = String::from("test");
+        let acc_dir = SaksahaFS::acc_dir(&String::from("test")).unwrap();
 
-        let m = MRS::init(&pk).await.unwrap();
+        let m = MRS::init(&acc_dir).await.unwrap();
         let m = Box::new(m) as MRSAccessor;
         Arc::new(m)
     };
@@ -62,9 +65,9 @@ pub(in crate::rpc) async fn make_test_context(
     };
 
     let ledger = {
-        let pk = String::from("test");
+        let acc_dir = SaksahaFS::acc_dir(&String::from("test")).unwrap();
 
-        Ledger::init(&pk, None, None, None, identity.clone(), vm)
+        Ledger::init(&acc_dir, None, None, None, identity.clone(), vm, None)
             .await
             .unwrap()
     };
@@ -77,7 +80,7 @@ pub(in crate::rpc) async fn make_test_context(
     };
 
     let p2p_peer_table = {
-        let ps = PeerTable::init(None)
+        let ps = PeerTable::init(None, None, vec![])
             .await
             .expect("Peer table should be initialized");
 
@@ -106,6 +109,10 @@ pub(in crate::rpc) async fn make_test_context(
             bootstrap_addrs: vec![],
             identity: identity.clone(),
             peer_table: p2p_peer_table,
+            addr_book_path: None,
+            external_addr: None,
+            port_mapping: false,
+            shutdown_token: CancellationToken::new(),
         };
 
         let p = P2PHost::init(p2p_host_args)
@@ -125,6 +132,9 @@ pub(in crate::rpc) async fn make_test_context(
         let sys_handle = SystemHandle {
             machine: machine.clone(),
             p2p_monitor,
+            light_client: None,
+            public_key: public_key_str,
+            started_at: Instant::now(),
         };
 
         let sys_handle = Arc::new(sys_handle);
@@ -132,6 +142,8 @@ pub(in crate::rpc) async fn make_test_context(
         let rpc_args = RPCArgs {
             sys_handle,
             rpc_socket,
+            shutdown_token: CancellationToken::new(),
+            metrics_path: None,
         };
 
         RPC::init(rpc_args).expect("RPC should be initialized")
@@ -178,9 +190,9 @@ pub(crate) async fn make_ledger(secret: &String, public_key_str: &String) -> Sak
     };
 
     let mrs = {
-        let pk = String::from("test");
+        let acc_dir = SaksahaFS::acc_dir(&String::from("test")).unwrap();
 
-        let m = MRS::init(&pk).await.unwrap();
+        let m = MRS::init(&acc_dir).await.unwrap();
         let m = Box::new(m) as MRSAccessor;
         Arc::new(m)
     };
@@ -191,12 +203,13 @@ pub(crate) async fn make_ledger(secret: &String, public_key_str: &String) -> Sak
     };
 
     let sak_ledger = Ledger::init(
-        &String::from("test"),
+        &SaksahaFS::acc_dir(&String::from("test")).unwrap(),
         None,
         None,
         None,
         identity.clone(),
         vm,
+        None,
     )
     .await
     .expect("Blockchain should be made");
@@ -0,0 +1,134 @@
+use super::utils::{self, TestContext};
+use crate::tests::SaksahaTestUtils;
+use futures::{SinkExt, StreamExt};
+use sak_credential::CredentialProfile;
+use sak_ledger::DistLedgerEvent;
+use sak_rpc_interface::{JsonRequest, SubscribeTxStatusRequest};
+use serde_json::Value;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+type WsClient = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn request(ws: &mut WsClient, id: u64, method: &str, params: Option<Value>) -> Value {
+    let req = JsonRequest {
+        jsonrpc: "2.0".to_string(),
+        method: method.to_string(),
+        params,
+        id: Some(id.into()),
+    };
+
+    ws.send(Message::Text(serde_json::to_string(&req).unwrap()))
+        .await
+        .unwrap();
+
+    next_json(ws).await
+}
+
+async fn next_json(ws: &mut WsClient) -> Value {
+    let msg = tokio::time::timeout(Duration::from_secs(10), ws.next())
+        .await
+        .expect("Ws msg should arrive in time")
+        .expect("Ws conn should be open")
+        .unwrap();
+
+    match msg {
+        Message::Text(t) => serde_json::from_str(&t).unwrap(),
+        m => panic!("Expected a text msg, msg: {:?}", m),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ws_subscriptions_are_notified_of_ledger_events() {
+    let test_credential_1 = CredentialProfile::test_1();
+
+    SaksahaTestUtils::init_test(&[&test_credential_1.public_key_str]);
+
+    let TestContext {
+        rpc,
+        rpc_socket_addr,
+        machine,
+    } = utils::make_test_context(test_credential_1.secret, test_credential_1.public_key_str).await;
+
+    tokio::spawn(async move { rpc.run().await });
+
+    let (mut ws, _) =
+        tokio_tungstenite::connect_async(format!("ws://localhost:{}/ws", rpc_socket_addr.port()))
+            .await
+            .expect("Ws conn should be made");
+
+    let new_blocks_sub = request(&mut ws, 1, "subscribe_new_blocks", None).await;
+    let new_blocks_sub = new_blocks_sub["result"].as_str().unwrap().to_string();
+
+    let pending_txs_sub = request(&mut ws, 2, "subscribe_pending_txs", None).await;
+    let pending_txs_sub = pending_txs_sub["result"].as_str().unwrap().to_string();
+
+    let tc = sak_types::mock_mint_tc_random();
+    let tx_hash = tc.get_tx_hash().clone();
+
+    let tx_status_sub = {
+        let params = serde_json::to_value(&SubscribeTxStatusRequest {
+            tx_hash: tx_hash.clone(),
+        })
+        .unwrap();
+
+        let resp = request(&mut ws, 3, "subscribe_tx_status", Some(params)).await;
+        let sub = resp["result"].as_str().unwrap().to_string();
+
+        let noti = next_json(&mut ws).await;
+
+        assert_eq!(noti["method"], "subscription");
+        assert_eq!(noti["params"]["subscription"], sub.as_str());
+        assert_eq!(noti["params"]["result"]["status"], "unknown");
+
+        sub
+    };
+
+    // Tx pool stat is sent once the tx sync interval passes
+    machine.ledger.sync_pool.insert_tx(tc).await.unwrap();
+
+    let mut is_pending_tx_notified = false;
+    let mut is_tx_status_notified = false;
+
+    while !(is_pending_tx_notified && is_tx_status_notified) {
+        let noti = next_json(&mut ws).await;
+        let params = &noti["params"];
+
+        if params["subscription"] == pending_txs_sub.as_str() {
+            assert_eq!(params["result"]["tx_hash"], tx_hash.as_str());
+            is_pending_tx_notified = true;
+        } else if params["subscription"] == tx_status_sub.as_str() {
+            assert_eq!(params["result"]["status"], "pending");
+            is_tx_status_notified = true;
+        }
+    }
+
+    let (height, block_hash) = machine
+        .ledger
+        .get_latest_block_hash()
+        .await
+        .unwrap()
+        .expect("Genesis block should be written");
+
+    machine
+        .ledger
+        .ledger_event_tx
+        .send(DistLedgerEvent::NewBlocks(vec![(
+            height,
+            block_hash.clone(),
+        )]))
+        .unwrap();
+
+    let noti = next_json(&mut ws).await;
+
+    assert_eq!(noti["params"]["subscription"], new_blocks_sub.as_str());
+    assert_eq!(noti["params"]["result"]["block_hash"], block_hash.as_str());
+
+    let params = serde_json::json!({ "subscription": new_blocks_sub });
+
+    let resp = request(&mut ws, 4, "unsubscribe", Some(params)).await;
+
+    assert_eq!(resp["result"], true);
+}
@@ -0,0 +1,4 @@
+mod session;
+mod subscription;
+
+pub(in crate::rpc) use session::*;
@@ -0,0 +1,253 @@
+use super::subscription::{self, Subscription, Subscriptions};
+use crate::rpc::RPCError;
+use crate::system::SystemHandle;
+use futures::{SinkExt, StreamExt};
+use hyper_server::{WsMessage, WsStream};
+use sak_logger::{debug, warn};
+use sak_rpc_interface::{
+    JsonRPCError, JsonRequest, JsonResponse, JsonRpcId, SubscribeCtrEventsRequest,
+    SubscribeTxStatusRequest, TxStatus, TxStatusEvent, UnsubscribeRequest, INTERNAL_ERROR,
+    INVALID_PARAMS, INVALID_REQUEST, JSON_RPC_2, METHOD_NOT_FOUND, PARSE_ERROR,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+
+const MAX_SUBSCRIPTION_COUNT: usize = 32;
+
+/// Serves the subscriptions of a WebSocket conn, until the client closes it
+/// or the node shuts down. Requests are JSON-RPC, the same as the ones over
+/// HTTP, and the events are pushed as `subscription` notifications.
+pub(in crate::rpc) async fn serve_ws_conn(
+    ws_stream: WsStream,
+    sys_handle: Arc<SystemHandle>,
+    shutdown_token: CancellationToken,
+) {
+    // Subscribed first, so that no event is missed while the requests are
+    // handled
+    let mut ledger_event_rx = sys_handle.machine.ledger.ledger_event_tx.subscribe();
+
+    let (mut ws_tx, mut ws_rx) = ws_stream.split();
+
+    let mut subs = Subscriptions::new();
+
+    loop {
+        let msgs = tokio::select! {
+            msg = ws_rx.next() => {
+                let text = match msg {
+                    Some(Ok(WsMessage::Text(t))) => t,
+                    Some(Ok(WsMessage::Close(_))) | None => break,
+                    // Pings are answered by the stream itself
+                    Some(Ok(_)) => continue,
+                    Some(Err(err)) => {
+                        warn!("Error reading a ws msg, err: {}", err);
+                        break;
+                    }
+                };
+
+                handle_request(&text, &mut subs, &sys_handle).await
+            }
+            ev = ledger_event_rx.recv() => match ev {
+                Ok(ev) => match subs.make_notifications(&ev, &sys_handle).await {
+                    Ok(n) => n,
+                    Err(err) => {
+                        warn!("Cannot make the notifications, ev: {}, err: {}", ev, err);
+                        continue;
+                    }
+                },
+                Err(RecvError::Lagged(count)) => {
+                    warn!("Ws conn has missed ledger events, count: {}", count);
+
+                    // A missed block may have confirmed a tx subscribed to,
+                    // so its status is read again from the ledger
+                    match subs.recheck_tx_statuses(&sys_handle).await {
+                        Ok(n) => n,
+                        Err(err) => {
+                            warn!("Cannot recheck the tx statuses, err: {}", err);
+                            continue;
+                        }
+                    }
+                }
+                Err(RecvError::Closed) => break,
+            },
+            _ = shutdown_token.cancelled() => {
+                let _ = ws_tx.send(WsMessage::Close(None)).await;
+                break;
+            }
+        };
+
+        for msg in msgs {
+            if let Err(err) = ws_tx.send(WsMessage::Text(msg)).await {
+                debug!("Ws conn is closed, err: {}", err);
+                return;
+            }
+        }
+    }
+}
+
+/// Reply to the request, followed by the notifications made right away,
+/// such as the current status of a tx. A request without an id is served
+/// but not replied to.
+async fn handle_request(
+    text: &str,
+    subs: &mut Subscriptions,
+    sys_handle: &SystemHandle,
+) -> Vec<String> {
+    let req = match parse_request(text) {
+        Ok(r) => r,
+        Err(err) => return vec![make_error_reply(JsonRpcId::Null, err)],
+    };
+
+    debug!("ws, json_rpc, method: {}", req.method);
+
+    let id = req.id.clone();
+
+    match (subscribe(req, subs, sys_handle).await, id) {
+        (Ok((result, notis)), Some(id)) => {
+            let mut msgs = vec![make_reply(id, result)];
+            msgs.extend(notis);
+
+            msgs
+        }
+        (Ok((_, notis)), None) => notis,
+        (Err(err), Some(id)) => vec![make_error_reply(id, err)],
+        (Err(err), None) => {
+            debug!("Ws notification has failed, err: {}", err.message);
+
+            vec![]
+        }
+    }
+}
+
+fn parse_request(text: &str) -> Result<JsonRequest, JsonRPCError> {
+    let req: Value = serde_json::from_str(text).map_err(|err| {
+        JsonRPCError::new(
+            PARSE_ERROR,
+            format!("Failed to parse as json, err: {}", err),
+        )
+    })?;
+
+    let req: JsonRequest = serde_json::from_value(req).map_err(|err| {
+        JsonRPCError::new(
+            INVALID_REQUEST,
+            format!("Failed to parse as json_request, err: {}", err),
+        )
+    })?;
+
+    if req.jsonrpc != JSON_RPC_2 {
+        return Err(JsonRPCError::new(
+            INVALID_REQUEST,
+            format!("jsonrpc should be {}, jsonrpc: {}", JSON_RPC_2, req.jsonrpc),
+        ));
+    }
+
+    Ok(req)
+}
+
+/// Result of the request, and the notifications to follow the reply.
+async fn subscribe(
+    req: JsonRequest,
+    subs: &mut Subscriptions,
+    sys_handle: &SystemHandle,
+) -> Result<(Value, Vec<String>), JsonRPCError> {
+    if req.method == "unsubscribe" {
+        let rb: UnsubscribeRequest = parse_params(&req)?;
+
+        return Ok((Value::Bool(subs.remove(&rb.subscription)), vec![]));
+    }
+
+    if subs.len() >= MAX_SUBSCRIPTION_COUNT {
+        return Err(JsonRPCError::new(
+            INTERNAL_ERROR,
+            format!(
+                "Too many subscriptions on this conn, max: {}",
+                MAX_SUBSCRIPTION_COUNT
+            ),
+        ));
+    }
+
+    let sub_id = match req.method.as_str() {
+        "subscribe_new_blocks" => subs.insert(Subscription::NewBlocks),
+        "subscribe_pending_txs" => subs.insert(Subscription::PendingTxs),
+        "subscribe_ctr_events" => {
+            let rb: SubscribeCtrEventsRequest = parse_params(&req)?;
+
+            subs.insert(Subscription::CtrEvents(rb.ctr_addr))
+        }
+        "subscribe_tx_status" => {
+            let rb: SubscribeTxStatusRequest = parse_params(&req)?;
+
+            return subscribe_tx_status(rb.tx_hash, subs, sys_handle)
+                .await
+                .map_err(internal_error);
+        }
+        _ => {
+            return Err(JsonRPCError::new(
+                METHOD_NOT_FOUND,
+                format!("Method is not found, method: {}", req.method),
+            ))
+        }
+    };
+
+    Ok((Value::String(sub_id), vec![]))
+}
+
+async fn subscribe_tx_status(
+    tx_hash: String,
+    subs: &mut Subscriptions,
+    sys_handle: &SystemHandle,
+) -> Result<(Value, Vec<String>), RPCError> {
+    let status = subscription::get_tx_status(&tx_hash, sys_handle).await?;
+
+    let sub_id = if status == TxStatus::Confirmed {
+        // Nothing is left to tell of, so it ends with the status
+        subs.new_id()
+    } else {
+        subs.insert(Subscription::TxStatus(tx_hash.clone()))
+    };
+
+    let ev = TxStatusEvent {
+        tx_hash,
+        status,
+        block_height: None,
+        block_hash: None,
+    };
+
+    let noti = subscription::make_notification(&sub_id, ev)?;
+
+    Ok((Value::String(sub_id), vec![noti]))
+}
+
+// No client sends the legacy byte array params over WebSocket, so they are
+// taken as they are
+fn parse_params<P: DeserializeOwned>(req: &JsonRequest) -> Result<P, JsonRPCError> {
+    let params = match &req.params {
+        Some(p) => p.clone(),
+        None => {
+            return Err(JsonRPCError::new(
+                INVALID_PARAMS,
+                format!("{} should contain params", req.method),
+            ))
+        }
+    };
+
+    serde_json::from_value(params).map_err(|err| JsonRPCError::new(INVALID_PARAMS, err.to_string()))
+}
+
+fn internal_error(err: RPCError) -> JsonRPCError {
+    JsonRPCError::new(INTERNAL_ERROR, err.to_string())
+}
+
+fn make_reply(id: JsonRpcId, result: Value) -> String {
+    let resp = JsonResponse::new_result(id, result);
+
+    serde_json::to_string(&resp).unwrap_or_default()
+}
+
+fn make_error_reply(id: JsonRpcId, err: JsonRPCError) -> String {
+    let resp: JsonResponse<()> = JsonResponse::new_error(id, err);
+
+    serde_json::to_string(&resp).unwrap_or_default()
+}
@@ -0,0 +1,291 @@
+use crate::rpc::RPCError;
+use crate::system::SystemHandle;
+use sak_ledger::DistLedgerEvent;
+use sak_rpc_interface::{
+    CtrEvent, CtrEventKind, JsonNotification, NewBlockEvent, PendingTxEvent, SubscriptionParams,
+    TxStatus, TxStatusEvent, JSON_RPC_2, SUBSCRIPTION_METHOD,
+};
+use sak_types::{Block, BlockHash, BlockHeight, TxCtrOp, TxHash};
+use serde::Serialize;
+use std::collections::HashMap;
+
+pub(in crate::rpc) enum Subscription {
+    NewBlocks,
+    PendingTxs,
+    TxStatus(TxHash),
+    CtrEvents(String),
+}
+
+/// Subscriptions of a WebSocket conn, keyed by their ids.
+pub(in crate::rpc) struct Subscriptions {
+    subs: HashMap<String, Subscription>,
+    next_id: u64,
+}
+
+impl Subscriptions {
+    pub fn new() -> Subscriptions {
+        Subscriptions {
+            subs: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.subs.len()
+    }
+
+    pub fn new_id(&mut self) -> String {
+        let id = format!("0x{:x}", self.next_id);
+
+        self.next_id += 1;
+
+        id
+    }
+
+    pub fn insert(&mut self, sub: Subscription) -> String {
+        let id = self.new_id();
+
+        self.subs.insert(id.clone(), sub);
+
+        id
+    }
+
+    pub fn remove(&mut self, id: &String) -> bool {
+        self.subs.remove(id).is_some()
+    }
+
+    /// Current status of each tx subscribed to, for the events a lagging
+    /// conn has missed. A confirmed tx ends its subscription, as it would
+    /// have on the block event.
+    pub async fn recheck_tx_statuses(
+        &mut self,
+        sys_handle: &SystemHandle,
+    ) -> Result<Vec<String>, RPCError> {
+        let mut notis = vec![];
+        let mut confirmed_sub_ids = vec![];
+
+        for (id, sub) in self.subs.iter() {
+            let tx_hash = match sub {
+                Subscription::TxStatus(h) => h,
+                _ => continue,
+            };
+
+            let status = get_tx_status(tx_hash, sys_handle).await?;
+
+            match status {
+                TxStatus::Unknown => continue,
+                TxStatus::Confirmed => confirmed_sub_ids.push(id.clone()),
+                TxStatus::Pending => {}
+            };
+
+            let ev = TxStatusEvent {
+                tx_hash: tx_hash.clone(),
+                status,
+                block_height: None,
+                block_hash: None,
+            };
+
+            notis.push(make_notification(id, ev)?);
+        }
+
+        for id in confirmed_sub_ids {
+            self.subs.remove(&id);
+        }
+
+        Ok(notis)
+    }
+
+    /// Notifications, serialized, the event makes to the subscribers. Tx
+    /// status subscriptions end once the tx is confirmed.
+    pub async fn make_notifications(
+        &mut self,
+        ev: &DistLedgerEvent,
+        sys_handle: &SystemHandle,
+    ) -> Result<Vec<String>, RPCError> {
+        if self.subs.is_empty() {
+            return Ok(vec![]);
+        }
+
+        match ev {
+            DistLedgerEvent::TxPoolStat(tx_hashes) => {
+                self.notify_pending_txs(tx_hashes, sys_handle).await
+            }
+            DistLedgerEvent::NewBlocks(blocks) => self.notify_new_blocks(blocks, sys_handle).await,
+        }
+    }
+
+    async fn notify_pending_txs(
+        &self,
+        tx_hashes: &[TxHash],
+        sys_handle: &SystemHandle,
+    ) -> Result<Vec<String>, RPCError> {
+        let mut notis = vec![];
+
+        // Txs already written are gone from the pool
+        let (tcs, _) = sys_handle
+            .machine
+            .ledger
+            .sync_pool
+            .find_txs(tx_hashes)
+            .await;
+
+        for (id, sub) in self.subs.iter() {
+            match sub {
+                Subscription::PendingTxs => {
+                    for tc in &tcs {
+                        let ev = PendingTxEvent {
+                            tx_hash: tc.get_tx_hash().clone(),
+                            ctr_addr: tc.get_ctr_addr().clone(),
+                            created_at: tc.get_created_at().clone(),
+                        };
+
+                        notis.push(make_notification(id, ev)?);
+                    }
+                }
+                Subscription::TxStatus(tx_hash) if tx_hashes.contains(tx_hash) => {
+                    let ev = TxStatusEvent {
+                        tx_hash: tx_hash.clone(),
+                        status: TxStatus::Pending,
+                        block_height: None,
+                        block_hash: None,
+                    };
+
+                    notis.push(make_notification(id, ev)?);
+                }
+                _ => {}
+            };
+        }
+
+        Ok(notis)
+    }
+
+    async fn notify_new_blocks(
+        &mut self,
+        blocks: &[(BlockHeight, BlockHash)],
+        sys_handle: &SystemHandle,
+    ) -> Result<Vec<String>, RPCError> {
+        let ledger = &sys_handle.machine.ledger;
+
+        let mut blocks = blocks.to_vec();
+        blocks.sort();
+
+        let mut notis = vec![];
+        let mut confirmed_sub_ids = vec![];
+
+        let has_ctr_subs = self
+            .subs
+            .values()
+            .any(|s| matches!(s, Subscription::CtrEvents(_)));
+
+        for (_, block_hash) in blocks {
+            let block = match ledger.get_block(&block_hash)? {
+                Some(b) => b,
+                None => continue,
+            };
+
+            let ctr_events = if has_ctr_subs {
+                make_ctr_events(&block, sys_handle).await?
+            } else {
+                vec![]
+            };
+
+            for (id, sub) in self.subs.iter() {
+                match sub {
+                    Subscription::NewBlocks => {
+                        let ev = NewBlockEvent {
+                            block_hash: block.get_block_hash().clone(),
+                            block_height: block.block_height,
+                            tx_hashes: block.tx_hashes.clone(),
+                            created_at: block.created_at.clone(),
+                        };
+
+                        notis.push(make_notification(id, ev)?);
+                    }
+                    Subscription::TxStatus(tx_hash) if block.tx_hashes.contains(tx_hash) => {
+                        let ev = TxStatusEvent {
+                            tx_hash: tx_hash.clone(),
+                            status: TxStatus::Confirmed,
+                            block_height: Some(block.block_height),
+                            block_hash: Some(block.get_block_hash().clone()),
+                        };
+
+                        notis.push(make_notification(id, ev)?);
+                        confirmed_sub_ids.push(id.clone());
+                    }
+                    Subscription::CtrEvents(ctr_addr) => {
+                        for ev in ctr_events.iter().filter(|e| &e.ctr_addr == ctr_addr) {
+                            notis.push(make_notification(id, ev)?);
+                        }
+                    }
+                    _ => {}
+                };
+            }
+        }
+
+        for id in confirmed_sub_ids {
+            self.subs.remove(&id);
+        }
+
+        Ok(notis)
+    }
+}
+
+pub(in crate::rpc) async fn get_tx_status(
+    tx_hash: &TxHash,
+    sys_handle: &SystemHandle,
+) -> Result<TxStatus, RPCError> {
+    let ledger = &sys_handle.machine.ledger;
+
+    let status = if ledger.get_tx(tx_hash).await?.is_some() {
+        TxStatus::Confirmed
+    } else if ledger.sync_pool.contains_tx(tx_hash).await {
+        TxStatus::Pending
+    } else {
+        TxStatus::Unknown
+    };
+
+    Ok(status)
+}
+
+async fn make_ctr_events(
+    block: &Block,
+    sys_handle: &SystemHandle,
+) -> Result<Vec<CtrEvent>, RPCError> {
+    let txs = sys_handle.machine.ledger.get_txs(&block.tx_hashes).await?;
+
+    let mut evs = vec![];
+
+    for tx in txs {
+        let kind = match tx.get_ctr_op() {
+            TxCtrOp::ContractDeploy => CtrEventKind::Deploy,
+            TxCtrOp::ContractCall => CtrEventKind::Call,
+            TxCtrOp::None => continue,
+        };
+
+        evs.push(CtrEvent {
+            ctr_addr: tx.get_ctr_addr().clone(),
+            kind,
+            tx_hash: tx.get_tx_hash().clone(),
+            block_height: block.block_height,
+            block_hash: block.get_block_hash().clone(),
+        });
+    }
+
+    Ok(evs)
+}
+
+pub(in crate::rpc) fn make_notification<R: Serialize>(
+    sub_id: &String,
+    result: R,
+) -> Result<String, RPCError> {
+    let noti = JsonNotification {
+        jsonrpc: JSON_RPC_2.into(),
+        method: SUBSCRIPTION_METHOD.into(),
+        params: SubscriptionParams {
+            subscription: sub_id.clone(),
+            result,
+        },
+    };
+
+    Ok(serde_json::to_string(&noti)?)
+}
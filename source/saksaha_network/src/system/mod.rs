@@ -5,5 +5,6 @@ mod sys_handle;
 mod system;
 
 pub(crate) use error::*;
+pub(crate) use shutdown::*;
 pub(crate) use sys_handle::*;
 pub use system::*;
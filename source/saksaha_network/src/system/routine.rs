@@ -22,6 +22,7 @@ use sak_store_interface::{MRSAccessor, MRSInterface};
 use sak_vm::SakVM;
 use sak_vm_interface::ContractProcessor;
 use std::sync::Arc;
+use std::time::Instant;
 
 pub(super) struct Routine {
     pub(super) shutdown_manager: ShutdownMng,
@@ -90,7 +91,20 @@ impl Routine {
         };
 
         let peer_table = {
-            let ps = PeerTable::init(config.p2p.p2p_peer_table_capacity).await?;
+            // Bootstrap peers are given reserved slots
+            let reserved_public_keys = config
+                .p2p
+                .bootstrap_addrs
+                .iter()
+                .filter_map(|a| a.public_key_str.clone())
+                .collect();
+
+            let ps = PeerTable::init(
+                config.p2p.p2p_peer_table_capacity,
+                config.p2p.p2p_outbound_capacity,
+                reserved_public_keys,
+            )
+            .await?;
 
             Arc::new(ps)
         };
@@ -150,7 +164,11 @@ impl Routine {
             Arc::new(i)
         };
 
+        let acc_dir = SaksahaFS::acc_dir(&identity.credential.public_key_str)?;
+
         let p2p_host = {
+            let addr_book_path = acc_dir.join("addr_book");
+
             let p2p_host_args = P2PHostArgs {
                 addr_expire_duration: config.p2p.addr_expire_duration,
                 addr_monitor_interval: config.p2p.addr_monitor_interval,
@@ -168,13 +186,17 @@ impl Routine {
                 bootstrap_addrs: config.p2p.bootstrap_addrs,
                 identity: identity.clone(),
                 peer_table: peer_table.clone(),
+                addr_book_path: Some(addr_book_path),
+                external_addr: config.p2p.external_addr,
+                port_mapping: config.p2p.port_mapping.unwrap_or(false),
+                shutdown_token: self.shutdown_manager.get_token(),
             };
 
             P2PHost::init(p2p_host_args).await?
         };
 
         let mrs: Arc<MRSAccessor> = {
-            let m = MRS::init(&config.p2p.public_key_str).await?;
+            let m = MRS::init(&acc_dir).await?;
 
             Arc::new(Box::new(m))
         };
@@ -186,12 +208,13 @@ impl Routine {
 
         let ledger = {
             let l = Ledger::init(
-                &config.p2p.public_key_str,
+                &acc_dir,
                 config.blockchain.tx_sync_interval,
                 None,
                 config.blockchain.block_sync_interval,
                 identity.clone(),
                 vm,
+                None,
             )
             .await?;
 
@@ -214,10 +237,15 @@ impl Routine {
                 config.node.mine_interval,
                 config.node.node_task_min_interval,
                 config.node.peer_register_interval,
+                config.node.full_block_relay,
+                config.node.light,
+                config.node.network_id.clone(),
+                None,
                 p2p_host.get_discovery().clone(),
+                self.shutdown_manager.get_token(),
             );
 
-            ln
+            Arc::new(ln)
         };
 
         let rpc = {
@@ -231,6 +259,9 @@ impl Routine {
                 let s = SystemHandle {
                     machine: machine.clone(),
                     p2p_monitor,
+                    light_client: local_node.light_client.clone(),
+                    public_key: config.p2p.public_key_str.clone(),
+                    started_at: Instant::now(),
                 };
 
                 Arc::new(s)
@@ -239,39 +270,60 @@ impl Routine {
             let rpc_args = RPCArgs {
                 sys_handle,
                 rpc_socket,
+                shutdown_token: self.shutdown_manager.get_token(),
+                metrics_path: config.rpc.metrics_path.clone(),
             };
 
             RPC::init(rpc_args)?
         };
 
-        let system_thread = tokio::spawn(async move {
-            let _ = tokio::join!(rpc.run(), p2p_host.run(), local_node.run(), machine.run(),);
-        });
-
-        tokio::select!(
-            c = tokio::signal::ctrl_c() => {
-                match c {
-                    Ok(_) => {
-                        info!(
-                            "System main routine terminated. This is likely \
-                            not what you have expected",
-                        );
-
-                        self.shutdown_manager.shutdown();
-                    },
-                    Err(err) => {
-                        error!(
-                            "Unexpected error while waiting for \
-                                ctrl+p, err: {}",
-                            err,
-                        );
-
-                        self.shutdown_manager.shutdown();
+        let discovery = p2p_host.get_discovery().clone();
+
+        let mut system_thread = {
+            let machine = machine.clone();
+            let local_node = local_node.clone();
+            let shutdown_token = self.shutdown_manager.get_token();
+
+            tokio::spawn(async move {
+                let _ = tokio::join!(rpc.run(), p2p_host.run(), local_node.run(), async {
+                    tokio::select! {
+                        _ = machine.run() => {},
+                        _ = shutdown_token.cancelled() => {},
                     }
+                });
+            })
+        };
+
+        let signaled = tokio::select! {
+            res = self.shutdown_manager.wait_for_signal() => {
+                if let Err(err) = res {
+                    error!(
+                        "Unexpected error while waiting for a signal, err: {}",
+                        err,
+                    );
                 }
+
+                true
             },
-            _ = system_thread => {}
-        );
+            _ = &mut system_thread => false,
+        };
+
+        if signaled {
+            info!("Received a shutdown signal");
+
+            self.shutdown_manager
+                .shutdown(Some(system_thread), local_node, machine, discovery)
+                .await?;
+        } else {
+            warn!(
+                "System main routine terminated. This is likely not what you \
+                have expected",
+            );
+
+            self.shutdown_manager
+                .shutdown(None, local_node, machine, discovery)
+                .await?;
+        }
 
         Ok(())
     }
@@ -1,12 +1,105 @@
-use sak_logger::{error, info};
+use super::SaksahaError;
+use crate::node::LocalNode;
+use sak_logger::{info, warn};
+use sak_machine::SakMachine;
+use sak_p2p_discovery::Discovery;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
 
-pub struct ShutdownMng;
+// Time given to the routines to wind down before they are aborted
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub(crate) struct ShutdownMng {
+    shutdown_token: CancellationToken,
+}
 
 impl ShutdownMng {
-    pub(crate) fn shutdown(&self) {
-        // TODO Shutdown behavior has to be implemented later
-        info!("Calling shutdown callback");
+    pub(crate) fn new(shutdown_token: CancellationToken) -> ShutdownMng {
+        ShutdownMng { shutdown_token }
+    }
+
+    /// Token every long running routine of the node stops on.
+    pub(crate) fn get_token(&self) -> CancellationToken {
+        self.shutdown_token.clone()
+    }
+
+    /// Resolves on SIGINT, or SIGTERM on unix.
+    pub(crate) async fn wait_for_signal(&self) -> Result<(), SaksahaError> {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+
+            let mut sigterm = signal(SignalKind::terminate())?;
+
+            tokio::select! {
+                c = tokio::signal::ctrl_c() => c?,
+                _ = sigterm.recv() => {},
+            };
+        }
+
+        #[cfg(not(unix))]
+        tokio::signal::ctrl_c().await?;
+
+        Ok(())
+    }
+
+    /// Cancels the routines, waits for the system thread and the peer nodes
+    /// to end, removes the port mappings and flushes the dbs. `system_thread`
+    /// is `None` if it has already ended.
+    pub(crate) async fn shutdown(
+        &self,
+        system_thread: Option<JoinHandle<()>>,
+        local_node: Arc<LocalNode>,
+        machine: Arc<SakMachine>,
+        discovery: Arc<Discovery>,
+    ) -> Result<(), SaksahaError> {
+        info!("Shutting down the node");
+
+        self.shutdown_token.cancel();
+
+        let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+
+        if let Some(mut system_thread) = system_thread {
+            match tokio::time::timeout_at(deadline, &mut system_thread).await {
+                Ok(_) => {
+                    info!("System routines have stopped");
+                }
+                Err(_) => {
+                    warn!(
+                        "System routines did not stop in {:?}, aborting",
+                        SHUTDOWN_TIMEOUT,
+                    );
+
+                    system_thread.abort();
+                }
+            };
+        }
+
+        // Peer nodes send their queued tasks before they stop, and may
+        // still read the dbs while doing so
+        match tokio::time::timeout_at(deadline, local_node.join_peer_nodes()).await {
+            Ok(_) => {
+                info!("Peer nodes have stopped");
+            }
+            Err(_) => {
+                warn!(
+                    "Peer nodes did not stop in {:?}, aborting",
+                    SHUTDOWN_TIMEOUT,
+                );
+
+                local_node.abort_peer_nodes().await;
+            }
+        };
+
+        discovery.unmap_ports().await;
+
+        machine.flush()?;
+
+        info!("Flushed the ledger and MRS dbs");
 
-        std::process::exit(1);
+        Ok(())
     }
 }
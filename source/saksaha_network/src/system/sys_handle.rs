@@ -1,8 +1,14 @@
+use crate::node::LightClient;
 use crate::p2p::P2PMonitor;
 use sak_machine::SakMachine;
 use std::sync::Arc;
+use std::time::Instant;
 
 pub(crate) struct SystemHandle {
     pub(crate) machine: Arc<SakMachine>,
     pub(crate) p2p_monitor: Arc<P2PMonitor>,
+    // Set when the node runs as a light node
+    pub(crate) light_client: Option<Arc<LightClient>>,
+    pub(crate) public_key: String,
+    pub(crate) started_at: Instant,
 }
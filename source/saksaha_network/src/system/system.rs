@@ -1,5 +1,7 @@
 use super::{routine::Routine, shutdown::ShutdownMng};
 use sak_logger::error;
+use std::net::SocketAddr;
+use tokio_util::sync::CancellationToken;
 
 pub struct System {}
 
@@ -13,18 +15,25 @@ pub struct SystemRunArgs {
     pub p2p_task_interval: Option<u16>,
     pub p2p_task_queue_capacity: Option<u16>,
     pub p2p_peer_table_capacity: Option<i16>,
+    pub p2p_outbound_capacity: Option<i16>,
     pub p2p_max_conn_count: Option<u16>,
     pub p2p_dial_interval: Option<u16>,
     pub rpc_port: Option<u16>,
+    pub metrics_path: Option<String>,
     pub p2p_port: Option<u16>,
     pub addr_expire_duration: Option<u64>,
     pub addr_monitor_interval: Option<u64>,
     pub bootstrap_urls: Option<Vec<String>>,
+    pub external_addr: Option<SocketAddr>,
+    pub port_mapping: Option<bool>,
     pub cfg_profile: Option<String>,
     pub miner: Option<bool>,
     pub mine_interval: Option<u64>,
     pub node_task_min_interval: Option<u64>,
     pub peer_register_interval: Option<u64>,
+    pub full_block_relay: Option<bool>,
+    pub light: Option<bool>,
+    pub network_id: Option<String>,
     pub tx_sync_interval: Option<u64>,
     pub block_sync_interval: Option<u64>,
     pub public_key: Option<String>,
@@ -36,28 +45,30 @@ impl System {
             .enable_all()
             .build();
 
-        match runtime {
-            Ok(r) => r.block_on(async {
-                let shutdown_manager = ShutdownMng {};
-
-                let routine = Routine { shutdown_manager };
-
-                match routine.run(sys_run_args).await {
-                    Ok(_) => (),
-                    Err(err) => {
-                        println!(
-                            "Error initializing (running) main routine, \
-                            err: {}",
-                            err,
-                        );
-                    }
-                };
-            }),
+        let runtime = match runtime {
+            Ok(r) => r,
             Err(err) => {
                 return Err(format!("runtime fail, err: {:?}", err));
             }
         };
 
-        Ok(())
+        runtime.block_on(async {
+            let shutdown_manager = ShutdownMng::new(CancellationToken::new());
+
+            let routine = Routine { shutdown_manager };
+
+            match routine.run(sys_run_args).await {
+                Ok(_) => Ok(()),
+                Err(err) => {
+                    error!(
+                        "Error initializing (running) main routine, \
+                        err: {}",
+                        err,
+                    );
+
+                    Err(err.to_string())
+                }
+            }
+        })
     }
 }
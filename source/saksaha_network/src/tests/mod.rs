@@ -1,3 +1,7 @@
+mod sim_net;
+mod test_net;
 mod utils;
 
+pub(crate) use sim_net::*;
+pub(crate) use test_net::*;
 pub(crate) use utils::*;
@@ -0,0 +1,617 @@
+use super::SaksahaTestUtils;
+use crate::ledger::Ledger;
+use crate::mrs::MRS;
+use crate::node::LocalNode;
+use crate::SaksahaError;
+use sak_crypto::{Credential, RandomSource, SeededRandomSource};
+use sak_logger::{debug, info, warn};
+use sak_machine::SakMachine;
+use sak_p2p_discovery::{DiscAddr, Discovery, DiscoveryArgs};
+use sak_p2p_id::Identity;
+use sak_p2p_peertable::{Peer, PeerStatus, PeerTable, SlotGuard};
+use sak_p2p_transport::{MemStream, Msg, Transport, UpgradedConn};
+use sak_store_interface::MRSAccessor;
+use sak_types::{BlockHash, BlockHeight, TxCandidate, TxHash};
+use sak_utils_time::{Clock, SimClock, Sleep};
+use sak_vm::SakVM;
+use sak_vm_interface::ContractProcessor;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+const NODE_COUNT: usize = 3;
+
+const MIN_LATENCY: Duration = Duration::from_millis(10);
+
+const MAX_LATENCY: Duration = Duration::from_millis(200);
+
+// Virtual time the nodes are given to say hello to each other
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Sleep on the paused tokio clock, which ends once the runtime is idle
+const SETTLE_PROBE: Duration = Duration::from_millis(1);
+
+// Discovery does not run in a simulation, so the addrs exchanged at hello
+// pile up in its task queue
+const DISC_TASK_QUEUE_CAPACITY: u16 = 1024;
+
+/// Builds a `SimNet`.
+pub(crate) struct SimNetBuilder {
+    seed: u64,
+    node_count: usize,
+    miners: Vec<usize>,
+    mine_interval: Option<u64>,
+    latency: (Duration, Duration),
+}
+
+impl SimNetBuilder {
+    pub fn seed(mut self, seed: u64) -> SimNetBuilder {
+        self.seed = seed;
+        self
+    }
+
+    pub fn nodes(mut self, node_count: usize) -> SimNetBuilder {
+        self.node_count = node_count;
+        self
+    }
+
+    /// Idxs of the nodes running a miner.
+    pub fn miners(mut self, miners: &[usize]) -> SimNetBuilder {
+        self.miners = miners.to_vec();
+        self
+    }
+
+    pub fn mine_interval(mut self, mine_interval: u64) -> SimNetBuilder {
+        self.mine_interval = Some(mine_interval);
+        self
+    }
+
+    /// Bounds of the latency of a msg, drawn from the seeded rng.
+    pub fn latency(mut self, min: Duration, max: Duration) -> SimNetBuilder {
+        self.latency = (min, max);
+        self
+    }
+
+    /// Starts the nodes, links every pair of them and runs the simulation
+    /// until each of them has said hello to all the others.
+    pub async fn build(self) -> SimNet {
+        let clock = Arc::new(SimClock::new());
+
+        let credentials: Vec<Credential> = (0..self.node_count)
+            .map(|_| Credential::new_random().expect("Credential should be created"))
+            .collect();
+
+        // Every node starts on a fresh data dir, nothing to clean up
+        SaksahaTestUtils::init_test(&[]);
+
+        let shutdown_token = CancellationToken::new();
+
+        let mut rng = SimRng::new(self.seed);
+
+        let mut nodes = vec![];
+
+        for (idx, credential) in credentials.iter().enumerate() {
+            let node = start_sim_node(
+                credential,
+                self.miners.contains(&idx),
+                self.mine_interval,
+                clock.clone(),
+                Arc::new(SeededRandomSource::new(rng.next_u64())),
+                shutdown_token.clone(),
+            )
+            .await
+            .expect("Sim node should start");
+
+            nodes.push(node);
+        }
+
+        let mut sim_net = SimNet {
+            clock,
+            nodes,
+            latency: self.latency,
+            rng,
+            shutdown_token,
+        };
+
+        // Node `j` dials node `i`, as a node bootstraps the ones before her
+        for j in 0..sim_net.nodes.len() {
+            for i in 0..j {
+                sim_net
+                    .link(j, i)
+                    .await
+                    .expect("Sim nodes should be linked");
+            }
+        }
+
+        sim_net
+            .run_until_connected(CONNECT_TIMEOUT)
+            .await
+            .expect("Sim nodes should connect to each other");
+
+        info!(
+            "Sim net is up, node count: {}, seed: {}",
+            sim_net.nodes.len(),
+            self.seed,
+        );
+
+        sim_net
+    }
+}
+
+/// A full node of a simulation. It has no sockets, its peers are linked in
+/// memory.
+pub(crate) struct SimNode {
+    pub public_key: String,
+    pub machine: Arc<SakMachine>,
+    pub peer_table: Arc<PeerTable>,
+    identity: Arc<Identity>,
+    // Removed when the node is dropped
+    _data_dir: TempDir,
+}
+
+impl SimNode {
+    /// Height and hash of the latest block.
+    pub async fn get_tip(&self) -> Result<(BlockHeight, BlockHash), SaksahaError> {
+        let ledger = &self.machine.ledger;
+
+        let height = ledger
+            .get_latest_block_height()?
+            .ok_or("Latest block height does not exist")?;
+
+        let block = ledger
+            .get_block_by_height(&height)
+            .await?
+            .ok_or("Latest block does not exist")?;
+
+        Ok((height, block.get_block_hash().to_string()))
+    }
+
+    // Peers that have said hello
+    async fn count_peers(&self) -> usize {
+        self.peer_table
+            .get_peer_infos()
+            .await
+            .iter()
+            .filter(|p| p.chain_status.is_some())
+            .count()
+    }
+}
+
+/// Nodes of one process running on virtual time, linked in memory. Every
+/// timer of the nodes goes through a `SimClock` the driver moves only once
+/// the nodes have settled, and msgs arrive after a latency drawn from a
+/// seeded rng, in the order they have been sent on each link. The nodes
+/// pick the peers they gossip to with rngs seeded from it too. A run is
+/// therefore reproducible from its seed, up to the order in which the
+/// runtime polls tasks woken at once, and takes a fraction of the wall time
+/// it simulates.
+///
+/// A test runs it on a paused tokio clock, `#[tokio::test(start_paused =
+/// true)]`, by which the driver tells when the nodes have settled.
+pub(crate) struct SimNet {
+    clock: Arc<SimClock>,
+    nodes: Vec<SimNode>,
+    latency: (Duration, Duration),
+    rng: SimRng,
+    shutdown_token: CancellationToken,
+}
+
+impl SimNet {
+    pub fn builder() -> SimNetBuilder {
+        SimNetBuilder {
+            seed: 0,
+            node_count: NODE_COUNT,
+            miners: vec![],
+            mine_interval: None,
+            latency: (MIN_LATENCY, MAX_LATENCY),
+        }
+    }
+
+    pub fn node(&self, idx: usize) -> &SimNode {
+        &self.nodes[idx]
+    }
+
+    /// Virtual time passed since the simulation has started.
+    pub fn elapsed(&self) -> Duration {
+        self.clock.elapsed()
+    }
+
+    pub async fn send_tx(&self, idx: usize, tx: TxCandidate) -> Result<TxHash, SaksahaError> {
+        let tx_hash = self.nodes[idx].machine.ledger.send_tx(tx).await?;
+
+        Ok(tx_hash)
+    }
+
+    /// Runs the simulation until every node has the same latest block, at
+    /// `min_height` or above, for at most `timeout` of virtual time.
+    pub async fn run_until_same_tip(
+        &self,
+        min_height: BlockHeight,
+        timeout: Duration,
+    ) -> Result<(BlockHeight, BlockHash), SaksahaError> {
+        let deadline = self.clock.elapsed() + timeout;
+
+        loop {
+            self.settle().await;
+
+            let mut tips = vec![];
+
+            for node in &self.nodes {
+                tips.push(node.get_tip().await?);
+            }
+
+            let first = tips[0].clone();
+
+            if first.0 >= min_height && tips.iter().all(|t| *t == first) {
+                return Ok(first);
+            }
+
+            if self.clock.elapsed() >= deadline {
+                return Err(format!(
+                    "Nodes have not converged in {:?}, tips: {:?}",
+                    timeout, tips,
+                )
+                .into());
+            }
+
+            self.step()?;
+        }
+    }
+
+    /// Stops the nodes and removes their data dirs.
+    pub async fn shutdown(self) {
+        self.shutdown_token.cancel();
+
+        self.settle().await;
+
+        for node in &self.nodes {
+            if let Err(err) = node.machine.flush() {
+                warn!("Could not flush a sim node, err: {}", err);
+            }
+        }
+    }
+
+    async fn run_until_connected(&self, timeout: Duration) -> Result<(), SaksahaError> {
+        let deadline = self.clock.elapsed() + timeout;
+
+        for (idx, node) in self.nodes.iter().enumerate() {
+            loop {
+                self.settle().await;
+
+                if node.count_peers().await >= self.nodes.len() - 1 {
+                    break;
+                }
+
+                if self.clock.elapsed() >= deadline {
+                    return Err(format!("Sim node has not connected, idx: {}", idx).into());
+                }
+
+                self.step()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Moves the clock to the next timer
+    fn step(&self) -> Result<(), SaksahaError> {
+        match self.clock.advance() {
+            true => Ok(()),
+            false => Err("Simulation has stalled, no routine is waiting on a timer".into()),
+        }
+    }
+
+    // A paused tokio clock is moved by the runtime only when no task is
+    // left to run, so the nodes have settled once the probe wakes up
+    async fn settle(&self) {
+        tokio::time::sleep(SETTLE_PROBE).await;
+    }
+
+    // Connects node `from` to node `to` over a pair of in-memory links, as
+    // if `from` had dialed `to` and they had shaken hands
+    async fn link(&mut self, from: usize, to: usize) -> Result<(), SaksahaError> {
+        let (from_stream, to_stream) = {
+            let (from_tx, from_rx) = self.spawn_link();
+            let (to_tx, to_rx) = self.spawn_link();
+
+            (
+                MemStream::new(from_tx, to_rx),
+                MemStream::new(to_tx, from_rx),
+            )
+        };
+
+        let conn_id = format!("sim-{}-{}", from, to);
+
+        let from_node = &self.nodes[from];
+        let to_node = &self.nodes[to];
+
+        let from_peer = {
+            let slot_guard = from_node
+                .peer_table
+                .get_outbound_slot(&to_node.public_key)
+                .await?;
+
+            make_peer(to_node, from_stream, &conn_id, slot_guard, false)
+        };
+
+        let to_peer = {
+            let slot_guard = to_node
+                .peer_table
+                .get_inbound_slot(&from_node.public_key, "127.0.0.1")
+                .await?;
+
+            make_peer(from_node, to_stream, &conn_id, slot_guard, true)
+        };
+
+        from_node.peer_table.insert_mapping(from_peer).await?;
+        to_node.peer_table.insert_mapping(to_peer).await?;
+
+        debug!("Linked sim nodes, from: {}, to: {}", from, to);
+
+        Ok(())
+    }
+
+    // One way of a link. Each link draws its latencies from its own rng,
+    // which is seeded in the order the links are made.
+    fn spawn_link(&mut self) -> (UnboundedSender<Msg>, UnboundedReceiver<Msg>) {
+        let (sent_tx, sent_rx) = mpsc::unbounded_channel();
+        let (delivered_tx, delivered_rx) = mpsc::unbounded_channel();
+
+        let link = SimLink {
+            clock: self.clock.clone(),
+            rng: self.rng.fork(),
+            latency: self.latency,
+            sent_rx,
+            delivered_tx,
+        };
+
+        let shutdown_token = self.shutdown_token.clone();
+
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = link.run() => {},
+                _ = shutdown_token.cancelled() => {},
+            }
+        });
+
+        (sent_tx, delivered_rx)
+    }
+}
+
+// Routines of a node still running when a test panics are stopped
+impl Drop for SimNet {
+    fn drop(&mut self) {
+        self.shutdown_token.cancel();
+    }
+}
+
+struct SimLink {
+    clock: Arc<SimClock>,
+    rng: SimRng,
+    latency: (Duration, Duration),
+    sent_rx: UnboundedReceiver<Msg>,
+    delivered_tx: UnboundedSender<Msg>,
+}
+
+impl SimLink {
+    async fn run(mut self) {
+        let mut in_flight: VecDeque<(Instant, Msg)> = VecDeque::new();
+
+        loop {
+            let next_delivery: Sleep = match in_flight.front() {
+                Some((at, _)) => self
+                    .clock
+                    .sleep(at.saturating_duration_since(self.clock.now())),
+                None => Box::pin(std::future::pending()),
+            };
+
+            tokio::select! {
+                biased;
+
+                msg = self.sent_rx.recv() => {
+                    let msg = match msg {
+                        Some(m) => m,
+                        None => return,
+                    };
+
+                    // A msg never overtakes the ones sent before it
+                    let mut at = self.clock.now() + self.draw_latency();
+
+                    if let Some((last_at, _)) = in_flight.back() {
+                        at = at.max(*last_at);
+                    }
+
+                    in_flight.push_back((at, msg));
+                },
+                _ = next_delivery => {
+                    if let Some((_, msg)) = in_flight.pop_front() {
+                        if self.delivered_tx.send(msg).is_err() {
+                            return;
+                        }
+                    }
+                },
+            }
+        }
+    }
+
+    fn draw_latency(&mut self) -> Duration {
+        let (min, max) = self.latency;
+
+        let range = (max.saturating_sub(min)).as_millis() as u64;
+
+        min + Duration::from_millis(self.rng.next_u64() % (range + 1))
+    }
+}
+
+// splitmix64. Good enough to spread latencies, and has no state to get
+// stuck at.
+struct SimRng {
+    state: u64,
+}
+
+impl SimRng {
+    fn new(seed: u64) -> SimRng {
+        SimRng { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+
+        z ^ (z >> 31)
+    }
+
+    // A new rng, seeded by the next number of this one
+    fn fork(&mut self) -> SimRng {
+        SimRng::new(self.next_u64())
+    }
+}
+
+fn make_peer(
+    her_node: &SimNode,
+    stream: MemStream,
+    conn_id: &str,
+    slot_guard: SlotGuard,
+    is_initiator: bool,
+) -> Arc<Peer> {
+    let credential = &her_node.identity.credential;
+
+    let addr = DiscAddr::new_dummy(
+        credential.public_key,
+        credential.public_key_str.clone(),
+        credential.sig,
+        her_node.identity.disc_port,
+        her_node.identity.p2p_port,
+    );
+
+    let transport = Transport {
+        conn: RwLock::new(UpgradedConn::from_stream(
+            Box::new(stream),
+            conn_id.to_string(),
+            credential.public_key_str.clone(),
+        )),
+    };
+
+    let peer = Peer::new(
+        transport,
+        RwLock::new(PeerStatus::HandshakeInit),
+        Arc::new(addr),
+        slot_guard,
+        is_initiator,
+    );
+
+    Arc::new(peer)
+}
+
+async fn start_sim_node(
+    credential: &Credential,
+    miner: bool,
+    mine_interval: Option<u64>,
+    clock: Arc<SimClock>,
+    rng: Arc<dyn RandomSource>,
+    shutdown_token: CancellationToken,
+) -> Result<SimNode, SaksahaError> {
+    let public_key = &credential.public_key;
+
+    // Ports are never bound, they only tell the nodes apart in the logs
+    let identity = {
+        let i = Identity::new(&credential.secret, public_key, 0, 0)?;
+
+        Arc::new(i)
+    };
+
+    let peer_table = {
+        let ps = PeerTable::init(None, None, vec![]).await?;
+
+        Arc::new(ps)
+    };
+
+    let discovery = {
+        let (udp_socket, _) = sak_utils_net::setup_udp_socket(None).await?;
+
+        let disc_args = DiscoveryArgs {
+            addr_expire_duration: None,
+            addr_monitor_interval: None,
+            disc_dial_interval: None,
+            disc_table_capacity: None,
+            disc_task_interval: None,
+            disc_task_queue_capacity: Some(DISC_TASK_QUEUE_CAPACITY),
+            p2p_port: 0,
+            bootstrap_addrs: vec![],
+            udp_socket,
+            identity: identity.clone(),
+            addr_book_path: None,
+            external_addr: None,
+            port_mapping: false,
+        };
+
+        let (d, _) = Discovery::init(disc_args).await?;
+
+        Arc::new(d)
+    };
+
+    let data_dir = TempDir::new()?;
+
+    let mrs = {
+        let m = MRS::init(data_dir.path()).await?;
+
+        Arc::new(Box::new(m) as MRSAccessor)
+    };
+
+    let vm: ContractProcessor = {
+        let v = SakVM::init(mrs.clone())?;
+        Box::new(v)
+    };
+
+    let ledger = Ledger::init(
+        data_dir.path(),
+        None,
+        None,
+        None,
+        identity.clone(),
+        vm,
+        Some(clock as Arc<dyn Clock>),
+    )
+    .await?;
+
+    let machine = Arc::new(SakMachine { ledger, mrs });
+
+    let local_node = {
+        let ln = LocalNode::new(
+            peer_table.clone(),
+            machine.clone(),
+            Some(miner),
+            mine_interval,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(rng),
+            discovery,
+            shutdown_token,
+        );
+
+        Arc::new(ln)
+    };
+
+    tokio::spawn(async move {
+        local_node.run().await;
+    });
+
+    Ok(SimNode {
+        public_key: public_key.to_string(),
+        machine,
+        peer_table,
+        identity,
+        _data_dir: data_dir,
+    })
+}
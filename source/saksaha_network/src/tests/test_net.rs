@@ -0,0 +1,547 @@
+use super::SaksahaTestUtils;
+use crate::ledger::Ledger;
+use crate::mrs::MRS;
+use crate::node::{LinkFault, LocalNode};
+use crate::p2p::{P2PHost, P2PHostArgs};
+use crate::system::ShutdownMng;
+use crate::SaksahaError;
+use sak_crypto::Credential;
+use sak_logger::{debug, info, warn};
+use sak_machine::SakMachine;
+use sak_p2p_addr::{AddrStatus, UnknownAddr};
+use sak_p2p_discovery::Discovery;
+use sak_p2p_id::Identity;
+use sak_p2p_peertable::PeerTable;
+use sak_store_interface::MRSAccessor;
+use sak_types::{BlockHash, BlockHeight, TxCandidate, TxHash};
+use sak_vm::SakVM;
+use sak_vm_interface::ContractProcessor;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::net::{TcpListener, UdpSocket};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+const NODE_COUNT: usize = 2;
+
+// Time the nodes are given to say hello to each other
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+// A stopped node may hold its dbs and sockets for a moment, until all of
+// its routines have dropped them
+const RESTART_ATTEMPTS: usize = 20;
+
+const RESTART_RETRY_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Builds a `TestNet`.
+pub(crate) struct TestNetBuilder {
+    node_count: usize,
+    miners: Vec<usize>,
+    mine_interval: Option<u64>,
+}
+
+impl TestNetBuilder {
+    pub fn nodes(mut self, node_count: usize) -> TestNetBuilder {
+        self.node_count = node_count;
+        self
+    }
+
+    /// Idxs of the nodes running a miner.
+    pub fn miners(mut self, miners: &[usize]) -> TestNetBuilder {
+        self.miners = miners.to_vec();
+        self
+    }
+
+    pub fn mine_interval(mut self, mine_interval: u64) -> TestNetBuilder {
+        self.mine_interval = Some(mine_interval);
+        self
+    }
+
+    /// Starts the nodes and waits until each of them has said hello to all
+    /// the others.
+    pub async fn build(self) -> TestNet {
+        // Sockets are bound first, so that every node knows the ports of
+        // the others
+        let mut bound = vec![];
+
+        for idx in 0..self.node_count {
+            let credential = Credential::new_random().expect("Credential should be created");
+
+            let (p2p_socket, disc_socket) = bind_sockets(None, None)
+                .await
+                .expect("Sockets should be bound");
+
+            let spec = TestNodeSpec {
+                credential,
+                p2p_port: p2p_socket.local_addr().unwrap().port(),
+                disc_port: disc_socket.local_addr().unwrap().port(),
+                miner: self.miners.contains(&idx),
+            };
+
+            bound.push((spec, p2p_socket, disc_socket));
+        }
+
+        // Every node starts on a fresh data dir, nothing to clean up
+        SaksahaTestUtils::init_test(&[]);
+
+        let mut specs = vec![];
+        let mut nodes = vec![];
+
+        for (idx, (spec, p2p_socket, disc_socket)) in bound.into_iter().enumerate() {
+            // Every node bootstraps the ones started before her
+            let bootstrap_addrs = specs.iter().map(TestNodeSpec::get_unknown_addr).collect();
+
+            let data_dir = TempDir::new().expect("Data dir should be created");
+
+            let node = start_node(
+                &spec,
+                Arc::new(data_dir),
+                p2p_socket,
+                disc_socket,
+                bootstrap_addrs,
+                self.mine_interval,
+            )
+            .await
+            .expect("Test node should start");
+
+            debug!(
+                "Started a test node, idx: {}, public_key: {}",
+                idx, spec.credential.public_key,
+            );
+
+            specs.push(spec);
+            nodes.push(node);
+        }
+
+        let test_net = TestNet {
+            specs,
+            nodes,
+            mine_interval: self.mine_interval,
+        };
+
+        test_net
+            .wait_until_connected(CONNECT_TIMEOUT)
+            .await
+            .expect("Test nodes should connect to each other");
+
+        info!("Test net is up, node count: {}", test_net.nodes.len());
+
+        test_net
+    }
+}
+
+// What a node is started with, kept to restart her as the same node
+struct TestNodeSpec {
+    credential: Credential,
+    p2p_port: u16,
+    disc_port: u16,
+    miner: bool,
+}
+
+impl TestNodeSpec {
+    fn get_unknown_addr(&self) -> UnknownAddr {
+        UnknownAddr {
+            ip: String::from("127.0.0.1"),
+            disc_port: self.disc_port,
+            p2p_port: None,
+            sig: None,
+            public_key_str: Some(self.credential.public_key.clone()),
+            status: AddrStatus::Initialized,
+        }
+    }
+}
+
+/// A full node running in the test process.
+pub(crate) struct TestNode {
+    pub public_key: String,
+    pub machine: Arc<SakMachine>,
+    pub local_node: Arc<LocalNode>,
+    pub peer_table: Arc<PeerTable>,
+    discovery: Arc<Discovery>,
+    shutdown_token: CancellationToken,
+    system_thread: Option<JoinHandle<()>>,
+    // Removed once the node is dropped, kept across a restart
+    data_dir: Arc<TempDir>,
+}
+
+impl TestNode {
+    /// Height and hash of the latest block.
+    pub async fn get_tip(&self) -> Result<(BlockHeight, BlockHash), SaksahaError> {
+        let ledger = &self.machine.ledger;
+
+        let height = ledger
+            .get_latest_block_height()?
+            .ok_or("Latest block height does not exist")?;
+
+        let block = ledger
+            .get_block_by_height(&height)
+            .await?
+            .ok_or("Latest block does not exist")?;
+
+        Ok((height, block.get_block_hash().to_string()))
+    }
+
+    // Peers that have said hello
+    async fn count_peers(&self) -> usize {
+        self.peer_table
+            .get_peer_infos()
+            .await
+            .iter()
+            .filter(|p| p.chain_status.is_some())
+            .count()
+    }
+
+    async fn stop(&mut self) -> Result<(), SaksahaError> {
+        let shutdown_manager = ShutdownMng::new(self.shutdown_token.clone());
+
+        shutdown_manager
+            .shutdown(
+                self.system_thread.take(),
+                self.local_node.clone(),
+                self.machine.clone(),
+                self.discovery.clone(),
+            )
+            .await
+    }
+}
+
+/// Full nodes connected to each other in one process, on ephemeral ports
+/// and their own data dirs. Messages between them can be dropped or delayed,
+/// and a node can be restarted, to test how the network converges.
+pub(crate) struct TestNet {
+    specs: Vec<TestNodeSpec>,
+    nodes: Vec<TestNode>,
+    mine_interval: Option<u64>,
+}
+
+impl TestNet {
+    pub fn builder() -> TestNetBuilder {
+        TestNetBuilder {
+            node_count: NODE_COUNT,
+            miners: vec![],
+            mine_interval: None,
+        }
+    }
+
+    pub fn node(&self, idx: usize) -> &TestNode {
+        &self.nodes[idx]
+    }
+
+    pub async fn send_tx(&self, idx: usize, tx: TxCandidate) -> Result<TxHash, SaksahaError> {
+        let tx_hash = self.nodes[idx].machine.ledger.send_tx(tx).await?;
+
+        Ok(tx_hash)
+    }
+
+    /// Waits until every node has the same latest block, at `min_height` or
+    /// above.
+    pub async fn wait_until_same_tip(
+        &self,
+        min_height: BlockHeight,
+        timeout: Duration,
+    ) -> Result<(BlockHeight, BlockHash), SaksahaError> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let mut tips = vec![];
+
+            for node in &self.nodes {
+                tips.push(node.get_tip().await?);
+            }
+
+            let first = tips[0].clone();
+
+            if first.0 >= min_height && tips.iter().all(|t| *t == first) {
+                return Ok(first);
+            }
+
+            if Instant::now() >= deadline {
+                return Err(format!(
+                    "Nodes have not converged in {:?}, tips: {:?}",
+                    timeout, tips,
+                )
+                .into());
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Msgs the node `from` sends to the node `to` are dropped.
+    pub async fn drop_msgs(&self, from: usize, to: usize) {
+        self.set_link_fault(from, to, LinkFault::Drop).await;
+    }
+
+    /// Msgs the node `from` sends to the node `to` arrive `delay` late.
+    pub async fn delay_msgs(&self, from: usize, to: usize, delay: Duration) {
+        self.set_link_fault(from, to, LinkFault::Delay(delay)).await;
+    }
+
+    /// Nodes of different groups drop each other's msgs. A node in none of
+    /// the groups is not affected.
+    pub async fn partition(&self, groups: &[&[usize]]) {
+        for (i, group) in groups.iter().enumerate() {
+            for other in &groups[i + 1..] {
+                for &a in group.iter() {
+                    for &b in other.iter() {
+                        self.drop_msgs(a, b).await;
+                        self.drop_msgs(b, a).await;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Clears all the faults injected so far.
+    pub async fn heal(&self) {
+        for node in &self.nodes {
+            node.local_node.fault_injector.clear_all().await;
+        }
+    }
+
+    /// Stops the node and starts her again on the same ports and data dir,
+    /// waiting until she has said hello to all the others.
+    pub async fn restart(&mut self, idx: usize) -> Result<(), SaksahaError> {
+        let data_dir = {
+            let mut node = self.nodes.remove(idx);
+
+            node.stop().await?;
+
+            node.data_dir
+        };
+
+        let spec = &self.specs[idx];
+
+        let bootstrap_addrs: Vec<UnknownAddr> = self
+            .specs
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != idx)
+            .map(|(_, s)| s.get_unknown_addr())
+            .collect();
+
+        let mut attempt = 0;
+
+        let node = loop {
+            attempt += 1;
+
+            let res = async {
+                let (p2p_socket, disc_socket) =
+                    bind_sockets(Some(spec.p2p_port), Some(spec.disc_port)).await?;
+
+                start_node(
+                    spec,
+                    data_dir.clone(),
+                    p2p_socket,
+                    disc_socket,
+                    bootstrap_addrs.clone(),
+                    self.mine_interval,
+                )
+                .await
+            }
+            .await;
+
+            match res {
+                Ok(n) => break n,
+                Err(err) if attempt < RESTART_ATTEMPTS => {
+                    debug!("Test node is not ready to restart yet, err: {}", err);
+
+                    tokio::time::sleep(RESTART_RETRY_INTERVAL).await;
+                }
+                Err(err) => return Err(err),
+            };
+        };
+
+        self.nodes.insert(idx, node);
+
+        let deadline = Instant::now() + CONNECT_TIMEOUT;
+
+        while self.nodes[idx].count_peers().await < self.nodes.len() - 1 {
+            if Instant::now() >= deadline {
+                return Err(format!("Restarted node has not reconnected, idx: {}", idx).into());
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        Ok(())
+    }
+
+    /// Stops all the nodes and removes their data dirs.
+    pub async fn shutdown(mut self) {
+        for mut node in std::mem::take(&mut self.nodes) {
+            if let Err(err) = node.stop().await {
+                warn!("Could not stop a test node, err: {}", err);
+            }
+        }
+    }
+
+    async fn set_link_fault(&self, from: usize, to: usize, fault: LinkFault) {
+        let from_public_key = &self.nodes[from].public_key;
+
+        self.nodes[to]
+            .local_node
+            .fault_injector
+            .set_fault(from_public_key, fault)
+            .await;
+    }
+
+    async fn wait_until_connected(&self, timeout: Duration) -> Result<(), SaksahaError> {
+        let deadline = Instant::now() + timeout;
+
+        for (idx, node) in self.nodes.iter().enumerate() {
+            while node.count_peers().await < self.nodes.len() - 1 {
+                if Instant::now() >= deadline {
+                    return Err(format!("Test node has not connected, idx: {}", idx).into());
+                }
+
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Routines of a node still running when a test panics are stopped
+impl Drop for TestNet {
+    fn drop(&mut self) {
+        for node in &self.nodes {
+            node.shutdown_token.cancel();
+        }
+    }
+}
+
+async fn bind_sockets(
+    p2p_port: Option<u16>,
+    disc_port: Option<u16>,
+) -> Result<(TcpListener, UdpSocket), SaksahaError> {
+    let (p2p_socket, _) = sak_utils_net::bind_tcp_socket(p2p_port).await?;
+
+    let (disc_socket, _) = sak_utils_net::setup_udp_socket(disc_port).await?;
+
+    Ok((p2p_socket, disc_socket))
+}
+
+async fn start_node(
+    spec: &TestNodeSpec,
+    data_dir: Arc<TempDir>,
+    p2p_socket: TcpListener,
+    disc_socket: UdpSocket,
+    bootstrap_addrs: Vec<UnknownAddr>,
+    mine_interval: Option<u64>,
+) -> Result<TestNode, SaksahaError> {
+    let public_key = &spec.credential.public_key;
+
+    let identity = {
+        let i = Identity::new(
+            &spec.credential.secret,
+            public_key,
+            spec.p2p_port,
+            spec.disc_port,
+        )?;
+
+        Arc::new(i)
+    };
+
+    let peer_table = {
+        let ps = PeerTable::init(None, None, vec![]).await?;
+
+        Arc::new(ps)
+    };
+
+    let shutdown_token = CancellationToken::new();
+
+    let p2p_host = {
+        let p2p_host_args = P2PHostArgs {
+            addr_expire_duration: None,
+            addr_monitor_interval: None,
+            disc_dial_interval: None,
+            disc_table_capacity: None,
+            disc_task_interval: None,
+            disc_task_queue_capacity: None,
+            p2p_task_interval: None,
+            p2p_task_queue_capacity: None,
+            p2p_dial_interval: None,
+            disc_socket,
+            p2p_socket,
+            p2p_max_conn_count: None,
+            p2p_port: spec.p2p_port,
+            bootstrap_addrs,
+            identity: identity.clone(),
+            peer_table: peer_table.clone(),
+            addr_book_path: None,
+            external_addr: None,
+            port_mapping: false,
+            shutdown_token: shutdown_token.clone(),
+        };
+
+        P2PHost::init(p2p_host_args).await?
+    };
+
+    let mrs = {
+        let m = MRS::init(data_dir.path()).await?;
+
+        Arc::new(Box::new(m) as MRSAccessor)
+    };
+
+    let vm: ContractProcessor = {
+        let v = SakVM::init(mrs.clone())?;
+        Box::new(v)
+    };
+
+    let ledger = Ledger::init(data_dir.path(), None, None, None, identity, vm, None).await?;
+
+    let machine = Arc::new(SakMachine { ledger, mrs });
+
+    let local_node = {
+        let ln = LocalNode::new(
+            peer_table.clone(),
+            machine.clone(),
+            Some(spec.miner),
+            mine_interval,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            p2p_host.get_discovery().clone(),
+            shutdown_token.clone(),
+        );
+
+        Arc::new(ln)
+    };
+
+    let discovery = p2p_host.get_discovery();
+
+    let system_thread = {
+        let machine = machine.clone();
+        let local_node = local_node.clone();
+        let shutdown_token = shutdown_token.clone();
+
+        tokio::spawn(async move {
+            tokio::join!(p2p_host.run(), local_node.run(), async {
+                tokio::select! {
+                    _ = machine.run() => {},
+                    _ = shutdown_token.cancelled() => {},
+                }
+            });
+        })
+    };
+
+    Ok(TestNode {
+        public_key: public_key.to_string(),
+        machine,
+        local_node,
+        peer_table,
+        discovery,
+        shutdown_token,
+        system_thread: Some(system_thread),
+        data_dir,
+    })
+}
@@ -1,6 +1,6 @@
 use crate::SaksahaSDKError;
 use hyper::{Body, Client, Method, Request, Uri};
-use sak_contract_std::{CtrCallType, CtrRequest, CtrRequestData, RequestArgs};
+use sak_contract_std::{ContractRevert, CtrCallType, CtrRequest, CtrRequestData, RequestArgs};
 use sak_crypto::encode_hex;
 use sak_ledger_cfg::CM_TREE_DEPTH;
 use sak_rpc_interface::{JsonRequest, JsonResponse, SendMintTxRequest, SendPourTxRequest};
@@ -12,12 +12,27 @@ use std::time;
 pub struct QueryCtrRequest {
     pub ctr_addr: String,
     pub req: CtrRequestData,
+    #[serde(default)]
+    pub public_key: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct QueryCtrResponse {
     pub result: Vec<u8>,
     // pub result: String,
+    pub session_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ConfirmSessionRequest {
+    pub session_id: String,
+    pub public_key: String,
+    pub sig: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ConfirmSessionResponse {
+    pub session_id: String,
 }
 
 pub fn new_empty_32_temp() -> [u8; 32] {
@@ -56,13 +71,13 @@ pub async fn send_tx_pour(
             merkle_rts,
         );
 
-        let params = serde_json::to_string(&send_req)?.as_bytes().to_vec();
+        let params = serde_json::to_value(&send_req)?;
 
         let json_request = JsonRequest {
             jsonrpc: "2.0".to_string(),
             method: "send_pour_tx".to_string(),
             params: Some(params),
-            id: "test_1".to_string(),
+            id: Some("test_1".into()),
         };
 
         let str = serde_json::to_string(&json_request)?;
@@ -126,13 +141,13 @@ pub async fn send_tx_mint(
             s,
         );
 
-        let params = serde_json::to_string(&send_req)?.as_bytes().to_vec();
+        let params = serde_json::to_value(&send_req)?;
 
         let json_request = JsonRequest {
             jsonrpc: "2.0".to_string(),
             method: "send_mint_tx".to_string(),
             params: Some(params),
-            id: "test_1".to_string(),
+            id: Some("test_1".into()),
         };
 
         let str = serde_json::to_string(&json_request)?;
@@ -160,6 +175,7 @@ pub async fn query_ctr(
     ctr_addr: String,
     req_type: String,
     args: RequestArgs,
+    public_key: Option<String>,
 ) -> Result<JsonResponse<QueryCtrResponse>, SaksahaSDKError> {
     let client = Client::new();
 
@@ -172,14 +188,18 @@ pub async fn query_ctr(
             ctr_call_type: CtrCallType::Query,
         };
 
-        let send_req = QueryCtrRequest { ctr_addr, req };
-        let params = serde_json::to_vec(&send_req)?;
+        let send_req = QueryCtrRequest {
+            ctr_addr,
+            req,
+            public_key,
+        };
+        let params = serde_json::to_value(&send_req)?;
 
         let json_request = JsonRequest {
             jsonrpc: "2.0".to_string(),
             method: "query_ctr".to_string(),
             params: Some(params),
-            id: "test_1".to_string(),
+            id: Some("test_1".into()),
         };
 
         let str = serde_json::to_string(&json_request)?;
@@ -203,6 +223,60 @@ pub async fn query_ctr(
     Ok(json_response)
 }
 
+/// Returns the contract error if the call has been reverted by the contract,
+/// as opposed to failing on the node or the transport.
+pub fn get_ctr_revert<R: Serialize>(json_response: &JsonResponse<R>) -> Option<ContractRevert> {
+    let data = json_response.error.as_ref()?.data.clone()?;
+
+    serde_json::from_value(data).ok()
+}
+
+pub async fn confirm_session(
+    saksaha_endpoint: String,
+    session_id: String,
+    public_key: String,
+    sig: Vec<u8>,
+) -> Result<JsonResponse<ConfirmSessionResponse>, SaksahaSDKError> {
+    let client = Client::new();
+
+    let uri: Uri = { saksaha_endpoint.parse().expect("URI should be made") };
+
+    let body = {
+        let send_req = ConfirmSessionRequest {
+            session_id,
+            public_key,
+            sig,
+        };
+
+        let params = serde_json::to_value(&send_req)?;
+
+        let json_request = JsonRequest {
+            jsonrpc: "2.0".to_string(),
+            method: "confirm_session".to_string(),
+            params: Some(params),
+            id: Some("test_1".into()),
+        };
+
+        let str = serde_json::to_string(&json_request)?;
+
+        Body::from(str)
+    };
+
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .body(body)
+        .expect("request builder should be made");
+
+    let resp = client.request(req).await?;
+
+    let b = hyper::body::to_bytes(resp.into_body()).await?;
+
+    let json_response = serde_json::from_slice::<JsonResponse<ConfirmSessionResponse>>(&b)?;
+
+    Ok(json_response)
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GetCmIdxRequest {
     pub cm: Cm,
@@ -223,13 +297,13 @@ pub async fn get_cm_idx(
     let body = {
         let req = GetCmIdxRequest { cm };
 
-        let params = serde_json::to_string(&req)?.as_bytes().to_vec();
+        let params = serde_json::to_value(&req)?;
 
         let json_request = JsonRequest {
             jsonrpc: "2.0".to_string(),
             method: "get_cm_idx".to_string(),
             params: Some(params),
-            id: "test_1".to_string(),
+            id: Some("test_1".into()),
         };
 
         let str = serde_json::to_string(&json_request)?;
@@ -272,13 +346,13 @@ pub async fn get_tx(
     let body = {
         let req = GetTxRequest { hash };
 
-        let params = serde_json::to_string(&req)?.as_bytes().to_vec();
+        let params = serde_json::to_value(&req)?;
 
         let json_request = JsonRequest {
             jsonrpc: "2.0".to_string(),
             method: "get_tx".to_string(),
             params: Some(params),
-            id: "test_1".to_string(),
+            id: Some("test_1".into()),
         };
 
         let str = serde_json::to_string(&json_request)?;
@@ -320,13 +394,13 @@ pub async fn get_auth_path(
 
     let body = {
         let send_req = GetAuthPathRequest { cm_idx: idx };
-        let params = serde_json::to_string(&send_req)?.as_bytes().to_vec();
+        let params = serde_json::to_value(&send_req)?;
 
         let json_request = JsonRequest {
             jsonrpc: "2.0".to_string(),
             method: "get_auth_path".to_string(),
             params: Some(params),
-            id: "test_1".to_string(),
+            id: Some("test_1".into()),
         };
 
         let str = serde_json::to_string(&json_request)?;
@@ -25,7 +25,7 @@ pub(in crate::rpc) async fn get_balance(
 
     let params = require_some_params!(route_state, params, "get_balance should contain params",);
 
-    debug!("params: {:?}", String::from_utf8(params.clone()));
+    debug!("params: {}", params);
 
     let rb: GetBalanceRequest = require_params_parsed!(route_state, &params);
 
@@ -26,13 +26,13 @@ async fn test_get_balance_default() {
             acc_addr: test_context.acc_addr.clone(),
         };
 
-        let params = serde_json::to_vec(&get_balance_req).unwrap();
+        let params = serde_json::to_value(&get_balance_req).unwrap();
 
         let json_request = JsonRequest {
             jsonrpc: "2.0".to_string(),
             method: "get_balance".to_string(),
             params: Some(params),
-            id: "test_1".to_string(),
+            id: Some("test_1".into()),
         };
 
         let str = serde_json::to_string(&json_request).unwrap();
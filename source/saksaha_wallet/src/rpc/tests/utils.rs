@@ -138,13 +138,13 @@ pub(crate) async fn mock_send_pour_tx(
             ctr_request_data,
         };
 
-        let params = serde_json::to_vec(&send_tx_req).unwrap();
+        let params = serde_json::to_value(&send_tx_req).unwrap();
 
         let json_request = JsonRequest {
             jsonrpc: "2.0".to_string(),
             method: "send_pour_tx".to_string(),
             params: Some(params),
-            id: "test_1".to_string(),
+            id: Some("test_1".into()),
         };
 
         let str = serde_json::to_string(&json_request).unwrap();
@@ -192,13 +192,13 @@ pub(crate) async fn mock_update_coin_status(
             ctr_request_data,
         };
 
-        let params = serde_json::to_vec(&send_tx_req).unwrap();
+        let params = serde_json::to_value(&send_tx_req).unwrap();
 
         let json_request = JsonRequest {
             jsonrpc: "2.0".to_string(),
             method: "update_coin_status".to_string(),
             params: Some(params),
-            id: "test_1".to_string(),
+            id: Some("test_1".into()),
         };
 
         let str = serde_json::to_string(&json_request).unwrap();